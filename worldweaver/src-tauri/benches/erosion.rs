@@ -0,0 +1,46 @@
+//! Benchmarks for hydraulic erosion: the tiled parallel pass against the serial one
+//! Run with `cargo bench --bench erosion`
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use worldweaver::terrain::erosion::{
+    erode_terrain_parallel_with_context, erode_terrain_with_context, ErosionContext, ErosionParams,
+};
+
+/// Rolling test terrain in 0..1 so droplets have slopes to run down
+fn test_heights(size: usize) -> Vec<f32> {
+    (0..size * size)
+        .map(|i| {
+            let (x, z) = ((i % size) as f32, (i / size) as f32);
+            0.5 + 0.25 * (x * 0.031).sin() * (z * 0.027).cos() + 0.1 * ((x + z) * 0.11).sin()
+        })
+        .collect()
+}
+
+fn bench_erosion(c: &mut Criterion) {
+    let params = ErosionParams { num_droplets: 50_000, seed: Some(7), ..Default::default() };
+    let context = ErosionContext::default();
+
+    let mut group = c.benchmark_group("erosion");
+    group.sample_size(10);
+    for size in [512, 1024] {
+        let heights = test_heights(size);
+        group.bench_with_input(BenchmarkId::new("parallel", size), &heights, |b, heights| {
+            b.iter_batched_ref(
+                || heights.clone(),
+                |map| erode_terrain_parallel_with_context(map, size, size, &params, &context),
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("serial", size), &heights, |b, heights| {
+            b.iter_batched_ref(
+                || heights.clone(),
+                |map| erode_terrain_with_context(map, size, size, &params, &context),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_erosion);
+criterion_main!(benches);
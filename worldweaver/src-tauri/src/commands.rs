@@ -1,5 +1,11 @@
 use tauri::State;
-use crate::simulation::world::{SharedWorld, RoomDetails, NpcInfo};
+use crate::simulation::world::{GameWorld, SharedWorld, RoomDetails, NpcInfo, ExplorationState, TravelEstimate, ItemInfo, ShopPrice, PartyMember, WorldWarning, PLAYER_ENTITY_ID};
+use crate::simulation::storylets::Quality;
+use crate::simulation::systems::GameTime;
+use crate::state::{WorldSlots, WorldSlotConfig, WorldSlotInfo};
+use crate::terrain::TerrainData;
+use std::collections::HashMap;
+use uuid::Uuid;
 
 /// Custom error type for Tauri commands
 #[derive(serde::Serialize)]
@@ -23,14 +29,24 @@ impl From<String> for CommandError {
     }
 }
 
-/// Get the current room where the player is located
+/// Parse an optional party member id; None means the original player
+fn parse_character_id(character_id: Option<String>) -> Result<Uuid, CommandError> {
+    match character_id {
+        Some(id) => Ok(Uuid::parse_str(&id).map_err(|e| anyhow::anyhow!("Invalid character id: {}", e))?),
+        None => Ok(PLAYER_ENTITY_ID),
+    }
+}
+
+/// Get the current room where the player (or another party member) is located
 #[tauri::command]
 pub async fn get_current_room(
+    character_id: Option<String>,
     world: State<'_, SharedWorld>
 ) -> Result<RoomDetails, CommandError> {
     let mut world_lock = world.lock().await;
     
-    let room_id = world_lock.get_player_room()
+    let character_uuid = parse_character_id(character_id)?;
+    let room_id = world_lock.get_character_room(character_uuid)
         .ok_or_else(|| anyhow::anyhow!("Player has no position"))?;
     
     let room_details = world_lock.get_room_details(room_id)
@@ -54,18 +70,72 @@ pub async fn get_npcs_in_current_room(
     Ok(npcs)
 }
 
+/// Get items lying in the current room
+#[tauri::command]
+pub async fn get_items_in_current_room(
+    world: State<'_, SharedWorld>
+) -> Result<Vec<ItemInfo>, CommandError> {
+    let mut world_lock = world.lock().await;
+    
+    let room_id = world_lock.get_player_room()
+        .ok_or_else(|| anyhow::anyhow!("Player has no position"))?;
+    
+    Ok(world_lock.get_items_in_room(room_id))
+}
+
+/// Get the items the player is carrying
+#[tauri::command]
+pub async fn get_inventory(
+    world: State<'_, SharedWorld>
+) -> Result<Vec<ItemInfo>, CommandError> {
+    let mut world_lock = world.lock().await;
+    Ok(world_lock.get_player_inventory())
+}
+
+/// Pick up an item from the current room
+#[tauri::command]
+pub async fn pick_up_item(
+    item_id: String,
+    world: State<'_, SharedWorld>
+) -> Result<(), CommandError> {
+    let mut world_lock = world.lock().await;
+    
+    let item_uuid = Uuid::parse_str(&item_id)
+        .map_err(|e| anyhow::anyhow!("Invalid item id: {}", e))?;
+    
+    world_lock.pick_up_item(item_uuid)?;
+    Ok(())
+}
+
+/// Drop a carried item into the current room
+#[tauri::command]
+pub async fn drop_item(
+    item_id: String,
+    world: State<'_, SharedWorld>
+) -> Result<(), CommandError> {
+    let mut world_lock = world.lock().await;
+    
+    let item_uuid = Uuid::parse_str(&item_id)
+        .map_err(|e| anyhow::anyhow!("Invalid item id: {}", e))?;
+    
+    world_lock.drop_item(item_uuid)?;
+    Ok(())
+}
+
 /// Move player in a direction
 #[tauri::command]
 pub async fn move_player(
     direction: String,
+    character_id: Option<String>,
     world: State<'_, SharedWorld>
 ) -> Result<RoomDetails, CommandError> {
     let mut world_lock = world.lock().await;
     
     let direction_lower = direction.trim().to_lowercase();
+    let character_uuid = parse_character_id(character_id)?;
     
-    // Attempt to move player
-    let new_room_id = world_lock.move_player(&direction_lower)?;
+    // Attempt to move the character; the rest of the party stays put
+    let new_room_id = world_lock.move_character(character_uuid, &direction_lower)?;
     
     // Get the new room details
     let room_details = world_lock.get_room_details(new_room_id)
@@ -74,73 +144,347 @@ pub async fn move_player(
     Ok(room_details)
 }
 
-/// Process a player action/command
+/// Move the player (or another party member) to an adjacent room by id, for map clicks
+#[tauri::command]
+pub async fn move_player_to(
+    room_id: String,
+    character_id: Option<String>,
+    world: State<'_, SharedWorld>
+) -> Result<RoomDetails, CommandError> {
+    let mut world_lock = world.lock().await;
+    
+    let room_uuid = Uuid::parse_str(&room_id)
+        .map_err(|e| anyhow::anyhow!("Invalid room id: {}", e))?;
+    let character_uuid = parse_character_id(character_id)?;
+    
+    let new_room_id = world_lock.move_character_to(character_uuid, room_uuid)?;
+    
+    let room_details = world_lock.get_room_details(new_room_id)
+        .ok_or_else(|| anyhow::anyhow!("New room not found after movement"))?;
+    
+    Ok(room_details)
+}
+
+/// Step back to the room the player came from
+#[tauri::command]
+pub async fn go_back(
+    world: State<'_, SharedWorld>
+) -> Result<RoomDetails, CommandError> {
+    let mut world_lock = world.lock().await;
+    
+    let new_room_id = world_lock.go_back()?;
+    
+    let room_details = world_lock.get_room_details(new_room_id)
+        .ok_or_else(|| anyhow::anyhow!("New room not found after movement"))?;
+    
+    Ok(room_details)
+}
+
+/// Move the player directly to a room (fast travel)
+/// Release builds only allow discovered rooms; debug builds can teleport anywhere
+#[tauri::command]
+pub async fn set_player_room(
+    room_id: String,
+    world: State<'_, SharedWorld>
+) -> Result<RoomDetails, CommandError> {
+    let mut world_lock = world.lock().await;
+    
+    let room_uuid = Uuid::parse_str(&room_id)
+        .map_err(|e| anyhow::anyhow!("Invalid room id: {}", e))?;
+    
+    let new_room_id = world_lock.teleport_player(room_uuid, !cfg!(debug_assertions))?;
+    
+    let room_details = world_lock.get_room_details(new_room_id)
+        .ok_or_else(|| anyhow::anyhow!("New room not found after movement"))?;
+    
+    Ok(room_details)
+}
+
+/// Estimate walking time between two rooms (fast-travel confirmation)
+/// Returns None if the destination can't be reached along exits
+#[tauri::command]
+pub async fn travel_time(
+    from_room: String,
+    to_room: String,
+    world: State<'_, SharedWorld>
+) -> Result<Option<TravelEstimate>, CommandError> {
+    let mut world_lock = world.lock().await;
+    
+    let from_uuid = Uuid::parse_str(&from_room)
+        .map_err(|e| anyhow::anyhow!("Invalid room id: {}", e))?;
+    let to_uuid = Uuid::parse_str(&to_room)
+        .map_err(|e| anyhow::anyhow!("Invalid room id: {}", e))?;
+    
+    Ok(world_lock.travel_time(from_uuid, to_uuid))
+}
+
+/// Rename and re-describe a room (world editor)
+#[tauri::command]
+pub async fn update_room(
+    room_id: String,
+    name: String,
+    description: String,
+    world: State<'_, SharedWorld>
+) -> Result<RoomDetails, CommandError> {
+    let mut world_lock = world.lock().await;
+    
+    let room_uuid = Uuid::parse_str(&room_id)
+        .map_err(|e| anyhow::anyhow!("Invalid room id: {}", e))?;
+    
+    world_lock.update_room(room_uuid, name, description)?;
+    
+    let room_details = world_lock.get_room_details(room_uuid)
+        .ok_or_else(|| anyhow::anyhow!("Room not found after update"))?;
+    
+    Ok(room_details)
+}
+
+/// Add a new, unconnected room (world editor)
+#[tauri::command]
+pub async fn create_room(
+    name: String,
+    description: String,
+    world: State<'_, SharedWorld>
+) -> Result<RoomDetails, CommandError> {
+    let mut world_lock = world.lock().await;
+    
+    let room_id = world_lock.create_room(name, description);
+    
+    let room_details = world_lock.get_room_details(room_id)
+        .ok_or_else(|| anyhow::anyhow!("Room not found after creation"))?;
+    
+    Ok(room_details)
+}
+
+/// Link two rooms with exits in both directions (world editor)
+#[tauri::command]
+pub async fn connect_rooms(
+    from_room: String,
+    to_room: String,
+    direction: String,
+    reverse_direction: String,
+    world: State<'_, SharedWorld>
+) -> Result<(), CommandError> {
+    let mut world_lock = world.lock().await;
+    
+    let from_uuid = Uuid::parse_str(&from_room)
+        .map_err(|e| anyhow::anyhow!("Invalid room id: {}", e))?;
+    let to_uuid = Uuid::parse_str(&to_room)
+        .map_err(|e| anyhow::anyhow!("Invalid room id: {}", e))?;
+    
+    world_lock.connect_rooms(from_uuid, to_uuid, &direction, &reverse_direction)?;
+    
+    Ok(())
+}
+
+/// Update an NPC's name, description, personality, and greeting (world editor)
+#[tauri::command]
+pub async fn update_npc(
+    npc_id: String,
+    name: String,
+    description: String,
+    personality: String,
+    greeting: String,
+    world: State<'_, SharedWorld>
+) -> Result<(), CommandError> {
+    let mut world_lock = world.lock().await;
+    
+    let npc_uuid = Uuid::parse_str(&npc_id)
+        .map_err(|e| anyhow::anyhow!("Invalid NPC id: {}", e))?;
+    
+    world_lock.update_npc(npc_uuid, name, description, personality, greeting)?;
+    
+    Ok(())
+}
+
+/// Add an NPC to a room (world editor); returns the new NPC's id
+#[tauri::command]
+pub async fn spawn_npc(
+    name: String,
+    description: String,
+    personality: String,
+    greeting: String,
+    room_id: String,
+    world: State<'_, SharedWorld>
+) -> Result<Uuid, CommandError> {
+    let mut world_lock = world.lock().await;
+    
+    let room_uuid = Uuid::parse_str(&room_id)
+        .map_err(|e| anyhow::anyhow!("Invalid room id: {}", e))?;
+    world_lock.get_room_details(room_uuid)
+        .ok_or_else(|| anyhow::anyhow!("Room not found"))?;
+    
+    Ok(world_lock.spawn_npc(name, description, personality, greeting, room_uuid))
+}
+
+/// Check the room graph for dangling and one-way exits (world editor)
+#[tauri::command]
+pub async fn validate_world(
+    world: State<'_, SharedWorld>
+) -> Result<Vec<WorldWarning>, CommandError> {
+    let mut world_lock = world.lock().await;
+    Ok(world_lock.validate_world())
+}
+
+/// Add another player-controlled character to the party; returns its character id
+#[tauri::command]
+pub async fn spawn_party_member(
+    name: String,
+    description: String,
+    room_id: String,
+    world: State<'_, SharedWorld>
+) -> Result<Uuid, CommandError> {
+    let mut world_lock = world.lock().await;
+    
+    let room_uuid = Uuid::parse_str(&room_id)
+        .map_err(|e| anyhow::anyhow!("Invalid room id: {}", e))?;
+    world_lock.get_room_details(room_uuid)
+        .ok_or_else(|| anyhow::anyhow!("Room not found"))?;
+    
+    Ok(world_lock.spawn_party_member(name, description, room_uuid))
+}
+
+/// List every player-controlled character and where they are
+#[tauri::command]
+pub async fn get_party(
+    world: State<'_, SharedWorld>
+) -> Result<Vec<PartyMember>, CommandError> {
+    let mut world_lock = world.lock().await;
+    Ok(world_lock.get_party())
+}
+
+/// Remove an NPC from the world (world editor); returns false if it didn't exist
+#[tauri::command]
+pub async fn despawn_npc(
+    npc_id: String,
+    world: State<'_, SharedWorld>
+) -> Result<bool, CommandError> {
+    let mut world_lock = world.lock().await;
+    
+    let npc_uuid = Uuid::parse_str(&npc_id)
+        .map_err(|e| anyhow::anyhow!("Invalid NPC id: {}", e))?;
+    
+    Ok(world_lock.despawn_npc(npc_uuid))
+}
+
+/// Get a shop's current prices; the shop id is its keeper's NPC id
+#[tauri::command]
+pub async fn get_shop_prices(
+    shop_id: String,
+    world: State<'_, SharedWorld>
+) -> Result<Vec<ShopPrice>, CommandError> {
+    let mut world_lock = world.lock().await;
+    
+    let shop_uuid = Uuid::parse_str(&shop_id)
+        .map_err(|e| anyhow::anyhow!("Invalid shop id: {}", e))?;
+    
+    Ok(world_lock.get_shop_prices(shop_uuid)?)
+}
+
+/// Describe the room a character stands in, with its exits, NPCs and items
+fn look_around(world: &mut GameWorld, character_id: Uuid) -> Result<String, CommandError> {
+    let room_id = world.get_character_room(character_id)
+        .ok_or_else(|| anyhow::anyhow!("No current room"))?;
+    let room = world.get_room_details(room_id)
+        .ok_or_else(|| anyhow::anyhow!("Room not found"))?;
+    let description = world.describe_room(room_id)
+        .unwrap_or_else(|| room.description.clone());
+    
+    let npcs = world.get_npcs_in_room(room_id);
+    
+    let mut response = format!("{}\n\n{}\n\nObvious exits: {}", 
+        room.name,
+        description,
+        room.exits.iter()
+            .map(|e| e.direction.clone())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    
+    if !npcs.is_empty() {
+        response.push_str("\n\nYou see:");
+        for npc in npcs {
+            response.push_str(&format!("\n  - {}", npc.name));
+        }
+    }
+    
+    let items = world.get_items_in_room(room_id);
+    if !items.is_empty() {
+        response.push_str("\n\nOn the ground:");
+        for item in items {
+            response.push_str(&format!("\n  - {}", item.name));
+        }
+    }
+    
+    Ok(response)
+}
+
+/// Process a player action/command for the player (or another party member)
+/// Directions move the character and then look around its new room
 #[tauri::command]
 pub async fn send_player_action(
     action: String,
+    character_id: Option<String>,
     world: State<'_, SharedWorld>
 ) -> Result<String, CommandError> {
     let action_lower = action.trim().to_lowercase();
+    let character_uuid = parse_character_id(character_id)?;
     
-    // Check if it's a movement command and provide helpful error
-    let movement_directions = ["north", "n", "south", "s", "east", "e", "west", "w", "up", "u", "down", "d"];
-    if movement_directions.contains(&action_lower.as_str()) {
-        return Err(CommandError {
-            message: format!("Movement detected! Just type '{}' to move.", action_lower)
-        });
+    let direction = match action_lower.as_str() {
+        "n" => Some("north"),
+        "s" => Some("south"),
+        "e" => Some("east"),
+        "w" => Some("west"),
+        "u" => Some("up"),
+        "d" => Some("down"),
+        "north" | "south" | "east" | "west" | "up" | "down" => Some(action_lower.as_str()),
+        _ => None,
+    };
+    if let Some(direction) = direction {
+        let mut world_lock = world.lock().await;
+        world_lock.move_character(character_uuid, direction)?;
+        return look_around(&mut world_lock, character_uuid);
     }
     
     let response = match action_lower.as_str() {
-        "look" | "l" => {
-            let mut world_lock = world.lock().await;
-            let room_id = world_lock.get_player_room()
-                .ok_or_else(|| anyhow::anyhow!("No current room"))?;
-            let room = world_lock.get_room_details(room_id)
-                .ok_or_else(|| anyhow::anyhow!("Room not found"))?;
-            
-            let npcs = world_lock.get_npcs_in_room(room_id);
-            
-            let mut response = format!("{}\n\n{}\n\nObvious exits: {}", 
-                room.name,
-                room.description,
-                room.exits.iter()
-                    .map(|e| e.direction.clone())
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            );
-            
-            if !npcs.is_empty() {
-                response.push_str("\n\nYou see:");
-                for npc in npcs {
-                    response.push_str(&format!("\n  - {}", npc.name));
-                }
-            }
-            
-            response
-        },
+        "look" | "l" => look_around(&mut *world.lock().await, character_uuid)?,
         "help" => {
             "Available commands:\n\
              - look (or l): Examine your surroundings\n\
              - north/south/east/west (n/s/e/w): Move in that direction\n\
              - up/down (u/d): Move up or down\n\
              - talk to [name]: Start a conversation\n\
+             - examine [name] (or look at [name]): Look someone over\n\
              - help: Show this message".to_string()
         },
+        _ if action_lower.starts_with("examine ") || action_lower.starts_with("look at ") => {
+            let mut world_lock = world.lock().await;
+            let room_id = world_lock.get_character_room(character_uuid)
+                .ok_or_else(|| anyhow::anyhow!("No current room"))?;
+            let target = action_lower.strip_prefix("examine ")
+                .or_else(|| action_lower.strip_prefix("look at "))
+                .unwrap_or_default();
+            
+            world_lock.examine_npc(room_id, target)
+                .unwrap_or_else(|| "You don't see anyone like that here.".to_string())
+        },
         _ if action_lower.starts_with("talk to") => {
             let mut world_lock = world.lock().await;
-            let room_id = world_lock.get_player_room()
+            let room_id = world_lock.get_character_room(character_uuid)
                 .ok_or_else(|| anyhow::anyhow!("No current room"))?;
             let npcs = world_lock.get_npcs_in_room(room_id);
             
             if npcs.is_empty() {
                 "There's nobody here to talk to.".to_string()
-            } else {
+            } else if let Some(npc) = world_lock.talk_to(room_id, &action_lower["talk to".len()..]) {
                 format!("{} looks up as you approach.\n\n\
                         [Full NPC dialogue powered by Claude coming in Phase 4]\n\n\
                         Present NPCs: {}", 
-                    npcs[0].name,
+                    npc.name,
                     npcs.iter().map(|n| n.name.as_str()).collect::<Vec<_>>().join(", ")
                 )
+            } else {
+                "There's nobody here by that name.".to_string()
             }
         },
         _ => {
@@ -159,3 +503,118 @@ pub async fn get_world_tick(
     let world_lock = world.lock().await;
     Ok(world_lock.tick_count)
 }
+
+/// Get the in-game date and hour (hour, day, month, year, season)
+#[tauri::command]
+pub async fn get_world_time(
+    world: State<'_, SharedWorld>
+) -> Result<GameTime, CommandError> {
+    let world_lock = world.lock().await;
+    Ok(world_lock.get_world_time())
+}
+
+/// Fast-forward the world by a number of in-game days
+/// Returns a "what happened while you were away" digest
+#[tauri::command]
+pub async fn simulate_world_progress(
+    days: u32,
+    world: State<'_, SharedWorld>
+) -> Result<Vec<String>, CommandError> {
+    if days > 365 {
+        return Err("Cannot fast-forward more than 365 days at once".to_string().into());
+    }
+    
+    let mut world_lock = world.lock().await;
+    Ok(world_lock.simulate_days(days))
+}
+
+/// Get visited and known-but-unvisited rooms for fog-of-war
+#[tauri::command]
+pub async fn get_explored_rooms(
+    world: State<'_, SharedWorld>
+) -> Result<ExplorationState, CommandError> {
+    let mut world_lock = world.lock().await;
+    Ok(world_lock.get_explored_rooms())
+}
+
+/// Change the log level at runtime ("trace", "debug", "info", "warn", "error", "off")
+#[tauri::command]
+pub async fn set_log_level(
+    level: String
+) -> Result<(), CommandError> {
+    crate::logging::set_log_level(&level)?;
+    Ok(())
+}
+
+/// Create a new named world alongside the active one
+#[tauri::command]
+pub async fn create_world(
+    name: String,
+    config: Option<WorldSlotConfig>,
+    slots: State<'_, tokio::sync::Mutex<WorldSlots>>
+) -> Result<(), CommandError> {
+    let mut slots = slots.lock().await;
+    slots.create(&name, config.unwrap_or_default())?;
+    Ok(())
+}
+
+/// Make a named world the one all game and terrain commands operate on
+#[tauri::command]
+pub async fn switch_world(
+    name: String,
+    slots: State<'_, tokio::sync::Mutex<WorldSlots>>,
+    world: State<'_, SharedWorld>,
+    terrain: State<'_, tokio::sync::Mutex<TerrainData>>
+) -> Result<(), CommandError> {
+    let mut slots = slots.lock().await;
+    let mut world_lock = world.lock().await;
+    let mut terrain_lock = terrain.lock().await;
+    
+    slots.switch(&name, &mut world_lock, &mut terrain_lock)?;
+    Ok(())
+}
+
+/// List all loaded worlds
+#[tauri::command]
+pub async fn list_worlds(
+    slots: State<'_, tokio::sync::Mutex<WorldSlots>>,
+    world: State<'_, SharedWorld>
+) -> Result<Vec<WorldSlotInfo>, CommandError> {
+    let slots = slots.lock().await;
+    let world_lock = world.lock().await;
+    Ok(slots.list(&world_lock))
+}
+
+/// Get the player's qualities (labeled, with ranges) for the character sheet
+#[tauri::command]
+pub async fn get_player_qualities(
+    world: State<'_, SharedWorld>
+) -> Result<Vec<Quality>, CommandError> {
+    let world_lock = world.lock().await;
+    Ok(world_lock.get_player_qualities())
+}
+
+/// Get the player's skill levels by name
+#[tauri::command]
+pub async fn get_player_skills(
+    world: State<'_, SharedWorld>
+) -> Result<HashMap<String, i32>, CommandError> {
+    let mut world_lock = world.lock().await;
+    Ok(world_lock.get_player_skills())
+}
+
+/// Set a player quality directly (debug builds only)
+/// Returns the stored value after clamping to the quality's range
+#[tauri::command]
+pub async fn set_player_quality(
+    id: String,
+    value: i32,
+    world: State<'_, SharedWorld>
+) -> Result<i32, CommandError> {
+    if !cfg!(debug_assertions) {
+        return Err("Setting qualities directly is only available in debug builds".to_string().into());
+    }
+    
+    let mut world_lock = world.lock().await;
+    Ok(world_lock.set_player_quality(&id, value)?)
+}
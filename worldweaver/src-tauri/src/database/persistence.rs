@@ -1,6 +1,7 @@
 use rusqlite::{Connection, params};
 use anyhow::{Result, Context};
 use crate::simulation::world::GameWorld;
+use super::queries::{load_world_from, save_world_to};
 
 /// Manages periodic persistence of game world to SQLite
 pub struct PersistenceManager {
@@ -41,16 +42,11 @@ impl PersistenceManager {
     }
     
     /// Save the world state to database
+    #[tracing::instrument(skip_all, fields(tick = world.tick_count))]
     pub async fn save_world(&mut self, world: &GameWorld) -> Result<()> {
         let tx = self.conn.transaction()
             .context("Failed to start transaction")?;
         
-        // Save world metadata
-        tx.execute(
-            "INSERT OR REPLACE INTO world_meta (key, value) VALUES (?, ?)",
-            params!["tick_count", world.tick_count.to_string()]
-        ).context("Failed to save tick count")?;
-        
         // Save event log (append-only for events since last save)
         let new_events = world.get_events_since(self.last_save_tick);
         for event in &new_events {
@@ -68,41 +64,24 @@ impl PersistenceManager {
             ).context("Failed to save event")?;
         }
         
-        // TODO: Save entity snapshots (only changed entities)
-        // This requires tracking dirty entities in GameWorld
-        // For now, we just save the tick count and events
+        // Full snapshot of every entity; the world is small enough that tracking
+        // dirty entities isn't worth it yet
+        let entities = save_world_to(&tx, world)?;
         
         tx.commit().context("Failed to commit transaction")?;
         
         self.last_save_tick = world.tick_count;
         
-        println!("💾 World saved at tick {} ({} events)", world.tick_count, new_events.len());
+        tracing::info!(tick = world.tick_count, events = new_events.len(), entities, "World saved");
         Ok(())
     }
     
     /// Load world state from database
+    #[tracing::instrument(skip_all)]
     pub fn load_world(&self) -> Result<GameWorld> {
-        // Load tick count
-        let tick_count: u64 = self.conn.query_row(
-            "SELECT value FROM world_meta WHERE key = ?",
-            params!["tick_count"],
-            |row| {
-                let value: String = row.get(0)?;
-                Ok(value)
-            }
-        ).unwrap_or_else(|_| "0".to_string())
-        .parse()
-        .unwrap_or(0);
+        let world = load_world_from(&self.conn)?;
         
-        // Create new world with loaded tick count
-        let mut world = GameWorld::new();
-        world.tick_count = tick_count;
-        
-        // TODO: Load entities from database and spawn them in ECS
-        // TODO: Replay events since last snapshot to reconstruct state
-        // For MVP, we start with the default starter world
-        
-        println!("📂 World loaded from database (tick: {})", tick_count);
+        tracing::info!(tick = world.tick_count, "World loaded from database");
         Ok(world)
     }
     
@@ -112,6 +91,7 @@ impl PersistenceManager {
     }
     
     /// Compact old events (keep only recent N ticks)
+    #[tracing::instrument(skip_all)]
     pub fn compact_events(&self, keep_ticks: u64) -> Result<usize> {
         let current_tick: u64 = self.conn.query_row(
             "SELECT value FROM world_meta WHERE key = ?",
@@ -131,7 +111,7 @@ impl PersistenceManager {
             params![cutoff_tick as i64]
         ).context("Failed to compact events")?;
         
-        println!("🗑️  Compacted {} old events (kept last {} ticks)", deleted, keep_ticks);
+        tracing::info!(deleted, keep_ticks, "Compacted old events");
         Ok(deleted)
     }
     
@@ -233,6 +213,19 @@ mod tests {
         assert_eq!(loaded_world.tick_count, world.tick_count);
     }
     
+    #[tokio::test]
+    async fn test_player_room_survives_reload() {
+        let mut manager = setup_test_db();
+        let mut world = GameWorld::new();
+        let square = world.move_player("north").unwrap();
+        
+        manager.save_world(&world).await.unwrap();
+        let mut loaded_world = manager.load_world().unwrap();
+        
+        assert_eq!(loaded_world.get_player_room(), Some(square));
+        assert!(manager.get_stats().unwrap().entity_count > 0);
+    }
+    
     #[test]
     fn test_database_stats() {
         let manager = setup_test_db();
@@ -1,9 +1,116 @@
-use rusqlite::{Connection, params};
+use rusqlite::{Connection, OptionalExtension, params};
 use anyhow::{Result, Context};
 use serde_json;
 use uuid::Uuid;
 
 use crate::simulation::world::{GameWorld, RoomDetails, NpcInfo};
+use crate::simulation::events::EventRecord;
+use crate::simulation::snapshot::{EntitySnapshot, ResourceSnapshot};
+
+/// Write the world's tick count, entities and resources; returns how many entities were saved
+/// Callers wrap this in a transaction alongside anything else they persist
+pub(crate) fn save_world_to(conn: &Connection, world: &GameWorld) -> Result<usize> {
+    conn.execute(
+        "INSERT OR REPLACE INTO world_meta (key, value) VALUES (?1, ?2)",
+        params!["tick_count", world.tick_count.to_string()]
+    ).context("Failed to save tick count")?;
+    
+    let entities = save_entities(conn, world)?;
+    save_resources(conn, world)?;
+    Ok(entities)
+}
+
+/// Rebuild the world saved by `save_world_to`
+/// Databases without saved entities start from the starter world
+pub(crate) fn load_world_from(conn: &Connection) -> Result<GameWorld> {
+    let tick_count = load_tick_count(conn);
+    
+    let snapshots = load_entities(conn)?;
+    if snapshots.is_empty() {
+        let mut world = GameWorld::new();
+        world.tick_count = tick_count;
+        return Ok(world);
+    }
+    Ok(GameWorld::from_snapshots(tick_count, snapshots, load_resources(conn)?))
+}
+
+/// Saved tick count; 0 when none has been stored
+fn load_tick_count(conn: &Connection) -> u64 {
+    conn.query_row(
+        "SELECT value FROM world_meta WHERE key = ?1",
+        params!["tick_count"],
+        |row| row.get::<_, String>(0)
+    ).ok()
+    .and_then(|tick| tick.parse().ok())
+    .unwrap_or(0)
+}
+
+/// Replace the `entities` table with a snapshot of every serializable ECS entity
+fn save_entities(conn: &Connection, world: &GameWorld) -> Result<usize> {
+    let now = chrono::Utc::now().timestamp();
+    conn.execute("DELETE FROM entities", [])
+        .context("Failed to clear entities")?;
+    
+    let snapshots = world.snapshot_entities();
+    for snapshot in &snapshots {
+        let data = serde_json::to_vec(snapshot)
+            .context("Failed to serialize entity")?;
+        conn.execute(
+            "INSERT OR REPLACE INTO entities (id, entity_type, data, created_at, modified_at)
+             VALUES (?1, ?2, ?3, ?4, ?4)",
+            params![
+                snapshot.id().unwrap_or_else(Uuid::new_v4).to_string(),
+                snapshot.entity_type(),
+                data,
+                now
+            ]
+        ).context("Failed to save entity")?;
+    }
+    
+    Ok(snapshots.len())
+}
+
+/// Read back every saved entity snapshot; rows in other formats are skipped
+fn load_entities(conn: &Connection) -> Result<Vec<EntitySnapshot>> {
+    let mut stmt = conn.prepare("SELECT data FROM entities")?;
+    let rows = stmt.query_map([], |row| row.get::<_, Vec<u8>>(0))?;
+    
+    let mut snapshots = Vec::new();
+    for data in rows {
+        match serde_json::from_slice::<EntitySnapshot>(&data?) {
+            Ok(snapshot) if snapshot.is_identified() => snapshots.push(snapshot),
+            _ => tracing::warn!("Skipping entity row that isn't a snapshot"),
+        }
+    }
+    
+    Ok(snapshots)
+}
+
+/// Store the world's clock, weather and qualities under the `resources` key of `world_meta`
+fn save_resources(conn: &Connection, world: &GameWorld) -> Result<()> {
+    let data = serde_json::to_string(&world.snapshot_resources())
+        .context("Failed to serialize world resources")?;
+    conn.execute(
+        "INSERT OR REPLACE INTO world_meta (key, value) VALUES (?1, ?2)",
+        params!["resources", data]
+    ).context("Failed to save world resources")?;
+    
+    Ok(())
+}
+
+/// Read back the saved world resources; worlds saved without them get the defaults
+fn load_resources(conn: &Connection) -> Result<ResourceSnapshot> {
+    let data: Option<String> = conn.query_row(
+        "SELECT value FROM world_meta WHERE key = ?1",
+        params!["resources"],
+        |row| row.get(0)
+    ).optional().context("Failed to load world resources")?;
+    
+    match data {
+        Some(data) => serde_json::from_str(&data).context("Failed to parse world resources"),
+        None => Ok(ResourceSnapshot::default()),
+    }
+}
 
 /// Database queries for world serialization and deserialization
 pub struct WorldQueries {
@@ -69,46 +176,29 @@ impl WorldQueries {
 
     /// Load world tick count
     pub fn load_tick_count(&self) -> Result<u64> {
-        let tick_str: String = self.conn.query_row(
-            "SELECT value FROM world_meta WHERE key = ?1",
-            params!["tick_count"],
-            |row| row.get(0)
-        ).unwrap_or_else(|_| "0".to_string());
-        
-        Ok(tick_str.parse().unwrap_or(0))
+        Ok(load_tick_count(&self.conn))
     }
 
     /// Save entire world state
+    #[tracing::instrument(skip_all, fields(tick = world.tick_count))]
     pub fn save_world(&mut self, world: &GameWorld) -> Result<()> {
         let tx = self.conn.transaction()
             .context("Failed to start transaction")?;
         
-        // Save tick count
-        tx.execute(
-            "INSERT OR REPLACE INTO world_meta (key, value) VALUES (?1, ?2)",
-            params!["tick_count", world.tick_count.to_string()]
-        ).context("Failed to save tick count in transaction")?;
-        
-        // TODO: Iterate through ECS entities and save them
-        // For MVP, we have limited entities, so this is a placeholder
+        let entities = save_world_to(&tx, world)?;
         
         tx.commit().context("Failed to commit transaction")?;
         
-        println!("💾 World saved to database (tick: {})", world.tick_count);
+        tracing::info!(tick = world.tick_count, entities, "World saved to database");
         Ok(())
     }
 
     /// Load world state from database
+    #[tracing::instrument(skip_all)]
     pub fn load_world(&self) -> Result<GameWorld> {
-        let mut world = GameWorld::new();
-        
-        // Load tick count
-        world.tick_count = self.load_tick_count()?;
-        
-        // TODO: Load entities from database and spawn them in ECS
-        // For MVP, we start with the default starter world
+        let world = load_world_from(&self.conn)?;
         
-        println!("📂 World loaded from database (tick: {})", world.tick_count);
+        tracing::info!(tick = world.tick_count, "World loaded from database");
         Ok(world)
     }
 
@@ -153,6 +243,32 @@ impl WorldQueries {
         
         Ok(result)
     }
+
+    /// Get persisted events that took place in a room, most recent first
+    /// Reads the full log so room history survives restarts and event compaction
+    pub fn get_events_in_room(&self, room_id: Uuid, limit: usize) -> Result<Vec<EventRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT data FROM event_log ORDER BY tick DESC, id DESC"
+        )?;
+        
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        
+        let mut result = Vec::new();
+        for data in rows {
+            // Skip free-form entries that aren't serialized event records
+            let Ok(record) = serde_json::from_str::<EventRecord>(&data?) else {
+                continue;
+            };
+            if record.event.involves_room(room_id) {
+                result.push(record);
+                if result.len() >= limit {
+                    break;
+                }
+            }
+        }
+        
+        Ok(result)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -167,6 +283,8 @@ mod tests {
     use super::*;
     use rusqlite::Connection;
     use crate::database::schema::CREATE_TABLES;
+    use crate::simulation::components::{Health, IsNpc, Name, NpcId, Stats};
+    use crate::simulation::systems::Weather;
 
     fn setup_test_db() -> WorldQueries {
         let conn = Connection::open_in_memory().unwrap();
@@ -184,6 +302,57 @@ mod tests {
         assert_eq!(loaded, 42);
     }
 
+    #[test]
+    fn test_world_round_trip_keeps_player_room() {
+        let mut queries = setup_test_db();
+        let mut world = GameWorld::new();
+        let square = world.move_player("north").unwrap();
+        world.simulate_days(1);
+        world.ecs_world.insert_resource(Weather::Storm);
+        world.set_player_quality("gold", 140).unwrap();
+        
+        queries.save_world(&world).unwrap();
+        let mut loaded = queries.load_world().unwrap();
+        
+        assert_eq!(loaded.get_player_room(), Some(square));
+        assert_eq!(loaded.get_world_time().day, world.get_world_time().day);
+        assert_eq!(loaded.get_world_time().hour, world.get_world_time().hour);
+        assert_eq!(*loaded.ecs_world.resource::<Weather>(), Weather::Storm);
+        let gold = loaded.get_player_qualities().into_iter().find(|q| q.id == "gold").unwrap();
+        assert_eq!(gold.value, 140);
+        assert_eq!(loaded.get_movement_history(), world.get_movement_history());
+        assert_eq!(loaded.room_registry, world.room_registry);
+        let inn = world.get_movement_history()[0];
+        assert_eq!(loaded.get_npcs_in_room(inn)[0].name, "Gareth the Innkeeper");
+        assert_eq!(loaded.move_player("south").unwrap(), inn);
+    }
+
+    #[test]
+    fn test_world_round_trip_keeps_combat_stats_and_wounds() {
+        let mut queries = setup_test_db();
+        let mut world = GameWorld::new();
+        let guard = Uuid::new_v4();
+        let mut health = Health::new(20);
+        health.damage(7);
+        world.ecs_world.spawn((
+            Name("Wounded Guard".to_string()),
+            Stats { strength: 15, ..Stats::default() },
+            health,
+            NpcId(guard),
+            IsNpc,
+        ));
+        
+        queries.save_world(&world).unwrap();
+        let mut loaded = queries.load_world().unwrap();
+        
+        let mut npcs = loaded.ecs_world.query::<(&NpcId, &Stats, &Health)>();
+        let (_, stats, health) = npcs.iter(&loaded.ecs_world)
+            .find(|(id, _, _)| id.0 == guard)
+            .expect("guard should keep its stats and health");
+        assert_eq!(stats.strength, 15);
+        assert_eq!((health.current, health.max), (13, 20));
+    }
+
     #[test]
     fn test_log_event() {
         let queries = setup_test_db();
@@ -199,4 +368,29 @@ mod tests {
         assert_eq!(events.len(), 1);
         assert_eq!(events[0].tick, 100);
     }
+
+    #[test]
+    fn test_get_events_in_room_from_persisted_log() {
+        let queries = setup_test_db();
+        let mut world = GameWorld::new();
+        let inn = world.get_player_room().unwrap();
+        let square = world.move_player("north").unwrap();
+        world.move_player("south").unwrap();
+        
+        for event in world.get_events_since(0) {
+            let data = serde_json::to_string(&event).unwrap();
+            queries.log_event(event.tick, event.event.event_type(), None, &data).unwrap();
+        }
+        queries.log_event(5, "player_action", None, "Player entered tavern").unwrap();
+        
+        // Drop the in-memory log; only the database remains
+        drop(world);
+        
+        let square_events = queries.get_events_in_room(square, 10).unwrap();
+        assert_eq!(square_events.len(), 1);
+        
+        let inn_events = queries.get_events_in_room(inn, 10).unwrap();
+        assert_eq!(inn_events.len(), 1);
+        assert!(inn_events[0].event.involves_room(inn));
+    }
 }
@@ -4,3 +4,4 @@ pub mod state;
 pub mod database;
 pub mod mcp_server;
 pub mod terrain;
+pub mod logging;
@@ -0,0 +1,53 @@
+use std::sync::OnceLock;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, reload, Registry};
+
+/// Handle for swapping the global level filter after startup
+static LEVEL_HANDLE: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+
+/// Install the global tracing subscriber (stdout, filtered by level)
+/// Calling this more than once is a no-op
+pub fn init(default_level: LevelFilter) {
+    if LEVEL_HANDLE.get().is_some() {
+        return;
+    }
+
+    let (filter, handle) = reload::Layer::new(default_level);
+    let installed = tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer())
+        .try_init()
+        .is_ok();
+
+    if installed {
+        let _ = LEVEL_HANDLE.set(handle);
+    }
+}
+
+/// Parse a level name ("trace", "debug", "info", "warn", "error", "off")
+pub fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    level.trim().parse::<LevelFilter>()
+        .map_err(|_| format!("Unknown log level: {}", level))
+}
+
+/// Change the global log level at runtime
+pub fn set_log_level(level: &str) -> Result<(), String> {
+    let filter = parse_level(level)?;
+    let handle = LEVEL_HANDLE.get()
+        .ok_or_else(|| "Logging has not been initialized".to_string())?;
+
+    handle.reload(filter).map_err(|e| format!("Failed to set log level: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!(parse_level("debug").unwrap(), LevelFilter::DEBUG);
+        assert_eq!(parse_level(" WARN ").unwrap(), LevelFilter::WARN);
+        assert!(parse_level("chatty").is_err());
+    }
+}
@@ -6,40 +6,110 @@ mod commands;
 mod state;
 mod database;
 mod terrain;
+mod logging;
 
 use simulation::world::create_shared_world;
 use tokio::sync::Mutex;
 use terrain::TerrainData;
+use state::WorldSlots;
 
 fn main() {
+    logging::init(tracing_subscriber::filter::LevelFilter::INFO);
+    
     // Initialize the game world with starter content
     let world = create_shared_world();
     
     // Initialize terrain data
     let terrain = Mutex::new(TerrainData::default());
     
-    println!("🌍 WorldWeaver starting...");
+    tracing::info!("WorldWeaver starting");
     
     tauri::Builder::default()
         .manage(world)
         .manage(terrain)
+        .manage(Mutex::new(WorldSlots::new()))
         .invoke_handler(tauri::generate_handler![
             commands::get_current_room,
             commands::get_npcs_in_current_room,
             commands::move_player,
+            commands::move_player_to,
+            commands::go_back,
             commands::send_player_action,
             commands::get_world_tick,
+            commands::get_world_time,
+            commands::get_explored_rooms,
+            commands::set_player_room,
+            commands::simulate_world_progress,
+            commands::update_room,
+            commands::create_room,
+            commands::connect_rooms,
+            commands::update_npc,
+            commands::spawn_npc,
+            commands::despawn_npc,
+            commands::validate_world,
+            commands::spawn_party_member,
+            commands::get_party,
+            commands::get_shop_prices,
+            commands::set_log_level,
+            commands::travel_time,
+            commands::get_items_in_current_room,
+            commands::get_inventory,
+            commands::pick_up_item,
+            commands::drop_item,
+            commands::create_world,
+            commands::switch_world,
+            commands::list_worlds,
+            commands::get_player_qualities,
+            commands::get_player_skills,
+            commands::set_player_quality,
             terrain::commands::generate_terrain,
             terrain::commands::get_chunk,
+            terrain::commands::regenerate_chunk,
+            terrain::commands::get_chunk_normals,
+            terrain::commands::get_slope_map,
+            terrain::commands::export_heightmap_png,
+            terrain::commands::export_world_heightmap_png,
+            terrain::commands::import_heightmap_png,
+            terrain::commands::export_mesh_obj,
+            terrain::commands::export_features_geojson,
             terrain::commands::apply_brush,
+            terrain::commands::apply_ramp,
+            terrain::commands::undo_brush,
+            terrain::commands::redo_brush,
+            terrain::commands::get_undo_state,
+            terrain::commands::clear_undo_history,
             terrain::commands::get_terrain_config,
             terrain::commands::get_rivers,
             terrain::commands::save_terrain,
             terrain::commands::load_terrain,
+            terrain::commands::load_terrain_region,
+            terrain::commands::list_saved_worlds,
             terrain::commands::apply_weathering,
+            terrain::commands::apply_thermal_erosion,
+            terrain::commands::generate_biomes,
+            terrain::commands::get_biome_blend,
+            terrain::commands::list_biomes,
+            terrain::commands::register_biome,
             terrain::commands::place_water_sources,
             terrain::commands::simulate_hydrology,
             terrain::commands::get_flow_data,
+            terrain::commands::erosion_delta_estimate,
+            terrain::commands::preview_terrain,
+            terrain::commands::regenerate_region,
+            terrain::commands::chunk_bounds,
+            terrain::commands::all_chunk_bounds,
+            terrain::commands::analyze_terrain,
+            terrain::commands::generate_resource_deposits,
+            terrain::commands::find_seed,
+            terrain::commands::mark_feature,
+            terrain::commands::get_feature_map,
+            terrain::commands::generate_caves,
+            terrain::commands::get_cave_mask,
+            terrain::commands::get_watershed_map,
+            terrain::commands::get_watersheds,
+            terrain::commands::get_lakes,
+            terrain::commands::get_waterfalls,
+            terrain::commands::generate_roads,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
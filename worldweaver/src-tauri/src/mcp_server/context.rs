@@ -1,24 +1,67 @@
 /// Context assembly for Claude LLM
 /// Prepares rich, structured context from ECS world state for narrative generation
 
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
 use anyhow::Result;
 
-use crate::simulation::world::{GameWorld, RoomDetails, NpcInfo};
+use crate::simulation::world::{GameWorld, RoomDetails, NpcInfo, npc_mood};
 use crate::simulation::events::EventRecord;
 use crate::simulation::components::RelationshipData;
+use crate::simulation::systems::Weather;
 
 /// Assembles context from game world for LLM consumption
 pub struct ContextAssembler {
     simulation: Arc<Mutex<GameWorld>>,
+    narrative_cache: Mutex<HashMap<Uuid, CachedNarrative>>,
+}
+
+/// Last narrative generated for a room and the context it was generated from
+struct CachedNarrative {
+    context_hash: u64,
+    text: String,
 }
 
 impl ContextAssembler {
     pub fn new(simulation: Arc<Mutex<GameWorld>>) -> Self {
-        Self { simulation }
+        Self {
+            simulation,
+            narrative_cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Get a room's narrative description, reusing the cached text while the
+    /// room context (room, NPCs, time bucket, weather) is unchanged
+    pub async fn get_or_build_room_narrative(&self, room_id: Uuid) -> Result<String> {
+        let context = self.build_room_context(room_id).await?;
+        let context_hash = context.context_hash();
+
+        let mut cache = self.narrative_cache.lock().await;
+        if let Some(cached) = cache.get(&room_id) {
+            if cached.context_hash == context_hash {
+                return Ok(cached.text.clone());
+            }
+        }
+
+        let text = self.build_room_narrative(&context);
+        cache.insert(room_id, CachedNarrative { context_hash, text: text.clone() });
+        Ok(text)
+    }
+
+    /// Generate narrative text from a room context
+    /// Template-based until LLM generation is wired in; this is the expensive call the cache avoids
+    fn build_room_narrative(&self, context: &RoomContext) -> String {
+        format!(
+            "{}\n\n{} It is {}, with {}.",
+            context.room_details.description,
+            context.ambient_conditions,
+            context.time_of_day,
+            context.weather,
+        )
     }
 
     /// Build comprehensive context for room description generation
@@ -32,11 +75,16 @@ impl ContextAssembler {
         
         let ambient = self.calculate_ambient_conditions(&room, &npcs);
         
+        let time_of_day = time_description(&sim);
+        let weather = sim.ecs_world.get_resource::<Weather>()
+            .map(|weather| format!("{} skies", weather.name()))
+            .unwrap_or_else(|| "clear skies".to_string());
+        
         Ok(RoomContext {
             room_details: room.clone(),
             npcs_present: npcs,
-            time_of_day: self.get_time_description(),
-            weather: "clear skies".to_string(), // Placeholder
+            time_of_day,
+            weather,
             recent_events: vec![], // TODO: Query from event log
             ambient_conditions: ambient,
         })
@@ -55,16 +103,19 @@ impl ContextAssembler {
             .ok_or_else(|| anyhow::anyhow!("Cannot find player location"))?;
 
         let npcs = sim.get_npcs_in_room(room_id);
-        let npc = npcs.iter()
+        let mut npc = npcs.iter()
             .find(|n| n.name.to_lowercase().contains(&npc_name.to_lowercase()))
             .ok_or_else(|| anyhow::anyhow!("NPC not found in current room"))?
             .clone();
+        if let Some(greeting) = sim.npc_greeting(npc.id) {
+            npc.greeting = greeting;
+        }
 
         // Get relevant world events (filtered by tags)
         let relevant_events = sim.query_events_by_tag("player", 20);
         
         // Calculate mood based on recent events and personality
-        let mood = self.calculate_npc_mood(&npc, &relevant_events);
+        let mood = npc_mood(&npc, &relevant_events);
         
         // Summarize events for context
         let event_summaries = self.summarize_events(&relevant_events);
@@ -76,7 +127,8 @@ impl ContextAssembler {
             last_interaction_tick: sim.tick_count,
         };
         
-        // Get room context
+        // Get room context (it takes the simulation lock itself)
+        drop(sim);
         let room_context = self.build_room_context(room_id).await?;
 
         Ok(DialogueContext {
@@ -93,9 +145,8 @@ impl ContextAssembler {
     }
 
     /// Get time of day description
-    fn get_time_description(&self) -> String {
-        // TODO: Get from WorldClock resource
-        "midday".to_string()
+    async fn get_time_description(&self) -> String {
+        time_description(&*self.simulation.lock().await)
     }
 
     /// Calculate ambient conditions based on room and NPCs
@@ -107,50 +158,6 @@ impl ContextAssembler {
         }
     }
 
-    /// Calculate NPC mood based on recent events and personality
-    fn calculate_npc_mood(&self, npc: &NpcInfo, events: &[EventRecord]) -> String {
-        // Mood calculation based on:
-        // - Base personality traits
-        // - Recent events affecting the NPC
-        // - Current time of day (future enhancement)
-        // - NPC needs/stress (future: Dwarf Fortress style)
-        
-        let mut mood_score = 0;
-        
-        // Base mood from personality
-        let personality_lower = npc.personality.to_lowercase();
-        if personality_lower.contains("friendly") || personality_lower.contains("welcoming") {
-            mood_score += 20;
-        } else if personality_lower.contains("grumpy") || personality_lower.contains("hostile") {
-            mood_score -= 20;
-        }
-        
-        // Adjust based on recent events
-        for event in events.iter().take(5) {
-            // Check if event involves this NPC (by name matching in tags)
-            if event.tags.iter().any(|tag| tag.to_lowercase().contains(&npc.name.to_lowercase())) {
-                // Positive events
-                if event.tags.contains(&"dialogue".to_string()) {
-                    mood_score += 5; // Conversation improves mood
-                }
-                // Negative events would decrease mood_score
-            }
-        }
-        
-        // Convert score to descriptive mood
-        if mood_score > 30 {
-            "cheerful and welcoming"
-        } else if mood_score > 10 {
-            "friendly and approachable"
-        } else if mood_score > -10 {
-            "neutral and professional"
-        } else if mood_score > -30 {
-            "reserved and cautious"
-        } else {
-            "hostile and suspicious"
-        }.to_string()
-    }
-
     /// Get NPC's current activity based on room context
     fn get_npc_activity(&self, room_context: &RoomContext) -> String {
         // Derive activity from room name for now
@@ -179,11 +186,17 @@ impl ContextAssembler {
             event_type: event_type.to_string(),
             world_state: "stable".to_string(), // TODO: Calculate from simulation
             affected_factions: vec![],
-            time_of_occurrence: self.get_time_description(),
+            time_of_occurrence: self.get_time_description().await,
         })
     }
 }
 
+/// Part of the day from the world clock, using the same buckets as NPC greetings
+/// so narration and dialogue agree on the hour
+fn time_description(sim: &GameWorld) -> String {
+    sim.time_of_day().name().to_string()
+}
+
 /// Rich context for room description generation
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RoomContext {
@@ -195,6 +208,28 @@ pub struct RoomContext {
     pub ambient_conditions: String,
 }
 
+impl RoomContext {
+    /// Hash of the parts of the context a room narrative depends on
+    /// Recent events and ambient text are derived or transient, so they're excluded
+    pub fn context_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.room_details.id.hash(&mut hasher);
+        self.room_details.name.hash(&mut hasher);
+        self.room_details.description.hash(&mut hasher);
+        for exit in &self.room_details.exits {
+            exit.direction.hash(&mut hasher);
+            exit.target_room_id.hash(&mut hasher);
+        }
+        for npc in &self.npcs_present {
+            npc.id.hash(&mut hasher);
+            npc.name.hash(&mut hasher);
+        }
+        self.time_of_day.hash(&mut hasher);
+        self.weather.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
 /// Context for NPC dialogue generation
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DialogueContext {
@@ -255,6 +290,7 @@ pub struct EventContext {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::simulation::systems::WorldClock;
 
     #[tokio::test]
     async fn test_context_assembler_creation() {
@@ -271,6 +307,7 @@ mod tests {
         let assembler = ContextAssembler::new(world);
         
         let room = RoomDetails {
+            id: Uuid::new_v4(),
             name: "Test Room".to_string(),
             description: "A test".to_string(),
             exits: vec![],
@@ -279,4 +316,39 @@ mod tests {
         let conditions = assembler.calculate_ambient_conditions(&room, &[]);
         assert!(conditions.contains("quiet"));
     }
+
+    #[tokio::test]
+    async fn test_room_narrative_is_cached_until_context_changes() {
+        let world = Arc::new(Mutex::new(GameWorld::new()));
+        let inn = world.lock().await.get_player_room().unwrap();
+        let assembler = ContextAssembler::new(world.clone());
+        
+        let first = assembler.get_or_build_room_narrative(inn).await.unwrap();
+        let second = assembler.get_or_build_room_narrative(inn).await.unwrap();
+        assert_eq!(first, second);
+        assert_eq!(assembler.narrative_cache.lock().await.len(), 1);
+        
+        world.lock().await.set_weather(Weather::Storm);
+        let stormy = assembler.get_or_build_room_narrative(inn).await.unwrap();
+        assert_ne!(first, stormy);
+        
+        world.lock().await.ecs_world.resource_mut::<WorldClock>().current_time.hour = 14;
+        let stormy_afternoon = assembler.get_or_build_room_narrative(inn).await.unwrap();
+        assert_ne!(stormy, stormy_afternoon);
+        assert_eq!(assembler.get_or_build_room_narrative(inn).await.unwrap(), stormy_afternoon);
+    }
+
+    #[tokio::test]
+    async fn test_dialogue_context_uses_world_clock() {
+        let world = Arc::new(Mutex::new(GameWorld::new()));
+        world.lock().await.ecs_world.resource_mut::<WorldClock>().current_time.hour = 2;
+        let assembler = ContextAssembler::new(world.clone());
+        
+        let context = assembler.build_dialogue_context("gareth", Uuid::nil()).await.unwrap();
+        assert_eq!(context.room_context.time_of_day, "night");
+        assert!(context.npc.greeting.starts_with("You're about late tonight."));
+        
+        let event = assembler.build_event_context("storm").await.unwrap();
+        assert_eq!(event.time_of_occurrence, "night");
+    }
 }
@@ -38,13 +38,14 @@ impl WorldWeaverMCP {
             .ok_or_else(|| anyhow::anyhow!("Room not found"))?;
         
         let npcs = sim.get_npcs_in_room(room_uuid);
+        let time = sim.get_world_time();
         
         Ok(RoomState {
             room_name: room.name,
             room_description: room.description,
             npcs_present: npcs.iter().map(|npc| npc.name.clone()).collect(),
             exits: room.exits.iter().map(|e| e.direction.clone()).collect(),
-            current_time: format!("Day {}, Hour {}", 1, 12), // Placeholder
+            current_time: format!("Day {}, Hour {}", time.day, time.hour),
         })
     }
 
@@ -61,13 +62,20 @@ impl WorldWeaverMCP {
             .find(|n| n.name.to_lowercase().contains(&npc_id.to_lowercase()))
             .ok_or_else(|| anyhow::anyhow!("NPC not found"))?;
         
+        let recent_conversations = sim.get_recent_conversations(npc.id, 5)
+            .into_iter()
+            .map(|c| c.summary)
+            .collect();
+        let greeting = sim.npc_greeting(npc.id).unwrap_or_else(|| npc.greeting.clone());
+        
         Ok(NPCContext {
             name: npc.name.clone(),
             personality: npc.personality.clone(),
-            greeting: npc.greeting.clone(),
+            greeting,
+            time_of_day: sim.time_of_day().name().to_string(),
             current_activity: "tending the bar".to_string(), // Placeholder
             mood: "friendly".to_string(), // Placeholder
-            recent_conversations: vec![], // Placeholder
+            recent_conversations,
         })
     }
 
@@ -77,9 +85,15 @@ impl WorldWeaverMCP {
         npc_id: String,
         player_name: String,
         summary: String,
+        topics: Vec<String>,
     ) -> Result<String> {
-        // TODO: Implement NPC memory storage
-        println!("📝 Recording conversation: {} with {}: {}", player_name, npc_id, summary);
+        let mut sim = self.simulation.lock().await;
+        
+        let npc_uuid = server::resolve_npc(&mut sim, &npc_id)?;
+        tracing::info!(%player_name, %npc_id, %summary, "Recording conversation");
+        sim.record_conversation(npc_uuid, summary, topics)
+            .map_err(|e| anyhow::anyhow!(e))?;
+        
         Ok("Conversation recorded".to_string())
     }
 
@@ -108,7 +122,8 @@ pub struct RoomState {
 pub struct NPCContext {
     pub name: String,
     pub personality: String,
-    pub greeting: String,
+    pub greeting: String,     // Already adjusted for the time of day
+    pub time_of_day: String,  // "dawn", "morning", "afternoon", "dusk" or "night"
     pub current_activity: String,
     pub mood: String,
     pub recent_conversations: Vec<String>,
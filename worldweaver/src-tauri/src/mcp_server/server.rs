@@ -6,15 +6,40 @@ use tokio::sync::Mutex;
 use uuid::Uuid;
 use anyhow::Result;
 use crate::simulation::world::GameWorld;
+use crate::terrain::TerrainData;
+
+/// Rivers within this many cells of a room count as nearby
+const NEARBY_RIVER_CELLS: f32 = 10.0;
+
+/// Resolve an NPC from its id, or by name among the NPCs in the player's room
+pub(crate) fn resolve_npc(world: &mut GameWorld, npc: &str) -> Result<Uuid> {
+    if let Ok(uuid) = Uuid::parse_str(npc) {
+        return Ok(uuid);
+    }
+    
+    let player_room = world.get_player_room()
+        .ok_or_else(|| anyhow::anyhow!("Player has no position"))?;
+    world.get_npcs_in_room(player_room).iter()
+        .find(|n| n.name.to_lowercase().contains(&npc.to_lowercase()))
+        .map(|n| n.id)
+        .ok_or_else(|| anyhow::anyhow!("NPC not found in current room"))
+}
 
 /// MCP server for WorldWeaver (placeholder for rmcp integration)
 pub struct WorldWeaverMCP {
     world: Arc<Mutex<GameWorld>>,
+    terrain: Option<Arc<Mutex<TerrainData>>>,
 }
 
 impl WorldWeaverMCP {
     pub fn new(world: Arc<Mutex<GameWorld>>) -> Self {
-        Self { world }
+        Self { world, terrain: None }
+    }
+    
+    /// Give the server access to terrain so it can describe geography
+    pub fn with_terrain(mut self, terrain: Arc<Mutex<TerrainData>>) -> Self {
+        self.terrain = Some(terrain);
+        self
     }
     
     /// Get current state of a room including NPCs, time, and recent events
@@ -57,18 +82,25 @@ impl WorldWeaverMCP {
         let npc = npcs.iter()
             .find(|n| n.name.to_lowercase().contains(&npc_id.to_lowercase()))
             .ok_or_else(|| anyhow::anyhow!("NPC not found in current room"))?;
+        let recent_conversations = world.get_recent_conversations(npc.id, 5)
+            .into_iter()
+            .map(|c| c.summary)
+            .collect();
+        let greeting = world.npc_greeting(npc.id).unwrap_or_else(|| npc.greeting.clone());
         
         Ok(NPCContext {
             name: npc.name.clone(),
             personality: npc.personality.clone(),
-            greeting: npc.greeting.clone(),
+            greeting,
+            time_of_day: world.time_of_day().name().to_string(),
             current_activity: "present in room".to_string(),
             mood: "neutral".to_string(),
-            recent_conversations: vec![],
+            recent_conversations,
         })
     }
     
     /// Record a conversation summary in NPC memory
+    /// `npc_name` may also be the NPC's id
     pub async fn record_conversation(
         &self,
         npc_name: String,
@@ -76,11 +108,12 @@ impl WorldWeaverMCP {
         summary: String,
         topics: Vec<String>
     ) -> Result<String> {
-        let _world = self.world.lock().await;
+        let mut world = self.world.lock().await;
         
-        // TODO: Implement actual memory storage when DialogueMemory is integrated
-        println!("📝 Recording conversation: {} with {}: {}", player_name, npc_name, summary);
-        println!("   Topics: {:?}", topics);
+        let npc_id = resolve_npc(&mut world, &npc_name)?;
+        tracing::info!(%player_name, %npc_name, ?topics, %summary, "Recording conversation");
+        world.record_conversation(npc_id, summary, topics)
+            .map_err(|e| anyhow::anyhow!(e))?;
         
         Ok("Conversation recorded".to_string())
     }
@@ -116,6 +149,88 @@ impl WorldWeaverMCP {
         Ok(summaries)
     }
     
+    /// Describe the terrain under a room using its RoomTerrainBinding
+    /// Gives the LLM real elevation, biome, river, and slope facts to narrate from
+    pub async fn describe_surroundings(&self, room_id: String) -> Result<Surroundings> {
+        let uuid = Uuid::parse_str(&room_id)?;
+        
+        let binding = {
+            let mut world = self.world.lock().await;
+            world.get_room_terrain_binding(uuid)
+                .ok_or_else(|| anyhow::anyhow!("Room is not bound to terrain"))?
+        };
+        
+        let terrain = self.terrain.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Terrain not available"))?;
+        let terrain = terrain.lock().await;
+        let config = &terrain.config;
+        
+        // Elevation above sea level in meters
+        let height = terrain.sample_height(binding.world_x, binding.world_z)
+            .unwrap_or(binding.elevation);
+        let elevation_meters = (height - config.sea_level) / (1.0 - config.sea_level) * config.max_elevation;
+        let elevation_description = if elevation_meters < 0.0 {
+            "below sea level"
+        } else if elevation_meters < 200.0 {
+            "low-lying"
+        } else if elevation_meters < 800.0 {
+            "hilly"
+        } else if elevation_meters < 2000.0 {
+            "highland"
+        } else {
+            "mountainous"
+        }.to_string();
+        
+        // Slope from central differences one cell either side
+        let cell = config.cell_size_meters;
+        let sample = |dx: f32, dz: f32| {
+            terrain.sample_height(binding.world_x + dx, binding.world_z + dz).unwrap_or(height)
+        };
+        let gradient_x = (sample(cell, 0.0) - sample(-cell, 0.0)) * config.max_elevation / (2.0 * cell);
+        let gradient_z = (sample(0.0, cell) - sample(0.0, -cell)) * config.max_elevation / (2.0 * cell);
+        let slope_degrees = (gradient_x * gradient_x + gradient_z * gradient_z).sqrt().atan().to_degrees();
+        let slope_description = if slope_degrees < 2.0 {
+            "flat"
+        } else if slope_degrees < 10.0 {
+            "gently sloping"
+        } else if slope_degrees < 25.0 {
+            "steep"
+        } else {
+            "sheer"
+        }.to_string();
+        
+        let biome = match binding.biome.as_deref() {
+            Some(name) => terrain.biome_definitions.get_name_by_key(name, config.theme),
+            None => "unknown".to_string(),
+        };
+        
+        // River paths are stored in grid cells
+        let cell_x = binding.world_x / cell;
+        let cell_z = binding.world_z / cell;
+        let mut nearby_rivers: Vec<NearbyRiver> = terrain.river_network.segments.iter()
+            .filter_map(|segment| {
+                let distance = segment.path.iter()
+                    .map(|(x, z)| ((x - cell_x).powi(2) + (z - cell_z).powi(2)).sqrt())
+                    .fold(f32::MAX, f32::min);
+                (distance <= NEARBY_RIVER_CELLS).then_some(NearbyRiver {
+                    distance_meters: distance * cell,
+                    width_meters: segment.width_meters,
+                })
+            })
+            .collect();
+        nearby_rivers.sort_by(|a, b| a.distance_meters.total_cmp(&b.distance_meters));
+        
+        Ok(Surroundings {
+            room_id,
+            elevation_meters,
+            elevation_description,
+            biome,
+            slope_degrees,
+            slope_description,
+            nearby_rivers,
+        })
+    }
+    
     /// Get the current world tick count and time
     pub async fn get_world_time(&self) -> Result<WorldTime> {
         let world = self.world.lock().await;
@@ -144,7 +259,8 @@ pub struct RoomState {
 pub struct NPCContext {
     pub name: String,
     pub personality: String,
-    pub greeting: String,
+    pub greeting: String,     // Already adjusted for the time of day
+    pub time_of_day: String,  // "dawn", "morning", "afternoon", "dusk" or "night"
     pub current_activity: String,
     pub mood: String,
     pub recent_conversations: Vec<String>,
@@ -159,6 +275,25 @@ pub struct EventSummary {
     pub tags: Vec<String>,
 }
 
+/// Terrain surroundings of a room for narrative grounding
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct Surroundings {
+    pub room_id: String,
+    pub elevation_meters: f32,
+    pub elevation_description: String,
+    pub biome: String,
+    pub slope_degrees: f32,
+    pub slope_description: String,
+    pub nearby_rivers: Vec<NearbyRiver>,
+}
+
+/// A river near a room
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct NearbyRiver {
+    pub distance_meters: f32,
+    pub width_meters: f32,
+}
+
 /// World time information
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub struct WorldTime {
@@ -174,7 +309,7 @@ pub async fn start_mcp_server(world: Arc<Mutex<GameWorld>>) -> Result<()> {
     // This requires proper rmcp setup with tool_router macros
     // For now, this is a placeholder structure
     
-    println!("🔌 MCP Server structure initialized (full integration pending)");
+    tracing::info!("MCP server structure initialized (full integration pending)");
     Ok(())
 }
 
@@ -190,4 +325,57 @@ mod tests {
         // Test that we can create the server
         assert!(true);
     }
+
+    #[tokio::test]
+    async fn test_recorded_conversation_appears_in_npc_context() {
+        let mcp = WorldWeaverMCP::new(Arc::new(Mutex::new(GameWorld::new())));
+        
+        mcp.record_conversation(
+            "Gareth".to_string(),
+            "Traveler".to_string(),
+            "Haggled over the price of a room.".to_string(),
+            vec!["lodging".to_string()],
+        ).await.unwrap();
+        
+        let context = mcp.get_npc_context("gareth".to_string()).await.unwrap();
+        assert_eq!(context.recent_conversations, vec!["Haggled over the price of a room."]);
+        assert!(mcp.record_conversation("Nobody".to_string(), String::new(), String::new(), vec![]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_describe_surroundings_for_bound_room() {
+        use crate::simulation::components::RoomTerrainBinding;
+        use crate::terrain::config::{TerrainConfig, WorldTheme};
+        use crate::terrain::heightmap::HeightmapChunk;
+        use crate::terrain::rivers::RiverSegment;
+        
+        let mut game_world = GameWorld::new();
+        let inn = game_world.get_player_room().unwrap();
+        game_world.bind_room_terrain(inn, RoomTerrainBinding {
+            world_x: 6400.0,
+            world_z: 6400.0,
+            chunk_coord: (0, 0),
+            elevation: 0.5,
+            biome: Some("Desert".to_string()),
+        }).unwrap();
+        
+        // A single chunk rising from west to east
+        let mut terrain = TerrainData::empty(TerrainConfig::new(128, 128, 1, WorldTheme::Fantasy));
+        let vertex_count = terrain.config.vertex_count as usize;
+        let heights = (0..vertex_count * vertex_count)
+            .map(|i| 0.3 + (i % vertex_count) as f32 * 0.002)
+            .collect();
+        terrain.chunks.insert((0, 0), HeightmapChunk::from_heights((0, 0), heights));
+        terrain.river_network.add_segment(RiverSegment::new(0, vec![(66.0, 64.0), (70.0, 64.0)], 2, 7.5));
+        
+        let mcp = WorldWeaverMCP::new(Arc::new(Mutex::new(game_world)))
+            .with_terrain(Arc::new(Mutex::new(terrain)));
+        let surroundings = mcp.describe_surroundings(inn.to_string()).await.unwrap();
+        
+        assert!(surroundings.elevation_meters > 0.0);
+        assert_eq!(surroundings.biome, "Scorching Sands");
+        assert!(surroundings.slope_degrees > 0.0);
+        assert_eq!(surroundings.nearby_rivers.len(), 1);
+        assert_eq!(surroundings.nearby_rivers[0].distance_meters, 200.0);
+    }
 }
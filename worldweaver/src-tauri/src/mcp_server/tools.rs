@@ -85,6 +85,27 @@ impl Default for GetWorldEventsTool {
     }
 }
 
+/// Tool: Describe Surroundings
+/// Returns the real geography under a terrain-bound room (elevation, biome, rivers, slope)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DescribeSurroundingsTool {
+    pub name: String,
+    pub description: String,
+    pub parameters: RoomStateParams,
+}
+
+impl Default for DescribeSurroundingsTool {
+    fn default() -> Self {
+        Self {
+            name: "describe_surroundings".to_string(),
+            description: "Get the terrain around a room (elevation, biome, nearby rivers, slope) to ground descriptions in real geography".to_string(),
+            parameters: RoomStateParams {
+                room_id: String::new(),
+            },
+        }
+    }
+}
+
 /// Tool: Record Conversation
 /// Records a conversation summary in NPC memory for future reference
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -99,6 +120,8 @@ pub struct RecordConversationParams {
     pub npc_id: String,
     pub player_name: String,
     pub summary: String,
+    #[serde(default)]
+    pub topics: Vec<String>,
 }
 
 impl Default for RecordConversationTool {
@@ -110,6 +133,7 @@ impl Default for RecordConversationTool {
                 npc_id: String::new(),
                 player_name: String::new(),
                 summary: String::new(),
+                topics: Vec::new(),
             },
         }
     }
@@ -181,6 +205,7 @@ pub enum ToolDefinition {
     GetRoomState(GetRoomStateTool),
     GetNPCContext(GetNPCContextTool),
     GetWorldEvents(GetWorldEventsTool),
+    DescribeSurroundings(DescribeSurroundingsTool),
     RecordConversation(RecordConversationTool),
     QueryFactionRelations(QueryFactionRelationsTool),
     GetEconomyState(GetEconomyStateTool),
@@ -193,6 +218,7 @@ impl ToolRegistry {
                 ToolDefinition::GetRoomState(GetRoomStateTool::default()),
                 ToolDefinition::GetNPCContext(GetNPCContextTool::default()),
                 ToolDefinition::GetWorldEvents(GetWorldEventsTool::default()),
+                ToolDefinition::DescribeSurroundings(DescribeSurroundingsTool::default()),
                 ToolDefinition::RecordConversation(RecordConversationTool::default()),
                 ToolDefinition::QueryFactionRelations(QueryFactionRelationsTool::default()),
                 ToolDefinition::GetEconomyState(GetEconomyStateTool::default()),
@@ -206,6 +232,7 @@ impl ToolRegistry {
                 ToolDefinition::GetRoomState(t) => t.name.clone(),
                 ToolDefinition::GetNPCContext(t) => t.name.clone(),
                 ToolDefinition::GetWorldEvents(t) => t.name.clone(),
+                ToolDefinition::DescribeSurroundings(t) => t.name.clone(),
                 ToolDefinition::RecordConversation(t) => t.name.clone(),
                 ToolDefinition::QueryFactionRelations(t) => t.name.clone(),
                 ToolDefinition::GetEconomyState(t) => t.name.clone(),
@@ -0,0 +1,79 @@
+use rand::Rng;
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+use super::components::{Health, Stats};
+
+/// Fights that nobody has lost by this many rounds end in a stalemate
+pub const MAX_COMBAT_ROUNDS: u32 = 20;
+
+/// Outcome of a fight between two entities
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CombatResult {
+    pub attacker: Uuid,
+    pub defender: Uuid,
+    pub rounds: u32,
+    pub winner: Option<Uuid>,  // None if both were still standing after MAX_COMBAT_ROUNDS
+    pub attacker_damage_dealt: i32,
+    pub defender_damage_dealt: i32,
+    pub attacker_health: i32,
+    pub defender_health: i32,
+}
+
+/// Roll one blow: a d20 plus half the dexterity gap must reach 10 to land,
+/// then strength plus a d6 minus the target's toughness sets the damage (at least 1)
+/// Returns 0 for a miss
+pub fn roll_attack(rng: &mut impl Rng, attacker: &Stats, defender: &Stats) -> i32 {
+    let to_hit = rng.random_range(1..=20) + (attacker.dexterity - defender.dexterity) / 2;
+    if to_hit < 10 {
+        return 0;
+    }
+    (attacker.strength / 3 + rng.random_range(1..=6) - defender.constitution / 5).max(1)
+}
+
+/// Trade blows until one side drops or MAX_COMBAT_ROUNDS pass; the attacker strikes first
+/// Health is updated in place
+pub fn fight(
+    rng: &mut impl Rng,
+    attacker: (Uuid, &Stats, &mut Health),
+    defender: (Uuid, &Stats, &mut Health),
+) -> CombatResult {
+    let (attacker_id, attacker_stats, attacker_health) = attacker;
+    let (defender_id, defender_stats, defender_health) = defender;
+    let mut result = CombatResult {
+        attacker: attacker_id,
+        defender: defender_id,
+        rounds: 0,
+        winner: None,
+        attacker_damage_dealt: 0,
+        defender_damage_dealt: 0,
+        attacker_health: 0,
+        defender_health: 0,
+    };
+
+    while result.rounds < MAX_COMBAT_ROUNDS && attacker_health.is_alive() && defender_health.is_alive() {
+        result.rounds += 1;
+
+        let damage = roll_attack(rng, attacker_stats, defender_stats);
+        defender_health.damage(damage);
+        result.attacker_damage_dealt += damage;
+        if !defender_health.is_alive() {
+            break;
+        }
+
+        let damage = roll_attack(rng, defender_stats, attacker_stats);
+        attacker_health.damage(damage);
+        result.defender_damage_dealt += damage;
+    }
+
+    result.winner = if !defender_health.is_alive() {
+        Some(attacker_id)
+    } else if !attacker_health.is_alive() {
+        Some(defender_id)
+    } else {
+        None
+    };
+    result.attacker_health = attacker_health.current;
+    result.defender_health = defender_health.current;
+    result
+}
@@ -21,8 +21,21 @@ pub struct Position {
 #[derive(Component, Serialize, Deserialize, Clone, Debug)]
 pub struct RoomId(pub Uuid);
 
+/// NPC ID component for identifying NPCs
+#[derive(Component, Serialize, Deserialize, Clone, Debug)]
+pub struct NpcId(pub Uuid);
+
+/// Item ID component for identifying items
+#[derive(Component, Serialize, Deserialize, Clone, Debug)]
+pub struct ItemId(pub Uuid);
+
+/// Character ID for party members beyond the original player, who has none
+/// and is addressed as PLAYER_ENTITY_ID
+#[derive(Component, Serialize, Deserialize, Clone, Debug)]
+pub struct CharacterId(pub Uuid);
+
 /// Player-specific data
-#[derive(Component, Serialize, Deserialize, Debug)]
+#[derive(Component, Serialize, Deserialize, Clone, Debug)]
 pub struct Player {
     pub current_input: String,
     pub movement_history: Vec<Uuid>,
@@ -59,6 +72,9 @@ pub struct IsPlayer;
 #[derive(Component)]
 pub struct IsNpc;
 
+#[derive(Component)]
+pub struct IsItem;
+
 /// Terrain binding for rooms - links room to world map position
 #[derive(Component, Serialize, Deserialize, Clone, Debug)]
 pub struct RoomTerrainBinding {
@@ -215,6 +231,7 @@ impl ScheduleCondition {
 pub enum ScheduleAction {
     StayInRoom { room_id: Uuid },
     MoveToRoom { room_id: Uuid },
+    TravelToRoom { room_id: Uuid },  // Walk one exit per tick along the shortest path
     PerformActivity { activity: String },
 }
 
@@ -409,11 +426,17 @@ impl FactionMembership {
     }
 }
 
+/// Faction ID component for identifying faction entities
+#[derive(Component, Serialize, Deserialize, Clone, Debug)]
+pub struct FactionId(pub Uuid);
+
 /// Faction entity
 #[derive(Component, Serialize, Deserialize, Clone, Debug)]
 pub struct Faction {
     pub name: String,
     pub relations: HashMap<Uuid, i32>,  // faction_id -> relation (-100 to 100)
+    #[serde(default)]
+    pub player_reputation: i32,         // How the faction regards the player (-100 to 100)
 }
 
 impl Faction {
@@ -421,6 +444,7 @@ impl Faction {
         Self {
             name,
             relations: HashMap::new(),
+            player_reputation: 0,
         }
     }
     
@@ -431,4 +455,50 @@ impl Faction {
     pub fn set_relation(&mut self, faction_id: Uuid, value: i32) {
         self.relations.insert(faction_id, value.clamp(-100, 100));
     }
+    
+    pub fn set_player_reputation(&mut self, value: i32) {
+        self.player_reputation = value.clamp(-100, 100);
+    }
+}
+
+/// Stock and current price of one item type in a shop
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ShopStock {
+    pub base_price: i32,
+    pub price: i32,
+    pub stock: u32,
+    pub equilibrium_stock: u32,  // Stock level at which the price settles at base_price
+}
+
+impl ShopStock {
+    /// Price the market pulls toward: scarce goods cost more, gluts less,
+    /// within a quarter to four times the base price
+    pub fn equilibrium_price(&self) -> i32 {
+        let base = self.base_price as f32;
+        let ratio = if self.stock == 0 {
+            4.0
+        } else {
+            (self.equilibrium_stock as f32 / self.stock as f32).clamp(0.25, 4.0)
+        };
+        ((base * ratio).round() as i32).max(1)
+    }
+}
+
+/// Shop run by an NPC, keyed by `Item::item_type`
+/// The shop's id is its keeper's NpcId, which is what `ItemSold` names as buyer or seller
+#[derive(Component, Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Shop {
+    pub goods: HashMap<String, ShopStock>,
+}
+
+impl Shop {
+    /// Start trading in an item type; the opening stock is taken as the equilibrium
+    pub fn add_goods(&mut self, item_type: String, base_price: i32, stock: u32) {
+        self.goods.insert(item_type, ShopStock {
+            base_price,
+            price: base_price,
+            stock,
+            equilibrium_stock: stock,
+        });
+    }
 }
@@ -7,14 +7,26 @@ use chrono::{DateTime, Utc};
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum GameEvent {
     // Movement
-    PlayerMoved { from_room: Uuid, to_room: Uuid, direction: String },
+    /// `character_id` is the party member who moved; events saved before parties had
+    /// no id and load as the original player (PLAYER_ENTITY_ID)
+    PlayerMoved {
+        #[serde(default)]
+        character_id: Uuid,
+        from_room: Uuid,
+        to_room: Uuid,
+        direction: String,
+    },
     NpcMoved { npc_id: Uuid, from_room: Uuid, to_room: Uuid },
+    NpcSpawned { npc_id: Uuid, room_id: Uuid },
     
     // Interaction
     PlayerTalkedToNpc { npc_id: Uuid, room_id: Uuid },
     ItemPickedUp { item_id: Uuid, player_id: Uuid },
     ItemDropped { item_id: Uuid, room_id: Uuid },
     
+    // Encounters
+    EncounterTriggered { room_id: Uuid, storylet_id: String },
+    
     // Combat
     CombatStarted { attacker: Uuid, defender: Uuid },
     CombatResolved { winner: Uuid, loser: Uuid, damage: i32 },
@@ -27,6 +39,9 @@ pub enum GameEvent {
     ItemCrafted { crafter: Uuid, item_id: Uuid, recipe: String },
     ItemSold { seller: Uuid, buyer: Uuid, item_id: Uuid, price: i32 },
     
+    // Progression
+    SkillImproved { entity_id: Uuid, skill: String, old_level: i32, new_level: i32 },
+    
     // Factions
     FactionRelationChanged { faction_a: Uuid, faction_b: Uuid, old_value: i32, new_value: i32 },
     PlayerReputationChanged { faction: Uuid, old_rep: i32, new_rep: i32 },
@@ -38,19 +53,35 @@ impl GameEvent {
         match self {
             GameEvent::PlayerMoved { .. } => "player_moved",
             GameEvent::NpcMoved { .. } => "npc_moved",
+            GameEvent::NpcSpawned { .. } => "npc_spawned",
             GameEvent::PlayerTalkedToNpc { .. } => "player_talked_to_npc",
             GameEvent::ItemPickedUp { .. } => "item_picked_up",
             GameEvent::ItemDropped { .. } => "item_dropped",
+            GameEvent::EncounterTriggered { .. } => "encounter_triggered",
             GameEvent::CombatStarted { .. } => "combat_started",
             GameEvent::CombatResolved { .. } => "combat_resolved",
             GameEvent::TimeAdvanced { .. } => "time_advanced",
             GameEvent::WeatherChanged { .. } => "weather_changed",
             GameEvent::ItemCrafted { .. } => "item_crafted",
             GameEvent::ItemSold { .. } => "item_sold",
+            GameEvent::SkillImproved { .. } => "skill_improved",
             GameEvent::FactionRelationChanged { .. } => "faction_relation_changed",
             GameEvent::PlayerReputationChanged { .. } => "player_reputation_changed",
         }
     }
+    
+    /// Whether this event took place in (or moved something into) a room
+    pub fn involves_room(&self, room_id: Uuid) -> bool {
+        match self {
+            GameEvent::PlayerMoved { to_room, .. } => *to_room == room_id,
+            GameEvent::NpcMoved { to_room, .. } => *to_room == room_id,
+            GameEvent::NpcSpawned { room_id: r, .. } => *r == room_id,
+            GameEvent::PlayerTalkedToNpc { room_id: r, .. } => *r == room_id,
+            GameEvent::ItemDropped { room_id: r, .. } => *r == room_id,
+            GameEvent::EncounterTriggered { room_id: r, .. } => *r == room_id,
+            _ => false,
+        }
+    }
 }
 
 /// A recorded event with metadata
@@ -110,15 +141,7 @@ impl EventLog {
     pub fn query_in_room(&self, room_id: Uuid, limit: usize) -> Vec<&EventRecord> {
         self.events.iter()
             .rev()
-            .filter(|e| {
-                match &e.event {
-                    GameEvent::PlayerMoved { to_room, .. } => *to_room == room_id,
-                    GameEvent::NpcMoved { to_room, .. } => *to_room == room_id,
-                    GameEvent::PlayerTalkedToNpc { room_id: r, .. } => *r == room_id,
-                    GameEvent::ItemDropped { room_id: r, .. } => *r == room_id,
-                    _ => false,
-                }
-            })
+            .filter(|e| e.event.involves_room(room_id))
             .take(limit)
             .collect()
     }
@@ -131,10 +154,15 @@ impl EventLog {
     /// Generate tags for an event for efficient querying
     fn generate_tags(event: &GameEvent) -> Vec<String> {
         match event {
-            GameEvent::PlayerMoved { .. } => vec!["player".into(), "movement".into()],
+            GameEvent::PlayerMoved { character_id, .. } => {
+                vec!["player".into(), "movement".into(), format!("character:{}", character_id)]
+            },
             GameEvent::NpcMoved { npc_id, .. } => {
                 vec!["npc".into(), "movement".into(), format!("npc:{}", npc_id)]
             },
+            GameEvent::NpcSpawned { npc_id, .. } => {
+                vec!["npc".into(), "spawn".into(), format!("npc:{}", npc_id)]
+            },
             GameEvent::PlayerTalkedToNpc { npc_id, .. } => {
                 vec!["player".into(), "dialogue".into(), format!("npc:{}", npc_id)]
             },
@@ -144,6 +172,9 @@ impl EventLog {
             GameEvent::ItemDropped { .. } => {
                 vec!["item".into(), "movement".into()]
             },
+            GameEvent::EncounterTriggered { storylet_id, .. } => {
+                vec!["player".into(), "encounter".into(), format!("storylet:{}", storylet_id)]
+            },
             GameEvent::CombatStarted { attacker, defender } => {
                 vec![
                     "combat".into(),
@@ -176,6 +207,9 @@ impl EventLog {
                     format!("buyer:{}", buyer),
                 ]
             },
+            GameEvent::SkillImproved { skill, .. } => {
+                vec!["progression".into(), "skill".into(), format!("skill:{}", skill)]
+            },
             GameEvent::FactionRelationChanged { faction_a, faction_b, .. } => {
                 vec![
                     "faction".into(),
@@ -205,6 +239,7 @@ mod tests {
         let mut log = EventLog::new();
         
         let event = GameEvent::PlayerMoved {
+            character_id: Uuid::nil(),
             from_room: Uuid::new_v4(),
             to_room: Uuid::new_v4(),
             direction: "north".to_string(),
@@ -221,6 +256,7 @@ mod tests {
         let mut log = EventLog::new();
         
         log.record(1, GameEvent::PlayerMoved {
+            character_id: Uuid::nil(),
             from_room: Uuid::new_v4(),
             to_room: Uuid::new_v4(),
             direction: "north".to_string(),
@@ -244,12 +280,14 @@ mod tests {
         let mut log = EventLog::new();
         
         log.record(1, GameEvent::PlayerMoved {
+            character_id: Uuid::nil(),
             from_room: Uuid::new_v4(),
             to_room: Uuid::new_v4(),
             direction: "north".to_string(),
         });
         
         log.record(5, GameEvent::PlayerMoved {
+            character_id: Uuid::nil(),
             from_room: Uuid::new_v4(),
             to_room: Uuid::new_v4(),
             direction: "south".to_string(),
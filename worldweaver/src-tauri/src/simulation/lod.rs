@@ -1,3 +1,4 @@
+use bevy_ecs::prelude::*;
 use uuid::Uuid;
 use std::collections::HashMap;
 
@@ -65,6 +66,8 @@ impl Default for RoomGraph {
 }
 
 /// Manages simulation level of detail based on distance from player
+/// Systems that see this resource stagger distant NPC updates through it
+#[derive(Resource)]
 pub struct LodManager {
     player_room: Uuid,
     room_graph: RoomGraph,
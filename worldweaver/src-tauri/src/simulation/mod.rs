@@ -1,7 +1,10 @@
 pub mod components;
+pub mod combat;
 pub mod world;
 pub mod systems;
 pub mod tick;
 pub mod events;
 pub mod lod;
+pub mod snapshot;
 pub mod storylets;
+pub mod npc_gen;
@@ -0,0 +1,134 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Serialize, Deserialize};
+
+use crate::terrain::biomes::Biome;
+use crate::terrain::config::WorldTheme;
+
+/// Procedurally generated NPC content, ready to be spawned into a room
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct NpcTemplate {
+    pub name: String,
+    pub description: String,
+    pub personality: String,
+    pub greeting: String,
+}
+
+// Name tables per theme (first names, family names/epithets)
+const FANTASY_FIRST: &[&str] = &[
+    "Aldric", "Brenna", "Corwin", "Dagny", "Elric", "Fenna", "Garrick", "Hilde",
+    "Isolde", "Joren", "Kestra", "Lorcan", "Maelis", "Niall", "Orla", "Perrin",
+];
+const FANTASY_LAST: &[&str] = &[
+    "Ashford", "Blackwood", "Copperkettle", "Dunmore", "Fairwind", "Greymantle",
+    "Hollowell", "Ironside", "Marsh", "Thornbury",
+];
+const MODERN_FIRST: &[&str] = &[
+    "Alex", "Blake", "Casey", "Dana", "Elliot", "Frankie", "Jordan", "Morgan",
+    "Riley", "Sam", "Taylor", "Quinn",
+];
+const MODERN_LAST: &[&str] = &[
+    "Anderson", "Brooks", "Chen", "Diaz", "Foster", "Garcia", "Hughes", "Novak",
+    "Patel", "Reyes",
+];
+const SCIFI_FIRST: &[&str] = &[
+    "Axon", "Brix", "Cyra", "Dax", "Eon", "Juno", "Kael", "Lyra", "Nova", "Orin",
+    "Sol", "Vex",
+];
+const SCIFI_LAST: &[&str] = &[
+    "7-Theta", "Arcturus", "Halcyon", "Kepler", "Mercer", "Oberon", "Quell",
+    "Vance", "Zero-Nine", "Zhao",
+];
+
+const TRAITS: &[(&str, &str)] = &[
+    ("Friendly and talkative", "eager to share the latest news"),
+    ("Gruff but honest", "wastes few words on strangers"),
+    ("Shrewd and calculating", "always weighing the value of a conversation"),
+    ("Cheerful and curious", "full of questions for travelers"),
+    ("Quiet and watchful", "notices more than they let on"),
+    ("Proud and stubborn", "takes great pride in their work"),
+];
+
+/// Occupation and appearance flavor for a biome
+fn biome_occupation(biome: Biome) -> (&'static str, &'static str) {
+    match biome {
+        Biome::Ocean | Biome::Coast => ("fisher", "smells faintly of salt and tar"),
+        Biome::TropicalRainforest => ("herbalist", "carries a satchel of strange leaves"),
+        Biome::TemperateForest => ("woodcutter", "has sawdust clinging to their sleeves"),
+        Biome::BorealForest => ("trapper", "wears thick furs against the cold"),
+        Biome::Tundra | Biome::Glacier => ("hunter", "has a face weathered by icy winds"),
+        Biome::Grassland => ("farmer", "has sun-browned arms and dirt under their nails"),
+        Biome::Savanna => ("herder", "leans on a long wooden staff"),
+        Biome::Desert => ("caravan guide", "is wrapped in pale, dust-streaked robes"),
+        Biome::Alpine => ("miner", "has hands calloused from pick and shovel"),
+    }
+}
+
+fn name_tables(theme: WorldTheme) -> (&'static [&'static str], &'static [&'static str]) {
+    match theme {
+        WorldTheme::Fantasy => (FANTASY_FIRST, FANTASY_LAST),
+        WorldTheme::Modern => (MODERN_FIRST, MODERN_LAST),
+        WorldTheme::SciFi => (SCIFI_FIRST, SCIFI_LAST),
+    }
+}
+
+fn pick<'a, T>(rng: &mut impl Rng, table: &'a [T]) -> &'a T {
+    &table[rng.random_range(0..table.len())]
+}
+
+/// Deterministically generate an NPC from a seed
+/// The same seed, theme, biome, and faction always produce the same template
+pub fn generate_npc(seed: u64, theme: WorldTheme, biome: Biome, faction: Option<&str>) -> NpcTemplate {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+    let (first_names, last_names) = name_tables(theme);
+    let first = pick(&mut rng, first_names);
+    let last = pick(&mut rng, last_names);
+    let (personality_trait, trait_detail) = pick(&mut rng, TRAITS);
+    let (occupation, appearance) = biome_occupation(biome);
+
+    let mut description = format!("A local {} who {}.", occupation, appearance);
+    if let Some(faction) = faction {
+        description.push_str(&format!(" They wear the colors of {}.", faction));
+    }
+
+    let personality = format!("{}, {}.", personality_trait, trait_detail);
+
+    let greeting = match *personality_trait {
+        "Friendly and talkative" => format!("Well met, traveler! Name's {}. Have you heard the news?", first),
+        "Gruff but honest" => "What do you want? Speak plainly.".to_string(),
+        "Shrewd and calculating" => format!("A new face. {} the {}, at your service... for a price.", first, occupation),
+        "Cheerful and curious" => "Oh, hello! Where have you come from? Tell me everything!".to_string(),
+        "Quiet and watchful" => "...Can I help you?".to_string(),
+        _ => format!("You're looking at the finest {} in these parts.", occupation),
+    };
+
+    NpcTemplate {
+        name: format!("{} {}", first, last),
+        description,
+        personality,
+        greeting,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_npc() {
+        let a = generate_npc(42, WorldTheme::Fantasy, Biome::Grassland, Some("the Town Guard"));
+        let b = generate_npc(42, WorldTheme::Fantasy, Biome::Grassland, Some("the Town Guard"));
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_different_seeds_differ() {
+        let npcs: Vec<NpcTemplate> = (0..8)
+            .map(|seed| generate_npc(seed, WorldTheme::Fantasy, Biome::Desert, None))
+            .collect();
+
+        assert!(npcs.iter().any(|npc| *npc != npcs[0]));
+        assert!(npcs[0].description.contains("caravan guide"));
+    }
+}
@@ -0,0 +1,200 @@
+use bevy_ecs::entity::Entity;
+use bevy_ecs::world::{EntityRef, World};
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::components::*;
+use super::storylets::StoryletManager;
+use super::systems::{GameTime, Weather, WorldClock};
+use super::world::PLAYER_ENTITY_ID;
+
+/// Serializable components of a single entity, for saving and restoring the ECS world
+/// Tag components are not stored; they are rebuilt from the id components on spawn
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct EntitySnapshot {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<Name>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<Description>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<Position>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub npc: Option<Npc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player: Option<Player>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub character_id: Option<CharacterId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room: Option<Room>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room_id: Option<RoomId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub npc_id: Option<NpcId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item_id: Option<ItemId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item: Option<Item>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inventory: Option<Inventory>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub terrain_binding: Option<RoomTerrainBinding>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<Schedule>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dialogue_memory: Option<DialogueMemory>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub faction_id: Option<FactionId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub faction: Option<Faction>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub faction_membership: Option<FactionMembership>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shop: Option<Shop>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skills: Option<Skills>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relationships: Option<Relationships>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<Stats>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<Health>,
+}
+
+impl EntitySnapshot {
+    /// Copy an entity's serializable components; None if it has none worth saving
+    pub fn capture(entity: EntityRef) -> Option<Self> {
+        let snapshot = Self {
+            name: entity.get::<Name>().cloned(),
+            description: entity.get::<Description>().cloned(),
+            position: entity.get::<Position>().cloned(),
+            npc: entity.get::<Npc>().cloned(),
+            player: entity.get::<Player>().cloned(),
+            character_id: entity.get::<CharacterId>().cloned(),
+            room: entity.get::<Room>().cloned(),
+            room_id: entity.get::<RoomId>().cloned(),
+            npc_id: entity.get::<NpcId>().cloned(),
+            item_id: entity.get::<ItemId>().cloned(),
+            item: entity.get::<Item>().cloned(),
+            inventory: entity.get::<Inventory>().cloned(),
+            terrain_binding: entity.get::<RoomTerrainBinding>().cloned(),
+            schedule: entity.get::<Schedule>().cloned(),
+            dialogue_memory: entity.get::<DialogueMemory>().cloned(),
+            faction_id: entity.get::<FactionId>().cloned(),
+            faction: entity.get::<Faction>().cloned(),
+            faction_membership: entity.get::<FactionMembership>().cloned(),
+            shop: entity.get::<Shop>().cloned(),
+            skills: entity.get::<Skills>().cloned(),
+            relationships: entity.get::<Relationships>().cloned(),
+            stats: entity.get::<Stats>().cloned(),
+            health: entity.get::<Health>().cloned(),
+        };
+        snapshot.is_identified().then_some(snapshot)
+    }
+
+    /// Whether the snapshot names or identifies anything; unnamed, id-less entities
+    /// (and rows saved in older formats) aren't worth restoring
+    pub fn is_identified(&self) -> bool {
+        self.name.is_some() || self.id().is_some()
+    }
+
+    /// Kind of entity, stored in the `entities.entity_type` column
+    pub fn entity_type(&self) -> &'static str {
+        if self.player.is_some() {
+            "player"
+        } else if self.room_id.is_some() {
+            "room"
+        } else if self.npc_id.is_some() {
+            "npc"
+        } else if self.item_id.is_some() {
+            "item"
+        } else if self.faction_id.is_some() {
+            "faction"
+        } else {
+            "entity"
+        }
+    }
+
+    /// Stable id for the `entities.id` column, if the entity has one
+    pub fn id(&self) -> Option<Uuid> {
+        if self.player.is_some() {
+            return Some(self.character_id.as_ref().map_or(PLAYER_ENTITY_ID, |id| id.0));
+        }
+        self.room_id.as_ref().map(|id| id.0)
+            .or_else(|| self.npc_id.as_ref().map(|id| id.0))
+            .or_else(|| self.item_id.as_ref().map(|id| id.0))
+            .or_else(|| self.faction_id.as_ref().map(|id| id.0))
+    }
+
+    /// Spawn the snapshot back into a world, restoring its tag components
+    pub fn spawn(self, world: &mut World) -> Entity {
+        let mut entity = world.spawn_empty();
+        if let Some(name) = self.name { entity.insert(name); }
+        if let Some(description) = self.description { entity.insert(description); }
+        if let Some(position) = self.position { entity.insert(position); }
+        if let Some(npc) = self.npc { entity.insert(npc); }
+        if let Some(player) = self.player { entity.insert((player, IsPlayer)); }
+        if let Some(character_id) = self.character_id { entity.insert(character_id); }
+        if let Some(room) = self.room { entity.insert(room); }
+        if let Some(room_id) = self.room_id { entity.insert((room_id, IsRoom)); }
+        if let Some(npc_id) = self.npc_id { entity.insert((npc_id, IsNpc)); }
+        if let Some(item_id) = self.item_id { entity.insert((item_id, IsItem)); }
+        if let Some(item) = self.item { entity.insert(item); }
+        if let Some(inventory) = self.inventory { entity.insert(inventory); }
+        if let Some(binding) = self.terrain_binding { entity.insert(binding); }
+        if let Some(schedule) = self.schedule { entity.insert(schedule); }
+        if let Some(memory) = self.dialogue_memory { entity.insert(memory); }
+        if let Some(faction_id) = self.faction_id { entity.insert(faction_id); }
+        if let Some(faction) = self.faction { entity.insert(faction); }
+        if let Some(membership) = self.faction_membership { entity.insert(membership); }
+        if let Some(shop) = self.shop { entity.insert(shop); }
+        if let Some(skills) = self.skills { entity.insert(skills); }
+        if let Some(relationships) = self.relationships { entity.insert(relationships); }
+        if let Some(stats) = self.stats { entity.insert(stats); }
+        if let Some(health) = self.health { entity.insert(health); }
+        entity.id()
+    }
+}
+
+/// World-wide resources saved next to the entities: the in-game date, the weather
+/// and every entity's storylet qualities. Missing parts keep the fresh world's values
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct ResourceSnapshot {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<GameTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weather: Option<Weather>,
+    #[serde(default)]
+    pub qualities: HashMap<Uuid, HashMap<String, i32>>,
+}
+
+impl ResourceSnapshot {
+    /// Copy the saved resources out of a world
+    pub fn capture(world: &World) -> Self {
+        Self {
+            time: world.get_resource::<WorldClock>().map(|clock| clock.current_time.clone()),
+            weather: world.get_resource::<Weather>().copied(),
+            qualities: world.get_resource::<StoryletManager>()
+                .map(|manager| manager.all_qualities().clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Write the saved resources back into a world; quality definitions must already
+    /// be registered so values are clamped to their ranges
+    pub fn restore(self, world: &mut World) {
+        if let (Some(time), Some(mut clock)) = (self.time, world.get_resource_mut::<WorldClock>()) {
+            clock.current_time = time;
+        }
+        if let Some(weather) = self.weather {
+            world.insert_resource(weather);
+        }
+        if let Some(mut manager) = world.get_resource_mut::<StoryletManager>() {
+            for (entity_id, qualities) in self.qualities {
+                for (quality_id, value) in qualities {
+                    manager.set_quality(entity_id, quality_id, value);
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,8 @@
+use bevy_ecs::prelude::*;
 use std::collections::HashMap;
 use uuid::Uuid;
 use serde::{Serialize, Deserialize};
+use rand::Rng;
 
 /// A quality (tracked stat/attribute) that gates storylets
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -43,6 +45,13 @@ pub struct Storylet {
     pub requirements: Vec<QualityRequirement>,
     pub branches: Vec<StoryletBranch>,
     pub category: String,  // "quest", "dialogue", "discovery", etc.
+    #[serde(default = "default_weight")]
+    pub weight: u32,       // Relative draw weight (rarer = lower)
+}
+
+/// Weight of storylets saved before weights existed, matching `Storylet::new`
+fn default_weight() -> u32 {
+    1
 }
 
 impl Storylet {
@@ -54,9 +63,20 @@ impl Storylet {
             requirements: Vec::new(),
             branches: Vec::new(),
             category: "general".to_string(),
+            weight: 1,
         }
     }
     
+    pub fn with_category(mut self, category: &str) -> Self {
+        self.category = category.to_string();
+        self
+    }
+    
+    pub fn with_weight(mut self, weight: u32) -> Self {
+        self.weight = weight;
+        self
+    }
+    
     pub fn add_requirement(&mut self, requirement: QualityRequirement) {
         self.requirements.push(requirement);
     }
@@ -163,8 +183,10 @@ impl QualityEffect {
 }
 
 /// Manages storylets and qualities for entities
+#[derive(Resource)]
 pub struct StoryletManager {
     qualities: HashMap<Uuid, HashMap<String, i32>>,  // entity_id -> quality_name -> value
+    definitions: HashMap<String, Quality>,           // quality_id -> name, range, description
     storylets: Vec<Storylet>,
 }
 
@@ -172,6 +194,7 @@ impl StoryletManager {
     pub fn new() -> Self {
         Self {
             qualities: HashMap::new(),
+            definitions: HashMap::new(),
             storylets: Vec::new(),
         }
     }
@@ -181,11 +204,68 @@ impl StoryletManager {
         self.storylets.push(storylet);
     }
     
+    /// Register a named quality; its min/max clamp every value set for it
+    /// The definition's value is the starting value for entities that don't have it yet
+    pub fn register_quality(&mut self, definition: Quality) {
+        self.definitions.insert(definition.id.clone(), definition);
+    }
+    
+    /// Get a registered quality definition
+    pub fn quality_definition(&self, quality_id: &str) -> Option<&Quality> {
+        self.definitions.get(quality_id)
+    }
+    
+    /// Clamp a value to the quality's registered range (unregistered qualities are unbounded)
+    fn clamp_quality(&self, quality_id: &str, value: i32) -> i32 {
+        self.definitions.get(quality_id)
+            .map(|def| value.clamp(def.min, def.max))
+            .unwrap_or(value)
+    }
+    
+    /// Every registered quality plus any unregistered ones the entity holds, sorted by id
+    /// Unregistered qualities are reported with an unbounded range
+    pub fn qualities_for(&self, entity_id: Uuid) -> Vec<Quality> {
+        let held = self.qualities.get(&entity_id);
+        
+        let mut qualities: Vec<Quality> = self.definitions.values()
+            .map(|def| {
+                let mut quality = def.clone();
+                if let Some(&value) = held.and_then(|q| q.get(&def.id)) {
+                    quality.set_value(value);
+                }
+                quality
+            })
+            .collect();
+        
+        if let Some(held) = held {
+            for (id, &value) in held {
+                if !self.definitions.contains_key(id) {
+                    qualities.push(Quality {
+                        id: id.clone(),
+                        name: id.clone(),
+                        value,
+                        min: i32::MIN,
+                        max: i32::MAX,
+                        description: String::new(),
+                    });
+                }
+            }
+        }
+        
+        qualities.sort_by(|a, b| a.id.cmp(&b.id));
+        qualities
+    }
+    
     /// Get or create qualities map for an entity
     fn get_qualities_mut(&mut self, entity_id: Uuid) -> &mut HashMap<String, i32> {
         self.qualities.entry(entity_id).or_insert_with(HashMap::new)
     }
     
+    /// Every entity's quality values, for saving
+    pub fn all_qualities(&self) -> &HashMap<Uuid, HashMap<String, i32>> {
+        &self.qualities
+    }
+    
     /// Get qualities for an entity (read-only)
     pub fn get_qualities(&self, entity_id: Uuid) -> Option<&HashMap<String, i32>> {
         self.qualities.get(&entity_id)
@@ -193,15 +273,16 @@ impl StoryletManager {
     
     /// Set a quality value for an entity
     pub fn set_quality(&mut self, entity_id: Uuid, quality_id: String, value: i32) {
+        let value = self.clamp_quality(&quality_id, value);
         let qualities = self.get_qualities_mut(entity_id);
         qualities.insert(quality_id, value);
     }
     
     /// Modify a quality value for an entity
     pub fn modify_quality(&mut self, entity_id: Uuid, quality_id: String, change: i32) {
-        let qualities = self.get_qualities_mut(entity_id);
-        let current = qualities.get(&quality_id).copied().unwrap_or(0);
-        qualities.insert(quality_id, current + change);
+        let current = self.get_quality(entity_id, &quality_id);
+        let value = self.clamp_quality(&quality_id, current + change);
+        self.get_qualities_mut(entity_id).insert(quality_id, value);
     }
     
     /// Get a quality value for an entity
@@ -209,6 +290,7 @@ impl StoryletManager {
         self.qualities.get(&entity_id)
             .and_then(|q| q.get(quality_id))
             .copied()
+            .or_else(|| self.definitions.get(quality_id).map(|def| def.value))
             .unwrap_or(0)
     }
     
@@ -222,6 +304,30 @@ impl StoryletManager {
             .collect()
     }
     
+    /// Draw one available storylet from a category, weighted by `Storylet::weight`
+    /// Used for "draw a random encounter" gameplay
+    pub fn pick_weighted(&self, entity_id: Uuid, category: &str, rng: &mut impl Rng) -> Option<&Storylet> {
+        let candidates: Vec<&Storylet> = self.available_storylets(entity_id)
+            .into_iter()
+            .filter(|s| s.category == category && s.weight > 0)
+            .collect();
+        
+        let total_weight: u64 = candidates.iter().map(|s| s.weight as u64).sum();
+        if total_weight == 0 {
+            return None;
+        }
+        
+        let mut roll = rng.random_range(0..total_weight);
+        for storylet in candidates {
+            if roll < storylet.weight as u64 {
+                return Some(storylet);
+            }
+            roll -= storylet.weight as u64;
+        }
+        
+        None
+    }
+    
     /// Get available branches for a storylet
     pub fn available_branches<'a>(&self, entity_id: Uuid, storylet: &'a Storylet) -> Vec<&'a StoryletBranch> {
         let empty_map = HashMap::new();
@@ -235,18 +341,17 @@ impl StoryletManager {
     /// Check if requirements are met
     fn check_requirements(&self, reqs: &[QualityRequirement], qualities: &HashMap<String, i32>) -> bool {
         reqs.iter().all(|req| {
-            let value = qualities.get(&req.quality_id).copied().unwrap_or(0);
+            let value = qualities.get(&req.quality_id).copied()
+                .or_else(|| self.definitions.get(&req.quality_id).map(|def| def.value))
+                .unwrap_or(0);
             req.check(value)
         })
     }
     
     /// Execute a branch (apply its effects)
     pub fn execute_branch(&mut self, entity_id: Uuid, branch: &StoryletBranch) {
-        let qualities = self.get_qualities_mut(entity_id);
-        
         for effect in &branch.effects {
-            let current = qualities.get(&effect.quality_id).copied().unwrap_or(0);
-            qualities.insert(effect.quality_id.clone(), current + effect.change);
+            self.modify_quality(entity_id, effect.quality_id.clone(), effect.change);
         }
     }
     
@@ -342,4 +447,57 @@ mod tests {
         assert_eq!(manager.get_quality(entity_id, "gold"), 50);
         assert_eq!(manager.get_quality(entity_id, "items"), 1);
     }
+    
+    #[test]
+    fn test_storylet_without_weight_deserializes_with_default() {
+        let json = r#"{
+            "id": "wolves",
+            "title": "Wolves",
+            "description_template": "A pack circles",
+            "requirements": [],
+            "branches": [],
+            "category": "encounter"
+        }"#;
+        let storylet: Storylet = serde_json::from_str(json).unwrap();
+        assert_eq!(storylet.weight, 1);
+    }
+    
+    #[test]
+    fn test_pick_weighted_favors_heavier_storylets() {
+        use rand::SeedableRng;
+        
+        let mut manager = StoryletManager::new();
+        let entity_id = Uuid::new_v4();
+        
+        manager.add_storylet(
+            Storylet::new("wolves".to_string(), "Wolves".to_string(), "A pack circles".to_string())
+                .with_category("encounter")
+                .with_weight(9),
+        );
+        manager.add_storylet(
+            Storylet::new("dragon".to_string(), "Dragon".to_string(), "A shadow passes".to_string())
+                .with_category("encounter")
+                .with_weight(1),
+        );
+        manager.add_storylet(
+            Storylet::new("rumor".to_string(), "Rumor".to_string(), "Whispers at the bar".to_string())
+                .with_category("dialogue")
+                .with_weight(100),
+        );
+        
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let mut wolves = 0;
+        let mut dragons = 0;
+        for _ in 0..1000 {
+            match manager.pick_weighted(entity_id, "encounter", &mut rng).map(|s| s.id.as_str()) {
+                Some("wolves") => wolves += 1,
+                Some("dragon") => dragons += 1,
+                other => panic!("unexpected draw: {:?}", other),
+            }
+        }
+        
+        assert!(wolves > dragons * 3);
+        assert!(dragons > 0);
+        assert!(manager.pick_weighted(entity_id, "quest", &mut rng).is_none());
+    }
 }
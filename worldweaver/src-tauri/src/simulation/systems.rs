@@ -1,4 +1,16 @@
 use bevy_ecs::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use pathfinding::prelude::bfs;
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::components::{CharacterId, Faction, FactionId, FactionMembership, IsNpc, IsPlayer, Item, ItemId, IsRoom, NpcId, Position, Relationships, Room, RoomId, Schedule, ScheduleAction, Shop};
+use super::events::{EventLog, GameEvent};
+use super::lod::{LodManager, RoomGraph};
+use super::world::PLAYER_ENTITY_ID;
 
 /// Resource to track world events that affect simulation
 #[derive(Resource, Default)]
@@ -16,6 +28,8 @@ pub struct WorldEvent {
 /// Resource for world clock tracking time progression
 #[derive(Resource)]
 pub struct WorldClock {
+    /// Mirrors GameWorld::tick_count, which sets it before the systems run, so events
+    /// recorded by systems and by GameWorld share one tick
     pub ticks_elapsed: u64,
     pub current_time: GameTime,
 }
@@ -29,7 +43,48 @@ impl Default for WorldClock {
     }
 }
 
-#[derive(Clone, Debug, Default)]
+/// Resource tuning how quickly affinity fades back toward neutral without contact
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct RelationshipDecay {
+    pub grace_ticks: u64,     // Ticks after an interaction before any fading starts
+    pub interval_ticks: u64,  // Ticks per point of affinity lost after that
+}
+
+impl Default for RelationshipDecay {
+    fn default() -> Self {
+        Self {
+            grace_ticks: 24,
+            interval_ticks: 12,
+        }
+    }
+}
+
+/// Resource for the current weather over the world
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Weather {
+    #[default]
+    Clear,
+    Cloudy,
+    Rain,
+    Storm,
+    Fog,
+    Snow,
+}
+
+impl Weather {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Weather::Clear => "clear",
+            Weather::Cloudy => "cloudy",
+            Weather::Rain => "rain",
+            Weather::Storm => "storm",
+            Weather::Fog => "fog",
+            Weather::Snow => "snow",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct GameTime {
     pub hour: u32,      // 0-23
     pub day: u32,       // 1-30
@@ -38,7 +93,20 @@ pub struct GameTime {
     pub season: Season,
 }
 
-#[derive(Clone, Debug, Default)]
+impl Default for GameTime {
+    /// Midnight on the first day of spring, year 1
+    fn default() -> Self {
+        Self {
+            hour: 0,
+            day: 1,
+            month: 3,
+            year: 1,
+            season: Season::Spring,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
 pub enum Season {
     #[default]
     Spring,
@@ -47,7 +115,43 @@ pub enum Season {
     Winter,
 }
 
+/// Broad part of the day; room descriptions, narration and dialogue all read the hour through this
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeOfDay {
+    Dawn,
+    Morning,
+    Afternoon,
+    Dusk,
+    Night,
+}
+
+impl TimeOfDay {
+    pub fn from_hour(hour: u32) -> Self {
+        match hour {
+            5..=7 => TimeOfDay::Dawn,
+            8..=11 => TimeOfDay::Morning,
+            12..=16 => TimeOfDay::Afternoon,
+            17..=20 => TimeOfDay::Dusk,
+            _ => TimeOfDay::Night,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimeOfDay::Dawn => "dawn",
+            TimeOfDay::Morning => "morning",
+            TimeOfDay::Afternoon => "afternoon",
+            TimeOfDay::Dusk => "dusk",
+            TimeOfDay::Night => "night",
+        }
+    }
+}
+
 impl GameTime {
+    pub fn time_of_day(&self) -> TimeOfDay {
+        TimeOfDay::from_hour(self.hour)
+    }
+
     /// Advance time by one tick (e.g., 1 hour)
     pub fn advance(&mut self, hours: u32) {
         self.hour += hours;
@@ -57,14 +161,15 @@ impl GameTime {
             self.hour %= 24;
         }
         
+        // Days and months count from 1, so roll over on their zero-based offsets
         if self.day > 30 {
-            self.month += self.day / 30;
-            self.day = (self.day % 30).max(1);
+            self.month += (self.day - 1) / 30;
+            self.day = (self.day - 1) % 30 + 1;
         }
         
         if self.month > 12 {
-            self.year += self.month / 12;
-            self.month = (self.month % 12).max(1);
+            self.year += (self.month - 1) / 12;
+            self.month = (self.month - 1) % 12 + 1;
         }
         
         // Update season based on month
@@ -79,50 +184,284 @@ impl GameTime {
 
 /// System: Advance the world clock by one tick
 pub fn advance_world_clock(mut clock: ResMut<WorldClock>) {
-    clock.ticks_elapsed += 1;
     clock.current_time.advance(1); // 1 hour per tick
 }
 
+/// System: Point the LodManager at the main player's room and rebuild its room graph
+/// from the current exits, so rooms created or rewired since the last tick count
+/// Without a player (or a LodManager) there's nothing to do
+pub fn update_lod(
+    lod: Option<ResMut<LodManager>>,
+    players: Query<&Position, (With<IsPlayer>, Without<CharacterId>)>,
+    rooms: Query<(&RoomId, &Room), With<IsRoom>>,
+) {
+    let (Some(mut lod), Some(player)) = (lod, players.iter().next()) else {
+        return;
+    };
+    lod.update_player_room(player.room_id);
+
+    let mut graph = RoomGraph::new();
+    for (id, room) in rooms.iter() {
+        for exit in &room.exits {
+            if !graph.is_adjacent(id.0, exit.target_room_id) {
+                graph.add_connection(id.0, exit.target_room_id);
+            }
+        }
+    }
+    *lod.room_graph_mut() = graph;
+}
+
+/// System: Roll the weather every 6 in-game hours
+/// Transitions are seeded from the clock so fast-forwarded runs are reproducible
+pub fn update_weather(
+    clock: Res<WorldClock>,
+    mut weather: ResMut<Weather>,
+    mut event_log: ResMut<EventLog>,
+) {
+    if clock.current_time.hour % 6 != 0 {
+        return;
+    }
+
+    let mut rng = ChaCha8Rng::seed_from_u64(clock.ticks_elapsed);
+    let precipitation = if clock.current_time.season == Season::Winter {
+        Weather::Snow
+    } else {
+        Weather::Rain
+    };
+
+    let new_weather = match rng.random_range(0..10) {
+        0..=3 => Weather::Clear,
+        4..=5 => Weather::Cloudy,
+        6..=7 => precipitation,
+        8 => Weather::Fog,
+        _ => Weather::Storm,
+    };
+
+    if new_weather != *weather {
+        event_log.record(
+            clock.ticks_elapsed,
+            GameEvent::WeatherChanged {
+                old_weather: weather.name().to_string(),
+                new_weather: new_weather.name().to_string(),
+            }
+        );
+        *weather = new_weather;
+    }
+}
+
+/// Fewest-exits route between two rooms, both ends included; None if `to` can't be reached
+/// `exits` maps each room to the rooms its exits lead to
+pub fn room_path(exits: &HashMap<Uuid, Vec<Uuid>>, from: Uuid, to: Uuid) -> Option<Vec<Uuid>> {
+    if !exits.contains_key(&from) || !exits.contains_key(&to) {
+        return None;
+    }
+    bfs(
+        &from,
+        |room| exits.get(room).into_iter().flatten().copied().collect::<Vec<_>>(),
+        |room| *room == to,
+    )
+}
+
+/// NPCs that follow a schedule, excluding the player so positions can be borrowed mutably
+type ScheduledNpcs<'w, 's> = Query<'w, 's, (&'static NpcId, &'static Schedule, &'static mut Position), (With<IsNpc>, Without<IsPlayer>)>;
+
 /// System: Update NPC schedules based on current time
-/// NPCs move to scheduled locations at specific times
+/// NPCs move to scheduled locations at specific times, either at once or walking
+/// room by room; with a LodManager present and a player in the world, NPCs away
+/// from every player-controlled character are only updated on their staggered ticks
 pub fn update_npc_schedules(
-    _clock: Res<WorldClock>,
-    // For MVP, we don't have Schedule component yet, so this is a placeholder
-    // In future: mut npcs: Query<(&Npc, &Schedule, &mut Position)>
+    clock: Res<WorldClock>,
+    lod: Option<Res<LodManager>>,
+    mut event_log: ResMut<EventLog>,
+    players: Query<&Position, With<IsPlayer>>,
+    rooms: Query<(&RoomId, &Room), With<IsRoom>>,
+    mut npcs: ScheduledNpcs,
 ) {
-    // TODO: Implement when Schedule component is added
-    // For each NPC:
-    //   - Check current time against schedule
-    //   - If time matches a scheduled event, update Position
-    //   - Log the movement as a WorldEvent
+    let player_rooms: Vec<Uuid> = players.iter().map(|pos| pos.room_id).collect();
+    let exits: HashMap<Uuid, Vec<Uuid>> = rooms.iter()
+        .map(|(id, room)| (id.0, room.exits.iter().map(|exit| exit.target_room_id).collect()))
+        .collect();
+
+    for (npc_id, schedule, mut position) in npcs.iter_mut() {
+        let player_nearby = player_rooms.contains(&position.room_id);
+        if let Some(lod) = lod.as_ref().filter(|_| !player_rooms.is_empty() && !player_nearby) {
+            let detail = lod.determine_lod(position.room_id);
+            if !lod.should_simulate_npc(clock.ticks_elapsed, npc_id.0, detail) {
+                continue;
+            }
+        }
+
+        let Some(package) = schedule.get_active_package(clock.current_time.hour, player_nearby) else {
+            continue;
+        };
+
+        let next_room = match package.action {
+            ScheduleAction::MoveToRoom { room_id } => Some(room_id),
+            // Unreachable goals leave the NPC where it is
+            ScheduleAction::TravelToRoom { room_id } => room_path(&exits, position.room_id, room_id)
+                .and_then(|path| path.get(1).copied()),
+            _ => None,
+        };
+
+        if let Some(room_id) = next_room.filter(|room_id| *room_id != position.room_id) {
+            event_log.record(
+                clock.ticks_elapsed,
+                GameEvent::NpcMoved { npc_id: npc_id.0, from_room: position.room_id, to_room: room_id }
+            );
+            position.room_id = room_id;
+        }
+    }
 }
 
+/// System: Fade relationships nobody has tended
+/// Once `grace_ticks` pass without an interaction, affinity moves one point toward
+/// neutral every `interval_ticks`; any new interaction restarts the grace period
+pub fn decay_relationships(
+    clock: Res<WorldClock>,
+    decay: Res<RelationshipDecay>,
+    mut relationships: Query<&mut Relationships>,
+) {
+    let interval = decay.interval_ticks.max(1);
+    for mut relationships in relationships.iter_mut() {
+        for relation in relationships.relations.values_mut() {
+            let idle = clock.ticks_elapsed.saturating_sub(relation.last_interaction_tick);
+            if idle > decay.grace_ticks && (idle - decay.grace_ticks) % interval == 0 {
+                relation.affinity -= relation.affinity.signum();
+            }
+        }
+    }
+}
+
+/// Fraction of the gap between a price and its equilibrium closed each tick
+pub const PRICE_DRIFT: f32 = 0.2;
+
 /// System: Simulate economy based on world events and time
-/// Adjusts shop prices based on supply/demand
+/// Adjusts shop prices based on supply/demand: every new `ItemSold` moves one unit of
+/// stock in or out of the shops involved, then each price drifts toward its equilibrium
 pub fn simulate_economy(
-    _clock: Res<WorldClock>,
-    _events: Res<WorldEvents>,
-    // For MVP, we don't have Shop component yet
-    // In future: mut shops: Query<(&mut Shop, &Position)>
+    mut processed: Local<usize>,
+    event_log: Res<EventLog>,
+    items: Query<(&ItemId, &Item)>,
+    mut shops: Query<(&NpcId, &mut Shop)>,
 ) {
-    // TODO: Implement when Shop component is added
-    // For each shop:
-    //   - Check recent events affecting trade routes
-    //   - Adjust price_modifier based on supply/demand
-    //   - Update inventory availability
+    for record in event_log.all_events().get(*processed..).unwrap_or_default() {
+        let GameEvent::ItemSold { seller, buyer, item_id, .. } = &record.event else {
+            continue;
+        };
+        let Some((_, item)) = items.iter().find(|(id, _)| id.0 == *item_id) else {
+            continue;
+        };
+        for (keeper, mut shop) in shops.iter_mut() {
+            let Some(goods) = shop.goods.get_mut(&item.item_type) else {
+                continue;
+            };
+            if keeper.0 == *seller {
+                goods.stock = goods.stock.saturating_sub(1);
+            } else if keeper.0 == *buyer {
+                goods.stock += 1;
+            }
+        }
+    }
+    *processed = event_log.all_events().len();
+
+    for (_, mut shop) in shops.iter_mut() {
+        for goods in shop.goods.values_mut() {
+            let gap = goods.equilibrium_price() - goods.price;
+            if gap == 0 {
+                continue;
+            }
+            // Always move at least a coin so small gaps still close
+            let step = (gap as f32 * PRICE_DRIFT).round() as i32;
+            goods.price += if step == 0 { gap.signum() } else { step };
+        }
+    }
+}
+
+/// How much an event shifts the target's faction's standing toward whoever acted,
+/// keyed by `GameEvent::event_type`; events not listed leave factions alone
+pub const REPUTATION_RULES: &[(&str, i32)] = &[
+    ("combat_started", -5),
+    ("combat_resolved", -15),
+    ("player_talked_to_npc", 1),
+    ("item_sold", 2),
+];
+
+/// Who acted on whom in an event, for the reputation rules
+fn event_parties(event: &GameEvent) -> Option<(Uuid, Uuid)> {
+    match event {
+        GameEvent::CombatStarted { attacker, defender } => Some((*attacker, *defender)),
+        GameEvent::CombatResolved { winner, loser, .. } => Some((*winner, *loser)),
+        GameEvent::PlayerTalkedToNpc { npc_id, .. } => Some((PLAYER_ENTITY_ID, *npc_id)),
+        GameEvent::ItemSold { seller, buyer, .. } if *buyer == PLAYER_ENTITY_ID => Some((*buyer, *seller)),
+        GameEvent::ItemSold { seller, buyer, .. } => Some((*seller, *buyer)),
+        _ => None,
+    }
 }
 
 /// System: Update faction relationships based on world events
+/// Each new event in the log is applied once: when the player acts on a faction member
+/// the player's reputation with that faction shifts; when a member of another faction
+/// does, the target's faction's relation toward the actor's faction shifts
 pub fn update_faction_relations(
-    _events: Res<WorldEvents>,
-    // For MVP, we don't have Faction component yet
-    // In future: mut factions: Query<&mut Faction>
+    clock: Res<WorldClock>,
+    mut processed: Local<usize>,
+    mut event_log: ResMut<EventLog>,
+    members: Query<(Option<&NpcId>, Has<IsPlayer>, &FactionMembership)>,
+    mut factions: Query<(&FactionId, &mut Faction)>,
 ) {
-    // TODO: Implement when Faction component is added
-    // Process events that affect faction relationships:
-    //   - Player kills bandit -> bandit faction -10, town guard +5
-    //   - Player completes quest -> quest giver faction +20
-    //   - Faction alliances/wars affect related factions
+    let new_events = event_log.all_events().get(*processed..).unwrap_or_default();
+    if new_events.is_empty() {
+        return;
+    }
+
+    let faction_of: HashMap<Uuid, Uuid> = members.iter()
+        .filter_map(|(npc_id, is_player, membership)| {
+            let id = if is_player { PLAYER_ENTITY_ID } else { npc_id?.0 };
+            Some((id, membership.faction_id))
+        })
+        .collect();
+
+    // (target faction, acting faction or None for the player, delta)
+    let shifts: Vec<(Uuid, Option<Uuid>, i32)> = new_events.iter()
+        .filter_map(|record| {
+            let event_type = record.event.event_type();
+            let &(_, delta) = REPUTATION_RULES.iter().find(|(rule, _)| *rule == event_type)?;
+            let (actor, target) = event_parties(&record.event)?;
+            let target_faction = *faction_of.get(&target)?;
+            if actor == PLAYER_ENTITY_ID {
+                return Some((target_faction, None, delta));
+            }
+            let actor_faction = *faction_of.get(&actor)?;
+            (actor_faction != target_faction).then_some((target_faction, Some(actor_faction), delta))
+        })
+        .collect();
+
+    for (target_faction, actor_faction, delta) in shifts {
+        let Some((_, mut faction)) = factions.iter_mut().find(|(id, _)| id.0 == target_faction) else {
+            continue;
+        };
+        let event = match actor_faction {
+            None => {
+                let old_rep = faction.player_reputation;
+                faction.set_player_reputation(old_rep + delta);
+                GameEvent::PlayerReputationChanged { faction: target_faction, old_rep, new_rep: faction.player_reputation }
+            }
+            Some(actor_faction) => {
+                let old_value = faction.get_relation(actor_faction);
+                faction.set_relation(actor_faction, old_value + delta);
+                GameEvent::FactionRelationChanged {
+                    faction_a: target_faction,
+                    faction_b: actor_faction,
+                    old_value,
+                    new_value: faction.get_relation(actor_faction),
+                }
+            }
+        };
+        event_log.record(clock.ticks_elapsed, event);
+    }
+
+    // The changes just recorded aren't in the rules, so skipping past them is safe
+    *processed = event_log.all_events().len();
 }
 
 /// System: Clean up old events to prevent memory bloat
@@ -35,18 +35,18 @@ impl TickManager {
         self.running.store(true, Ordering::SeqCst);
         let mut ticker = interval(self.tick_rate);
         
-        println!("⏰ Tick manager starting real-time loop (tick rate: {:?})", self.tick_rate);
+        tracing::info!(tick_rate = ?self.tick_rate, "Tick manager starting real-time loop");
         
         while self.running.load(Ordering::SeqCst) {
             ticker.tick().await;
             
             // Execute one simulation tick
             if let Err(e) = self.execute_tick().await {
-                eprintln!("❌ Error during tick execution: {}", e);
+                tracing::error!(error = %e, "Error during tick execution");
             }
         }
         
-        println!("⏰ Tick manager stopped");
+        tracing::info!("Tick manager stopped");
     }
 
     /// Stop the real-time simulation loop
@@ -57,7 +57,7 @@ impl TickManager {
     /// Fast-forward the simulation by a specified number of ticks
     /// Used when player logs in after being offline
     pub async fn fast_forward(&self, num_ticks: u64) -> Result<()> {
-        println!("⏩ Fast-forwarding {} ticks...", num_ticks);
+        tracing::info!(num_ticks, "Fast-forwarding");
         
         let start_time = std::time::Instant::now();
         
@@ -66,14 +66,16 @@ impl TickManager {
             
             // Progress update every 100 ticks
             if (i + 1) % 100 == 0 {
-                println!("⏩ Progress: {}/{} ticks", i + 1, num_ticks);
+                tracing::debug!(done = i + 1, num_ticks, "Fast-forward progress");
             }
         }
         
         let elapsed = start_time.elapsed();
-        println!("✅ Fast-forward complete in {:?} ({} ticks/sec)", 
-                 elapsed, 
-                 num_ticks as f64 / elapsed.as_secs_f64());
+        tracing::info!(
+            elapsed = ?elapsed,
+            ticks_per_sec = num_ticks as f64 / elapsed.as_secs_f64(),
+            "Fast-forward complete"
+        );
         
         Ok(())
     }
@@ -109,13 +111,13 @@ impl TickManager {
     /// Pause the simulation (stop ticking but don't destroy the manager)
     pub fn pause(&self) {
         self.running.store(false, Ordering::SeqCst);
-        println!("⏸️  Simulation paused");
+        tracing::info!("Simulation paused");
     }
 
     /// Resume the simulation after pausing
     pub fn resume(&self) {
         self.running.store(true, Ordering::SeqCst);
-        println!("▶️  Simulation resumed");
+        tracing::info!("Simulation resumed");
     }
 }
 
@@ -1,14 +1,51 @@
 use bevy_ecs::world::World;
+use bevy_ecs::schedule::IntoSystemConfigs;
 use bevy_ecs::schedule::Schedule;
 use std::sync::Arc;
 use std::collections::HashMap;
 use tokio::sync::Mutex;
 use uuid::Uuid;
 use serde::{Serialize, Deserialize};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use pathfinding::prelude::dijkstra;
 
 use super::components::*;
 use super::systems;
+use super::combat::{self, CombatResult};
 use super::events::{EventLog, GameEvent, EventRecord};
+use super::lod::LodManager;
+use super::snapshot::{EntitySnapshot, ResourceSnapshot};
+use super::storylets::{Quality, StoryletManager};
+use crate::terrain::biomes::{Biome, biome_traversal_cost, biome_encounter_chance};
+
+/// Entity id the player's qualities are tracked under in the StoryletManager
+pub const PLAYER_ENTITY_ID: Uuid = Uuid::nil();
+
+/// Shortest distance a single exit counts as, so hop count matters even
+/// between rooms bound to the same (or no) map position
+const MIN_HOP_KM: f32 = 1.0;
+
+/// Distance in meters and in-game hours to walk one exit between two rooms
+/// The destination's biome cost is charged per km (at least MIN_HOP_KM); rooms
+/// without a bound biome count as grassland, and unbound positions as no distance
+fn hop_travel(from: Option<&RoomTerrainBinding>, to: Option<&RoomTerrainBinding>) -> (f32, f32) {
+    let distance_meters = match (from, to) {
+        (Some(a), Some(b)) => ((a.world_x - b.world_x).powi(2) + (a.world_z - b.world_z).powi(2)).sqrt(),
+        _ => 0.0,
+    };
+    let cost = to
+        .and_then(|binding| binding.biome.as_deref().and_then(Biome::from_name))
+        .map(biome_traversal_cost)
+        .unwrap_or(1.0);
+    (distance_meters, cost * (distance_meters / 1000.0).max(MIN_HOP_KM))
+}
+
+/// Query filter for the original player, leaving out other party members
+type MainPlayer = (bevy_ecs::query::With<IsPlayer>, bevy_ecs::query::Without<CharacterId>);
+
+/// Skill points the player earns each time an action exercising the skill succeeds
+pub const SKILL_GAIN_PER_ACTION: i32 = 1;
 
 /// Main game world wrapper around Bevy ECS
 pub struct GameWorld {
@@ -21,37 +58,132 @@ pub struct GameWorld {
 impl GameWorld {
     /// Create a new game world with starter content
     pub fn new() -> Self {
+        let mut game_world = Self::empty();
+        game_world.room_registry = Self::spawn_starter_content(&mut game_world.ecs_world);
+        game_world
+    }
+    
+    /// Create a game world with only the essential resources and systems
+    /// No rooms, NPCs, or player are spawned, so tests can build precise worlds
+    pub fn empty() -> Self {
         let mut world = World::new();
         
         // Initialize resources for systems
         world.insert_resource(systems::WorldClock::default());
         world.insert_resource(systems::WorldEvents::default());
+        world.insert_resource(systems::Weather::default());
+        world.insert_resource(systems::RelationshipDecay::default());
         world.insert_resource(EventLog::default());
+        world.insert_resource(StoryletManager::default());
+        // Points at the player's room once update_lod first sees a player
+        world.insert_resource(LodManager::new(Uuid::nil()));
         
         // Build schedule with systems
         let mut schedule = Schedule::default();
         schedule.add_systems((
             systems::advance_world_clock,
-            systems::update_npc_schedules,
+            systems::update_weather.after(systems::advance_world_clock),
+            systems::update_lod,
+            systems::update_npc_schedules.after(systems::advance_world_clock).after(systems::update_lod),
+            systems::simulate_economy.after(systems::advance_world_clock),
+            systems::update_faction_relations.after(systems::advance_world_clock),
+            systems::decay_relationships.after(systems::advance_world_clock),
             systems::cleanup_old_events,
         ));
         
-        let room_registry = Self::spawn_starter_content(&mut world);
-        
         Self { 
             ecs_world: world,
             schedule,
             tick_count: 0,
-            room_registry,
+            room_registry: HashMap::new(),
+        }
+    }
+    
+    /// Rebuild a world from saved entity and resource snapshots instead of the starter content
+    pub fn from_snapshots(tick_count: u64, snapshots: Vec<EntitySnapshot>, resources: ResourceSnapshot) -> Self {
+        let mut game_world = Self::empty();
+        game_world.tick_count = tick_count;
+        Self::register_player_qualities(&mut game_world.ecs_world);
+        
+        for snapshot in snapshots {
+            if let (Some(room_id), Some(name)) = (&snapshot.room_id, &snapshot.name) {
+                game_world.room_registry.insert(room_id.0, name.0.clone());
+            }
+            snapshot.spawn(&mut game_world.ecs_world);
         }
+        resources.restore(&mut game_world.ecs_world);
+        game_world
+    }
+    
+    /// Capture every entity that has serializable components, for saving
+    pub fn snapshot_entities(&self) -> Vec<EntitySnapshot> {
+        self.ecs_world.iter_entities()
+            .filter_map(EntitySnapshot::capture)
+            .collect()
+    }
+    
+    /// Capture the clock, weather and storylet qualities, for saving
+    pub fn snapshot_resources(&self) -> ResourceSnapshot {
+        ResourceSnapshot::capture(&self.ecs_world)
     }
     
     /// Execute one simulation tick
     pub fn tick(&mut self) {
         self.tick_count += 1;
+        let _span = tracing::trace_span!("tick", tick = self.tick_count).entered();
+        if let Some(mut clock) = self.ecs_world.get_resource_mut::<systems::WorldClock>() {
+            clock.ticks_elapsed = self.tick_count;
+        }
         self.schedule.run(&mut self.ecs_world);
     }
 
+    /// Fast-forward the simulation by a number of in-game days (24 ticks each)
+    /// Returns a digest of notable events that happened in the meantime
+    pub fn simulate_days(&mut self, days: u32) -> Vec<String> {
+        let start_tick = self.tick_count;
+        
+        for _ in 0..days as u64 * 24 {
+            self.tick();
+        }
+        
+        let mut digest = Vec::new();
+        if let Some(clock) = self.ecs_world.get_resource::<systems::WorldClock>() {
+            digest.push(format!(
+                "{} day(s) passed. It is now day {}, hour {}.",
+                days, clock.current_time.day, clock.current_time.hour
+            ));
+        }
+        
+        let mut npcs = self.ecs_world.query_filtered::<(&NpcId, &Name), bevy_ecs::query::With<IsNpc>>();
+        let npc_names: HashMap<Uuid, String> = npcs.iter(&self.ecs_world)
+            .map(|(id, name)| (id.0, name.0.clone()))
+            .collect();
+        
+        for record in self.get_events_since(start_tick + 1) {
+            let line = match &record.event {
+                GameEvent::WeatherChanged { old_weather, new_weather } => {
+                    format!("The weather turned from {} to {}.", old_weather, new_weather)
+                }
+                GameEvent::NpcMoved { npc_id, to_room, .. } => {
+                    let room = self.room_registry.get(to_room).cloned().unwrap_or_else(|| "somewhere".to_string());
+                    match npc_names.get(npc_id) {
+                        Some(name) => format!("{} moved to {}.", name, room),
+                        None => format!("Someone ({}) moved to {}.", npc_id, room),  // Despawned since
+                    }
+                }
+                GameEvent::ItemSold { price, .. } => format!("A trade was made for {} coins.", price),
+                GameEvent::ItemCrafted { recipe, .. } => format!("Someone crafted {}.", recipe),
+                GameEvent::FactionRelationChanged { old_value, new_value, .. } => {
+                    format!("Faction relations shifted from {} to {}.", old_value, new_value)
+                }
+                _ => continue,
+            };
+            digest.push(format!("Tick {}: {}", record.tick, line));
+        }
+        
+        digest
+    }
+
     /// Spawn the initial world with multiple connected rooms
     fn spawn_starter_content(world: &mut World) -> HashMap<Uuid, String> {
         let mut registry = HashMap::new();
@@ -179,10 +311,16 @@ impl GameWorld {
                              Protective of his establishment and regular customers.".to_string(),
                 greeting: "Welcome to the Crossroads! What can I get you?".to_string(),
             },
+            NpcId(Uuid::new_v4()),
+            DialogueMemory::default(),
             IsNpc,
         ));
         
-        // NPC: Kael the Blacksmith (in the Forge)
+        // NPC: Kael the Blacksmith (in the Forge), who also runs the forge's shop
+        let mut forge_shop = Shop::default();
+        forge_shop.add_goods("weapon".to_string(), 25, 6);
+        forge_shop.add_goods("tool".to_string(), 8, 10);
+        forge_shop.add_goods("material".to_string(), 2, 20);
         world.spawn((
             Name("Kael the Blacksmith".to_string()),
             Description(
@@ -195,6 +333,9 @@ impl GameWorld {
                              Respects those who work hard and despises laziness.".to_string(),
                 greeting: "Looking for quality steel? You've come to the right place.".to_string(),
             },
+            NpcId(Uuid::new_v4()),
+            DialogueMemory::default(),
+            forge_shop,
             IsNpc,
         ));
         
@@ -207,23 +348,112 @@ impl GameWorld {
                 current_input: String::new(),
                 movement_history: vec![inn_id],
             },
+            Inventory::default(),
+            Skills::default(),
             IsPlayer,
         ));
 
-        println!("✓ Spawned world: 4 rooms, 2 NPCs, 1 player");
-        println!("  - The Crossroads Inn (start)");
-        println!("  - Town Square");
-        println!("  - Merchant District");
-        println!("  - Blacksmith's Forge");
+        // Items lying around to pick up
+        world.spawn((
+            Name("Iron Horseshoe".to_string()),
+            Description("A freshly forged horseshoe, still faintly warm.".to_string()),
+            Item::new("material".to_string(), 0.5, 2),
+            Position { room_id: forge_id },
+            ItemId(Uuid::new_v4()),
+            IsItem,
+        ));
+        world.spawn((
+            Name("Bolt of Linen".to_string()),
+            Description("A neatly rolled length of undyed linen.".to_string()),
+            Item::new("material".to_string(), 2.0, 8),
+            Position { room_id: merchant_id },
+            ItemId(Uuid::new_v4()),
+            IsItem,
+        ));
+
+        Self::register_player_qualities(world);
+
+        tracing::info!(rooms = 4, npcs = 2, items = 2, "Spawned starter world");
+        tracing::debug!("Starter rooms: The Crossroads Inn (start), Town Square, Merchant District, Blacksmith's Forge");
         
         registry
     }
 
+    /// Player qualities shown as labeled bars in the UI
+    fn register_player_qualities(world: &mut World) {
+        if let Some(mut storylets) = world.get_resource_mut::<StoryletManager>() {
+            let mut gold = Quality::new("gold".to_string(), "Gold".to_string(), 0, 10_000);
+            gold.description = "Coins in your purse.".to_string();
+            gold.set_value(25);
+            storylets.register_quality(gold);
+            
+            let mut reputation = Quality::new("reputation".to_string(), "Reputation".to_string(), -100, 100);
+            reputation.description = "How the townsfolk regard you.".to_string();
+            reputation.set_value(0);
+            storylets.register_quality(reputation);
+            
+            let mut wounds = Quality::new("wounds".to_string(), "Wounds".to_string(), 0, 10);
+            wounds.description = "Injuries you've yet to recover from.".to_string();
+            storylets.register_quality(wounds);
+        }
+    }
+
     /// Get the room ID where the player currently is
     pub fn get_player_room(&mut self) -> Option<Uuid> {
-        let mut query = self.ecs_world.query_filtered::<&Position, bevy_ecs::query::With<IsPlayer>>();
+        let mut query = self.ecs_world.query_filtered::<&Position, MainPlayer>();
         query.iter(&self.ecs_world).next().map(|pos| pos.room_id)
     }
+    
+    /// Current room of a player-controlled character (PLAYER_ENTITY_ID for the original player)
+    pub fn get_character_room(&mut self, character_id: Uuid) -> Option<Uuid> {
+        let entity = self.find_character(character_id)?;
+        self.ecs_world.get::<Position>(entity).map(|pos| pos.room_id)
+    }
+    
+    /// Entity of a player-controlled character
+    fn find_character(&mut self, character_id: Uuid) -> Option<bevy_ecs::entity::Entity> {
+        if character_id == PLAYER_ENTITY_ID {
+            let mut query = self.ecs_world.query_filtered::<bevy_ecs::entity::Entity, MainPlayer>();
+            return query.iter(&self.ecs_world).next();
+        }
+        let mut query = self.ecs_world.query_filtered::<(bevy_ecs::entity::Entity, &CharacterId), bevy_ecs::query::With<IsPlayer>>();
+        query.iter(&self.ecs_world)
+            .find(|(_, id)| id.0 == character_id)
+            .map(|(entity, _)| entity)
+    }
+    
+    /// Add another player-controlled character to the party; returns its character id
+    pub fn spawn_party_member(&mut self, name: String, description: String, room_id: Uuid) -> Uuid {
+        let character_id = Uuid::new_v4();
+        self.ecs_world.spawn((
+            Name(name),
+            Description(description),
+            Position { room_id },
+            Player {
+                current_input: String::new(),
+                movement_history: vec![room_id],
+            },
+            Inventory::default(),
+            Skills::default(),
+            CharacterId(character_id),
+            IsPlayer,
+        ));
+        character_id
+    }
+    
+    /// Every player-controlled character, the original player (PLAYER_ENTITY_ID) first
+    pub fn get_party(&mut self) -> Vec<PartyMember> {
+        let mut query = self.ecs_world.query_filtered::<(Option<&CharacterId>, Option<&Name>, &Position), bevy_ecs::query::With<IsPlayer>>();
+        let mut party: Vec<PartyMember> = query.iter(&self.ecs_world)
+            .map(|(id, name, pos)| PartyMember {
+                id: id.map_or(PLAYER_ENTITY_ID, |id| id.0),
+                name: name.map(|n| n.0.clone()).unwrap_or_default(),
+                room_id: pos.room_id,
+            })
+            .collect();
+        party.sort_by_key(|member| (member.id != PLAYER_ENTITY_ID, member.name.clone()));
+        party
+    }
 
     /// Get detailed information about a room by ID
     pub fn get_room_details(&mut self, room_id: Uuid) -> Option<RoomDetails> {
@@ -242,13 +472,70 @@ impl GameWorld {
         None
     }
 
+    /// Describe a room, layering time-of-day and weather flavor over its base description
+    pub fn describe_room(&mut self, room_id: Uuid) -> Option<String> {
+        let room = self.get_room_details(room_id)?;
+        let mut description = room.description;
+        
+        if let Some(clock) = self.ecs_world.get_resource::<systems::WorldClock>() {
+            let time_phrase = match clock.current_time.time_of_day() {
+                systems::TimeOfDay::Dawn => "The pale light of dawn creeps across the scene.",
+                systems::TimeOfDay::Morning | systems::TimeOfDay::Afternoon => "Daylight brightens every corner.",
+                systems::TimeOfDay::Dusk => "The long shadows of dusk stretch across the ground.",
+                systems::TimeOfDay::Night => "Moonlight filters in, casting everything in silver.",
+            };
+            description.push(' ');
+            description.push_str(time_phrase);
+        }
+        
+        if let Some(weather) = self.ecs_world.get_resource::<systems::Weather>() {
+            let weather_phrase = match weather {
+                systems::Weather::Clear => None,
+                systems::Weather::Cloudy => Some("Heavy clouds hang low overhead."),
+                systems::Weather::Rain => Some("Rain patters steadily on the roof."),
+                systems::Weather::Storm => Some("Rain patters on the roof as thunder rumbles in the distance."),
+                systems::Weather::Fog => Some("A thick fog muffles every sound."),
+                systems::Weather::Snow => Some("Snowflakes drift down in the still air."),
+            };
+            if let Some(phrase) = weather_phrase {
+                description.push(' ');
+                description.push_str(phrase);
+            }
+        }
+        
+        Some(description)
+    }
+    
+    /// Change the current weather, recording the transition
+    pub fn set_weather(&mut self, weather: systems::Weather) {
+        let old_weather = self.ecs_world
+            .get_resource::<systems::Weather>()
+            .copied()
+            .unwrap_or_default();
+        if old_weather == weather {
+            return;
+        }
+        
+        self.ecs_world.insert_resource(weather);
+        if let Some(mut event_log) = self.ecs_world.get_resource_mut::<EventLog>() {
+            event_log.record(
+                self.tick_count,
+                GameEvent::WeatherChanged {
+                    old_weather: old_weather.name().to_string(),
+                    new_weather: weather.name().to_string(),
+                }
+            );
+        }
+    }
+
     /// Get all NPCs in a specific room
     pub fn get_npcs_in_room(&mut self, room_id: Uuid) -> Vec<NpcInfo> {
-        let mut query = self.ecs_world.query_filtered::<(&Name, &Description, &Position, &Npc), bevy_ecs::query::With<IsNpc>>();
+        let mut query = self.ecs_world.query_filtered::<(&NpcId, &Name, &Description, &Position, &Npc), bevy_ecs::query::With<IsNpc>>();
         
         query.iter(&self.ecs_world)
-            .filter(|(_, _, pos, _)| pos.room_id == room_id)
-            .map(|(name, desc, _, npc)| NpcInfo {
+            .filter(|(_, _, _, pos, _)| pos.room_id == room_id)
+            .map(|(id, name, desc, _, npc)| NpcInfo {
+                id: id.0,
                 name: name.0.clone(),
                 description: desc.0.clone(),
                 personality: npc.personality.clone(),
@@ -257,116 +544,954 @@ impl GameWorld {
             .collect()
     }
     
-    /// Move player in a direction
-    pub fn move_player(&mut self, direction: &str) -> Result<Uuid, String> {
-        // Get current room
-        let current_room_id = self.get_player_room()
-            .ok_or_else(|| "Player has no current room".to_string())?;
+    /// Spawn an NPC into a room at runtime, recording the arrival
+    pub fn spawn_npc(
+        &mut self,
+        name: String,
+        description: String,
+        personality: String,
+        greeting: String,
+        room_id: Uuid,
+    ) -> Uuid {
+        let npc_id = Uuid::new_v4();
+        self.ecs_world.spawn((
+            Name(name),
+            Description(description),
+            Position { room_id },
+            Npc { personality, greeting },
+            NpcId(npc_id),
+            DialogueMemory::default(),
+            IsNpc,
+        ));
+        if let Some(mut event_log) = self.ecs_world.get_resource_mut::<EventLog>() {
+            event_log.record(self.tick_count, GameEvent::NpcSpawned { npc_id, room_id });
+        }
+        npc_id
+    }
+    
+    /// Remove an NPC from the world; returns false if no NPC has that id
+    pub fn despawn_npc(&mut self, npc_id: Uuid) -> bool {
+        let mut query = self.ecs_world.query_filtered::<(bevy_ecs::entity::Entity, &NpcId), bevy_ecs::query::With<IsNpc>>();
+        let entity = query.iter(&self.ecs_world)
+            .find(|(_, id)| id.0 == npc_id)
+            .map(|(entity, _)| entity);
         
-        // Get room details to check exits
-        let room = self.get_room_details(current_room_id)
-            .ok_or_else(|| "Current room not found".to_string())?;
+        entity.is_some_and(|entity| self.ecs_world.despawn(entity))
+    }
+    
+    /// Remember a conversation the player had with an NPC and log it in the NPC's room
+    pub fn record_conversation(&mut self, npc_id: Uuid, summary: String, topics: Vec<String>) -> Result<(), String> {
+        let mut query = self.ecs_world.query_filtered::<(bevy_ecs::entity::Entity, &NpcId, &Position), bevy_ecs::query::With<IsNpc>>();
+        let (entity, room_id) = query.iter(&self.ecs_world)
+            .find(|(_, id, _)| id.0 == npc_id)
+            .map(|(entity, _, pos)| (entity, pos.room_id))
+            .ok_or_else(|| "NPC not found".to_string())?;
         
-        // Find matching exit
-        let exit = room.exits.iter()
-            .find(|e| e.direction == direction)
-            .ok_or_else(|| format!("You can't go {} from here.", direction))?;
+        // NPCs spawned before memories existed (or from old saves) start with an empty one
+        let mut npc = self.ecs_world.entity_mut(entity);
+        if !npc.contains::<DialogueMemory>() {
+            npc.insert(DialogueMemory::default());
+        }
+        let mut memory = npc.get_mut::<DialogueMemory>()
+            .ok_or_else(|| "NPC has no dialogue memory".to_string())?;
+        memory.add_conversation(PLAYER_ENTITY_ID, self.tick_count, summary, topics);
         
-        let target_room_id = exit.target_room_id;
+        if let Some(mut event_log) = self.ecs_world.get_resource_mut::<EventLog>() {
+            event_log.record(self.tick_count, GameEvent::PlayerTalkedToNpc { npc_id, room_id });
+        }
         
-        // Verify target room exists
-        self.get_room_details(target_room_id)
-            .ok_or_else(|| "Target room not found (world error)".to_string())?;
+        Ok(())
+    }
+    
+    /// The NPC's most recent conversations with the player, newest first
+    pub fn get_recent_conversations(&mut self, npc_id: Uuid, limit: usize) -> Vec<ConversationRecord> {
+        let mut query = self.ecs_world.query_filtered::<(&NpcId, &DialogueMemory), bevy_ecs::query::With<IsNpc>>();
+        query.iter(&self.ecs_world)
+            .find(|(id, _)| id.0 == npc_id)
+            .map(|(_, memory)| {
+                memory.get_recent_conversations(PLAYER_ENTITY_ID, limit)
+                    .into_iter()
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+    
+    /// Current prices of a shop (its keeper's NPC id), sorted by item type
+    /// These are the simulation's prices; narration should quote them, never invent them
+    pub fn get_shop_prices(&mut self, shop_id: Uuid) -> Result<Vec<ShopPrice>, String> {
+        let mut query = self.ecs_world.query::<(&NpcId, &Shop)>();
+        let (_, shop) = query.iter(&self.ecs_world)
+            .find(|(id, _)| id.0 == shop_id)
+            .ok_or_else(|| "Shop not found".to_string())?;
+        
+        let mut prices: Vec<ShopPrice> = shop.goods.iter()
+            .map(|(item_type, goods)| ShopPrice {
+                item_type: item_type.clone(),
+                price: goods.price,
+                base_price: goods.base_price,
+                stock: goods.stock,
+            })
+            .collect();
+        prices.sort_by(|a, b| a.item_type.cmp(&b.item_type));
+        Ok(prices)
+    }
+    
+    /// Fight between two entities (NPC ids, or PLAYER_ENTITY_ID for the player) with
+    /// Health; missing Stats count as average. The dice are seeded from the tick and both
+    /// ids, so the same fight at the same tick always plays out the same way
+    pub fn resolve_combat(&mut self, attacker: Uuid, defender: Uuid) -> Result<CombatResult, String> {
+        if attacker == defender {
+            return Err("An entity can't fight itself".to_string());
+        }
+        let attacker_entity = self.find_combatant(attacker)
+            .ok_or_else(|| "Attacker not found".to_string())?;
+        let defender_entity = self.find_combatant(defender)
+            .ok_or_else(|| "Defender not found".to_string())?;
+        
+        let combatant = |entity| {
+            let stats = self.ecs_world.get::<Stats>(entity).cloned().unwrap_or_default();
+            let health = self.ecs_world.get::<Health>(entity).cloned()
+                .filter(Health::is_alive)
+                .ok_or_else(|| "Both sides need to be alive to fight".to_string())?;
+            Ok::<_, String>((stats, health))
+        };
+        let (attacker_stats, mut attacker_health) = combatant(attacker_entity)?;
+        let (defender_stats, mut defender_health) = combatant(defender_entity)?;
+        
+        let seed = self.tick_count ^ attacker.as_u128() as u64 ^ (defender.as_u128() as u64).rotate_left(32);
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let result = combat::fight(
+            &mut rng,
+            (attacker, &attacker_stats, &mut attacker_health),
+            (defender, &defender_stats, &mut defender_health),
+        );
+        
+        self.ecs_world.entity_mut(attacker_entity).insert(attacker_health);
+        self.ecs_world.entity_mut(defender_entity).insert(defender_health);
+        
+        if let Some(mut event_log) = self.ecs_world.get_resource_mut::<EventLog>() {
+            event_log.record(self.tick_count, GameEvent::CombatStarted { attacker, defender });
+            if let Some(winner) = result.winner {
+                let (loser, damage) = if winner == attacker {
+                    (defender, result.attacker_damage_dealt)
+                } else {
+                    (attacker, result.defender_damage_dealt)
+                };
+                event_log.record(self.tick_count, GameEvent::CombatResolved { winner, loser, damage });
+            }
+        }
+        
+        Ok(result)
+    }
+    
+    fn find_combatant(&mut self, id: Uuid) -> Option<bevy_ecs::entity::Entity> {
+        if let Some(character) = self.find_character(id) {
+            return Some(character);
+        }
+        let mut query = self.ecs_world.query_filtered::<(bevy_ecs::entity::Entity, &NpcId), bevy_ecs::query::With<IsNpc>>();
+        query.iter(&self.ecs_world)
+            .find(|(_, npc_id)| npc_id.0 == id)
+            .map(|(entity, _)| entity)
+    }
+    
+    /// Spawn an item lying on the floor of a room
+    pub fn spawn_item_in_room(&mut self, room_id: Uuid, name: String, description: String, item: Item) -> Uuid {
+        let item_id = Uuid::new_v4();
+        self.ecs_world.spawn((
+            Name(name),
+            Description(description),
+            item,
+            Position { room_id },
+            ItemId(item_id),
+            IsItem,
+        ));
+        item_id
+    }
+    
+    /// Get all items lying in a specific room
+    pub fn get_items_in_room(&mut self, room_id: Uuid) -> Vec<ItemInfo> {
+        let mut query = self.ecs_world.query_filtered::<(&ItemId, &Name, &Description, &Item, &Position), bevy_ecs::query::With<IsItem>>();
+        
+        query.iter(&self.ecs_world)
+            .filter(|(_, _, _, _, pos)| pos.room_id == room_id)
+            .map(|(id, name, desc, item, _)| ItemInfo::new(id.0, name, desc, item))
+            .collect()
+    }
+    
+    /// Get the items the player is carrying
+    pub fn get_player_inventory(&mut self) -> Vec<ItemInfo> {
+        let carried = {
+            let mut query = self.ecs_world.query_filtered::<&Inventory, MainPlayer>();
+            query.iter(&self.ecs_world).next().map(|inv| inv.items.clone()).unwrap_or_default()
+        };
+        
+        let mut query = self.ecs_world.query_filtered::<(&ItemId, &Name, &Description, &Item), bevy_ecs::query::With<IsItem>>();
+        carried.iter()
+            .filter_map(|item_id| {
+                query.iter(&self.ecs_world)
+                    .find(|(id, _, _, _)| id.0 == *item_id)
+                    .map(|(id, name, desc, item)| ItemInfo::new(id.0, name, desc, item))
+            })
+            .collect()
+    }
+    
+    /// Take an item from the player's current room into their inventory
+    pub fn pick_up_item(&mut self, item_id: Uuid) -> Result<(), String> {
+        let room_id = self.get_player_room()
+            .ok_or_else(|| "Player has no current room".to_string())?;
+        
+        let item_entity = self.find_item_entity(item_id)
+            .ok_or_else(|| "Item not found".to_string())?;
+        let in_room = self.ecs_world.get::<Position>(item_entity)
+            .is_some_and(|pos| pos.room_id == room_id);
+        if !in_room {
+            return Err("That item isn't here.".to_string());
+        }
+        
+        let mut query = self.ecs_world.query_filtered::<&mut Inventory, MainPlayer>();
+        let mut inventory = query.iter_mut(&mut self.ecs_world).next()
+            .ok_or_else(|| "Player has no inventory".to_string())?;
+        inventory.add_item(item_id)?;
         
-        // Record movement event first (before mutable borrow of query)
+        self.ecs_world.entity_mut(item_entity).remove::<Position>();
         if let Some(mut event_log) = self.ecs_world.get_resource_mut::<EventLog>() {
             event_log.record(
                 self.tick_count,
-                GameEvent::PlayerMoved {
-                    from_room: current_room_id,
-                    to_room: target_room_id,
-                    direction: direction.to_string(),
-                }
+                GameEvent::ItemPickedUp { item_id, player_id: PLAYER_ENTITY_ID }
             );
         }
         
-        // Update player position
-        let mut query = self.ecs_world.query_filtered::<(&mut Position, &mut Player), bevy_ecs::query::With<IsPlayer>>();
+        Ok(())
+    }
+    
+    /// Drop an item from the player's inventory into their current room
+    pub fn drop_item(&mut self, item_id: Uuid) -> Result<(), String> {
+        let room_id = self.get_player_room()
+            .ok_or_else(|| "Player has no current room".to_string())?;
+        
+        let item_entity = self.find_item_entity(item_id)
+            .ok_or_else(|| "Item not found".to_string())?;
         
-        if let Some((mut pos, mut player)) = query.iter_mut(&mut self.ecs_world).next() {
-            pos.room_id = target_room_id;
-            player.movement_history.push(target_room_id);
-            Ok(target_room_id)
-        } else {
-            Err("Player entity not found".to_string())
+        let mut query = self.ecs_world.query_filtered::<&mut Inventory, MainPlayer>();
+        let removed = query.iter_mut(&mut self.ecs_world).next()
+            .is_some_and(|mut inventory| inventory.remove_item(item_id));
+        if !removed {
+            return Err("You aren't carrying that.".to_string());
+        }
+        
+        self.ecs_world.entity_mut(item_entity).insert(Position { room_id });
+        if let Some(mut event_log) = self.ecs_world.get_resource_mut::<EventLog>() {
+            event_log.record(
+                self.tick_count,
+                GameEvent::ItemDropped { item_id, room_id }
+            );
         }
+        
+        Ok(())
     }
     
-    /// Get player's movement history
-    pub fn get_movement_history(&mut self) -> Vec<Uuid> {
-        let mut query = self.ecs_world.query_filtered::<&Player, bevy_ecs::query::With<IsPlayer>>();
-        
+    fn find_item_entity(&mut self, item_id: Uuid) -> Option<bevy_ecs::entity::Entity> {
+        let mut query = self.ecs_world.query_filtered::<(bevy_ecs::entity::Entity, &ItemId), bevy_ecs::query::With<IsItem>>();
+        query.iter(&self.ecs_world)
+            .find(|(_, id)| id.0 == item_id)
+            .map(|(entity, _)| entity)
+    }
+    
+    /// Get the player's qualities with their names and ranges
+    pub fn get_player_qualities(&self) -> Vec<Quality> {
+        self.ecs_world.get_resource::<StoryletManager>()
+            .map(|storylets| storylets.qualities_for(PLAYER_ENTITY_ID))
+            .unwrap_or_default()
+    }
+    
+    /// The player's skill levels by name; skills never practiced are absent
+    pub fn get_player_skills(&mut self) -> HashMap<String, i32> {
+        let mut query = self.ecs_world.query_filtered::<&Skills, MainPlayer>();
         query.iter(&self.ecs_world)
             .next()
-            .map(|player| player.movement_history.clone())
+            .map(|skills| skills.skills.clone())
             .unwrap_or_default()
     }
     
-    /// Query events by tag
-    pub fn query_events_by_tag(&self, tag: &str, limit: usize) -> Vec<EventRecord> {
-        if let Some(event_log) = self.ecs_world.get_resource::<EventLog>() {
-            event_log.query_by_tag(tag, limit)
-                .into_iter()
-                .cloned()
-                .collect()
-        } else {
-            Vec::new()
+    /// Raise one of the player's skills (capped at 100), recording the gain
+    /// Returns the new level, or None if there is no player
+    pub fn improve_player_skill(&mut self, skill: &str, amount: i32) -> Option<i32> {
+        self.improve_character_skill(PLAYER_ENTITY_ID, skill, amount)
+    }
+    
+    /// Raise a player-controlled character's skill (capped at 100), recording the gain
+    /// Returns the new level, or None if there is no such character
+    pub fn improve_character_skill(&mut self, character_id: Uuid, skill: &str, amount: i32) -> Option<i32> {
+        let entity = self.find_character(character_id)?;
+        
+        // Players from saves made before skills existed start untrained
+        let mut player = self.ecs_world.entity_mut(entity);
+        if !player.contains::<Skills>() {
+            player.insert(Skills::default());
+        }
+        let mut skills = player.get_mut::<Skills>()?;
+        let old_level = skills.get_skill(skill);
+        skills.improve_skill(skill, amount);
+        let new_level = skills.get_skill(skill);
+        
+        if new_level != old_level {
+            if let Some(mut event_log) = self.ecs_world.get_resource_mut::<EventLog>() {
+                event_log.record(self.tick_count, GameEvent::SkillImproved {
+                    entity_id: character_id,
+                    skill: skill.to_string(),
+                    old_level,
+                    new_level,
+                });
+            }
         }
+        Some(new_level)
     }
     
-    /// Query events in a specific room
-    pub fn query_events_in_room(&self, room_id: Uuid, limit: usize) -> Vec<EventRecord> {
-        if let Some(event_log) = self.ecs_world.get_resource::<EventLog>() {
-            event_log.query_in_room(room_id, limit)
-                .into_iter()
-                .cloned()
-                .collect()
-        } else {
-            Vec::new()
+    /// Strike up a conversation with an NPC in the given room, practicing persuasion
+    /// An empty target picks the first NPC present; otherwise names are matched
+    /// case-insensitively. None if nobody there fits
+    pub fn talk_to(&mut self, room_id: Uuid, target: &str) -> Option<NpcInfo> {
+        let target = target.trim().to_lowercase();
+        let npc = self.get_npcs_in_room(room_id)
+            .into_iter()
+            .find(|npc| target.is_empty() || npc.name.to_lowercase().contains(&target))?;
+        
+        self.improve_player_skill("persuasion", SKILL_GAIN_PER_ACTION);
+        Some(npc)
+    }
+    
+    /// Current in-game date and hour according to the world clock
+    pub fn get_world_time(&self) -> systems::GameTime {
+        self.ecs_world.get_resource::<systems::WorldClock>()
+            .map(|clock| clock.current_time.clone())
+            .unwrap_or_default()
+    }
+    
+    /// Part of the day according to the world clock
+    pub fn time_of_day(&self) -> systems::TimeOfDay {
+        self.ecs_world.get_resource::<systems::WorldClock>()
+            .map(|clock| clock.current_time.time_of_day())
+            .unwrap_or_else(|| systems::GameTime::default().time_of_day())
+    }
+    
+    /// An NPC's greeting, opened with a salutation for the current time of day
+    pub fn npc_greeting(&mut self, npc_id: Uuid) -> Option<String> {
+        let salutation = match self.time_of_day() {
+            systems::TimeOfDay::Dawn => "You're up early.",
+            systems::TimeOfDay::Morning => "Good morning!",
+            systems::TimeOfDay::Afternoon => "Good afternoon.",
+            systems::TimeOfDay::Dusk => "Good evening.",
+            systems::TimeOfDay::Night => "You're about late tonight.",
+        };
+        let mut query = self.ecs_world.query_filtered::<(&NpcId, &Npc), bevy_ecs::query::With<IsNpc>>();
+        query.iter(&self.ecs_world)
+            .find(|(id, _)| id.0 == npc_id)
+            .map(|(_, npc)| format!("{} {}", salutation, npc.greeting))
+    }
+    
+    /// Look an NPC in the given room over without speaking: their description and
+    /// apparent mood. Names match case-insensitively; None if nobody there fits
+    pub fn examine_npc(&mut self, room_id: Uuid, target: &str) -> Option<String> {
+        let target = target.trim().to_lowercase();
+        if target.is_empty() {
+            return None;
         }
+        let npc = self.get_npcs_in_room(room_id)
+            .into_iter()
+            .find(|npc| npc.name.to_lowercase().contains(&target))?;
+        
+        let mood = npc_mood(&npc, &self.query_events_by_tag("player", 20));
+        Some(format!("{}\n\n{}\n\n{} seems {}.", npc.name, npc.description, npc.name, mood))
     }
     
-    /// Get all events since a specific tick
-    pub fn get_events_since(&self, tick: u64) -> Vec<EventRecord> {
-        if let Some(event_log) = self.ecs_world.get_resource::<EventLog>() {
-            event_log.query_since_tick(tick)
-                .into_iter()
-                .cloned()
-                .collect()
-        } else {
-            Vec::new()
+    /// Set one of the player's qualities, clamped to its registered range
+    /// Returns the value actually stored
+    pub fn set_player_quality(&mut self, quality_id: &str, value: i32) -> Result<i32, String> {
+        let mut storylets = self.ecs_world.get_resource_mut::<StoryletManager>()
+            .ok_or_else(|| "Storylets are not initialized".to_string())?;
+        
+        storylets.set_quality(PLAYER_ENTITY_ID, quality_id.to_string(), value);
+        Ok(storylets.get_quality(PLAYER_ENTITY_ID, quality_id))
+    }
+    
+    /// Get a room's terrain binding, if it has been placed on the world map
+    pub fn get_room_terrain_binding(&mut self, room_id: Uuid) -> Option<RoomTerrainBinding> {
+        let mut query = self.ecs_world.query_filtered::<(&RoomId, &RoomTerrainBinding), bevy_ecs::query::With<IsRoom>>();
+        query.iter(&self.ecs_world)
+            .find(|(id, _)| id.0 == room_id)
+            .map(|(_, binding)| binding.clone())
+    }
+    
+    /// Place a room on the world map, replacing any existing binding
+    pub fn bind_room_terrain(&mut self, room_id: Uuid, binding: RoomTerrainBinding) -> Result<(), String> {
+        let mut query = self.ecs_world.query_filtered::<(bevy_ecs::entity::Entity, &RoomId), bevy_ecs::query::With<IsRoom>>();
+        let entity = query.iter(&self.ecs_world)
+            .find(|(_, id)| id.0 == room_id)
+            .map(|(entity, _)| entity)
+            .ok_or_else(|| "Room not found".to_string())?;
+        
+        self.ecs_world.entity_mut(entity).insert(binding);
+        Ok(())
+    }
+    
+    /// Add an unconnected room to the world, keeping the room registry in sync
+    pub fn create_room(&mut self, name: String, description: String) -> Uuid {
+        let room_id = Uuid::new_v4();
+        self.ecs_world.spawn((
+            Name(name.clone()),
+            Description(description),
+            Room { exits: Vec::new() },
+            RoomId(room_id),
+            IsRoom,
+        ));
+        self.room_registry.insert(room_id, name);
+        room_id
+    }
+    
+    /// Link two rooms with an exit each way
+    /// Fails without changing either room if a direction is already taken
+    pub fn connect_rooms(&mut self, from: Uuid, to: Uuid, direction: &str, reverse_direction: &str) -> Result<(), String> {
+        if from == to {
+            return Err("A room can't connect to itself".to_string());
+        }
+        
+        for (room_id, dir) in [(from, direction), (to, reverse_direction)] {
+            let room = self.get_room_details(room_id)
+                .ok_or_else(|| "Room not found".to_string())?;
+            if room.exits.iter().any(|exit| exit.direction == dir) {
+                return Err(format!("{} already has an exit {}", room.name, dir));
+            }
+        }
+        
+        let mut query = self.ecs_world.query_filtered::<(&RoomId, &mut Room), bevy_ecs::query::With<IsRoom>>();
+        for (id, mut room) in query.iter_mut(&mut self.ecs_world) {
+            let (target_room_id, dir) = match id.0 {
+                id if id == from => (to, direction),
+                id if id == to => (from, reverse_direction),
+                _ => continue,
+            };
+            room.exits.push(Exit {
+                direction: dir.to_string(),
+                target_room_id,
+                description: None,
+            });
+        }
+        
+        Ok(())
+    }
+    
+    /// Check every room's exits for authoring mistakes: exits into rooms that don't
+    /// exist, and exits the target room has no way back from. Sorted by room and direction
+    pub fn validate_world(&mut self) -> Vec<WorldWarning> {
+        let mut query = self.ecs_world.query_filtered::<(&RoomId, &Room), bevy_ecs::query::With<IsRoom>>();
+        let exits: HashMap<Uuid, Vec<Exit>> = query.iter(&self.ecs_world)
+            .map(|(id, room)| (id.0, room.exits.clone()))
+            .collect();
+        
+        let mut warnings = Vec::new();
+        for (&room_id, room_exits) in &exits {
+            for exit in room_exits {
+                let target_room_id = exit.target_room_id;
+                let direction = exit.direction.clone();
+                match exits.get(&target_room_id) {
+                    None => warnings.push(WorldWarning::DanglingExit { room_id, direction, target_room_id }),
+                    Some(back) if !back.iter().any(|e| e.target_room_id == room_id) => {
+                        warnings.push(WorldWarning::OneWayExit { room_id, direction, target_room_id })
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+        warnings.sort_by_key(|warning| {
+            let (room_id, direction) = warning.exit();
+            (self.room_registry.get(&room_id).cloned(), direction.to_string())
+        });
+        warnings
+    }
+    
+    /// Rename and re-describe a room, keeping the room registry in sync
+    pub fn update_room(&mut self, room_id: Uuid, name: String, description: String) -> Result<(), String> {
+        let mut query = self.ecs_world.query_filtered::<(&RoomId, &mut Name, &mut Description), bevy_ecs::query::With<IsRoom>>();
+        
+        let (_, mut room_name, mut room_description) = query.iter_mut(&mut self.ecs_world)
+            .find(|(id, _, _)| id.0 == room_id)
+            .ok_or_else(|| "Room not found".to_string())?;
+        
+        room_name.0 = name.clone();
+        room_description.0 = description;
+        self.room_registry.insert(room_id, name);
+        
+        Ok(())
+    }
+    
+    /// Update an NPC's name, description, personality, and greeting
+    pub fn update_npc(
+        &mut self,
+        npc_id: Uuid,
+        name: String,
+        description: String,
+        personality: String,
+        greeting: String,
+    ) -> Result<(), String> {
+        let mut query = self.ecs_world.query_filtered::<(&NpcId, &mut Name, &mut Description, &mut Npc), bevy_ecs::query::With<IsNpc>>();
+        
+        let (_, mut npc_name, mut npc_description, mut npc) = query.iter_mut(&mut self.ecs_world)
+            .find(|(id, _, _, _)| id.0 == npc_id)
+            .ok_or_else(|| "NPC not found".to_string())?;
+        
+        npc_name.0 = name;
+        npc_description.0 = description;
+        npc.personality = personality;
+        npc.greeting = greeting;
+        
+        Ok(())
+    }
+    
+    /// Move player in a direction
+    pub fn move_player(&mut self, direction: &str) -> Result<Uuid, String> {
+        self.move_character(PLAYER_ENTITY_ID, direction)
+    }
+    
+    /// Move the player to an adjacent room by id, as when clicking it on the map
+    pub fn move_player_to(&mut self, room_id: Uuid) -> Result<Uuid, String> {
+        self.move_character_to(PLAYER_ENTITY_ID, room_id)
+    }
+    
+    /// Move a character to an adjacent room by id, through whichever exit leads there
+    /// Rooms with no exit from the character's current room are rejected
+    pub fn move_character_to(&mut self, character_id: Uuid, room_id: Uuid) -> Result<Uuid, String> {
+        let current_room_id = self.get_character_room(character_id)
+            .ok_or_else(|| "Character has no current room".to_string())?;
+        let room = self.get_room_details(current_room_id)
+            .ok_or_else(|| "Current room not found".to_string())?;
+        
+        let direction = room.exits.iter()
+            .find(|e| e.target_room_id == room_id)
+            .map(|e| e.direction.clone())
+            .ok_or_else(|| "You can't get there from here.".to_string())?;
+        
+        self.move_character(character_id, &direction)
+    }
+    
+    /// Move one player-controlled character through an exit; the rest of the party stays put
+    pub fn move_character(&mut self, character_id: Uuid, direction: &str) -> Result<Uuid, String> {
+        // Get current room
+        let current_room_id = self.get_character_room(character_id)
+            .ok_or_else(|| "Character has no current room".to_string())?;
+        
+        // Get room details to check exits
+        let room = self.get_room_details(current_room_id)
+            .ok_or_else(|| "Current room not found".to_string())?;
+        
+        // Find matching exit
+        let exit = room.exits.iter()
+            .find(|e| e.direction == direction)
+            .ok_or_else(|| format!("You can't go {} from here.", direction))?;
+        
+        let target_room_id = exit.target_room_id;
+        
+        // Verify target room exists
+        self.get_room_details(target_room_id)
+            .ok_or_else(|| "Target room not found (world error)".to_string())?;
+        
+        self.relocate_character(character_id, current_room_id, target_room_id, direction)?;
+        self.apply_terrain_travel(current_room_id, target_room_id);
+        self.improve_character_skill(character_id, "navigation", SKILL_GAIN_PER_ACTION);
+        
+        Ok(target_room_id)
+    }
+    
+    /// Estimate the in-game hours to walk between two rooms along exits, pricing each
+    /// hop with hop_travel. None if either room is missing or unreachable
+    pub fn travel_time(&mut self, from_room: Uuid, to_room: Uuid) -> Option<TravelEstimate> {
+        let mut query = self.ecs_world.query_filtered::<(&RoomId, &Room, Option<&RoomTerrainBinding>), bevy_ecs::query::With<IsRoom>>();
+        let mut exits: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        let mut bindings: HashMap<Uuid, RoomTerrainBinding> = HashMap::new();
+        for (id, room, binding) in query.iter(&self.ecs_world) {
+            exits.insert(id.0, room.exits.iter().map(|e| e.target_room_id).collect());
+            if let Some(binding) = binding {
+                bindings.insert(id.0, binding.clone());
+            }
+        }
+        
+        if !exits.contains_key(&from_room) || !exits.contains_key(&to_room) {
+            return None;
+        }
+        
+        let hop = |from: &Uuid, to: &Uuid| hop_travel(bindings.get(from), bindings.get(to));
+        
+        // Dijkstra needs integer costs, so search in whole minutes
+        let (path, _) = dijkstra(
+            &from_room,
+            |room| {
+                exits.get(room)
+                    .into_iter()
+                    .flatten()
+                    .filter(|target| exits.contains_key(target))
+                    .map(|target| (*target, (hop(room, target).1 * 60.0).round() as u64))
+                    .collect::<Vec<_>>()
+            },
+            |room| *room == to_room,
+        )?;
+        
+        let (distance_meters, hours) = path.windows(2)
+            .map(|pair| hop(&pair[0], &pair[1]))
+            .fold((0.0, 0.0), |(d, h), (hop_d, hop_h)| (d + hop_d, h + hop_h));
+        
+        Some(TravelEstimate {
+            hops: path.len() - 1,
+            path,
+            distance_meters,
+            hours,
+        })
+    }
+    
+    /// Fewest-exits route between two rooms, both ends included, ignoring terrain
+    /// This is the route scheduled NPCs walk; None if either room is missing or unreachable
+    pub fn find_room_path(&mut self, from_room: Uuid, to_room: Uuid) -> Option<Vec<Uuid>> {
+        let mut query = self.ecs_world.query_filtered::<(&RoomId, &Room), bevy_ecs::query::With<IsRoom>>();
+        let exits: HashMap<Uuid, Vec<Uuid>> = query.iter(&self.ecs_world)
+            .map(|(id, room)| (id.0, room.exits.iter().map(|e| e.target_room_id).collect()))
+            .collect();
+        systems::room_path(&exits, from_room, to_room)
+    }
+    
+    /// Spend the hop's travel time (as travel_time prices it, rounded to whole hours) and
+    /// roll for an encounter at the biome's chance when entering a terrain-bound room
+    /// Rooms without a RoomTerrainBinding biome are unaffected
+    fn apply_terrain_travel(&mut self, from_room: Uuid, room_id: Uuid) {
+        let from = self.get_room_terrain_binding(from_room);
+        let to = self.get_room_terrain_binding(room_id);
+        let biome = to.as_ref()
+            .and_then(|binding| binding.biome.as_deref().and_then(Biome::from_name));
+        
+        let Some(biome) = biome else {
+            return;
+        };
+        
+        let hours = hop_travel(from.as_ref(), to.as_ref()).1.round().max(1.0) as u32;
+        if let Some(mut clock) = self.ecs_world.get_resource_mut::<systems::WorldClock>() {
+            clock.current_time.advance(hours);
+        }
+        
+        self.roll_encounter(room_id, biome_encounter_chance(biome));
+    }
+    
+    /// Draw a weighted "encounter" storylet with the given chance and record it for the room
+    /// Seeded from the tick and room, so the same arrival always rolls the same way
+    fn roll_encounter(&mut self, room_id: Uuid, chance: f32) {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.tick_count ^ room_id.as_u128() as u64);
+        if rng.random::<f32>() >= chance {
+            return;
+        }
+        
+        let storylet_id = self.ecs_world.get_resource::<StoryletManager>()
+            .and_then(|manager| manager.pick_weighted(PLAYER_ENTITY_ID, "encounter", &mut rng))
+            .map(|storylet| storylet.id.clone());
+        
+        if let Some(storylet_id) = storylet_id {
+            if let Some(mut event_log) = self.ecs_world.get_resource_mut::<EventLog>() {
+                event_log.record(
+                    self.tick_count,
+                    GameEvent::EncounterTriggered { room_id, storylet_id }
+                );
+            }
+        }
+    }
+    
+    /// Move the player directly to a room without needing a connecting exit
+    /// When `require_discovered` is set, only visited or known rooms are valid
+    /// destinations (fast travel); otherwise any existing room is (debug teleport)
+    pub fn teleport_player(&mut self, room_id: Uuid, require_discovered: bool) -> Result<Uuid, String> {
+        let current_room_id = self.get_player_room()
+            .ok_or_else(|| "Player has no current room".to_string())?;
+        
+        self.get_room_details(room_id)
+            .ok_or_else(|| "Target room not found".to_string())?;
+        
+        if require_discovered {
+            let exploration = self.get_explored_rooms();
+            if !exploration.visited.contains(&room_id) && !exploration.known.contains(&room_id) {
+                return Err("You haven't discovered that place yet.".to_string());
+            }
+        }
+        
+        self.relocate_character(PLAYER_ENTITY_ID, current_room_id, room_id, "teleport")
+    }
+    
+    /// Record a movement event and update a character's position
+    fn relocate_character(&mut self, character_id: Uuid, from_room: Uuid, to_room: Uuid, direction: &str) -> Result<Uuid, String> {
+        let entity = self.find_character(character_id)
+            .ok_or_else(|| "Player entity not found".to_string())?;
+        
+        // Record movement event first (before mutable borrow of the character)
+        if let Some(mut event_log) = self.ecs_world.get_resource_mut::<EventLog>() {
+            event_log.record(
+                self.tick_count,
+                GameEvent::PlayerMoved {
+                    character_id,
+                    from_room,
+                    to_room,
+                    direction: direction.to_string(),
+                }
+            );
+        }
+        
+        // Update character position
+        let mut character = self.ecs_world.entity_mut(entity);
+        if let Some(mut pos) = character.get_mut::<Position>() {
+            pos.room_id = to_room;
+        } else {
+            character.insert(Position { room_id: to_room });
+        }
+        if let Some(mut player) = character.get_mut::<Player>() {
+            player.movement_history.push(to_room);
+        }
+        Ok(to_room)
+    }
+    
+    /// Get player's movement history
+    pub fn get_movement_history(&mut self) -> Vec<Uuid> {
+        let mut query = self.ecs_world.query_filtered::<&Player, MainPlayer>();
+        
+        query.iter(&self.ecs_world)
+            .next()
+            .map(|player| player.movement_history.clone())
+            .unwrap_or_default()
+    }
+    
+    /// Retrace the player's last step along their route, if an exit still leads back
+    /// Each call goes one step further back; the movement history itself is kept whole
+    /// (it's also the record of explored rooms), with doubled-back steps cancelled out
+    pub fn go_back(&mut self) -> Result<Uuid, String> {
+        let route = retraceable_route(&self.get_movement_history());
+        let [.., previous, _] = route[..] else {
+            return Err("There's nowhere to go back to.".to_string());
+        };
+        self.move_player_to(previous)
+            .map_err(|_| "The way you came is closed.".to_string())
+    }
+    
+    /// Get the rooms the player has visited and the unvisited rooms adjacent to them
+    /// Used by the map to reveal explored areas (fog-of-war)
+    pub fn get_explored_rooms(&mut self) -> ExplorationState {
+        let mut visited: Vec<Uuid> = Vec::new();
+        for room_id in self.get_movement_history() {
+            if !visited.contains(&room_id) {
+                visited.push(room_id);
+            }
+        }
+        
+        let mut known: Vec<Uuid> = Vec::new();
+        let mut query = self.ecs_world.query_filtered::<(&RoomId, &Room), bevy_ecs::query::With<IsRoom>>();
+        for (id, room) in query.iter(&self.ecs_world) {
+            if !visited.contains(&id.0) {
+                continue;
+            }
+            for exit in &room.exits {
+                if !visited.contains(&exit.target_room_id) && !known.contains(&exit.target_room_id) {
+                    known.push(exit.target_room_id);
+                }
+            }
+        }
+        
+        ExplorationState { visited, known }
+    }
+    
+    /// Query events by tag
+    pub fn query_events_by_tag(&self, tag: &str, limit: usize) -> Vec<EventRecord> {
+        if let Some(event_log) = self.ecs_world.get_resource::<EventLog>() {
+            event_log.query_by_tag(tag, limit)
+                .into_iter()
+                .cloned()
+                .collect()
+        } else {
+            Vec::new()
+        }
+    }
+    
+    /// Query events in a specific room
+    pub fn query_events_in_room(&self, room_id: Uuid, limit: usize) -> Vec<EventRecord> {
+        if let Some(event_log) = self.ecs_world.get_resource::<EventLog>() {
+            event_log.query_in_room(room_id, limit)
+                .into_iter()
+                .cloned()
+                .collect()
+        } else {
+            Vec::new()
+        }
+    }
+    
+    /// Get all events since a specific tick
+    pub fn get_events_since(&self, tick: u64) -> Vec<EventRecord> {
+        if let Some(event_log) = self.ecs_world.get_resource::<EventLog>() {
+            event_log.query_since_tick(tick)
+                .into_iter()
+                .cloned()
+                .collect()
+        } else {
+            Vec::new()
         }
     }
 }
 
-/// Serializable room details for sending to frontend
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct RoomDetails {
-    pub id: Uuid,
-    pub name: String,
-    pub description: String,
-    pub exits: Vec<Exit>,
-}
-
-/// Serializable NPC info for sending to frontend
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct NpcInfo {
-    pub name: String,
-    pub description: String,
-    pub personality: String,
-    pub greeting: String,
-}
-
+/// Serializable room details for sending to frontend
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RoomDetails {
+    pub id: Uuid,
+    pub name: String,
+    pub description: String,
+    pub exits: Vec<Exit>,
+}
+
+/// Serializable NPC info for sending to frontend
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NpcInfo {
+    pub id: Uuid,
+    pub name: String,
+    pub description: String,
+    pub personality: String,
+    pub greeting: String,
+}
+
+/// Serializable item info for sending to frontend
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ItemInfo {
+    pub id: Uuid,
+    pub name: String,
+    pub description: String,
+    pub item_type: String,
+    pub weight: f32,
+    pub value: i32,
+    pub stack_count: u32,
+}
+
+impl ItemInfo {
+    fn new(id: Uuid, name: &Name, description: &Description, item: &Item) -> Self {
+        Self {
+            id,
+            name: name.0.clone(),
+            description: description.0.clone(),
+            item_type: item.item_type.clone(),
+            weight: item.weight,
+            value: item.value,
+            stack_count: item.stack_count,
+        }
+    }
+}
+
+/// Problem with a room's exits found by `GameWorld::validate_world`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum WorldWarning {
+    /// The exit leads to a room that doesn't exist
+    DanglingExit { room_id: Uuid, direction: String, target_room_id: Uuid },
+    /// The target room has no exit leading back
+    OneWayExit { room_id: Uuid, direction: String, target_room_id: Uuid },
+}
+
+impl WorldWarning {
+    /// Room and direction of the offending exit
+    pub fn exit(&self) -> (Uuid, &str) {
+        match self {
+            WorldWarning::DanglingExit { room_id, direction, .. }
+            | WorldWarning::OneWayExit { room_id, direction, .. } => (*room_id, direction),
+        }
+    }
+}
+
+/// Serializable party member info for sending to frontend
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PartyMember {
+    pub id: Uuid,
+    pub name: String,
+    pub room_id: Uuid,
+}
+
+/// Serializable shop price for sending to frontend
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ShopPrice {
+    pub item_type: String,
+    pub price: i32,
+    pub base_price: i32,
+    pub stock: u32,
+}
+
+/// Estimated walking route between two rooms
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TravelEstimate {
+    pub path: Vec<Uuid>,
+    pub hops: usize,
+    pub distance_meters: f32,
+    pub hours: f32,
+}
+
+/// Rooms revealed to the player for fog-of-war rendering
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ExplorationState {
+    pub visited: Vec<Uuid>,
+    pub known: Vec<Uuid>,
+}
+
+/// The route a movement history describes, ending at the current room, with every
+/// step straight back into the room just left cancelling the step before it
+fn retraceable_route(history: &[Uuid]) -> Vec<Uuid> {
+    let mut route: Vec<Uuid> = Vec::new();
+    for &room in history {
+        if route.len() >= 2 && route[route.len() - 2] == room {
+            route.pop();
+        } else {
+            route.push(room);
+        }
+    }
+    route
+}
+
+/// Calculate NPC mood based on recent events and personality
+/// Shared by dialogue context assembly and the player's `examine` action
+pub fn npc_mood(npc: &NpcInfo, events: &[EventRecord]) -> String {
+    // Mood calculation based on:
+    // - Base personality traits
+    // - Recent events affecting the NPC
+    // - Current time of day (future enhancement)
+    // - NPC needs/stress (future: Dwarf Fortress style)
+    
+    let mut mood_score = 0;
+    
+    // Base mood from personality
+    let personality_lower = npc.personality.to_lowercase();
+    if personality_lower.contains("friendly") || personality_lower.contains("welcoming") {
+        mood_score += 20;
+    } else if personality_lower.contains("grumpy") || personality_lower.contains("hostile") {
+        mood_score -= 20;
+    }
+    
+    // Adjust based on recent events
+    for event in events.iter().take(5) {
+        // Check if event involves this NPC (by name matching in tags)
+        if event.tags.iter().any(|tag| tag.to_lowercase().contains(&npc.name.to_lowercase())) {
+            // Positive events
+            if event.tags.contains(&"dialogue".to_string()) {
+                mood_score += 5; // Conversation improves mood
+            }
+            // Negative events would decrease mood_score
+        }
+    }
+    
+    // Convert score to descriptive mood
+    if mood_score > 30 {
+        "cheerful and welcoming"
+    } else if mood_score > 10 {
+        "friendly and approachable"
+    } else if mood_score > -10 {
+        "neutral and professional"
+    } else if mood_score > -30 {
+        "reserved and cautious"
+    } else {
+        "hostile and suspicious"
+    }.to_string()
+}
+
 /// Thread-safe shared reference to the game world
 pub type SharedWorld = Arc<Mutex<GameWorld>>;
 
@@ -374,3 +1499,893 @@ pub type SharedWorld = Arc<Mutex<GameWorld>>;
 pub fn create_shared_world() -> SharedWorld {
     Arc::new(Mutex::new(GameWorld::new()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::storylets::Storylet;
+
+    #[test]
+    fn test_explored_rooms_after_movement() {
+        let mut world = GameWorld::new();
+        let inn = world.get_player_room().unwrap();
+        
+        let square = world.move_player("north").unwrap();
+        let merchant = world.move_player("east").unwrap();
+        
+        let exploration = world.get_explored_rooms();
+        assert_eq!(exploration.visited, vec![inn, square, merchant]);
+        
+        // The forge is reachable from the square but hasn't been visited
+        assert_eq!(exploration.known.len(), 1);
+        let forge = exploration.known[0];
+        assert_eq!(world.get_room_details(forge).unwrap().name, "Blacksmith's Forge");
+    }
+
+    #[test]
+    fn test_describe_room_at_night_in_storm() {
+        let mut world = GameWorld::new();
+        let inn = world.get_player_room().unwrap();
+        let base = world.get_room_details(inn).unwrap().description;
+        
+        world.ecs_world.resource_mut::<systems::WorldClock>().current_time.hour = 23;
+        world.set_weather(systems::Weather::Storm);
+        
+        let description = world.describe_room(inn).unwrap();
+        assert!(description.starts_with(&base));
+        assert!(description.contains("Moonlight"));
+        assert!(description.contains("thunder"));
+    }
+
+    #[test]
+    fn test_teleport_player_to_forge() {
+        let mut world = GameWorld::new();
+        let forge = world.room_registry.iter()
+            .find(|(_, name)| name.as_str() == "Blacksmith's Forge")
+            .map(|(id, _)| *id)
+            .unwrap();
+        
+        // The forge hasn't been discovered from the inn, so fast travel refuses
+        assert!(world.teleport_player(forge, true).is_err());
+        
+        assert_eq!(world.teleport_player(forge, false), Ok(forge));
+        assert_eq!(world.get_player_room(), Some(forge));
+        
+        let events = world.query_events_in_room(forge, 1);
+        assert!(matches!(
+            &events[0].event,
+            GameEvent::PlayerMoved { direction, .. } if direction == "teleport"
+        ));
+    }
+
+    #[test]
+    fn test_empty_world_has_no_content() {
+        let mut world = GameWorld::empty();
+        
+        let mut rooms = world.ecs_world.query_filtered::<&RoomId, bevy_ecs::query::With<IsRoom>>();
+        assert_eq!(rooms.iter(&world.ecs_world).count(), 0);
+        assert!(world.room_registry.is_empty());
+        assert_eq!(world.get_player_room(), None);
+    }
+
+    #[test]
+    fn test_simulate_days_produces_digest() {
+        let mut world = GameWorld::new();
+        
+        let digest = world.simulate_days(3);
+        
+        assert_eq!(world.tick_count, 72);
+        assert!(digest.len() > 1);
+        assert!(digest.iter().any(|line| line.contains("weather")));
+    }
+
+    #[test]
+    fn test_digest_names_moving_npcs() {
+        use crate::simulation::components::Schedule as NpcSchedule;
+        
+        let mut world = GameWorld::empty();
+        let home = world.create_room("Cottage".to_string(), "A small cottage.".to_string());
+        let market = world.create_room("Market".to_string(), "A busy market.".to_string());
+        world.ecs_world.spawn((
+            Name("Bram the Baker".to_string()),
+            Position { room_id: home },
+            NpcSchedule {
+                packages: vec![SchedulePackage {
+                    priority: 1,
+                    condition: ScheduleCondition::TimeRange { start_hour: 8, end_hour: 12 },
+                    action: ScheduleAction::MoveToRoom { room_id: market },
+                }],
+            },
+            NpcId(Uuid::new_v4()),
+            IsNpc,
+        ));
+        
+        let digest = world.simulate_days(1);
+        
+        assert!(digest.iter().any(|line| line.ends_with("Bram the Baker moved to Market.")), "{:?}", digest);
+    }
+
+    #[test]
+    fn test_update_room_and_npc() {
+        let mut world = GameWorld::new();
+        let inn = world.get_player_room().unwrap();
+        
+        world.update_room(inn, "The Rusty Tankard".to_string(), "A renamed tavern.".to_string()).unwrap();
+        let room = world.get_room_details(inn).unwrap();
+        assert_eq!(room.name, "The Rusty Tankard");
+        assert_eq!(room.description, "A renamed tavern.");
+        assert_eq!(world.room_registry[&inn], "The Rusty Tankard");
+        
+        let innkeeper = world.get_npcs_in_room(inn)[0].id;
+        world.update_npc(
+            innkeeper,
+            "Greta".to_string(),
+            "A new innkeeper.".to_string(),
+            "Brisk".to_string(),
+            "Hello.".to_string(),
+        ).unwrap();
+        assert_eq!(world.get_npcs_in_room(inn)[0].name, "Greta");
+        
+        assert!(world.update_room(Uuid::new_v4(), String::new(), String::new()).is_err());
+    }
+
+    #[test]
+    fn test_spawn_and_despawn_npc() {
+        let mut world = GameWorld::new();
+        let inn = world.get_player_room().unwrap();
+        
+        let bard = world.spawn_npc(
+            "Lirael the Bard".to_string(),
+            "A wandering minstrel with a battered lute.".to_string(),
+            "Cheerful and curious".to_string(),
+            "A song for a coin?".to_string(),
+            inn,
+        );
+        let npcs = world.get_npcs_in_room(inn);
+        assert_eq!(npcs.len(), 2);
+        assert!(npcs.iter().any(|npc| npc.id == bard && npc.name == "Lirael the Bard"));
+        
+        let log = world.ecs_world.resource::<EventLog>();
+        assert!(log.query_in_room(inn, 10).iter().any(|e| matches!(e.event, GameEvent::NpcSpawned { npc_id, .. } if npc_id == bard)));
+        
+        assert!(world.despawn_npc(bard));
+        assert!(!world.despawn_npc(bard));
+        assert_eq!(world.get_npcs_in_room(inn).len(), 1);
+    }
+
+    #[test]
+    fn test_create_and_connect_rooms() {
+        let mut world = GameWorld::empty();
+        let hall = world.create_room("Hall".to_string(), "A draughty hall.".to_string());
+        let tower = world.create_room("Tower".to_string(), "A narrow tower room.".to_string());
+        world.ecs_world.spawn((
+            Position { room_id: hall },
+            Player { current_input: String::new(), movement_history: vec![hall] },
+            IsPlayer,
+        ));
+        
+        world.connect_rooms(hall, tower, "north", "south").unwrap();
+        assert_eq!(world.room_registry[&tower], "Tower");
+        
+        assert_eq!(world.move_player("north").unwrap(), tower);
+        assert_eq!(world.move_player("south").unwrap(), hall);
+        
+        // Both directions are now taken
+        let cellar = world.create_room("Cellar".to_string(), "Damp stone.".to_string());
+        assert!(world.connect_rooms(hall, cellar, "north", "up").is_err());
+        assert!(world.connect_rooms(cellar, tower, "up", "south").is_err());
+        assert!(world.get_room_details(cellar).unwrap().exits.is_empty());
+    }
+
+    #[test]
+    fn test_schedule_moves_npc_during_time_range() {
+        use crate::simulation::components::Schedule as NpcSchedule;
+        
+        let mut world = GameWorld::empty();
+        let home = world.create_room("Cottage".to_string(), "A small cottage.".to_string());
+        let market = world.create_room("Market".to_string(), "A busy market.".to_string());
+        let baker = Uuid::new_v4();
+        world.ecs_world.spawn((
+            Position { room_id: home },
+            NpcSchedule {
+                packages: vec![SchedulePackage {
+                    priority: 1,
+                    condition: ScheduleCondition::TimeRange { start_hour: 8, end_hour: 12 },
+                    action: ScheduleAction::MoveToRoom { room_id: market },
+                }],
+            },
+            NpcId(baker),
+            IsNpc,
+        ));
+        let npc_room = |world: &mut GameWorld| {
+            let mut query = world.ecs_world.query::<(&NpcId, &Position)>();
+            query.iter(&world.ecs_world).find(|(id, _)| id.0 == baker).unwrap().1.room_id
+        };
+        
+        // The clock starts at midnight and advances an hour per tick
+        for _ in 0..7 {
+            world.tick();
+        }
+        assert_eq!(npc_room(&mut world), home);
+        
+        world.tick();
+        assert_eq!(npc_room(&mut world), market);
+        let log = world.ecs_world.resource::<EventLog>();
+        assert!(log.query_in_room(market, 10).iter().any(|e| matches!(e.event, GameEvent::NpcMoved { npc_id, .. } if npc_id == baker)));
+    }
+
+    #[test]
+    fn test_lod_follows_the_player() {
+        use crate::simulation::lod::SimulationDetail;
+        
+        let mut world = GameWorld::new();
+        let inn = world.get_player_room().unwrap();
+        world.tick();
+        assert_eq!(world.ecs_world.resource::<LodManager>().determine_lod(inn), SimulationDetail::Full);
+        
+        let square = world.move_player("north").unwrap();
+        world.tick();
+        let lod = world.ecs_world.resource::<LodManager>();
+        assert_eq!(lod.determine_lod(square), SimulationDetail::Full);
+        assert_eq!(lod.determine_lod(inn), SimulationDetail::Reduced);
+        
+        // Events from systems and from GameWorld share the world's tick
+        assert_eq!(world.ecs_world.resource::<systems::WorldClock>().ticks_elapsed, world.tick_count);
+    }
+
+    #[test]
+    fn test_npc_walks_room_by_room_to_goal() {
+        use crate::simulation::components::Schedule as NpcSchedule;
+        
+        let mut world = GameWorld::new();
+        // Simulate every room at full detail so the walk isn't staggered
+        world.ecs_world.remove_resource::<LodManager>();
+        let room_named = |world: &GameWorld, name: &str| {
+            *world.room_registry.iter().find(|(_, n)| n.as_str() == name).unwrap().0
+        };
+        let forge = room_named(&world, "Blacksmith's Forge");
+        let square = room_named(&world, "Town Square");
+        let market = room_named(&world, "Merchant District");
+        assert_eq!(world.find_room_path(forge, market), Some(vec![forge, square, market]));
+        
+        let smith = world.get_npcs_in_room(forge)[0].id;
+        let npc_room = |world: &mut GameWorld| {
+            let mut query = world.ecs_world.query::<(&NpcId, &Position)>();
+            query.iter(&world.ecs_world).find(|(id, _)| id.0 == smith).unwrap().1.room_id
+        };
+        let mut query = world.ecs_world.query::<(bevy_ecs::entity::Entity, &NpcId)>();
+        let entity = query.iter(&world.ecs_world).find(|(_, id)| id.0 == smith).unwrap().0;
+        world.ecs_world.entity_mut(entity).insert(NpcSchedule {
+            packages: vec![SchedulePackage {
+                priority: 1,
+                condition: ScheduleCondition::Always,
+                action: ScheduleAction::TravelToRoom { room_id: market },
+            }],
+        });
+        
+        world.tick();
+        assert_eq!(npc_room(&mut world), square);
+        world.tick();
+        assert_eq!(npc_room(&mut world), market);
+        world.tick();
+        assert_eq!(npc_room(&mut world), market);
+        
+        // A room with no way in is never reached
+        let island = world.create_room("Island".to_string(), "Cut off by the tide.".to_string());
+        assert_eq!(world.find_room_path(market, island), None);
+        world.ecs_world.entity_mut(entity).insert(NpcSchedule {
+            packages: vec![SchedulePackage {
+                priority: 1,
+                condition: ScheduleCondition::Always,
+                action: ScheduleAction::TravelToRoom { room_id: island },
+            }],
+        });
+        world.tick();
+        assert_eq!(npc_room(&mut world), market);
+    }
+
+    #[test]
+    fn test_talking_and_walking_train_skills() {
+        let mut world = GameWorld::new();
+        assert!(world.get_player_skills().is_empty());
+        let inn = world.get_player_room().unwrap();
+        
+        for _ in 0..3 {
+            assert_eq!(world.talk_to(inn, "gareth").unwrap().name, "Gareth the Innkeeper");
+        }
+        assert!(world.talk_to(inn, "kael").is_none());
+        let exits = world.get_room_details(inn).unwrap().exits;
+        world.move_player(&exits[0].direction).unwrap();
+        
+        let skills = world.get_player_skills();
+        assert_eq!(skills["persuasion"], 3 * SKILL_GAIN_PER_ACTION);
+        assert_eq!(skills["navigation"], SKILL_GAIN_PER_ACTION);
+        let log = world.ecs_world.resource::<EventLog>();
+        assert_eq!(log.query_by_tag("skill:persuasion", 10).len(), 3);
+        
+        // Mastered skills stop improving, and stop being recorded
+        world.improve_player_skill("persuasion", 200);
+        assert_eq!(world.improve_player_skill("persuasion", 1), Some(100));
+        let log = world.ecs_world.resource::<EventLog>();
+        assert_eq!(log.query_by_tag("skill:persuasion", 10).len(), 4);
+    }
+
+    #[test]
+    fn test_affinity_fades_without_interaction() {
+        let mut world = GameWorld::empty();
+        let mut relationships = Relationships::default();
+        relationships.modify_affinity(PLAYER_ENTITY_ID, 80, 0);
+        relationships.modify_affinity(Uuid::from_u128(1), -30, 0);
+        let npc = world.ecs_world.spawn((relationships, IsNpc)).id();
+        let affinity = |world: &GameWorld, with: Uuid| {
+            world.ecs_world.get::<Relationships>(npc).unwrap().get_affinity(with)
+        };
+        
+        let decay = *world.ecs_world.resource::<systems::RelationshipDecay>();
+        for _ in 0..decay.grace_ticks {
+            world.tick();
+        }
+        assert_eq!(affinity(&world, PLAYER_ENTITY_ID), 80);
+        
+        for _ in 0..decay.interval_ticks * 10 {
+            world.tick();
+        }
+        assert_eq!(affinity(&world, PLAYER_ENTITY_ID), 70);
+        assert_eq!(affinity(&world, Uuid::from_u128(1)), -20);
+        
+        // A faster rate runs feelings down to neutral, and no further
+        world.ecs_world.insert_resource(systems::RelationshipDecay { grace_ticks: 0, interval_ticks: 1 });
+        for _ in 0..100 {
+            world.tick();
+        }
+        assert_eq!(affinity(&world, PLAYER_ENTITY_ID), 0);
+        assert_eq!(affinity(&world, Uuid::from_u128(1)), 0);
+    }
+
+    #[test]
+    fn test_greeting_follows_time_of_day() {
+        let mut world = GameWorld::new();
+        let inn = world.get_player_room().unwrap();
+        let innkeeper = world.get_npcs_in_room(inn)[0].id;
+        
+        world.ecs_world.resource_mut::<systems::WorldClock>().current_time.hour = 2;
+        assert_eq!(world.time_of_day(), systems::TimeOfDay::Night);
+        let night = world.npc_greeting(innkeeper).unwrap();
+        assert!(night.starts_with("You're about late tonight."));
+        assert!(night.ends_with("Welcome to the Crossroads! What can I get you?"));
+        
+        world.ecs_world.resource_mut::<systems::WorldClock>().current_time.hour = 14;
+        assert_eq!(world.time_of_day(), systems::TimeOfDay::Afternoon);
+        assert!(world.npc_greeting(innkeeper).unwrap().starts_with("Good afternoon."));
+        
+        // Hour 21 is night everywhere, including the room description
+        world.ecs_world.resource_mut::<systems::WorldClock>().current_time.hour = 21;
+        assert_eq!(world.time_of_day(), systems::TimeOfDay::Night);
+        assert!(world.describe_room(inn).unwrap().contains("Moonlight"));
+        assert_eq!(world.npc_greeting(Uuid::new_v4()), None);
+    }
+
+    #[test]
+    fn test_examine_npc_shows_description_and_mood() {
+        let mut world = GameWorld::new();
+        let inn = world.get_player_room().unwrap();
+        
+        let text = world.examine_npc(inn, "Gareth").unwrap();
+        assert!(text.starts_with("Gareth the Innkeeper"));
+        assert!(text.contains("A broad-shouldered man with graying hair and a welcoming smile."));
+        assert!(text.ends_with("Gareth the Innkeeper seems friendly and approachable."));
+        
+        // Kael is in the forge, not the inn
+        assert_eq!(world.examine_npc(inn, "kael"), None);
+        assert_eq!(world.examine_npc(inn, ""), None);
+    }
+
+    #[test]
+    fn test_party_members_meet_npcs_in_their_own_rooms() {
+        let mut world = GameWorld::new();
+        let inn = world.get_player_room().unwrap();
+        let forge = world.room_registry.iter()
+            .find(|(_, name)| name.as_str() == "Blacksmith's Forge")
+            .map(|(id, _)| *id)
+            .unwrap();
+        let companion = world.spawn_party_member(
+            "Wren".to_string(),
+            "A wiry scout with a longbow.".to_string(),
+            forge,
+        );
+        
+        // Each character only finds the NPCs standing where they are
+        let companion_room = world.get_character_room(companion).unwrap();
+        assert_eq!(companion_room, forge);
+        assert!(world.examine_npc(companion_room, "kael").unwrap().starts_with("Kael the Blacksmith"));
+        assert_eq!(world.examine_npc(companion_room, "gareth"), None);
+        assert_eq!(world.talk_to(companion_room, "").unwrap().name, "Kael the Blacksmith");
+        
+        let player_room = world.get_character_room(PLAYER_ENTITY_ID).unwrap();
+        assert_eq!(player_room, inn);
+        assert_eq!(world.examine_npc(player_room, "kael"), None);
+        assert_eq!(world.talk_to(player_room, "").unwrap().name, "Gareth the Innkeeper");
+    }
+
+    #[test]
+    fn test_party_members_move_independently() {
+        let mut world = GameWorld::new();
+        let inn = world.get_player_room().unwrap();
+        let companion = world.spawn_party_member(
+            "Wren".to_string(),
+            "A wiry scout with a longbow.".to_string(),
+            inn,
+        );
+        let party = world.get_party();
+        assert_eq!(party.iter().map(|m| m.id).collect::<Vec<_>>(), vec![PLAYER_ENTITY_ID, companion]);
+        
+        let direction = world.get_room_details(inn).unwrap().exits[0].direction.clone();
+        let elsewhere = world.move_character(companion, &direction).unwrap();
+        assert_eq!(world.get_character_room(companion), Some(elsewhere));
+        assert_eq!(world.get_player_room(), Some(inn));
+        assert_eq!(world.get_character_room(PLAYER_ENTITY_ID), Some(inn));
+        let log = world.ecs_world.resource::<EventLog>();
+        let moves = log.query_by_tag(&format!("character:{}", companion), 10);
+        assert!(matches!(moves[0].event, GameEvent::PlayerMoved { character_id, to_room, .. } if character_id == companion && to_room == elsewhere));
+        
+        // Only the companion practiced navigation
+        assert!(world.get_player_skills().is_empty());
+        
+        world.move_player(&direction).unwrap();
+        world.move_player(&direction).ok();
+        assert_eq!(world.get_character_room(companion), Some(elsewhere));
+        assert!(world.move_character(Uuid::new_v4(), &direction).is_err());
+        
+        // Each character saves under its own id
+        let ids: Vec<Uuid> = world.snapshot_entities().iter().filter_map(|s| s.player.as_ref().and(s.id())).collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&PLAYER_ENTITY_ID) && ids.contains(&companion));
+    }
+
+    #[test]
+    fn test_validate_world_flags_broken_exits() {
+        assert!(GameWorld::new().validate_world().is_empty());
+        
+        let mut world = GameWorld::empty();
+        let hall = world.create_room("Hall".to_string(), "A long hall.".to_string());
+        let vault = world.create_room("Vault".to_string(), "A locked vault.".to_string());
+        world.connect_rooms(hall, vault, "down", "up").unwrap();
+        assert!(world.validate_world().is_empty());
+        
+        let nowhere = Uuid::new_v4();
+        let mut query = world.ecs_world.query::<(&RoomId, &mut Room)>();
+        for (id, mut room) in query.iter_mut(&mut world.ecs_world) {
+            if id.0 == hall {
+                room.exits.push(Exit { direction: "west".to_string(), target_room_id: nowhere, description: None });
+            }
+        }
+        assert_eq!(world.validate_world(), vec![WorldWarning::DanglingExit {
+            room_id: hall,
+            direction: "west".to_string(),
+            target_room_id: nowhere,
+        }]);
+        
+        // A second exit into the vault with no way back from a new room
+        let attic = world.create_room("Attic".to_string(), "Dusty rafters.".to_string());
+        let mut query = world.ecs_world.query::<(&RoomId, &mut Room)>();
+        for (id, mut room) in query.iter_mut(&mut world.ecs_world) {
+            if id.0 == attic {
+                room.exits.push(Exit { direction: "down".to_string(), target_room_id: vault, description: None });
+            }
+        }
+        let warnings = world.validate_world();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0], WorldWarning::OneWayExit {
+            room_id: attic,
+            direction: "down".to_string(),
+            target_room_id: vault,
+        });
+    }
+
+    #[test]
+    fn test_move_player_to_adjacent_room_only() {
+        let mut world = GameWorld::new();
+        let room_named = |world: &GameWorld, name: &str| {
+            *world.room_registry.iter().find(|(_, n)| n.as_str() == name).unwrap().0
+        };
+        let inn = room_named(&world, "The Crossroads Inn");
+        let square = room_named(&world, "Town Square");
+        let forge = room_named(&world, "Blacksmith's Forge");
+        
+        assert!(world.move_player_to(forge).is_err());
+        assert_eq!(world.get_player_room(), Some(inn));
+        
+        assert_eq!(world.move_player_to(square), Ok(square));
+        assert_eq!(world.get_player_room(), Some(square));
+        assert_eq!(world.get_movement_history(), vec![inn, square]);
+        let log = world.ecs_world.resource::<EventLog>();
+        assert!(log.query_in_room(square, 10).iter().any(|e| matches!(e.event, GameEvent::PlayerMoved { from_room, .. } if from_room == inn)));
+        
+        assert_eq!(world.move_player_to(forge), Ok(forge));
+    }
+
+    #[test]
+    fn test_go_back_retraces_steps() {
+        let mut world = GameWorld::new();
+        let inn = world.get_player_room().unwrap();
+        assert!(world.go_back().is_err());
+        
+        let square = world.move_player("north").unwrap();
+        assert_eq!(world.go_back(), Ok(inn));
+        assert_eq!(world.get_player_room(), Some(inn));
+        assert!(world.go_back().is_err());
+        
+        // Going back twice unwinds a two-step trip, and exploration is kept
+        world.move_player("north").unwrap();
+        let forge = *world.room_registry.iter().find(|(_, n)| n.as_str() == "Blacksmith's Forge").unwrap().0;
+        world.move_player_to(forge).unwrap();
+        assert_eq!(world.go_back(), Ok(square));
+        assert_eq!(world.go_back(), Ok(inn));
+        assert!(world.get_explored_rooms().visited.contains(&forge));
+        
+        let log = world.ecs_world.resource::<EventLog>();
+        assert!(log.query_in_room(inn, 10).iter().any(|e| matches!(e.event, GameEvent::PlayerMoved { from_room, .. } if from_room == square)));
+    }
+
+    #[test]
+    fn test_world_time_rolls_over_days_months_and_years() {
+        use crate::simulation::systems::{GameTime, Season};
+        
+        let mut world = GameWorld::empty();
+        for _ in 0..25 {
+            world.tick();
+        }
+        let time = world.get_world_time();
+        assert_eq!((time.day, time.hour), (2, 1));
+        assert_eq!((time.month, time.year, time.season), (3, 1, Season::Spring));
+        
+        // The 30th day is still in its month; the next one starts a new month
+        let mut time = GameTime::default();
+        time.advance(29 * 24);
+        assert_eq!((time.day, time.month), (30, 3));
+        time.advance(24);
+        assert_eq!((time.day, time.month), (1, 4));
+        
+        // Ten more months runs into the next year's winter
+        time.advance(10 * 30 * 24);
+        assert_eq!((time.day, time.month, time.year, time.season), (1, 2, 2, Season::Winter));
+    }
+
+    #[test]
+    fn test_record_conversation_is_remembered() {
+        let mut world = GameWorld::new();
+        let inn = world.get_player_room().unwrap();
+        let innkeeper = world.get_npcs_in_room(inn)[0].id;
+        
+        world.record_conversation(
+            innkeeper,
+            "Asked about the road north; warned of bandits.".to_string(),
+            vec!["bandits".to_string(), "roads".to_string()],
+        ).unwrap();
+        
+        let conversations = world.get_recent_conversations(innkeeper, 5);
+        assert_eq!(conversations.len(), 1);
+        assert_eq!(conversations[0].summary, "Asked about the road north; warned of bandits.");
+        assert_eq!(conversations[0].topics, vec!["bandits", "roads"]);
+        
+        let log = world.ecs_world.resource::<EventLog>();
+        assert!(log.query_in_room(inn, 10).iter().any(|e| matches!(e.event, GameEvent::PlayerTalkedToNpc { npc_id, .. } if npc_id == innkeeper)));
+        assert!(world.record_conversation(Uuid::new_v4(), String::new(), vec![]).is_err());
+    }
+
+    fn spawn_biome_room(world: &mut GameWorld, biome: &str, exits: Vec<Exit>) -> Uuid {
+        let room_id = Uuid::new_v4();
+        world.ecs_world.spawn((
+            Name(format!("{} Trail", biome)),
+            Description(format!("A trail through the {}.", biome)),
+            Room { exits },
+            RoomId(room_id),
+            RoomTerrainBinding {
+                world_x: 0.0,
+                world_z: 0.0,
+                chunk_coord: (0, 0),
+                elevation: 0.5,
+                biome: Some(biome.to_string()),
+            },
+            IsRoom,
+        ));
+        room_id
+    }
+
+    fn hours_to_enter(biome: &str) -> u32 {
+        let mut world = GameWorld::empty();
+        let target = spawn_biome_room(&mut world, biome, vec![]);
+        let start = spawn_biome_room(&mut world, "Grassland", vec![Exit {
+            direction: "north".to_string(),
+            target_room_id: target,
+            description: None,
+        }]);
+        world.ecs_world.spawn((
+            Position { room_id: start },
+            Player { current_input: String::new(), movement_history: vec![start] },
+            IsPlayer,
+        ));
+        
+        world.move_player("north").unwrap();
+        world.ecs_world.resource::<systems::WorldClock>().current_time.hour
+    }
+
+    #[test]
+    fn test_desert_travel_costs_more_than_grassland() {
+        assert!(biome_traversal_cost(Biome::Desert) > biome_traversal_cost(Biome::Grassland));
+        assert!(hours_to_enter("Desert") > hours_to_enter("Grassland"));
+    }
+
+    fn spawn_bound_room(world: &mut GameWorld, biome: &str, world_x: f32) -> Uuid {
+        let room_id = spawn_biome_room(world, biome, vec![]);
+        let mut binding = world.get_room_terrain_binding(room_id).unwrap();
+        binding.world_x = world_x;
+        world.bind_room_terrain(room_id, binding).unwrap();
+        room_id
+    }
+
+    fn connect(world: &mut GameWorld, from: Uuid, to: Uuid) {
+        let mut query = world.ecs_world.query::<(&RoomId, &mut Room)>();
+        let (_, mut room) = query.iter_mut(&mut world.ecs_world).find(|(id, _)| id.0 == from).unwrap();
+        room.exits.push(Exit { direction: "east".to_string(), target_room_id: to, description: None });
+    }
+
+    #[test]
+    fn test_travel_time_grows_with_distance_and_desert() {
+        let mut world = GameWorld::empty();
+        // Grassland chain a - b - c at 2km spacing, plus a desert room d 2km from a
+        let a = spawn_bound_room(&mut world, "Grassland", 0.0);
+        let b = spawn_bound_room(&mut world, "Grassland", 2000.0);
+        let c = spawn_bound_room(&mut world, "Grassland", 4000.0);
+        let d = spawn_bound_room(&mut world, "Desert", -2000.0);
+        connect(&mut world, a, b);
+        connect(&mut world, b, c);
+        connect(&mut world, a, d);
+        
+        let to_b = world.travel_time(a, b).unwrap();
+        let to_c = world.travel_time(a, c).unwrap();
+        let to_d = world.travel_time(a, d).unwrap();
+        
+        assert_eq!(to_c.hops, 2);
+        assert_eq!(to_c.path, vec![a, b, c]);
+        assert!((to_c.distance_meters - 4000.0).abs() < 0.01);
+        assert!(to_c.hours > to_b.hours);
+        assert!(to_d.hours > to_b.hours);
+        
+        // Exits are one-way here
+        assert!(world.travel_time(c, a).is_none());
+    }
+
+    #[test]
+    fn test_forced_encounter_records_storylet() {
+        let mut world = GameWorld::empty();
+        let room = spawn_biome_room(&mut world, "Grassland", vec![]);
+        world.ecs_world.resource_mut::<StoryletManager>().add_storylet(
+            Storylet::new("wolves".to_string(), "Wolves".to_string(), "A pack circles".to_string())
+                .with_category("encounter"),
+        );
+        
+        world.roll_encounter(room, 0.0);
+        assert!(world.ecs_world.resource::<EventLog>().query_in_room(room, 10).is_empty());
+        
+        world.roll_encounter(room, 1.0);
+        let log = world.ecs_world.resource::<EventLog>();
+        let events = log.query_in_room(room, 10);
+        assert_eq!(events.len(), 1);
+        assert!(matches!(&events[0].event, GameEvent::EncounterTriggered { room_id, storylet_id } if *room_id == room && storylet_id == "wolves"));
+    }
+
+    #[test]
+    fn test_walking_spends_the_estimated_travel_time() {
+        let mut world = GameWorld::empty();
+        let a = spawn_bound_room(&mut world, "Grassland", 0.0);
+        let d = spawn_bound_room(&mut world, "Desert", 3000.0);
+        connect(&mut world, a, d);
+        world.ecs_world.spawn((
+            Position { room_id: a },
+            Player { current_input: String::new(), movement_history: vec![a] },
+            IsPlayer,
+        ));
+        
+        let estimate = world.travel_time(a, d).unwrap();
+        let start = world.ecs_world.resource::<systems::WorldClock>().current_time.hour;
+        world.move_player("east").unwrap();
+        let end = world.ecs_world.resource::<systems::WorldClock>().current_time.hour;
+        
+        // 3km of desert at 2.5 hours per km
+        assert!((estimate.hours - 7.5).abs() < 0.01);
+        assert_eq!(end - start, estimate.hours.round() as u32);
+    }
+
+    #[test]
+    fn test_drop_and_pick_up_item() {
+        let mut world = GameWorld::new();
+        let inn = world.get_player_room().unwrap();
+        let lantern = world.spawn_item_in_room(
+            inn,
+            "Brass Lantern".to_string(),
+            "A dented but serviceable lantern.".to_string(),
+            Item::new("tool".to_string(), 1.5, 10),
+        );
+        
+        world.pick_up_item(lantern).unwrap();
+        assert!(world.get_items_in_room(inn).is_empty());
+        assert_eq!(world.get_player_inventory()[0].id, lantern);
+        
+        world.drop_item(lantern).unwrap();
+        let items = world.get_items_in_room(inn);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].name, "Brass Lantern");
+        assert!(world.get_player_inventory().is_empty());
+        assert!(world.drop_item(lantern).is_err());
+        
+        world.pick_up_item(lantern).unwrap();
+        assert_eq!(world.get_player_inventory()[0].id, lantern);
+        
+        let log = world.ecs_world.resource::<EventLog>();
+        assert!(log.query_in_room(inn, 10).iter().any(|e| matches!(e.event, GameEvent::ItemDropped { .. })));
+    }
+
+    #[test]
+    fn test_pick_up_respects_inventory_capacity() {
+        let mut world = GameWorld::new();
+        let inn = world.get_player_room().unwrap();
+        let mut inventories = world.ecs_world.query_filtered::<&mut Inventory, bevy_ecs::query::With<IsPlayer>>();
+        *inventories.single_mut(&mut world.ecs_world) = Inventory::new(1);
+        
+        let spawn = |world: &mut GameWorld, name: &str| {
+            world.spawn_item_in_room(inn, name.to_string(), String::new(), Item::new("tool".to_string(), 1.0, 1))
+        };
+        let rope = spawn(&mut world, "Rope");
+        let torch = spawn(&mut world, "Torch");
+        
+        world.pick_up_item(rope).unwrap();
+        assert!(world.pick_up_item(torch).is_err());
+        
+        // The refused item stays where it was
+        let items = world.get_items_in_room(inn);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, torch);
+        assert_eq!(world.get_player_inventory().len(), 1);
+    }
+
+    fn spawn_fighter(world: &mut GameWorld, id: Uuid, stats: Stats, health: i32) {
+        world.ecs_world.spawn((
+            Name(format!("Fighter {}", id)),
+            stats,
+            Health::new(health),
+            NpcId(id),
+            IsNpc,
+        ));
+    }
+
+    fn staged_fight() -> (CombatResult, GameWorld) {
+        let brute = Uuid::from_u128(1);
+        let weakling = Uuid::from_u128(2);
+        let mut world = GameWorld::empty();
+        spawn_fighter(&mut world, brute, Stats { strength: 18, dexterity: 14, ..Stats::default() }, 30);
+        spawn_fighter(&mut world, weakling, Stats { strength: 6, dexterity: 8, constitution: 6, ..Stats::default() }, 8);
+        (world.resolve_combat(brute, weakling).unwrap(), world)
+    }
+
+    #[test]
+    fn test_combat_is_deterministic_and_strong_attacker_wins() {
+        let (result, world) = staged_fight();
+        let (replay, _) = staged_fight();
+        
+        assert_eq!(result, replay);
+        assert_eq!(result.winner, Some(Uuid::from_u128(1)));
+        assert_eq!(result.defender_health, 0);
+        assert!(result.rounds >= 1 && result.rounds <= combat::MAX_COMBAT_ROUNDS);
+        assert!(result.attacker_damage_dealt >= 8);
+        
+        let log = world.ecs_world.resource::<EventLog>();
+        assert_eq!(log.query_by_tag("combat", 10).len(), 2);
+    }
+
+    #[test]
+    fn test_combat_shifts_faction_standing() {
+        let bandits = Uuid::from_u128(10);
+        let guild = Uuid::from_u128(11);
+        let mut world = GameWorld::empty();
+        for (id, name) in [(bandits, "Red Hand Bandits"), (guild, "Merchants' Guild")] {
+            world.ecs_world.spawn((FactionId(id), Faction::new(name.to_string())));
+        }
+        world.ecs_world.spawn((
+            Player { current_input: String::new(), movement_history: vec![] },
+            Stats { strength: 18, dexterity: 14, ..Stats::default() },
+            Health::new(30),
+            IsPlayer,
+        ));
+        let bandit = Uuid::from_u128(1);
+        let raider = Uuid::from_u128(2);
+        let merchant = Uuid::from_u128(3);
+        spawn_fighter(&mut world, bandit, Stats::default(), 6);
+        spawn_fighter(&mut world, raider, Stats::default(), 20);
+        spawn_fighter(&mut world, merchant, Stats::default(), 20);
+        for (npc, faction) in [(bandit, bandits), (raider, bandits), (merchant, guild)] {
+            let entity = world.find_combatant(npc).unwrap();
+            world.ecs_world.entity_mut(entity).insert(FactionMembership::new(faction, "member".to_string()));
+        }
+        
+        world.resolve_combat(PLAYER_ENTITY_ID, bandit).unwrap();
+        world.resolve_combat(merchant, raider).unwrap();
+        world.tick();
+        
+        let mut factions = world.ecs_world.query::<(&FactionId, &Faction)>();
+        let standing: HashMap<Uuid, Faction> = factions.iter(&world.ecs_world)
+            .map(|(id, faction)| (id.0, faction.clone()))
+            .collect();
+        assert!(standing[&bandits].player_reputation < 0);
+        assert_eq!(standing[&guild].player_reputation, 0);
+        assert!(standing[&bandits].get_relation(guild) < 0);
+        assert!(standing[&guild].relations.is_empty());
+        
+        let log = world.ecs_world.resource::<EventLog>();
+        assert!(!log.query_by_tag("faction", 10).is_empty());
+        
+        // A second tick doesn't apply the same events again
+        let reputation = standing[&bandits].player_reputation;
+        world.tick();
+        let mut factions = world.ecs_world.query::<(&FactionId, &Faction)>();
+        let again = factions.iter(&world.ecs_world).find(|(id, _)| id.0 == bandits).unwrap().1.player_reputation;
+        assert_eq!(again, reputation);
+    }
+
+    #[test]
+    fn test_selling_to_a_shop_lowers_its_price() {
+        let keeper = Uuid::from_u128(1);
+        let mut world = GameWorld::empty();
+        let mut shop = Shop::default();
+        shop.add_goods("material".to_string(), 10, 5);
+        shop.add_goods("weapon".to_string(), 40, 2);
+        world.ecs_world.spawn((NpcId(keeper), shop, IsNpc));
+        
+        let pelts: Vec<Uuid> = (0..10).map(|n| Uuid::from_u128(100 + n)).collect();
+        for &pelt in &pelts {
+            world.ecs_world.spawn((ItemId(pelt), Item::new("material".to_string(), 1.0, 10), IsItem));
+        }
+        for &pelt in &pelts {
+            let mut log = world.ecs_world.resource_mut::<EventLog>();
+            log.record(0, GameEvent::ItemSold { seller: PLAYER_ENTITY_ID, buyer: keeper, item_id: pelt, price: 10 });
+        }
+        for _ in 0..10 {
+            world.tick();
+        }
+        
+        let prices = world.get_shop_prices(keeper).unwrap();
+        assert_eq!(prices.iter().map(|p| p.item_type.as_str()).collect::<Vec<_>>(), vec!["material", "weapon"]);
+        assert_eq!(prices[0].stock, 15);
+        assert!(prices[0].price < prices[0].base_price);
+        assert_eq!(prices[1].price, 40);
+        assert!(world.get_shop_prices(Uuid::from_u128(2)).is_err());
+    }
+
+    #[test]
+    fn test_defeated_combatant_cannot_fight_again() {
+        let (_, mut world) = staged_fight();
+        assert!(world.resolve_combat(Uuid::from_u128(1), Uuid::from_u128(2)).is_err());
+        assert!(world.resolve_combat(Uuid::from_u128(1), Uuid::new_v4()).is_err());
+    }
+
+    #[test]
+    fn test_set_player_quality_is_clamped() {
+        let mut world = GameWorld::new();
+        
+        assert_eq!(world.set_player_quality("wounds", 3).unwrap(), 3);
+        assert_eq!(world.set_player_quality("reputation", 500).unwrap(), 100);
+        
+        let qualities = world.get_player_qualities();
+        let wounds = qualities.iter().find(|q| q.id == "wounds").unwrap();
+        assert_eq!(wounds.value, 3);
+        assert_eq!((wounds.min, wounds.max), (0, 10));
+        assert_eq!(wounds.name, "Wounds");
+        
+        let reputation = qualities.iter().find(|q| q.id == "reputation").unwrap();
+        assert_eq!(reputation.value, 100);
+        
+        // Unregistered qualities are stored as-is
+        assert_eq!(world.set_player_quality("met_gareth", 1).unwrap(), 1);
+        assert!(world.get_player_qualities().iter().any(|q| q.id == "met_gareth" && q.value == 1));
+    }
+}
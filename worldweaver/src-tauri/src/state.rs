@@ -1,6 +1,146 @@
-use crate::simulation::world::SharedWorld;
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
+use crate::simulation::world::{GameWorld, SharedWorld};
+use crate::terrain::TerrainData;
+use crate::terrain::config::TerrainConfig;
+
+/// Name of the world the app starts with
+pub const DEFAULT_WORLD_NAME: &str = "default";
 
 /// Shared application state accessible from all Tauri commands
 pub struct AppState {
     pub world: SharedWorld,
 }
+
+/// How to build a new world slot
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct WorldSlotConfig {
+    pub empty: bool,                     // Skip the starter rooms and NPCs
+    pub terrain: Option<TerrainConfig>,  // Terrain config for the (ungenerated) map
+}
+
+/// Summary of a world slot for the world picker
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WorldSlotInfo {
+    pub name: String,
+    pub active: bool,
+    pub tick_count: u64,
+}
+
+/// A world that is loaded but not currently active
+struct ParkedWorld {
+    world: GameWorld,
+    terrain: TerrainData,
+}
+
+/// Holds every loaded world except the active one
+/// The active world lives in the managed SharedWorld/TerrainData state, so switching
+/// swaps contents in place and all existing commands follow the active world
+pub struct WorldSlots {
+    active: String,
+    parked: HashMap<String, ParkedWorld>,
+}
+
+impl Default for WorldSlots {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WorldSlots {
+    pub fn new() -> Self {
+        Self {
+            active: DEFAULT_WORLD_NAME.to_string(),
+            parked: HashMap::new(),
+        }
+    }
+
+    /// Name of the world the commands currently operate on
+    pub fn active_name(&self) -> &str {
+        &self.active
+    }
+
+    /// Create a new (inactive) world slot
+    pub fn create(&mut self, name: &str, config: WorldSlotConfig) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("World name cannot be empty".to_string());
+        }
+        if name == self.active || self.parked.contains_key(name) {
+            return Err(format!("A world named '{}' already exists", name));
+        }
+
+        let world = if config.empty { GameWorld::empty() } else { GameWorld::new() };
+        let terrain = TerrainData::new(config.terrain.unwrap_or_default());
+        self.parked.insert(name.to_string(), ParkedWorld { world, terrain });
+        Ok(())
+    }
+
+    /// Make a parked world active, parking the currently active one in its place
+    pub fn switch(&mut self, name: &str, world: &mut GameWorld, terrain: &mut TerrainData) -> Result<(), String> {
+        if name == self.active {
+            return Ok(());
+        }
+
+        let mut incoming = self.parked.remove(name)
+            .ok_or_else(|| format!("No world named '{}'", name))?;
+
+        std::mem::swap(world, &mut incoming.world);
+        std::mem::swap(terrain, &mut incoming.terrain);
+
+        let outgoing = std::mem::replace(&mut self.active, name.to_string());
+        self.parked.insert(outgoing, incoming);
+        Ok(())
+    }
+
+    /// List all loaded worlds, sorted by name
+    pub fn list(&self, active_world: &GameWorld) -> Vec<WorldSlotInfo> {
+        let mut worlds: Vec<WorldSlotInfo> = self.parked.iter()
+            .map(|(name, parked)| WorldSlotInfo {
+                name: name.clone(),
+                active: false,
+                tick_count: parked.world.tick_count,
+            })
+            .collect();
+        worlds.push(WorldSlotInfo {
+            name: self.active.clone(),
+            active: true,
+            tick_count: active_world.tick_count,
+        });
+        worlds.sort_by(|a, b| a.name.cmp(&b.name));
+        worlds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_switch_world_changes_active_tick() {
+        let mut slots = WorldSlots::new();
+        let mut world = GameWorld::empty();
+        let mut terrain = TerrainData::default();
+
+        slots.create("scenario", WorldSlotConfig { empty: true, terrain: None }).unwrap();
+        assert!(slots.create("scenario", WorldSlotConfig::default()).is_err());
+
+        for _ in 0..5 {
+            world.tick();
+        }
+        assert_eq!(world.tick_count, 5);
+
+        slots.switch("scenario", &mut world, &mut terrain).unwrap();
+        assert_eq!(slots.active_name(), "scenario");
+        assert_eq!(world.tick_count, 0);
+
+        world.tick();
+        slots.switch(DEFAULT_WORLD_NAME, &mut world, &mut terrain).unwrap();
+        assert_eq!(world.tick_count, 5);
+
+        let worlds = slots.list(&world);
+        assert_eq!(worlds.len(), 2);
+        assert!(worlds.iter().any(|w| w.name == "scenario" && !w.active && w.tick_count == 1));
+        assert!(slots.switch("missing", &mut world, &mut terrain).is_err());
+    }
+}
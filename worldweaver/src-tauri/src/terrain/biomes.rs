@@ -1,6 +1,6 @@
 use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
-use super::config::WorldTheme;
+use super::config::{TerrainConfig, WindDirection, WorldTheme};
 
 /// Biome types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -18,18 +18,76 @@ pub enum Biome {
     Glacier,
 }
 
+impl Biome {
+    pub const ALL: [Biome; 11] = [
+        Biome::Ocean,
+        Biome::Coast,
+        Biome::TropicalRainforest,
+        Biome::TemperateForest,
+        Biome::BorealForest,
+        Biome::Tundra,
+        Biome::Grassland,
+        Biome::Savanna,
+        Biome::Desert,
+        Biome::Alpine,
+        Biome::Glacier,
+    ];
+
+    /// Parse a biome from its variant name (case-insensitive), e.g. "Desert"
+    pub fn from_name(name: &str) -> Option<Biome> {
+        Self::ALL.into_iter().find(|b| b.key().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Registry key of a built-in biome: its variant name
+    pub fn key(self) -> String {
+        format!("{:?}", self)
+    }
+}
+
 /// Biome definition with display properties
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BiomeDefinition {
     pub name: String,
     pub color: [u8; 3],
     pub theme_names: HashMap<WorldTheme, String>,
+    #[serde(default)]
+    pub climate: Option<BiomeClimate>, // Where a custom biome appears; built-ins use the Whittaker diagram
 }
 
-/// Registry of all biome definitions
+/// Conditions a custom biome claims during classification, each an inclusive range
+/// Temperature is in °C, moisture 0..1 and elevation normalized like heights
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BiomeClimate {
+    pub temperature: (f32, f32),
+    pub moisture: (f32, f32),
+    pub elevation: (f32, f32),
+}
+
+impl BiomeClimate {
+    pub fn contains(&self, elevation: f32, temperature: f32, moisture: f32) -> bool {
+        let within = |(min, max): (f32, f32), value: f32| (min..=max).contains(&value);
+        within(self.elevation, elevation) && within(self.temperature, temperature) && within(self.moisture, moisture)
+    }
+}
+
+/// Ids are 8-bit so biome maps stay one byte per cell
+const MAX_BIOMES: usize = u8::MAX as usize + 1;
+
+/// A registered biome with the id chunk biome maps store for it
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RegisteredBiome {
+    pub id: u8,
+    pub key: String,
+    pub definition: BiomeDefinition,
+}
+
+/// Registry of all biome definitions, keyed by name
+/// Built-in biomes take ids `Biome as u8` under their variant names; biomes registered at
+/// runtime (e.g. by mods) get the following ids in registration order
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BiomeRegistry {
-    pub definitions: HashMap<Biome, BiomeDefinition>,
+    keys: Vec<String>, // Index = biome id
+    definitions: HashMap<String, BiomeDefinition>,
 }
 
 impl Default for BiomeRegistry {
@@ -52,6 +110,7 @@ impl BiomeRegistry {
                     (WorldTheme::Modern, "Ocean".to_string()),
                     (WorldTheme::SciFi, "Liquid Expanse".to_string()),
                 ]),
+                climate: None,
             },
         );
 
@@ -65,6 +124,7 @@ impl BiomeRegistry {
                     (WorldTheme::Modern, "Coastline".to_string()),
                     (WorldTheme::SciFi, "Shore Zone".to_string()),
                 ]),
+                climate: None,
             },
         );
 
@@ -78,6 +138,7 @@ impl BiomeRegistry {
                     (WorldTheme::Modern, "Rainforest".to_string()),
                     (WorldTheme::SciFi, "Bio-Dense Zone".to_string()),
                 ]),
+                climate: None,
             },
         );
 
@@ -91,6 +152,7 @@ impl BiomeRegistry {
                     (WorldTheme::Modern, "Forest".to_string()),
                     (WorldTheme::SciFi, "Temperate Biomass".to_string()),
                 ]),
+                climate: None,
             },
         );
 
@@ -104,6 +166,7 @@ impl BiomeRegistry {
                     (WorldTheme::Modern, "Taiga".to_string()),
                     (WorldTheme::SciFi, "Cold Forest Zone".to_string()),
                 ]),
+                climate: None,
             },
         );
 
@@ -117,6 +180,7 @@ impl BiomeRegistry {
                     (WorldTheme::Modern, "Tundra".to_string()),
                     (WorldTheme::SciFi, "Cryo-Plains".to_string()),
                 ]),
+                climate: None,
             },
         );
 
@@ -130,6 +194,7 @@ impl BiomeRegistry {
                     (WorldTheme::Modern, "Grassland".to_string()),
                     (WorldTheme::SciFi, "Grass Expanse".to_string()),
                 ]),
+                climate: None,
             },
         );
 
@@ -143,6 +208,7 @@ impl BiomeRegistry {
                     (WorldTheme::Modern, "Savanna".to_string()),
                     (WorldTheme::SciFi, "Dry Grassland".to_string()),
                 ]),
+                climate: None,
             },
         );
 
@@ -156,6 +222,7 @@ impl BiomeRegistry {
                     (WorldTheme::Modern, "Desert".to_string()),
                     (WorldTheme::SciFi, "Arid Zone".to_string()),
                 ]),
+                climate: None,
             },
         );
 
@@ -169,6 +236,7 @@ impl BiomeRegistry {
                     (WorldTheme::Modern, "Alpine".to_string()),
                     (WorldTheme::SciFi, "High Altitude Zone".to_string()),
                 ]),
+                climate: None,
             },
         );
 
@@ -182,30 +250,114 @@ impl BiomeRegistry {
                     (WorldTheme::Modern, "Glacier".to_string()),
                     (WorldTheme::SciFi, "Ice Sheet".to_string()),
                 ]),
+                climate: None,
             },
         );
 
-        Self { definitions }
+        let mut registry = Self { keys: Vec::new(), definitions: HashMap::new() };
+        for biome in Biome::ALL {
+            let definition = definitions.remove(&biome).expect("every built-in biome has a definition");
+            let _ = registry.register(biome.key(), definition);
+        }
+        registry
+    }
+
+    /// Add a biome, or replace the definition of an existing key (case-insensitive) keeping its id
+    /// Returns the biome's id, or an error once every 8-bit id is taken
+    pub fn register(&mut self, biome_key: String, def: BiomeDefinition) -> Result<u8, String> {
+        let biome_key = biome_key.trim().to_string();
+        if biome_key.is_empty() {
+            return Err("Biome key must not be empty".to_string());
+        }
+
+        if let Some(id) = self.id_of(&biome_key) {
+            let existing = self.keys[id as usize].clone();
+            self.definitions.insert(existing, def);
+            return Ok(id);
+        }
+        if self.keys.len() >= MAX_BIOMES {
+            return Err(format!("Biome registry is full ({} biomes)", MAX_BIOMES));
+        }
+
+        self.keys.push(biome_key.clone());
+        self.definitions.insert(biome_key, def);
+        Ok((self.keys.len() - 1) as u8)
+    }
+
+    /// Id of a registered biome key (case-insensitive)
+    pub fn id_of(&self, biome_key: &str) -> Option<u8> {
+        self.keys.iter()
+            .position(|key| key.eq_ignore_ascii_case(biome_key.trim()))
+            .map(|id| id as u8)
+    }
+
+    /// Definition stored under a biome id, as found in chunk biome maps
+    pub fn definition(&self, id: u8) -> Option<&BiomeDefinition> {
+        self.keys.get(id as usize).and_then(|key| self.definitions.get(key))
+    }
+
+    /// Every registered biome in id order
+    pub fn list(&self) -> Vec<RegisteredBiome> {
+        self.keys.iter()
+            .enumerate()
+            .map(|(id, key)| RegisteredBiome {
+                id: id as u8,
+                key: key.clone(),
+                definition: self.definitions[key].clone(),
+            })
+            .collect()
+    }
+
+    /// First custom biome, in id order, whose climate covers the conditions
+    pub fn custom_biome_for(&self, elevation: f32, temperature: f32, moisture: f32) -> Option<u8> {
+        (Biome::ALL.len()..self.keys.len())
+            .find(|&id| {
+                self.definition(id as u8)
+                    .and_then(|def| def.climate)
+                    .is_some_and(|climate| climate.contains(elevation, temperature, moisture))
+            })
+            .map(|id| id as u8)
     }
 
     pub fn get_name(&self, biome: Biome, theme: WorldTheme) -> String {
-        self.definitions
-            .get(&biome)
+        self.get_name_by_key(&biome.key(), theme)
+    }
+
+    /// Themed display name for any registered biome key; unknown keys are returned as given
+    pub fn get_name_by_key(&self, biome_key: &str, theme: WorldTheme) -> String {
+        self.id_of(biome_key)
+            .and_then(|id| self.definition(id))
             .and_then(|def| def.theme_names.get(&theme))
             .cloned()
-            .unwrap_or_else(|| format!("{:?}", biome))
+            .unwrap_or_else(|| biome_key.to_string())
     }
 }
 
-/// Classify biome based on temperature and moisture (Whittaker diagram)
-pub fn classify_biome(elevation: f32, temperature: f32, moisture: f32, sea_level: f32) -> Biome {
+/// Classify a cell into a registry biome id
+/// Custom biomes with a climate are tried first, in id order, so they can carve their
+/// range out of the built-ins; everything else falls to the Whittaker diagram
+pub fn classify_biome(
+    registry: &BiomeRegistry,
+    elevation: f32,
+    temperature: f32,
+    moisture: f32,
+    sea_level: f32,
+    coast_width: f32,
+) -> u8 {
+    registry.custom_biome_for(elevation, temperature, moisture)
+        .unwrap_or_else(|| whittaker_biome(elevation, temperature, moisture, sea_level, coast_width) as u8)
+}
+
+/// Classify a built-in biome based on temperature and moisture (Whittaker diagram)
+/// Land within `coast_width` (normalized height) of sea level is beach
+pub fn whittaker_biome(elevation: f32, temperature: f32, moisture: f32, sea_level: f32, coast_width: f32) -> Biome {
     // Below sea level = ocean
     if elevation < sea_level {
         return Biome::Ocean;
     }
 
     // Just above sea level = coast
-    if elevation < sea_level + 0.02 {
+    if elevation < sea_level + coast_width {
         return Biome::Coast;
     }
 
@@ -247,6 +399,31 @@ pub fn classify_biome(elevation: f32, temperature: f32, moisture: f32, sea_level
     }
 }
 
+/// In-game hours to travel through a biome
+pub fn biome_traversal_cost(biome: Biome) -> f32 {
+    match biome {
+        Biome::Grassland => 1.0,
+        Biome::Coast | Biome::Savanna => 1.25,
+        Biome::TemperateForest => 1.5,
+        Biome::BorealForest | Biome::Tundra => 2.0,
+        Biome::Desert => 2.5,
+        Biome::TropicalRainforest => 3.0,
+        Biome::Alpine => 3.5,
+        Biome::Glacier | Biome::Ocean => 4.0,
+    }
+}
+
+/// Chance (0.0-1.0) of a random encounter when entering a biome
+pub fn biome_encounter_chance(biome: Biome) -> f32 {
+    match biome {
+        Biome::Grassland | Biome::Coast => 0.05,
+        Biome::Savanna | Biome::Tundra => 0.1,
+        Biome::TemperateForest | Biome::Desert | Biome::Ocean => 0.15,
+        Biome::BorealForest | Biome::Alpine | Biome::Glacier => 0.2,
+        Biome::TropicalRainforest => 0.25,
+    }
+}
+
 /// Generate temperature map based on elevation and latitude
 pub fn generate_temperature(
     elevation: f32,
@@ -293,3 +470,258 @@ pub fn generate_moisture(
 
     new_moisture.clamp(0.0, 1.0)
 }
+
+/// Sweep air across a world-sized heightmap (row-major) in the wind direction, one row or
+/// column at a time: it picks up moisture over the sea and rains it out on windward slopes,
+/// leaving rain shadows behind mountains. Returns the moisture (0..1) reaching every cell
+pub fn generate_moisture_map(heights: &[f32], config: &TerrainConfig, wind_dir: WindDirection) -> Vec<f32> {
+    let width = config.world_width as usize;
+    let height = config.world_height as usize;
+    let mut moisture_map = vec![0.0; width * height];
+
+    // Each line is walked from its upwind end
+    let (lines, length) = match wind_dir {
+        WindDirection::West | WindDirection::East => (height, width),
+        WindDirection::North | WindDirection::South => (width, height),
+    };
+    let cell = |line: usize, step: usize| -> (usize, usize) {
+        match wind_dir {
+            WindDirection::West => (step, line),
+            WindDirection::East => (length - 1 - step, line),
+            WindDirection::North => (line, step),
+            WindDirection::South => (line, length - 1 - step),
+        }
+    };
+
+    for line in 0..lines {
+        let mut moisture = 0.5;
+        let (x, z) = cell(line, 0);
+        let mut prev_elevation = heights[z * width + x];
+
+        for step in 0..length {
+            let (x, z) = cell(line, step);
+            let elevation = heights[z * width + x];
+            moisture = generate_moisture(x, z, elevation, prev_elevation, moisture, config.sea_level);
+            moisture_map[z * width + x] = moisture;
+            prev_elevation = elevation;
+        }
+    }
+
+    moisture_map
+}
+
+/// Classify every cell of a world-sized heightmap (row-major), returning `registry` ids
+/// Latitude runs from the equator at the middle row to the poles at the top and bottom edges
+pub fn generate_biome_map(
+    heights: &[f32],
+    width: usize,
+    height: usize,
+    config: &TerrainConfig,
+    registry: &BiomeRegistry,
+) -> Vec<u8> {
+    let moisture_map = generate_moisture_map(heights, config, config.wind_direction);
+    let half_height = (height.max(2) - 1) as f32 / 2.0;
+
+    heights.iter()
+        .zip(&moisture_map)
+        .enumerate()
+        .map(|(idx, (&elevation, &moisture))| {
+            let latitude = ((idx / width) as f32 - half_height).abs() / half_height;
+            let temperature = generate_temperature(elevation, latitude, config.max_elevation);
+            classify_biome(registry, elevation, temperature, moisture, config.sea_level, config.coast_width)
+        })
+        .collect()
+}
+
+/// Soft biome edges for rendering, alongside the unchanged hard ids
+/// `weights` is the share of each cell's neighborhood that matches its own biome (1.0 inside
+/// a region); `neighbor_ids` is the most common other biome nearby, to blend toward
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BiomeBlend {
+    pub weights: Vec<f32>,
+    pub neighbor_ids: Vec<u8>,
+}
+
+/// Measure how mixed each cell's (2 * radius + 1)² neighborhood is, clipped to the map
+/// Each biome's count comes from a summed-area table, so the cost doesn't grow with the radius
+pub fn blend_biome_edges(biome_ids: &[u8], width: usize, height: usize, radius: usize) -> BiomeBlend {
+    let biome_count = biome_ids.iter().copied().max().map_or(0, |max| max as usize + 1);
+    let stride = width + 1;
+
+    let mut blend = BiomeBlend {
+        weights: vec![1.0; width * height],
+        neighbor_ids: biome_ids.to_vec(),
+    };
+    let mut neighbor_share = vec![0.0f32; width * height];
+    let mut table = vec![0u32; stride * (height + 1)];
+
+    // One biome at a time keeps a single table in memory
+    for biome in 0..biome_count {
+        // table[(z + 1) * stride + x + 1] = cells of this biome in rows 0..=z, columns 0..=x
+        for z in 0..height {
+            let mut row_count = 0;
+            for x in 0..width {
+                row_count += u32::from(biome_ids[z * width + x] as usize == biome);
+                table[(z + 1) * stride + x + 1] = table[z * stride + x + 1] + row_count;
+            }
+        }
+
+        for z in 0..height {
+            let (z0, z1) = (z.saturating_sub(radius), (z + radius + 1).min(height));
+            for x in 0..width {
+                let (x0, x1) = (x.saturating_sub(radius), (x + radius + 1).min(width));
+                let count = table[z1 * stride + x1] + table[z0 * stride + x0]
+                    - table[z0 * stride + x1] - table[z1 * stride + x0];
+                let share = count as f32 / ((x1 - x0) * (z1 - z0)) as f32;
+
+                let idx = z * width + x;
+                if biome_ids[idx] as usize == biome {
+                    blend.weights[idx] = share;
+                } else if share > neighbor_share[idx] {
+                    neighbor_share[idx] = share;
+                    blend.neighbor_ids[idx] = biome as u8;
+                }
+            }
+        }
+    }
+
+    blend
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wider_coast_width_widens_beaches() {
+        // Land rising steadily inland from the shoreline at x = 8
+        let mut config = TerrainConfig::new(64, 4, 1, WorldTheme::Fantasy);
+        let heights: Vec<f32> = (0..64 * 4).map(|i| 0.151 + (i % 64) as f32 * 0.00625).collect();
+
+        let coast_cells = |config: &TerrainConfig| {
+            generate_biome_map(&heights, 64, 4, config, &BiomeRegistry::new()).iter()
+                .filter(|&&id| id == Biome::Coast as u8)
+                .count()
+        };
+
+        let narrow = coast_cells(&config);
+        config.coast_width = 0.1;
+        let wide = coast_cells(&config);
+        assert_eq!(narrow, 4 * 4); // 0.02 / 0.00625 rounds up to 4 cells per row
+        assert!(wide >= narrow * 4, "narrow {} vs wide {}", narrow, wide);
+    }
+
+    #[test]
+    fn test_registered_biome_gets_next_id_and_themed_name() {
+        let mut registry = BiomeRegistry::new();
+        assert_eq!(registry.id_of("Desert"), Some(Biome::Desert as u8));
+
+        let volcanic = BiomeDefinition {
+            name: "Volcanic".to_string(),
+            color: [90, 30, 20],
+            theme_names: HashMap::from([
+                (WorldTheme::Fantasy, "Ashen Wastes".to_string()),
+                (WorldTheme::SciFi, "Magma Fields".to_string()),
+            ]),
+            climate: None,
+        };
+        let id = registry.register("Volcanic".to_string(), volcanic.clone()).unwrap();
+        assert_eq!(id as usize, Biome::ALL.len());
+        assert_eq!(registry.get_name_by_key("volcanic", WorldTheme::Fantasy), "Ashen Wastes");
+        assert_eq!(registry.get_name_by_key("Volcanic", WorldTheme::Modern), "Volcanic");
+        assert_eq!(registry.definition(id).unwrap().color, [90, 30, 20]);
+
+        // Re-registering replaces the definition in place
+        assert_eq!(registry.register(" VOLCANIC ".to_string(), volcanic).unwrap(), id);
+        assert_eq!(registry.list().len(), Biome::ALL.len() + 1);
+
+        // Built-ins still resolve through the enum
+        assert_eq!(registry.get_name(Biome::Desert, WorldTheme::Fantasy), "Scorching Sands");
+    }
+
+    #[test]
+    fn test_custom_climate_claims_cells_in_biome_map() {
+        let config = TerrainConfig::new(64, 4, 1, WorldTheme::Fantasy);
+        // Sea on the west, rising to high peaks in the east
+        let heights: Vec<f32> = (0..64 * 4).map(|i| (i % 64) as f32 / 63.0).collect();
+        let mut registry = BiomeRegistry::new();
+        let volcanic = BiomeDefinition {
+            name: "Volcanic".to_string(),
+            color: [90, 30, 20],
+            theme_names: HashMap::new(),
+            climate: Some(BiomeClimate {
+                temperature: (-100.0, 100.0),
+                moisture: (0.0, 1.0),
+                elevation: (0.7, 1.0),
+            }),
+        };
+        let id = registry.register("Volcanic".to_string(), volcanic).unwrap();
+
+        let builtin = generate_biome_map(&heights, 64, 4, &config, &BiomeRegistry::new());
+        let custom = generate_biome_map(&heights, 64, 4, &config, &registry);
+        for (i, (&before, &after)) in builtin.iter().zip(&custom).enumerate() {
+            if heights[i] >= 0.7 {
+                assert_eq!(after, id);
+            } else {
+                assert_eq!(after, before);
+            }
+        }
+    }
+
+    #[test]
+    fn test_blend_softens_only_boundaries() {
+        // Grassland west of x = 10, desert to the east
+        let (width, height) = (20, 6);
+        let biome_ids: Vec<u8> = (0..width * height)
+            .map(|i| if i % width < 10 { Biome::Grassland as u8 } else { Biome::Desert as u8 })
+            .collect();
+
+        let blend = blend_biome_edges(&biome_ids, width, height, 2);
+        assert_eq!(blend.weights.len(), width * height);
+
+        for z in 0..height {
+            let row = z * width;
+            // Interior cells see only their own biome
+            assert_eq!(blend.weights[row + 2], 1.0);
+            assert_eq!(blend.weights[row + 17], 1.0);
+            assert_eq!(blend.neighbor_ids[row + 2], Biome::Grassland as u8);
+
+            // Cells beside the boundary are partly blended toward the other side
+            for x in [9, 10] {
+                assert!(blend.weights[row + x] > 0.0 && blend.weights[row + x] < 1.0);
+            }
+            assert_eq!(blend.neighbor_ids[row + 9], Biome::Desert as u8);
+            assert_eq!(blend.neighbor_ids[row + 10], Biome::Grassland as u8);
+            assert!(blend.weights[row + 8] > blend.weights[row + 9]);
+        }
+
+        // The hard ids are left alone
+        assert_eq!(biome_ids[9], Biome::Grassland as u8);
+    }
+
+    #[test]
+    fn test_ridge_casts_rain_shadow() {
+        // Sea to the west, then land rising to a north-south ridge at x = 40 and falling away
+        let config = TerrainConfig::new(64, 8, 1, WorldTheme::Fantasy);
+        let heights: Vec<f32> = (0..64 * 8)
+            .map(|i| match i % 64 {
+                x if x < 16 => 0.1,
+                x => 0.8 - (x as f32 - 40.0).abs() * 0.02,
+            })
+            .collect();
+
+        let mean = |moisture: &[f32], xs: std::ops::Range<usize>| {
+            let cells: Vec<f32> = (0..8).flat_map(|z| xs.clone().map(move |x| moisture[z * 64 + x])).collect();
+            cells.iter().sum::<f32>() / cells.len() as f32
+        };
+
+        // Compare slopes at matching heights either side of the ridge
+        let westerly = generate_moisture_map(&heights, &config, WindDirection::West);
+        let (windward, leeward) = (mean(&westerly, 30..38), mean(&westerly, 43..51));
+        assert!(leeward < windward * 0.8, "windward {} vs leeward {}", windward, leeward);
+
+        // With the wind reversed the east slope is upwind instead
+        let easterly = generate_moisture_map(&heights, &config, WindDirection::East);
+        assert!(mean(&easterly, 43..51) > mean(&easterly, 30..38));
+    }
+}
@@ -1,5 +1,5 @@
 use serde::{Serialize, Deserialize};
-use super::heightmap::HeightmapChunk;
+use super::heightmap::{HeightmapChunk, TerrainFeature};
 
 /// Brush operation types
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,11 +9,92 @@ pub enum BrushOp {
     Smooth,
     Flatten { target_height: f32 },
     Erode { droplet_count: u32 },
-    Noise { scale: f32, strength: f32 },
+    Noise { scale: f32, strength: f32, seed: u32 },
+    PaintLock { locked: bool },
+    Stamp { pattern: Vec<f32>, pattern_size: u32, blend: StampBlend },
+}
+
+/// How a stamped pattern combines with the existing terrain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum StampBlend {
+    #[default]
+    Add,
+    Max,
+    Replace,
+}
+
+/// A straight slope graded between two points, in vertex coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Ramp {
+    pub start: (f32, f32),
+    pub end: (f32, f32),
+    pub start_height: f32,
+    pub end_height: f32,
+    pub width: f32,  // Full width of the graded corridor
+}
+
+impl Ramp {
+    /// The same ramp with both endpoints shifted by (dx, dz)
+    pub fn translated(&self, dx: f32, dz: f32) -> Self {
+        Self {
+            start: (self.start.0 + dx, self.start.1 + dz),
+            end: (self.end.0 + dx, self.end.1 + dz),
+            ..*self
+        }
+    }
+}
+
+/// Pre-stroke heights around a stroke in global vertex coordinates, so neighbourhood
+/// brushes (smooth, erode) average across chunk edges instead of stopping at them
+/// Vertices in chunks that aren't loaded are None
+#[derive(Debug, Clone)]
+pub struct HeightWindow {
+    pub origin: (i32, i32),
+    pub width: usize,
+    pub heights: Vec<Option<f32>>,
+}
+
+impl HeightWindow {
+    /// Height at a global vertex, or None outside the window or the loaded chunks
+    pub fn get(&self, x: i32, z: i32) -> Option<f32> {
+        let local_x = usize::try_from(x - self.origin.0).ok()?;
+        let local_z = usize::try_from(z - self.origin.1).ok()?;
+        if local_x >= self.width {
+            return None;
+        }
+        self.heights.get(local_z * self.width + local_x).copied().flatten()
+    }
+}
+
+/// Brush falloff profile from the centre (1.0) to the rim
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FalloffCurve {
+    #[default]
+    Gaussian,
+    Linear,
+    Smoothstep,
+    Constant,
+}
+
+impl FalloffCurve {
+    /// Weight of the brush at `distance` from its centre
+    pub fn weight(self, distance: f32, radius: f32) -> f32 {
+        match self {
+            FalloffCurve::Gaussian => gaussian_falloff(distance, radius),
+            FalloffCurve::Linear => (1.0 - distance / radius).clamp(0.0, 1.0),
+            FalloffCurve::Smoothstep => {
+                let t = (1.0 - distance / radius).clamp(0.0, 1.0);
+                t * t * (3.0 - 2.0 * t)
+            }
+            FalloffCurve::Constant => 1.0,
+        }
+    }
 }
 
 impl HeightmapChunk {
     /// Apply a brush operation to the chunk
+    /// Lock painting and stamps always have a hard edge and ignore `curve`
+    #[allow(clippy::too_many_arguments)]
     pub fn apply_brush(
         &mut self,
         center_x: f32,
@@ -21,26 +102,110 @@ impl HeightmapChunk {
         radius: f32,
         strength: f32,
         op: BrushOp,
+        curve: FalloffCurve,
+        vertex_count: u32,
+    ) {
+        self.apply_brush_with_neighbors(center_x, center_z, radius, strength, op, curve, vertex_count, None);
+    }
+
+    /// Apply a brush operation, with smooth and erode averaging over `neighbors`
+    /// (global pre-stroke heights) instead of this chunk's own vertices only
+    #[allow(clippy::too_many_arguments)]
+    pub fn apply_brush_with_neighbors(
+        &mut self,
+        center_x: f32,
+        center_z: f32,
+        radius: f32,
+        strength: f32,
+        op: BrushOp,
+        curve: FalloffCurve,
         vertex_count: u32,
+        neighbors: Option<&HeightWindow>,
     ) {
         match op {
-            BrushOp::Raise => self.apply_raise(center_x, center_z, radius, strength, vertex_count),
-            BrushOp::Lower => self.apply_lower(center_x, center_z, radius, strength, vertex_count),
-            BrushOp::Smooth => self.apply_smooth(center_x, center_z, radius, strength, vertex_count),
+            BrushOp::Raise => self.apply_raise(center_x, center_z, radius, strength, curve, vertex_count),
+            BrushOp::Lower => self.apply_lower(center_x, center_z, radius, strength, curve, vertex_count),
+            BrushOp::Smooth => self.apply_smooth(center_x, center_z, radius, strength, curve, vertex_count, neighbors),
             BrushOp::Flatten { target_height } => {
-                self.apply_flatten(center_x, center_z, radius, strength, target_height, vertex_count)
+                self.apply_flatten(center_x, center_z, radius, strength, target_height, curve, vertex_count)
             }
             BrushOp::Erode { droplet_count } => {
-                self.apply_erode(center_x, center_z, radius, droplet_count, vertex_count)
+                self.apply_erode(center_x, center_z, radius, droplet_count, curve, vertex_count, neighbors)
+            }
+            BrushOp::Noise { scale, strength: noise_strength, seed } => {
+                self.apply_noise(center_x, center_z, radius, scale, noise_strength, seed, curve, vertex_count)
             }
-            BrushOp::Noise { scale, strength: noise_strength } => {
-                self.apply_noise(center_x, center_z, radius, scale, noise_strength, vertex_count)
+            BrushOp::PaintLock { locked } => self.paint_lock(center_x, center_z, radius, locked, vertex_count),
+            BrushOp::Stamp { pattern, pattern_size, blend } => {
+                self.apply_stamp(center_x, center_z, radius, strength, &pattern, pattern_size, blend, vertex_count)
             }
         }
+
+        self.recompute_bounds();
     }
 
-    /// Raise terrain with Gaussian falloff
-    fn apply_raise(&mut self, center_x: f32, center_z: f32, radius: f32, strength: f32, vertex_count: u32) {
+    /// Paint a feature marker onto every vertex within the radius (hard edge, no falloff)
+    /// Painting TerrainFeature::None erases markers
+    pub fn mark_feature(
+        &mut self,
+        center_x: f32,
+        center_z: f32,
+        radius: f32,
+        feature: TerrainFeature,
+        vertex_count: u32,
+    ) {
+        let size = (vertex_count * vertex_count) as usize;
+        let features = self.feature_ids.get_or_insert_with(|| vec![TerrainFeature::None.code(); size]);
+
+        let min_x = ((center_x - radius).floor().max(0.0) as usize).min(vertex_count as usize - 1);
+        let max_x = ((center_x + radius).ceil().min(vertex_count as f32 - 1.0) as usize).min(vertex_count as usize - 1);
+        let min_z = ((center_z - radius).floor().max(0.0) as usize).min(vertex_count as usize - 1);
+        let max_z = ((center_z + radius).ceil().min(vertex_count as f32 - 1.0) as usize).min(vertex_count as usize - 1);
+
+        for z in min_z..=max_z {
+            for x in min_x..=max_x {
+                let dx = x as f32 - center_x;
+                let dz = z as f32 - center_z;
+                if (dx * dx + dz * dz).sqrt() <= radius {
+                    let idx = z * vertex_count as usize + x;
+                    if idx < features.len() {
+                        features[idx] = feature.code();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Lock (or unlock) every vertex within the radius against erosion (hard edge, no falloff)
+    fn paint_lock(&mut self, center_x: f32, center_z: f32, radius: f32, locked: bool, vertex_count: u32) {
+        let size = (vertex_count * vertex_count) as usize;
+        let mask = self.locked.get_or_insert_with(|| vec![false; size]);
+
+        let min_x = ((center_x - radius).floor().max(0.0) as usize).min(vertex_count as usize - 1);
+        let max_x = ((center_x + radius).ceil().min(vertex_count as f32 - 1.0) as usize).min(vertex_count as usize - 1);
+        let min_z = ((center_z - radius).floor().max(0.0) as usize).min(vertex_count as usize - 1);
+        let max_z = ((center_z + radius).ceil().min(vertex_count as f32 - 1.0) as usize).min(vertex_count as usize - 1);
+
+        for z in min_z..=max_z {
+            for x in min_x..=max_x {
+                let dx = x as f32 - center_x;
+                let dz = z as f32 - center_z;
+                if (dx * dx + dz * dz).sqrt() <= radius {
+                    let idx = z * vertex_count as usize + x;
+                    if idx < mask.len() {
+                        mask[idx] = locked;
+                    }
+                }
+            }
+        }
+
+        if mask.iter().all(|&l| !l) {
+            self.locked = None;
+        }
+    }
+
+    /// Raise terrain with the given falloff
+    fn apply_raise(&mut self, center_x: f32, center_z: f32, radius: f32, strength: f32, curve: FalloffCurve, vertex_count: u32) {
         let min_x = ((center_x - radius).floor().max(0.0) as usize).min(vertex_count as usize - 1);
         let max_x = ((center_x + radius).ceil().min(vertex_count as f32 - 1.0) as usize).min(vertex_count as usize - 1);
         let min_z = ((center_z - radius).floor().max(0.0) as usize).min(vertex_count as usize - 1);
@@ -53,7 +218,7 @@ impl HeightmapChunk {
                 let dist = (dx * dx + dz * dz).sqrt();
 
                 if dist <= radius {
-                    let falloff = gaussian_falloff(dist, radius);
+                    let falloff = curve.weight(dist, radius);
                     let idx = z * vertex_count as usize + x;
                     if idx < self.heights.len() {
                         self.heights[idx] += strength * falloff * 0.01;
@@ -64,13 +229,23 @@ impl HeightmapChunk {
         }
     }
 
-    /// Lower terrain with Gaussian falloff
-    fn apply_lower(&mut self, center_x: f32, center_z: f32, radius: f32, strength: f32, vertex_count: u32) {
-        self.apply_raise(center_x, center_z, radius, -strength, vertex_count);
+    /// Lower terrain with the given falloff
+    fn apply_lower(&mut self, center_x: f32, center_z: f32, radius: f32, strength: f32, curve: FalloffCurve, vertex_count: u32) {
+        self.apply_raise(center_x, center_z, radius, -strength, curve, vertex_count);
     }
 
     /// Smooth terrain using box blur
-    fn apply_smooth(&mut self, center_x: f32, center_z: f32, radius: f32, strength: f32, vertex_count: u32) {
+    #[allow(clippy::too_many_arguments)]
+    fn apply_smooth(
+        &mut self,
+        center_x: f32,
+        center_z: f32,
+        radius: f32,
+        strength: f32,
+        curve: FalloffCurve,
+        vertex_count: u32,
+        neighbors: Option<&HeightWindow>,
+    ) {
         let min_x = ((center_x - radius).floor().max(0.0) as usize).min(vertex_count as usize - 1);
         let max_x = ((center_x + radius).ceil().min(vertex_count as f32 - 1.0) as usize).min(vertex_count as usize - 1);
         let min_z = ((center_z - radius).floor().max(0.0) as usize).min(vertex_count as usize - 1);
@@ -85,8 +260,8 @@ impl HeightmapChunk {
                 let dist = (dx * dx + dz * dz).sqrt();
 
                 if dist <= radius {
-                    let falloff = gaussian_falloff(dist, radius);
-                    let avg = self.calculate_average(x, z, 1, vertex_count);
+                    let falloff = curve.weight(dist, radius);
+                    let avg = self.calculate_average(x, z, 1, vertex_count, neighbors);
                     let idx = z * vertex_count as usize + x;
                     if idx < smoothed.len() {
                         smoothed[idx] = self.heights[idx] * (1.0 - strength * falloff) + avg * (strength * falloff);
@@ -99,6 +274,7 @@ impl HeightmapChunk {
     }
 
     /// Flatten terrain to target height
+    #[allow(clippy::too_many_arguments)]
     fn apply_flatten(
         &mut self,
         center_x: f32,
@@ -106,6 +282,7 @@ impl HeightmapChunk {
         radius: f32,
         strength: f32,
         target_height: f32,
+        curve: FalloffCurve,
         vertex_count: u32,
     ) {
         let min_x = ((center_x - radius).floor().max(0.0) as usize).min(vertex_count as usize - 1);
@@ -120,7 +297,7 @@ impl HeightmapChunk {
                 let dist = (dx * dx + dz * dz).sqrt();
 
                 if dist <= radius {
-                    let falloff = gaussian_falloff(dist, radius);
+                    let falloff = curve.weight(dist, radius);
                     let idx = z * vertex_count as usize + x;
                     if idx < self.heights.len() {
                         self.heights[idx] = self.heights[idx] * (1.0 - strength * falloff)
@@ -131,8 +308,18 @@ impl HeightmapChunk {
         }
     }
 
-    /// Apply localized erosion (simplified version)
-    fn apply_erode(&mut self, center_x: f32, center_z: f32, radius: f32, _droplet_count: u32, vertex_count: u32) {
+    /// Apply localized erosion (simplified version); locked vertices are left alone
+    #[allow(clippy::too_many_arguments)]
+    fn apply_erode(
+        &mut self,
+        center_x: f32,
+        center_z: f32,
+        radius: f32,
+        _droplet_count: u32,
+        curve: FalloffCurve,
+        vertex_count: u32,
+        neighbors: Option<&HeightWindow>,
+    ) {
         // Simplified erosion: slightly lower peaks and raise valleys
         let min_x = ((center_x - radius).floor().max(0.0) as usize).min(vertex_count as usize - 1);
         let max_x = ((center_x + radius).ceil().min(vertex_count as f32 - 1.0) as usize).min(vertex_count as usize - 1);
@@ -146,10 +333,11 @@ impl HeightmapChunk {
                 let dist = (dx * dx + dz * dz).sqrt();
 
                 if dist <= radius {
-                    let falloff = gaussian_falloff(dist, radius);
-                    let avg = self.calculate_average(x, z, 2, vertex_count);
+                    let falloff = curve.weight(dist, radius);
+                    let avg = self.calculate_average(x, z, 2, vertex_count, neighbors);
                     let idx = z * vertex_count as usize + x;
-                    if idx < self.heights.len() {
+                    let locked = self.locked.as_ref().is_some_and(|mask| mask.get(idx).copied().unwrap_or(false));
+                    if idx < self.heights.len() && !locked {
                         // Move toward average (erosion effect)
                         self.heights[idx] = self.heights[idx] * (1.0 - 0.3 * falloff) + avg * (0.3 * falloff);
                     }
@@ -159,6 +347,7 @@ impl HeightmapChunk {
     }
 
     /// Add procedural noise
+    #[allow(clippy::too_many_arguments)]
     fn apply_noise(
         &mut self,
         center_x: f32,
@@ -166,10 +355,17 @@ impl HeightmapChunk {
         radius: f32,
         scale: f32,
         strength: f32,
+        seed: u32,
+        curve: FalloffCurve,
         vertex_count: u32,
     ) {
         use noise::{NoiseFn, Perlin};
-        let perlin = Perlin::new(rand::random());
+        let perlin = Perlin::new(seed);
+
+        // Sample at global vertex positions so strokes spanning chunks stay seamless
+        let chunk_size = (vertex_count - 1) as f64;
+        let origin_x = self.coord.0 as f64 * chunk_size;
+        let origin_z = self.coord.1 as f64 * chunk_size;
 
         let min_x = ((center_x - radius).floor().max(0.0) as usize).min(vertex_count as usize - 1);
         let max_x = ((center_x + radius).ceil().min(vertex_count as f32 - 1.0) as usize).min(vertex_count as usize - 1);
@@ -183,8 +379,11 @@ impl HeightmapChunk {
                 let dist = (dx * dx + dz * dz).sqrt();
 
                 if dist <= radius {
-                    let falloff = gaussian_falloff(dist, radius);
-                    let noise_val = perlin.get([x as f64 * scale as f64, z as f64 * scale as f64]) as f32;
+                    let falloff = curve.weight(dist, radius);
+                    let noise_val = perlin.get([
+                        (origin_x + x as f64) * scale as f64,
+                        (origin_z + z as f64) * scale as f64,
+                    ]) as f32;
                     let idx = z * vertex_count as usize + x;
                     if idx < self.heights.len() {
                         self.heights[idx] += noise_val * strength * falloff * 0.01;
@@ -195,11 +394,116 @@ impl HeightmapChunk {
         }
     }
 
+    /// Blend cells within the ramp's corridor toward the height interpolated along its
+    /// axis; `curve` softens the corridor edges. `ramp` is in this chunk's local coordinates
+    pub fn apply_ramp(&mut self, ramp: &Ramp, strength: f32, curve: FalloffCurve, vertex_count: u32) {
+        let half_width = ramp.width / 2.0;
+        let (axis_x, axis_z) = (ramp.end.0 - ramp.start.0, ramp.end.1 - ramp.start.1);
+        let length_sq = axis_x * axis_x + axis_z * axis_z;
+        if half_width <= 0.0 || length_sq <= f32::EPSILON {
+            return;
+        }
+
+        let last = vertex_count as f32 - 1.0;
+        let min_x = (ramp.start.0.min(ramp.end.0) - half_width).floor().clamp(0.0, last) as usize;
+        let max_x = (ramp.start.0.max(ramp.end.0) + half_width).ceil().clamp(0.0, last) as usize;
+        let min_z = (ramp.start.1.min(ramp.end.1) - half_width).floor().clamp(0.0, last) as usize;
+        let max_z = (ramp.start.1.max(ramp.end.1) + half_width).ceil().clamp(0.0, last) as usize;
+
+        for z in min_z..=max_z {
+            for x in min_x..=max_x {
+                let (dx, dz) = (x as f32 - ramp.start.0, z as f32 - ramp.start.1);
+                let t = (dx * axis_x + dz * axis_z) / length_sq;
+                if !(0.0..=1.0).contains(&t) {
+                    continue;
+                }
+
+                let (off_x, off_z) = (dx - t * axis_x, dz - t * axis_z);
+                let dist = (off_x * off_x + off_z * off_z).sqrt();
+                if dist <= half_width {
+                    let weight = (strength * curve.weight(dist, half_width)).clamp(0.0, 1.0);
+                    let target = ramp.start_height + (ramp.end_height - ramp.start_height) * t;
+                    let idx = z * vertex_count as usize + x;
+                    if idx < self.heights.len() {
+                        self.heights[idx] = self.heights[idx] * (1.0 - weight) + target * weight;
+                    }
+                }
+            }
+        }
+
+        self.recompute_bounds();
+    }
+
+    /// Imprint a square heightmap pattern (row-major, `pattern_size`² values) scaled to
+    /// span the brush diameter; pattern values are multiplied by `strength` before blending
+    #[allow(clippy::too_many_arguments)]
+    fn apply_stamp(
+        &mut self,
+        center_x: f32,
+        center_z: f32,
+        radius: f32,
+        strength: f32,
+        pattern: &[f32],
+        pattern_size: u32,
+        blend: StampBlend,
+        vertex_count: u32,
+    ) {
+        let pattern_size = pattern_size as usize;
+        if pattern_size == 0 || pattern_size.checked_mul(pattern_size) != Some(pattern.len()) || radius <= 0.0 {
+            return;
+        }
+
+        let min_x = ((center_x - radius).floor().max(0.0) as usize).min(vertex_count as usize - 1);
+        let max_x = ((center_x + radius).ceil().min(vertex_count as f32 - 1.0) as usize).min(vertex_count as usize - 1);
+        let min_z = ((center_z - radius).floor().max(0.0) as usize).min(vertex_count as usize - 1);
+        let max_z = ((center_z + radius).ceil().min(vertex_count as f32 - 1.0) as usize).min(vertex_count as usize - 1);
+
+        for z in min_z..=max_z {
+            for x in min_x..=max_x {
+                // Position within the stamp footprint, 0..1 across the diameter
+                let u = (x as f32 - center_x + radius) / (2.0 * radius);
+                let v = (z as f32 - center_z + radius) / (2.0 * radius);
+                if !(0.0..=1.0).contains(&u) || !(0.0..=1.0).contains(&v) {
+                    continue;
+                }
+
+                let value = sample_pattern(pattern, pattern_size, u, v) * strength;
+                let idx = z * vertex_count as usize + x;
+                if idx < self.heights.len() {
+                    let height = match blend {
+                        StampBlend::Add => self.heights[idx] + value,
+                        StampBlend::Max => self.heights[idx].max(value),
+                        StampBlend::Replace => value,
+                    };
+                    self.heights[idx] = height.clamp(0.0, 1.0);
+                }
+            }
+        }
+    }
+
     /// Calculate average height in a neighborhood
-    fn calculate_average(&self, x: usize, z: usize, kernel_size: usize, vertex_count: u32) -> f32 {
+    /// With `neighbors`, the kernel reaches into adjacent chunks; otherwise it clamps to this one
+    fn calculate_average(&self, x: usize, z: usize, kernel_size: usize, vertex_count: u32, neighbors: Option<&HeightWindow>) -> f32 {
         let mut sum = 0.0;
         let mut count = 0;
 
+        if let Some(window) = neighbors {
+            let chunk_size = vertex_count as i32 - 1;
+            let global_x = self.coord.0 * chunk_size + x as i32;
+            let global_z = self.coord.1 * chunk_size + z as i32;
+            for dz in -(kernel_size as i32)..=(kernel_size as i32) {
+                for dx in -(kernel_size as i32)..=(kernel_size as i32) {
+                    if let Some(h) = window.get(global_x + dx, global_z + dz) {
+                        sum += h;
+                        count += 1;
+                    }
+                }
+            }
+            if count > 0 {
+                return sum / count as f32;
+            }
+        }
+
         for dz in -(kernel_size as i32)..=(kernel_size as i32) {
             for dx in -(kernel_size as i32)..=(kernel_size as i32) {
                 let nx = (x as i32 + dx).max(0).min(vertex_count as i32 - 1) as usize;
@@ -220,8 +524,95 @@ impl HeightmapChunk {
     }
 }
 
+/// Bilinearly sample a square pattern at normalized (u, v)
+fn sample_pattern(pattern: &[f32], size: usize, u: f32, v: f32) -> f32 {
+    let px = u * (size - 1) as f32;
+    let pz = v * (size - 1) as f32;
+    let x0 = (px.floor() as usize).min(size - 1);
+    let z0 = (pz.floor() as usize).min(size - 1);
+    let x1 = (x0 + 1).min(size - 1);
+    let z1 = (z0 + 1).min(size - 1);
+    let fx = px - x0 as f32;
+    let fz = pz - z0 as f32;
+
+    let top = pattern[z0 * size + x0] * (1.0 - fx) + pattern[z0 * size + x1] * fx;
+    let bottom = pattern[z1 * size + x0] * (1.0 - fx) + pattern[z1 * size + x1] * fx;
+    top * (1.0 - fz) + bottom * fz
+}
+
 /// Gaussian falloff function
 fn gaussian_falloff(distance: f32, radius: f32) -> f32 {
     let normalized = distance / radius;
     (-normalized * normalized * 4.0).exp()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_noise_brush_is_reproducible() {
+        let stroke = |seed: u32| {
+            let mut chunk = HeightmapChunk::new((0, 0), 129);
+            let op = BrushOp::Noise { scale: 0.1, strength: 1.0, seed };
+            chunk.apply_brush(64.0, 64.0, 20.0, 1.0, op, FalloffCurve::Gaussian, 129);
+            chunk.heights
+        };
+
+        assert_eq!(stroke(42), stroke(42));
+        assert_ne!(stroke(42), stroke(43));
+    }
+
+    #[test]
+    fn test_stamp_raises_bump_center() {
+        let bump = vec![
+            0.0, 0.0, 0.0,
+            0.0, 1.0, 0.0,
+            0.0, 0.0, 0.0,
+        ];
+        let mut chunk = HeightmapChunk::new((0, 0), 129);
+        let op = BrushOp::Stamp { pattern: bump, pattern_size: 3, blend: StampBlend::Add };
+        chunk.apply_brush(64.0, 64.0, 4.0, 0.25, op, FalloffCurve::Gaussian, 129);
+
+        assert!((chunk.heights[64 * 129 + 64] - 0.45).abs() < 1e-6);
+        // The bump fades to nothing at the stamp's corners and outside it
+        assert_eq!(chunk.heights[60 * 129 + 60], 0.2);
+        assert_eq!(chunk.heights[64 * 129 + 70], 0.2);
+    }
+
+    #[test]
+    fn test_linear_falloff_differs_from_gaussian() {
+        let raise_at_half_radius = |curve: FalloffCurve| {
+            let mut chunk = HeightmapChunk::new((0, 0), 129);
+            chunk.apply_brush(64.0, 64.0, 20.0, 10.0, BrushOp::Raise, curve, 129);
+            chunk.heights[64 * 129 + 74] - 0.2
+        };
+
+        let linear = raise_at_half_radius(FalloffCurve::Linear);
+        let gaussian = raise_at_half_radius(FalloffCurve::Gaussian);
+        // Linear keeps half strength at half radius; Gaussian has dropped to e^-1
+        assert!((linear - 0.05).abs() < 1e-4);
+        assert!((linear - gaussian).abs() > 0.01);
+    }
+
+    #[test]
+    fn test_erode_brush_skips_locked_vertices() {
+        let mut chunk = HeightmapChunk::new((0, 0), 33);
+        for (i, h) in chunk.heights.iter_mut().enumerate() {
+            *h = if i % 2 == 0 { 0.8 } else { 0.2 };
+        }
+        chunk.apply_brush(16.0, 16.0, 6.0, 1.0, BrushOp::PaintLock { locked: true }, FalloffCurve::Constant, 33);
+        let before = chunk.heights.clone();
+
+        let op = BrushOp::Erode { droplet_count: 100 };
+        chunk.apply_brush(16.0, 16.0, 10.0, 1.0, op, FalloffCurve::Constant, 33);
+
+        let locked = chunk.locked.as_ref().unwrap();
+        for i in 0..before.len() {
+            if locked[i] {
+                assert_eq!(chunk.heights[i], before[i], "locked vertex {} moved", i);
+            }
+        }
+        assert_ne!(chunk.heights[16 * 33 + 24], before[16 * 33 + 24]);
+    }
+}
@@ -1,22 +1,65 @@
 use tauri::{State, Emitter};
 use tokio::sync::Mutex;
 use serde::{Serialize, Deserialize};
-use super::TerrainData;
-use super::config::{TerrainConfig, WorldTheme};
-use super::brush::BrushOp;
+use super::{TerrainAnalysis, TerrainData};
+use super::config::{TerrainConfig, WindDirection, WorldTheme};
+use super::brush::{BrushOp, FalloffCurve, Ramp, StampBlend};
+use super::heightmap::TerrainFeature;
+use super::seed_search::{FoundWorld, SeedCriteria};
+use super::noise_gen::TerrainStyle;
+use super::hydrology::FlowRouting;
+
+/// Highest octave count a noise layer may use; each octave is another full noise evaluation
+pub const MAX_NOISE_OCTAVES: usize = 8;
+
+/// Valid range for non-zero noise frequencies (cycles per meter)
+/// Zero frequencies are kept as-is since all-zero requests a flat world
+pub const MIN_NOISE_FREQUENCY: f64 = 0.00001;
+pub const MAX_NOISE_FREQUENCY: f64 = 0.05;
+
+/// Valid ranges for per-layer fractal shaping
+pub const MAX_NOISE_PERSISTENCE: f64 = 1.0;
+pub const MIN_NOISE_LACUNARITY: f64 = 1.0;
+pub const MAX_NOISE_LACUNARITY: f64 = 4.0;
+
+/// Largest coastline domain-warp offset (meters)
+pub const MAX_WARP_STRENGTH: f32 = 100_000.0;
+
+/// Base noise function the fractal layers are built from
+/// Perlin shows axis-aligned artifacts at low frequencies; the simplex variants avoid them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum NoiseBackend {
+    #[default]
+    Perlin,
+    OpenSimplex,
+    Simplex,
+}
 
 /// Noise generation parameters
+/// Missing fields take their defaults so requests saved before a field existed still load
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct NoiseParameters {
     pub continent_frequency: f64,
     pub continent_octaves: usize,
+    pub continent_persistence: f64,  // Amplitude kept per octave (lower = smoother)
+    pub continent_lacunarity: f64,   // Frequency gain per octave
     pub mountain_frequency: f64,
     pub mountain_octaves: usize,
+    pub mountain_persistence: f64,
+    pub mountain_lacunarity: f64,
     pub hill_frequency: f64,
     pub hill_octaves: usize,
+    pub hill_persistence: f64,
+    pub hill_lacunarity: f64,
     pub detail_frequency: f64,
     pub detail_octaves: usize,
-    pub land_coverage: Option<f32>,  // Threshold for land vs ocean
+    pub detail_persistence: f64,
+    pub detail_lacunarity: f64,
+    pub land_coverage: Option<f32>,  // Fraction of the world that is land (0.0 = all ocean, 1.0 = all land)
+    pub noise_backend: NoiseBackend,
+    pub warp_strength: f32,          // Max continent-mask offset in meters (0 = no warping)
+    pub warp_frequency: f64,         // Frequency of the warp field
 }
 
 impl Default for NoiseParameters {
@@ -24,13 +67,71 @@ impl Default for NoiseParameters {
         Self {
             continent_frequency: 0.00005,
             continent_octaves: 3,
+            continent_persistence: 0.5,
+            continent_lacunarity: 2.0,
             mountain_frequency: 0.0002,
             mountain_octaves: 4,
+            mountain_persistence: 1.0,
+            mountain_lacunarity: 2.2,
             hill_frequency: 0.0005,
             hill_octaves: 3,
+            hill_persistence: 0.4,
+            hill_lacunarity: 2.3,
             detail_frequency: 0.001,
             detail_octaves: 2,
-            land_coverage: Some(0.45),
+            detail_persistence: 0.25,
+            detail_lacunarity: 2.5,
+            land_coverage: Some(0.55),
+            noise_backend: NoiseBackend::Perlin,
+            warp_strength: 0.0,
+            warp_frequency: 0.0001,
+        }
+    }
+}
+
+impl NoiseParameters {
+    /// Return a copy with octaves and frequencies limited to ranges that generate in bounded time
+    pub fn clamped(&self) -> Self {
+        if super::noise_gen::is_flat_params(self) {
+            return self.clone();
+        }
+
+        let octaves = |value: usize| value.clamp(1, MAX_NOISE_OCTAVES);
+        let frequency = |value: f64, fallback: f64| {
+            if value == 0.0 {
+                0.0
+            } else if value.is_finite() {
+                value.clamp(MIN_NOISE_FREQUENCY, MAX_NOISE_FREQUENCY)
+            } else {
+                fallback
+            }
+        };
+        let ranged = |value: f64, min: f64, max: f64, fallback: f64| {
+            if value.is_finite() { value.clamp(min, max) } else { fallback }
+        };
+        let defaults = Self::default();
+
+        Self {
+            continent_frequency: frequency(self.continent_frequency, defaults.continent_frequency),
+            continent_octaves: octaves(self.continent_octaves),
+            continent_persistence: ranged(self.continent_persistence, 0.0, MAX_NOISE_PERSISTENCE, defaults.continent_persistence),
+            continent_lacunarity: ranged(self.continent_lacunarity, MIN_NOISE_LACUNARITY, MAX_NOISE_LACUNARITY, defaults.continent_lacunarity),
+            mountain_frequency: frequency(self.mountain_frequency, defaults.mountain_frequency),
+            mountain_octaves: octaves(self.mountain_octaves),
+            mountain_persistence: ranged(self.mountain_persistence, 0.0, MAX_NOISE_PERSISTENCE, defaults.mountain_persistence),
+            mountain_lacunarity: ranged(self.mountain_lacunarity, MIN_NOISE_LACUNARITY, MAX_NOISE_LACUNARITY, defaults.mountain_lacunarity),
+            hill_frequency: frequency(self.hill_frequency, defaults.hill_frequency),
+            hill_octaves: octaves(self.hill_octaves),
+            hill_persistence: ranged(self.hill_persistence, 0.0, MAX_NOISE_PERSISTENCE, defaults.hill_persistence),
+            hill_lacunarity: ranged(self.hill_lacunarity, MIN_NOISE_LACUNARITY, MAX_NOISE_LACUNARITY, defaults.hill_lacunarity),
+            detail_frequency: frequency(self.detail_frequency, defaults.detail_frequency),
+            detail_octaves: octaves(self.detail_octaves),
+            detail_persistence: ranged(self.detail_persistence, 0.0, MAX_NOISE_PERSISTENCE, defaults.detail_persistence),
+            detail_lacunarity: ranged(self.detail_lacunarity, MIN_NOISE_LACUNARITY, MAX_NOISE_LACUNARITY, defaults.detail_lacunarity),
+            land_coverage: self.land_coverage.map(|coverage| coverage.clamp(0.0, 1.0)),
+            noise_backend: self.noise_backend,
+            warp_strength: if self.warp_strength.is_finite() { self.warp_strength.clamp(0.0, MAX_WARP_STRENGTH) } else { 0.0 },
+            warp_frequency: frequency(self.warp_frequency, defaults.warp_frequency),
         }
     }
 }
@@ -45,6 +146,39 @@ pub struct GenerateTerrainRequest {
     pub use_erosion: bool,
     pub erosion_iterations: u32,
     pub noise_params: Option<NoiseParameters>,
+    #[serde(default)]
+    pub wrap: bool,
+    #[serde(default)]
+    pub style: TerrainStyle,
+    #[serde(default)]
+    pub flow_routing: FlowRouting,
+    #[serde(default)]
+    pub wind_direction: WindDirection,
+}
+
+/// Request to preview terrain before a full generation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviewTerrainRequest {
+    pub width: u32,
+    pub height: u32,
+    pub seed: u32,
+    pub theme: WorldTheme,
+    pub noise_params: Option<NoiseParameters>,
+    pub preview_size: u32,
+    #[serde(default)]
+    pub wrap: bool,
+}
+
+/// Request to search successive seeds for a world meeting the criteria
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FindSeedRequest {
+    pub width: u32,
+    pub height: u32,
+    pub start_seed: u32,
+    pub theme: WorldTheme,
+    pub noise_params: Option<NoiseParameters>,
+    pub criteria: SeedCriteria,
+    pub max_tries: u32,
 }
 
 /// Response with generation progress
@@ -53,6 +187,7 @@ pub struct GenerateTerrainResponse {
     pub success: bool,
     pub message: String,
     pub chunk_count: usize,
+    pub noise_params: Option<NoiseParameters>, // Parameters actually used, after clamping
 }
 
 /// Progress update during generation
@@ -71,6 +206,31 @@ pub struct GetChunkRequest {
     pub lod: u8,
 }
 
+/// Elevation bounds of a chunk for culling and LOD decisions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkBounds {
+    pub chunk_x: i32,
+    pub chunk_z: i32,
+    pub min_height: f32,
+    pub max_height: f32,
+}
+
+/// Heights of one chunk changed by a brush stroke, as raw f32 LE bytes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrushedChunk {
+    pub chunk_x: i32,
+    pub chunk_z: i32,
+    pub heights: Vec<u8>,
+}
+
+/// Undo/redo availability for enabling the editor's buttons
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndoState {
+    pub can_undo: bool,
+    pub can_redo: bool,
+    pub entry_count: usize,
+}
+
 /// Request to apply brush
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApplyBrushRequest {
@@ -81,6 +241,64 @@ pub struct ApplyBrushRequest {
     pub radius: f32,
     pub strength: f32,
     pub brush_type: String,
+    #[serde(default)]
+    pub seed: Option<u32>,  // Noise brush seed; derived from the stroke position when omitted
+    #[serde(default)]
+    pub falloff: FalloffCurve,
+    #[serde(default)]
+    pub stamp: Option<StampPattern>,  // Required by the stamp brush
+}
+
+/// Square heightmap imprinted by the stamp brush, scaled to the brush diameter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StampPattern {
+    pub pattern: Vec<f32>,  // Row-major, pattern_size × pattern_size
+    pub pattern_size: u32,
+    #[serde(default)]
+    pub blend: StampBlend,
+}
+
+impl ApplyBrushRequest {
+    /// Seed for the noise brush, so replaying the same request reproduces the same stroke
+    fn noise_seed(&self) -> u32 {
+        self.seed.unwrap_or_else(|| {
+            let mut hash = 2_166_136_261u32; // FNV-1a over the stroke's position
+            for word in [self.chunk_x as u32, self.chunk_z as u32, self.center_x.to_bits(), self.center_z.to_bits()] {
+                for byte in word.to_le_bytes() {
+                    hash = (hash ^ byte as u32).wrapping_mul(16_777_619);
+                }
+            }
+            hash
+        })
+    }
+}
+
+/// Request to grade a ramp between two global vertex positions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyRampRequest {
+    pub start_x: f32,
+    pub start_z: f32,
+    pub end_x: f32,
+    pub end_z: f32,
+    pub width: f32,
+    pub strength: f32,
+    #[serde(default)]
+    pub start_height: Option<f32>,  // Defaults to the terrain height at the start point
+    #[serde(default)]
+    pub end_height: Option<f32>,    // Defaults to the terrain height at the end point
+    #[serde(default)]
+    pub falloff: FalloffCurve,
+}
+
+/// Request to paint a feature marker onto a chunk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarkFeatureRequest {
+    pub chunk_x: i32,
+    pub chunk_z: i32,
+    pub center_x: f32,
+    pub center_z: f32,
+    pub radius: f32,
+    pub feature: TerrainFeature,
 }
 
 /// Generate new terrain
@@ -90,9 +308,9 @@ pub async fn generate_terrain(
     terrain: State<'_, Mutex<TerrainData>>,
     app: tauri::AppHandle,
 ) -> Result<GenerateTerrainResponse, String> {
-    use super::noise_gen::{generate_terrain_simd, generate_terrain_with_params, post_process_terrain};
-    use super::erosion::{erode_terrain_parallel, ErosionParams};
-    use super::hydrology::{fill_depressions, calculate_flow_direction, calculate_flow_accumulation};
+    use super::noise_gen::generate_world;
+    use super::erosion::{erode_terrain_parallel_with_context, ErosionContext, ErosionParams};
+    use super::hydrology::{fill_depressions, calculate_flow_direction, accumulate_flow};
 
     // Helper to emit progress
     let emit_progress = |stage: &str, progress: f32, message: &str| {
@@ -103,94 +321,72 @@ pub async fn generate_terrain(
         });
     };
 
+    let mut config = TerrainConfig::new(request.width, request.height, request.seed, request.theme);
+    config.validate_dimensions()?;
+
     emit_progress("🌍 Shaping continents...", 0.0, "Generating base terrain");
 
-    let config = TerrainConfig::new(request.width, request.height, request.seed, request.theme);
-    
-    // Generate base terrain with custom noise parameters if provided
-    let mut chunks = if let Some(params) = request.noise_params {
-        generate_terrain_with_params(&config, &params)
-    } else {
-        generate_terrain_simd(&config)
-    };
+    config.wrap = request.wrap;
+    config.flow_routing = request.flow_routing;
+    config.wind_direction = request.wind_direction;
+    config.style = request.style;
     
+    // Generate base terrain with custom noise parameters if provided, then shape it
+    let noise_params = request.noise_params.map(|params| params.clamped());
+    config.noise_params = noise_params.clone();
     emit_progress("⛰️ Raising mountains...", 0.2, "Applying elevation curves");
-    post_process_terrain(&mut chunks, &config);
+    let chunks = generate_world(&mut config);
+
+    // Assemble the new world apart from the live terrain, which stays usable until the swap
+    let mut generated = TerrainData::empty(config);
+    for chunk in chunks {
+        generated.chunks.insert(chunk.coord, chunk);
+    }
 
     // Apply erosion if requested
     if request.use_erosion {
         emit_progress("🏔️ Carving valleys...", 0.35, "Preparing erosion simulation");
         
         // Flatten chunks into single heightmap for erosion
-        let vertex_count = config.vertex_count as usize;
-        let total_width = config.world_width as usize;
-        let total_height = config.world_height as usize;
-        let mut heights = vec![0.0; total_width * total_height];
-
-        for chunk in &chunks {
-            let chunk_start_x = (chunk.coord.0 * config.chunk_size as i32) as usize;
-            let chunk_start_z = (chunk.coord.1 * config.chunk_size as i32) as usize;
-
-            for local_z in 0..vertex_count {
-                for local_x in 0..vertex_count {
-                    let global_x = chunk_start_x + local_x;
-                    let global_z = chunk_start_z + local_z;
-                    if global_x < total_width && global_z < total_height {
-                        let chunk_idx = local_z * vertex_count + local_x;
-                        let global_idx = global_z * total_width + global_x;
-                        heights[global_idx] = chunk.heights[chunk_idx];
-                    }
-                }
-            }
-        }
+        let total_width = generated.config.world_width as usize;
+        let total_height = generated.config.world_height as usize;
+        let mut heights = generated.flatten_heights();
 
         emit_progress("🌊 Filling lakes...", 0.45, "Removing terrain depressions");
-        // Fill depressions
-        fill_depressions(&mut heights, total_width, total_height);
+        // Fill depressions (freshly generated chunks carry no locks)
+        fill_depressions(&mut heights, total_width, total_height, None);
 
         emit_progress("💧 Simulating erosion...", 0.55, "Running hydraulic erosion");
         // Apply hydraulic erosion
         let params = ErosionParams {
             num_droplets: request.erosion_iterations * 1000,
+            seed: Some(generated.config.seed as u64),  // Same seed, same world
             ..Default::default()
         };
-        erode_terrain_parallel(&mut heights, total_width, total_height, &params);
+        let report_droplets = |done: f32| {
+            let message = format!("Running hydraulic erosion ({:.0}%)", done * 100.0);
+            emit_progress("💧 Simulating erosion...", 0.55 + done * 0.2, &message);
+        };
+        let context = ErosionContext { progress: Some(&report_droplets), ..Default::default() };
+        erode_terrain_parallel_with_context(&mut heights, total_width, total_height, &params, &context);
 
         emit_progress("🏞️ Tracing rivers...", 0.75, "Calculating water flow");
         // Calculate flow for rivers
         let flow_direction = calculate_flow_direction(&heights, total_width, total_height);
-        let flow_accumulation = calculate_flow_accumulation(&heights, &flow_direction, total_width, total_height);
-
-        // Copy back to chunks
-        for chunk in &mut chunks {
-            let chunk_start_x = (chunk.coord.0 * config.chunk_size as i32) as usize;
-            let chunk_start_z = (chunk.coord.1 * config.chunk_size as i32) as usize;
-
-            let mut flow_data = Vec::new();
-
-            for local_z in 0..vertex_count {
-                for local_x in 0..vertex_count {
-                    let global_x = chunk_start_x + local_x;
-                    let global_z = chunk_start_z + local_z;
-                    if global_x < total_width && global_z < total_height {
-                        let chunk_idx = local_z * vertex_count + local_x;
-                        let global_idx = global_z * total_width + global_x;
-                        chunk.heights[chunk_idx] = heights[global_idx];
-                        flow_data.push(flow_accumulation[global_idx]);
-                    }
-                }
-            }
+        let flow_accumulation = accumulate_flow(generated.config.flow_routing, &heights, &flow_direction, total_width, total_height);
 
-            chunk.flow_accumulation = Some(flow_data);
-        }
+        // Copy back to chunks; droplets don't cross a wrapped world's seam, so
+        // store_heights re-stitches the edges
+        generated.store_heights(&heights);
+        generated.store_flow_accumulation(&flow_accumulation);
 
         emit_progress("🌲 Placing forests...", 0.85, "Extracting river networks");
         // Extract rivers
-        use super::rivers::extract_rivers;
-        let river_network = extract_rivers(&flow_accumulation, &flow_direction, total_width, total_height, 1000.0);
-        
-        let mut terrain = terrain.lock().await;
-        terrain.river_network = river_network;
+        use super::rivers::{extract_rivers, RiverParams};
+        generated.river_network = extract_rivers(
+            &flow_accumulation, &flow_direction, total_width, total_height,
+            &RiverParams::with_threshold(1000.0, generated.config.cell_size_meters),
+        );
     } else {
         emit_progress("🌲 Placing forests...", 0.7, "Skipping erosion");
     }
@@ -198,78 +394,552 @@ pub async fn generate_terrain(
     emit_progress("✨ Finalizing world...", 0.95, "Saving terrain data");
     
     // Update terrain data
-    let chunk_count = chunks.len();
+    let chunk_count = generated.chunks.len();
     let mut terrain = terrain.lock().await;
-    terrain.config = config;
-    terrain.chunks.clear();
-    for chunk in chunks {
-        terrain.chunks.insert(chunk.coord, chunk);
+    terrain.config = generated.config;
+    terrain.chunks = generated.chunks;
+    terrain.river_network = generated.river_network;
+    terrain.dirty_chunks.clear();
+    // Roads and deposits were placed on the old heights, and undo history refers to the old chunks
+    terrain.roads.clear();
+    terrain.deposits.clear();
+    terrain.undo_stack.clear();
+
+    emit_progress("✅ Complete!", 1.0, "Terrain generation finished");
+
+    Ok(GenerateTerrainResponse {
+        success: true,
+        message: format!("Generated {} chunks", chunk_count),
+        chunk_count,
+        noise_params,
+    })
+}
+
+/// Generate a quick low-resolution preview as grayscale bytes (row-major, preview_size²)
+/// No chunks are created or stored
+#[tauri::command]
+pub async fn preview_terrain(
+    request: PreviewTerrainRequest,
+) -> Result<Vec<u8>, String> {
+    use super::noise_gen::preview_terrain;
+
+    if request.preview_size == 0 || request.preview_size > 1024 {
+        return Err("Preview size must be between 1 and 1024".to_string());
+    }
+
+    let mut config = TerrainConfig::new(request.width, request.height, request.seed, request.theme);
+    config.wrap = request.wrap;
+    let params = request.noise_params.unwrap_or_default();
+
+    let bytes = preview_terrain(&config, &params, request.preview_size)
+        .into_iter()
+        .map(|h| (h.clamp(0.0, 1.0) * 255.0) as u8)
+        .collect();
+
+    Ok(bytes)
+}
+
+/// Search seeds from `start_seed` for the first world meeting the criteria
+/// Returns None if no seed matched within `max_tries`
+#[tauri::command]
+pub async fn find_seed(
+    request: FindSeedRequest,
+) -> Result<Option<FoundWorld>, String> {
+    if request.max_tries == 0 || request.max_tries > 10_000 {
+        return Err("max_tries must be between 1 and 10000".to_string());
+    }
+
+    let config = TerrainConfig::new(request.width, request.height, request.start_seed, request.theme);
+    let params = request.noise_params.unwrap_or_default();
+
+    Ok(super::seed_search::find_seed(&config, &params, &request.criteria, request.max_tries))
+}
+
+/// Re-roll only the chunks in a rectangle, keeping edits elsewhere
+/// `noise_params` replaces the world's parameters for this region only
+#[tauri::command]
+pub async fn regenerate_region(
+    min_chunk: (i32, i32),
+    max_chunk: (i32, i32),
+    seed_offset: u32,
+    noise_params: Option<NoiseParameters>,
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<GenerateTerrainResponse, String> {
+    let mut terrain_data = terrain.lock().await;
+
+    if terrain_data.chunks.is_empty() {
+        return Err("No terrain generated".to_string());
+    }
+
+    let params = noise_params.map(|params| params.clamped());
+    let regenerated = terrain_data.regenerate_region(min_chunk, max_chunk, seed_offset, params.as_ref());
+
+    Ok(GenerateTerrainResponse {
+        success: true,
+        message: format!("Regenerated {} chunks", regenerated.len()),
+        chunk_count: regenerated.len(),
+        noise_params: params.or_else(|| terrain_data.config.noise_params.clone()),
+    })
+}
+
+/// Rebuild one chunk from the world's seed and noise parameters, discarding its edits
+/// Returns the new heights as raw bytes
+#[tauri::command]
+pub async fn regenerate_chunk(
+    chunk_x: i32,
+    chunk_z: i32,
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<Vec<u8>, String> {
+    let mut terrain = terrain.lock().await;
+
+    terrain.regenerate_chunk(chunk_x, chunk_z)?;
+
+    let chunk = &terrain.chunks[&(chunk_x, chunk_z)];
+    Ok(chunk.heights.iter().flat_map(|h| h.to_le_bytes()).collect())
+}
+
+/// Get a chunk's height data, at reduced resolution when `lod` > 0
+#[tauri::command]
+pub async fn get_chunk(
+    request: GetChunkRequest,
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<Vec<u8>, String> {
+    let terrain = terrain.lock().await;
+    
+    let chunk = terrain.chunks.get(&(request.chunk_x, request.chunk_z))
+        .ok_or("Chunk not found")?;
+
+    // Return raw f32 bytes (binary IPC), downsampled for distant chunks
+    let bytes: Vec<u8> = chunk.downsample(request.lod, terrain.config.vertex_count).iter()
+        .flat_map(|h| h.to_le_bytes())
+        .collect();
+
+    Ok(bytes)
+}
+
+/// Get a chunk's per-vertex unit normals (+y up) as raw f32 bytes, 3 per vertex
+#[tauri::command]
+pub async fn get_chunk_normals(
+    chunk_x: i32,
+    chunk_z: i32,
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<Vec<u8>, String> {
+    let terrain = terrain.lock().await;
+
+    let chunk = terrain.chunks.get(&(chunk_x, chunk_z))
+        .ok_or("Chunk not found")?;
+    let config = &terrain.config;
+
+    let bytes: Vec<u8> = chunk.calculate_normals(config.vertex_count, config.cell_size_meters, config.max_elevation)
+        .iter()
+        .flatten()
+        .flat_map(|n| n.to_le_bytes())
+        .collect();
+
+    Ok(bytes)
+}
+
+/// Get a chunk's slope per vertex, 0..90° scaled to 0..255 (one byte per vertex)
+#[tauri::command]
+pub async fn get_slope_map(
+    chunk_x: i32,
+    chunk_z: i32,
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<Vec<u8>, String> {
+    let terrain = terrain.lock().await;
+
+    let chunk = terrain.chunks.get(&(chunk_x, chunk_z))
+        .ok_or("Chunk not found")?;
+    let config = &terrain.config;
+
+    let bytes = chunk.calculate_slopes(config.vertex_count, config.cell_size_meters, config.max_elevation)
+        .into_iter()
+        .map(|degrees| (degrees / 90.0 * 255.0).round().clamp(0.0, 255.0) as u8)
+        .collect();
+
+    Ok(bytes)
+}
+
+/// Export a chunk's heights as a 16-bit grayscale PNG (vertex_count × vertex_count)
+#[tauri::command]
+pub async fn export_heightmap_png(
+    path: String,
+    chunk_x: i32,
+    chunk_z: i32,
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<(), String> {
+    let terrain = terrain.lock().await;
+
+    let chunk = terrain.chunks.get(&(chunk_x, chunk_z))
+        .ok_or("Chunk not found")?;
+    let vertex_count = terrain.config.vertex_count;
+
+    super::export::write_heightmap_png(&path, &chunk.heights, vertex_count, vertex_count)
+        .map_err(|e| format!("Failed to export heightmap: {}", e))
+}
+
+/// Export the whole world's heights as a 16-bit grayscale PNG (world_width × world_height)
+#[tauri::command]
+pub async fn export_world_heightmap_png(
+    path: String,
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<(), String> {
+    let terrain = terrain.lock().await;
+
+    if terrain.chunks.is_empty() {
+        return Err("No terrain generated".to_string());
+    }
+    terrain.validate_chunk_coords()?;
+    let heights = terrain.flatten_heights();
+
+    super::export::write_heightmap_png(&path, &heights, terrain.config.world_width, terrain.config.world_height)
+        .map_err(|e| format!("Failed to export heightmap: {}", e))
+}
+
+/// Export the loaded terrain as a Wavefront OBJ mesh in metres, optionally with normals
+/// `lod` keeps every 2^lod-th vertex
+#[tauri::command]
+pub async fn export_mesh_obj(
+    path: String,
+    lod: u8,
+    include_normals: Option<bool>,
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<String, String> {
+    let terrain = terrain.lock().await;
+
+    if terrain.chunks.is_empty() {
+        return Err("No terrain generated".to_string());
+    }
+    terrain.validate_chunk_coords()?;
+
+    let file = std::fs::File::create(&path)
+        .map_err(|e| format!("Failed to create {}: {}", path, e))?;
+    let mut writer = std::io::BufWriter::new(file);
+    let stats = super::export::write_mesh_obj(&terrain, lod, include_normals.unwrap_or(false), &mut writer)
+        .and_then(|stats| {
+            use std::io::Write;
+            writer.flush()?;
+            Ok(stats)
+        })
+        .map_err(|e| format!("Failed to export mesh: {}", e))?;
+
+    Ok(format!("Exported {} vertices and {} triangles", stats.vertices, stats.faces))
+}
+
+/// Export rivers and roads as a GeoJSON FeatureCollection (coordinates in world metres)
+#[tauri::command]
+pub async fn export_features_geojson(
+    path: String,
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<String, String> {
+    let terrain = terrain.lock().await;
+
+    let collection = super::export::features_geojson(&terrain);
+    let json = serde_json::to_string_pretty(&collection)
+        .map_err(|e| format!("Failed to serialize features: {}", e))?;
+    std::fs::write(&path, json)
+        .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+
+    Ok(format!(
+        "Exported {} rivers and {} roads",
+        terrain.river_network.segments.len(), terrain.roads.len()
+    ))
+}
+
+/// Replace the terrain with a grayscale PNG heightmap, bilinearly resampled to the world size
+#[tauri::command]
+pub async fn import_heightmap_png(
+    path: String,
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<GenerateTerrainResponse, String> {
+    let (heights, width, height) = super::export::read_heightmap_png(&path)
+        .map_err(|e| format!("Failed to import heightmap: {}", e))?;
+    if width == 0 || height == 0 {
+        return Err("Heightmap image is empty".to_string());
+    }
+
+    let mut terrain = terrain.lock().await;
+    terrain.import_heights(&heights, width as usize, height as usize);
+
+    Ok(GenerateTerrainResponse {
+        success: true,
+        message: format!(
+            "Imported {}×{} heightmap into {} chunks",
+            width, height, terrain.chunks.len()
+        ),
+        chunk_count: terrain.chunks.len(),
+        noise_params: None,
+    })
+}
+
+/// Get a chunk's (min, max) height without downloading its heights
+#[tauri::command]
+pub async fn chunk_bounds(
+    chunk_x: i32,
+    chunk_z: i32,
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<(f32, f32), String> {
+    let mut terrain = terrain.lock().await;
+
+    let chunk = terrain.get_chunk_mut(chunk_x, chunk_z)
+        .ok_or("Chunk not found")?;
+
+    Ok(chunk.bounds())
+}
+
+/// Get the elevation bounds of every chunk
+#[tauri::command]
+pub async fn all_chunk_bounds(
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<Vec<ChunkBounds>, String> {
+    let mut terrain = terrain.lock().await;
+
+    let mut bounds: Vec<ChunkBounds> = terrain.chunks.iter_mut()
+        .map(|(&(chunk_x, chunk_z), chunk)| {
+            let (min_height, max_height) = chunk.bounds();
+            ChunkBounds { chunk_x, chunk_z, min_height, max_height }
+        })
+        .collect();
+    bounds.sort_by_key(|b| (b.chunk_z, b.chunk_x));
+
+    Ok(bounds)
+}
+
+/// Elevation histogram and land/ocean ratio across all chunks (default 32 bins)
+#[tauri::command]
+pub async fn analyze_terrain(
+    bins: Option<usize>,
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<TerrainAnalysis, String> {
+    let bins = bins.unwrap_or(32);
+    if bins == 0 || bins > 1024 {
+        return Err("Histogram bins must be between 1 and 1024".to_string());
+    }
+
+    let terrain = terrain.lock().await;
+    if terrain.chunks.is_empty() {
+        return Err("No terrain generated".to_string());
+    }
+    terrain.validate_chunk_coords()?;
+
+    Ok(terrain.analyze(bins))
+}
+
+/// Apply brush operation around a point in a chunk
+/// The stroke continues into every neighbouring chunk its radius overlaps;
+/// returns the heights of all affected chunks
+#[tauri::command]
+pub async fn apply_brush(
+    request: ApplyBrushRequest,
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<Vec<BrushedChunk>, String> {
+    let mut terrain = terrain.lock().await;
+    
+    let op = match request.brush_type.as_str() {
+        "raise" => BrushOp::Raise,
+        "lower" => BrushOp::Lower,
+        "smooth" => BrushOp::Smooth,
+        "flatten" => BrushOp::Flatten { target_height: 0.5 },
+        "erode" => BrushOp::Erode { droplet_count: 100 },
+        "noise" => BrushOp::Noise { scale: 0.1, strength: request.strength, seed: request.noise_seed() },
+        "lock" => BrushOp::PaintLock { locked: true },
+        "unlock" => BrushOp::PaintLock { locked: false },
+        "stamp" => {
+            let stamp = request.stamp.clone().ok_or("Stamp brush requires a pattern")?;
+            let cells = (stamp.pattern_size as usize).checked_mul(stamp.pattern_size as usize);
+            if stamp.pattern_size == 0 || cells != Some(stamp.pattern.len()) {
+                return Err("Stamp pattern must hold pattern_size × pattern_size values".into());
+            }
+            BrushOp::Stamp { pattern: stamp.pattern, pattern_size: stamp.pattern_size, blend: stamp.blend }
+        }
+        _ => return Err("Unknown brush type".into()),
+    };
+
+    if !terrain.chunks.contains_key(&(request.chunk_x, request.chunk_z)) {
+        return Err("Chunk not found".into());
+    }
+
+    // Apply brush to every overlapped chunk (marks them dirty)
+    let touched = terrain.apply_brush(
+        (request.chunk_x, request.chunk_z),
+        request.center_x,
+        request.center_z,
+        request.radius,
+        request.strength,
+        op,
+        request.falloff,
+    );
+
+    // Return modified heights as raw bytes
+    Ok(brushed_chunks(&terrain, touched))
+}
+
+/// Undo the last brush stroke (or whole-world height pass), returning the restored chunks' heights
+#[tauri::command]
+pub async fn undo_brush(
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<Vec<BrushedChunk>, String> {
+    let mut terrain = terrain.lock().await;
+
+    let restored = terrain.undo();
+    Ok(brushed_chunks(&terrain, restored))
+}
+
+/// Redo the last undone stroke or height pass, returning the changed chunks' heights
+#[tauri::command]
+pub async fn redo_brush(
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<Vec<BrushedChunk>, String> {
+    let mut terrain = terrain.lock().await;
+
+    let restored = terrain.redo();
+    Ok(brushed_chunks(&terrain, restored))
+}
+
+/// Get whether undo/redo are available
+#[tauri::command]
+pub async fn get_undo_state(
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<UndoState, String> {
+    let terrain = terrain.lock().await;
+
+    Ok(UndoState {
+        can_undo: terrain.undo_stack.can_undo(),
+        can_redo: terrain.undo_stack.can_redo(),
+        entry_count: terrain.undo_stack.len(),
+    })
+}
+
+/// Forget all brush undo/redo history
+#[tauri::command]
+pub async fn clear_undo_history(
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<(), String> {
+    let mut terrain = terrain.lock().await;
+    terrain.undo_stack.clear();
+    Ok(())
+}
+
+/// Encode the heights of the given chunks as raw f32 LE bytes, skipping unloaded ones
+fn brushed_chunks(terrain: &TerrainData, coords: Vec<(i32, i32)>) -> Vec<BrushedChunk> {
+    coords.into_iter()
+        .filter_map(|(chunk_x, chunk_z)| {
+            let chunk = terrain.chunks.get(&(chunk_x, chunk_z))?;
+            let heights = chunk.heights.iter()
+                .flat_map(|h| h.to_le_bytes())
+                .collect();
+            Some(BrushedChunk { chunk_x, chunk_z, heights })
+        })
+        .collect()
+}
+
+/// Grade a linear slope between two points, e.g. for terraces or road beds
+/// Returns the heights of all affected chunks
+#[tauri::command]
+pub async fn apply_ramp(
+    request: ApplyRampRequest,
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<Vec<BrushedChunk>, String> {
+    if request.width.is_nan() || request.width <= 0.0 {
+        return Err("Ramp width must be positive".into());
+    }
+
+    let mut terrain = terrain.lock().await;
+
+    let start_height = match request.start_height {
+        Some(height) => height,
+        None => terrain.vertex_height(request.start_x, request.start_z).ok_or("Ramp start is outside the terrain")?,
+    };
+    let end_height = match request.end_height {
+        Some(height) => height,
+        None => terrain.vertex_height(request.end_x, request.end_z).ok_or("Ramp end is outside the terrain")?,
+    };
+
+    let ramp = Ramp {
+        start: (request.start_x, request.start_z),
+        end: (request.end_x, request.end_z),
+        start_height: start_height.clamp(0.0, 1.0),
+        end_height: end_height.clamp(0.0, 1.0),
+        width: request.width,
+    };
+    let touched = terrain.apply_ramp(&ramp, request.strength, request.falloff);
+
+    Ok(brushed_chunks(&terrain, touched))
+}
+
+/// Paint cave/cliff/ruin markers onto a chunk
+/// Returns the chunk's feature codes (one byte per vertex)
+#[tauri::command]
+pub async fn mark_feature(
+    request: MarkFeatureRequest,
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<Vec<u8>, String> {
+    let mut terrain = terrain.lock().await;
+
+    let vertex_count = terrain.config.vertex_count;
+
+    let chunk = terrain.chunks.get_mut(&(request.chunk_x, request.chunk_z))
+        .ok_or("Chunk not found")?;
+    chunk.mark_feature(request.center_x, request.center_z, request.radius, request.feature, vertex_count);
+    let features = chunk.feature_ids.clone().unwrap_or_default();
+
+    terrain.dirty_chunks.insert((request.chunk_x, request.chunk_z));
+
+    Ok(features)
+}
+
+/// Get a chunk's feature codes, or None if nothing has been marked
+#[tauri::command]
+pub async fn get_feature_map(
+    chunk_x: i32,
+    chunk_z: i32,
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<Option<Vec<u8>>, String> {
+    let terrain = terrain.lock().await;
+
+    let chunk = terrain.chunks.get(&(chunk_x, chunk_z))
+        .ok_or("Chunk not found")?;
+
+    Ok(chunk.feature_ids.clone())
+}
+
+/// Mark cave openings on every chunk; density 0..1 controls how much land is hollow
+#[tauri::command]
+pub async fn generate_caves(
+    density: f32,
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<GenerateTerrainResponse, String> {
+    if !(0.0..=1.0).contains(&density) {
+        return Err("Cave density must be between 0 and 1".to_string());
     }
-    terrain.dirty_chunks.clear();
 
-    emit_progress("✅ Complete!", 1.0, "Terrain generation finished");
+    let mut terrain_data = terrain.lock().await;
+    if terrain_data.chunks.is_empty() {
+        return Err("No terrain generated".to_string());
+    }
+    terrain_data.generate_caves(density);
 
     Ok(GenerateTerrainResponse {
         success: true,
-        message: format!("Generated {} chunks", chunk_count),
-        chunk_count,
+        message: "Generated cave mask".to_string(),
+        chunk_count: terrain_data.chunks.len(),
+        noise_params: None,
     })
 }
 
-/// Get a chunk's height data
+/// Get a chunk's cave mask (1 = cave opening), or None if caves haven't been generated
 #[tauri::command]
-pub async fn get_chunk(
-    request: GetChunkRequest,
+pub async fn get_cave_mask(
+    chunk_x: i32,
+    chunk_z: i32,
     terrain: State<'_, Mutex<TerrainData>>,
-) -> Result<Vec<u8>, String> {
+) -> Result<Option<Vec<u8>>, String> {
     let terrain = terrain.lock().await;
-    
-    let chunk = terrain.chunks.get(&(request.chunk_x, request.chunk_z))
-        .ok_or("Chunk not found")?;
-
-    // Return raw f32 bytes (binary IPC)
-    let bytes: Vec<u8> = chunk.heights.iter()
-        .flat_map(|h| h.to_le_bytes())
-        .collect();
-
-    Ok(bytes)
-}
-
-/// Apply brush operation to chunk
-#[tauri::command]
-pub async fn apply_brush(
-    request: ApplyBrushRequest,
-    terrain: State<'_, Mutex<TerrainData>>,
-) -> Result<Vec<u8>, String> {
-    let mut terrain = terrain.lock().await;
-    
-    let op = match request.brush_type.as_str() {
-        "raise" => BrushOp::Raise,
-        "lower" => BrushOp::Lower,
-        "smooth" => BrushOp::Smooth,
-        "flatten" => BrushOp::Flatten { target_height: 0.5 },
-        "erode" => BrushOp::Erode { droplet_count: 100 },
-        "noise" => BrushOp::Noise { scale: 0.1, strength: request.strength },
-        _ => return Err("Unknown brush type".into()),
-    };
 
-    let vertex_count = terrain.config.vertex_count;
-    
-    // Get chunk and apply brush
-    let chunk = terrain.chunks.get_mut(&(request.chunk_x, request.chunk_z))
+    let chunk = terrain.chunks.get(&(chunk_x, chunk_z))
         .ok_or("Chunk not found")?;
-    chunk.apply_brush(request.center_x, request.center_z, request.radius, request.strength, op, vertex_count);
-    
-    // Mark dirty
-    terrain.dirty_chunks.insert((request.chunk_x, request.chunk_z));
 
-    // Return modified heights as raw bytes
-    let chunk = terrain.chunks.get(&(request.chunk_x, request.chunk_z)).unwrap();
-    let bytes: Vec<u8> = chunk.heights.iter()
-        .flat_map(|h| h.to_le_bytes())
-        .collect();
-
-    Ok(bytes)
+    Ok(chunk.cave_mask.clone())
 }
 
 /// Get terrain configuration
@@ -296,25 +966,8 @@ pub async fn place_water_sources(
     // Flatten chunks to get heightmap
     let total_width = config.world_width as usize;
     let total_height = config.world_height as usize;
-    let mut heights = vec![0.0; total_width * total_height];
-    
-    for ((chunk_x, chunk_z), chunk) in &terrain_data.chunks {
-        let chunk_offset_x = *chunk_x as usize * config.chunk_size as usize;
-        let chunk_offset_z = *chunk_z as usize * config.chunk_size as usize;
-        
-        for local_z in 0..config.vertex_count as usize {
-            for local_x in 0..config.vertex_count as usize {
-                let global_x = chunk_offset_x + local_x;
-                let global_z = chunk_offset_z + local_z;
-                
-                if global_x < total_width && global_z < total_height {
-                    let chunk_idx = local_z * config.vertex_count as usize + local_x;
-                    let global_idx = global_z * total_width + global_x;
-                    heights[global_idx] = chunk.heights[chunk_idx];
-                }
-            }
-        }
-    }
+    terrain_data.validate_chunk_coords()?;
+    let heights = terrain_data.flatten_heights();
     
     // Place water sources based on type
     let mut sources = Vec::new();
@@ -429,9 +1082,9 @@ pub async fn simulate_hydrology(
     terrain: State<'_, Mutex<TerrainData>>,
     app: tauri::AppHandle,
 ) -> Result<GenerateTerrainResponse, String> {
-    use super::erosion::{erode_terrain_parallel, ErosionParams};
-    use super::hydrology::{fill_depressions, calculate_flow_direction, calculate_flow_accumulation};
-    use super::rivers::extract_rivers;
+    use super::erosion::{erode_terrain_parallel_with_context, ErosionContext, ErosionParams};
+    use super::hydrology::{fill_depressions, calculate_flow_direction, accumulate_flow};
+    use super::rivers::{extract_rivers, RiverParams};
     
     let emit_progress = |stage: &str, progress: f32, message: &str| {
         let _ = app.emit("terrain-progress", GenerationProgress {
@@ -454,25 +1107,8 @@ pub async fn simulate_hydrology(
     let total_height = config.world_height as usize;
     
     // Flatten chunks into heightmap
-    let mut heights = vec![0.0; total_width * total_height];
-    
-    for ((chunk_x, chunk_z), chunk) in &terrain_data.chunks {
-        let chunk_offset_x = *chunk_x as usize * config.chunk_size as usize;
-        let chunk_offset_z = *chunk_z as usize * config.chunk_size as usize;
-        
-        for local_z in 0..config.vertex_count as usize {
-            for local_x in 0..config.vertex_count as usize {
-                let global_x = chunk_offset_x + local_x;
-                let global_z = chunk_offset_z + local_z;
-                
-                if global_x < total_width && global_z < total_height {
-                    let chunk_idx = local_z * config.vertex_count as usize + local_x;
-                    let global_idx = global_z * total_width + global_x;
-                    heights[global_idx] = chunk.heights[chunk_idx];
-                }
-            }
-        }
-    }
+    terrain_data.validate_chunk_coords()?;
+    let mut heights = terrain_data.flatten_heights();
     
     emit_progress("🌊 Simulating water flow...", 0.2, format!("Running {} time steps", steps).as_str());
     
@@ -481,44 +1117,27 @@ pub async fn simulate_hydrology(
         num_droplets: steps * terrain_data.water_sources.len() as u32 * 10,
         ..Default::default()
     };
-    erode_terrain_parallel(&mut heights, total_width, total_height, &params);
+    let locked_mask = terrain_data.flatten_locked_mask();
+    let context = ErosionContext { locked_mask: locked_mask.as_deref(), ..Default::default() };
+    erode_terrain_parallel_with_context(&mut heights, total_width, total_height, &params, &context);
     
     if enable_lakes {
         emit_progress("🏞️ Forming lakes...", 0.5, "Filling depressions");
-        fill_depressions(&mut heights, total_width, total_height);
+        fill_depressions(&mut heights, total_width, total_height, locked_mask.as_deref());
     }
     
     emit_progress("🌊 Calculating flow...", 0.7, "Tracing water paths");
     let flow_direction = calculate_flow_direction(&heights, total_width, total_height);
-    let flow_accumulation = calculate_flow_accumulation(&heights, &flow_direction, total_width, total_height);
+    let flow_accumulation = accumulate_flow(config.flow_routing, &heights, &flow_direction, total_width, total_height);
     
     emit_progress("🏞️ Extracting rivers...", 0.85, "Finding river networks");
-    let river_network = extract_rivers(&flow_accumulation, &flow_direction, total_width, total_height, 500.0);
+    let river_network = extract_rivers(
+        &flow_accumulation, &flow_direction, total_width, total_height,
+        &RiverParams::with_threshold(500.0, config.cell_size_meters),
+    );
     
     // Update terrain with eroded heights
-    let mut dirty_chunks = Vec::new();
-    for ((chunk_x, chunk_z), chunk) in &mut terrain_data.chunks {
-        let chunk_offset_x = *chunk_x as usize * config.chunk_size as usize;
-        let chunk_offset_z = *chunk_z as usize * config.chunk_size as usize;
-        
-        for local_z in 0..config.vertex_count as usize {
-            for local_x in 0..config.vertex_count as usize {
-                let global_x = chunk_offset_x + local_x;
-                let global_z = chunk_offset_z + local_z;
-                
-                if global_x < total_width && global_z < total_height {
-                    let chunk_idx = local_z * config.vertex_count as usize + local_x;
-                    let global_idx = global_z * total_width + global_x;
-                    chunk.heights[chunk_idx] = heights[global_idx];
-                }
-            }
-        }
-        dirty_chunks.push((*chunk_x, *chunk_z));
-    }
-    
-    for coord in dirty_chunks {
-        terrain_data.dirty_chunks.insert(coord);
-    }
+    terrain_data.store_heights(&heights);
     
     terrain_data.river_network = river_network;
     
@@ -528,6 +1147,7 @@ pub async fn simulate_hydrology(
         success: true,
         message: format!("Simulated {} steps from {} water sources", steps, terrain_data.water_sources.len()),
         chunk_count: terrain_data.chunks.len(),
+        noise_params: None,
     })
 }
 
@@ -538,9 +1158,9 @@ pub async fn apply_weathering(
     terrain: State<'_, Mutex<TerrainData>>,
     app: tauri::AppHandle,
 ) -> Result<GenerateTerrainResponse, String> {
-    use super::erosion::{erode_terrain_parallel, ErosionParams};
-    use super::hydrology::{fill_depressions, calculate_flow_direction, calculate_flow_accumulation};
-    use super::rivers::extract_rivers;
+    use super::erosion::{erode_terrain_parallel_with_context, ErosionContext, ErosionParams};
+    use super::hydrology::{fill_depressions, calculate_flow_direction, accumulate_flow};
+    use super::rivers::{extract_rivers, RiverParams};
     
     let emit_progress = |stage: &str, progress: f32, message: &str| {
         let _ = app.emit("terrain-progress", GenerationProgress {
@@ -558,68 +1178,41 @@ pub async fn apply_weathering(
     // Flatten chunks into single heightmap
     let total_width = config.world_width as usize;
     let total_height = config.world_height as usize;
-    let mut heights = vec![0.0; total_width * total_height];
-    
-    for ((chunk_x, chunk_z), chunk) in &terrain_data.chunks {
-        let chunk_offset_x = *chunk_x as usize * config.chunk_size as usize;
-        let chunk_offset_z = *chunk_z as usize * config.chunk_size as usize;
-        
-        for local_z in 0..config.vertex_count as usize {
-            for local_x in 0..config.vertex_count as usize {
-                let global_x = chunk_offset_x + local_x;
-                let global_z = chunk_offset_z + local_z;
-                
-                if global_x < total_width && global_z < total_height {
-                    let chunk_idx = local_z * config.vertex_count as usize + local_x;
-                    let global_idx = global_z * total_width + global_x;
-                    heights[global_idx] = chunk.heights[chunk_idx];
-                }
-            }
-        }
-    }
+    terrain_data.validate_chunk_coords()?;
+    let mut heights = terrain_data.flatten_heights();
+    let locked_mask = terrain_data.flatten_locked_mask();
     
     emit_progress("💧 Filling depressions...", 0.2, "Removing terrain pits");
-    fill_depressions(&mut heights, total_width, total_height);
+    fill_depressions(&mut heights, total_width, total_height, locked_mask.as_deref());
     
     emit_progress("🏔️ Eroding terrain...", 0.4, "Simulating water erosion");
     let params = ErosionParams {
         num_droplets: iterations * 1000,
         ..Default::default()
     };
-    erode_terrain_parallel(&mut heights, total_width, total_height, &params);
+    let report_droplets = |done: f32| {
+        let message = format!("Simulating water erosion ({:.0}%)", done * 100.0);
+        emit_progress("🏔️ Eroding terrain...", 0.4 + done * 0.3, &message);
+    };
+    let context = ErosionContext {
+        locked_mask: locked_mask.as_deref(),
+        progress: Some(&report_droplets),
+        ..Default::default()
+    };
+    erode_terrain_parallel_with_context(&mut heights, total_width, total_height, &params, &context);
     
     emit_progress("🌊 Calculating flow...", 0.7, "Tracing water paths");
     let flow_direction = calculate_flow_direction(&heights, total_width, total_height);
-    let flow_accumulation = calculate_flow_accumulation(&heights, &flow_direction, total_width, total_height);
+    let flow_accumulation = accumulate_flow(config.flow_routing, &heights, &flow_direction, total_width, total_height);
     
     emit_progress("🏞️ Extracting rivers...", 0.85, "Finding river networks");
-    let river_network = extract_rivers(&flow_accumulation, &flow_direction, total_width, total_height, 1000.0);
+    let river_network = extract_rivers(
+        &flow_accumulation, &flow_direction, total_width, total_height,
+        &RiverParams::with_threshold(1000.0, config.cell_size_meters),
+    );
     
     // Update terrain with eroded heights
-    let mut dirty_chunks = Vec::new();
-    for ((chunk_x, chunk_z), chunk) in &mut terrain_data.chunks {
-        let chunk_offset_x = *chunk_x as usize * config.chunk_size as usize;
-        let chunk_offset_z = *chunk_z as usize * config.chunk_size as usize;
-        
-        for local_z in 0..config.vertex_count as usize {
-            for local_x in 0..config.vertex_count as usize {
-                let global_x = chunk_offset_x + local_x;
-                let global_z = chunk_offset_z + local_z;
-                
-                if global_x < total_width && global_z < total_height {
-                    let chunk_idx = local_z * config.vertex_count as usize + local_x;
-                    let global_idx = global_z * total_width + global_x;
-                    chunk.heights[chunk_idx] = heights[global_idx];
-                }
-            }
-        }
-        dirty_chunks.push((*chunk_x, *chunk_z));
-    }
-    
-    // Mark all chunks as dirty
-    for coord in dirty_chunks {
-        terrain_data.dirty_chunks.insert(coord);
-    }
+    terrain_data.store_heights(&heights);
     
     terrain_data.river_network = river_network;
     
@@ -629,9 +1222,183 @@ pub async fn apply_weathering(
         success: true,
         message: format!("Applied {} weathering iterations", iterations),
         chunk_count: terrain_data.chunks.len(),
+        noise_params: None,
+    })
+}
+
+/// Apply thermal erosion: slopes steeper than the talus angle (radians) slump downhill
+#[tauri::command]
+pub async fn apply_thermal_erosion(
+    talus_angle: f32,
+    iterations: u32,
+    terrain: State<'_, Mutex<TerrainData>>,
+    app: tauri::AppHandle,
+) -> Result<GenerateTerrainResponse, String> {
+    if !(talus_angle > 0.0 && talus_angle < std::f32::consts::FRAC_PI_2) {
+        return Err("Talus angle must be between 0 and π/2 radians".to_string());
+    }
+    if !(1..=500).contains(&iterations) {
+        return Err("Iterations must be between 1 and 500".to_string());
+    }
+
+    let emit_progress = |stage: &str, progress: f32, message: &str| {
+        let _ = app.emit("terrain-progress", GenerationProgress {
+            stage: stage.to_string(),
+            progress,
+            message: message.to_string(),
+        });
+    };
+
+    emit_progress("🪨 Settling slopes...", 0.0, "Preparing terrain");
+
+    let mut terrain_data = terrain.lock().await;
+    let config = terrain_data.config.clone();
+    let total_width = config.world_width as usize;
+    let total_height = config.world_height as usize;
+    terrain_data.validate_chunk_coords()?;
+    let mut heights = terrain_data.flatten_heights();
+    let locked_mask = terrain_data.flatten_locked_mask();
+
+    emit_progress("🪨 Slumping steep slopes...", 0.3, format!("Running {} iterations", iterations).as_str());
+    super::hydrology::apply_thermal_erosion(
+        &mut heights, total_width, total_height,
+        talus_angle, config.cell_size_meters, config.max_elevation, iterations,
+        locked_mask.as_deref(),
+    );
+
+    terrain_data.store_heights(&heights);
+
+    emit_progress("✅ Complete!", 1.0, "Thermal erosion finished");
+
+    Ok(GenerateTerrainResponse {
+        success: true,
+        message: format!("Applied {} thermal erosion iterations", iterations),
+        chunk_count: terrain_data.chunks.len(),
+        noise_params: None,
+    })
+}
+
+/// Classify every chunk vertex into a biome from its temperature and moisture
+#[tauri::command]
+pub async fn generate_biomes(
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<GenerateTerrainResponse, String> {
+    let mut terrain_data = terrain.lock().await;
+    if terrain_data.chunks.is_empty() {
+        return Err("No terrain generated".to_string());
+    }
+    terrain_data.validate_chunk_coords()?;
+    terrain_data.generate_biomes();
+
+    Ok(GenerateTerrainResponse {
+        success: true,
+        message: "Generated biome map".to_string(),
+        chunk_count: terrain_data.chunks.len(),
+        noise_params: None,
     })
 }
 
+/// List every registered biome (built-in and custom) in id order
+#[tauri::command]
+pub async fn list_biomes(
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<Vec<super::biomes::RegisteredBiome>, String> {
+    let terrain_data = terrain.lock().await;
+    Ok(terrain_data.biome_definitions.list())
+}
+
+/// Register a custom biome, or redefine an existing one; returns its biome id
+#[tauri::command]
+pub async fn register_biome(
+    key: String,
+    definition: super::biomes::BiomeDefinition,
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<u8, String> {
+    let mut terrain_data = terrain.lock().await;
+    terrain_data.biome_definitions.register(key, definition)
+}
+
+/// Get soft biome edges for rendering (2 bytes per cell, row-major): the dominant neighboring
+/// biome id, then the cell's own-biome weight scaled to 0-255 (255 = fully its own biome)
+#[tauri::command]
+pub async fn get_biome_blend(
+    radius: u32,
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<Vec<u8>, String> {
+    if !(1..=32).contains(&radius) {
+        return Err("Radius must be between 1 and 32".to_string());
+    }
+
+    let terrain_data = terrain.lock().await;
+    terrain_data.validate_chunk_coords()?;
+    let biome_ids = terrain_data.flatten_biome_ids()
+        .ok_or("Biomes have not been generated")?;
+    let width = terrain_data.config.world_width as usize;
+    let height = terrain_data.config.world_height as usize;
+
+    let blend = super::biomes::blend_biome_edges(&biome_ids, width, height, radius as usize);
+    Ok(blend.neighbor_ids.iter()
+        .zip(&blend.weights)
+        .flat_map(|(&neighbor, &weight)| [neighbor, (weight * 255.0).round() as u8])
+        .collect())
+}
+
+/// Estimate how much further erosion would change the terrain, without modifying it
+/// The estimate runs on a copy of the heightmap, so the terrain stays unlocked meanwhile
+#[tauri::command]
+pub async fn erosion_delta_estimate(
+    sample_droplets: u32,
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<f32, String> {
+    if !(1..=50_000).contains(&sample_droplets) {
+        return Err("Sample droplets must be between 1 and 50000".to_string());
+    }
+
+    let (heights, width, height) = {
+        let terrain_data = terrain.lock().await;
+        if terrain_data.chunks.is_empty() {
+            return Err("No terrain generated".to_string());
+        }
+        terrain_data.validate_chunk_coords()?;
+        (
+            terrain_data.flatten_heights(),
+            terrain_data.config.world_width as usize,
+            terrain_data.config.world_height as usize,
+        )
+    };
+
+    Ok(super::erosion::erosion_delta_estimate(&heights, width, height, sample_droplets))
+}
+
+/// Place ore and resource deposits across the terrain
+/// Defaults to every resource type when none are given
+#[tauri::command]
+pub async fn generate_resource_deposits(
+    resource_types: Option<Vec<super::resources::ResourceType>>,
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<Vec<super::resources::Deposit>, String> {
+    use super::resources::{generate_resource_deposits, ResourceType};
+
+    let mut terrain_data = terrain.lock().await;
+
+    if terrain_data.chunks.is_empty() {
+        return Err("No terrain generated".to_string());
+    }
+
+    terrain_data.validate_chunk_coords()?;
+    let resource_types = resource_types.unwrap_or_else(|| ResourceType::ALL.to_vec());
+    let heights = terrain_data.flatten_heights();
+    let deposits = generate_resource_deposits(
+        &terrain_data.config,
+        &heights,
+        &terrain_data.river_network,
+        &resource_types,
+    );
+
+    terrain_data.deposits = deposits.clone();
+    Ok(deposits)
+}
+
 /// Get river network
 #[tauri::command]
 pub async fn get_rivers(
@@ -646,67 +1413,181 @@ pub async fn get_rivers(
 pub async fn get_flow_data(
     terrain: State<'_, Mutex<TerrainData>>,
 ) -> Result<Vec<u8>, String> {
-    use super::hydrology::{calculate_flow_direction, calculate_flow_accumulation};
-    
     let terrain_data = terrain.lock().await;
-    let config = &terrain_data.config;
-    
-    // Flatten chunks into heightmap
-    let total_width = config.world_width as usize;
-    let total_height = config.world_height as usize;
-    let mut heights = vec![0.0; total_width * total_height];
-    
-    for ((chunk_x, chunk_z), chunk) in &terrain_data.chunks {
-        let chunk_offset_x = *chunk_x as usize * config.chunk_size as usize;
-        let chunk_offset_z = *chunk_z as usize * config.chunk_size as usize;
-        
-        for local_z in 0..config.vertex_count as usize {
-            for local_x in 0..config.vertex_count as usize {
-                let global_x = chunk_offset_x + local_x;
-                let global_z = chunk_offset_z + local_z;
-                
-                if global_x < total_width && global_z < total_height {
-                    let chunk_idx = local_z * config.vertex_count as usize + local_x;
-                    let global_idx = global_z * total_width + global_x;
-                    heights[global_idx] = chunk.heights[chunk_idx];
-                }
+    terrain_data.flow_data()
+}
+
+/// Get drainage basins as an RGB overlay (3 bytes per cell, row-major)
+/// Each basin gets a stable color derived from its id; sea cells are black
+#[tauri::command]
+pub async fn get_watershed_map(
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<Vec<u8>, String> {
+    use super::hydrology::NO_BASIN;
+
+    let terrain_data = terrain.lock().await;
+    terrain_data.validate_chunk_coords()?;
+    let watersheds = terrain_data.compute_watersheds();
+
+    let bytes = watersheds.labels.iter()
+        .flat_map(|&label| {
+            if label == NO_BASIN {
+                [0, 0, 0]
+            } else {
+                // Knuth multiplicative hash spreads neighboring ids across the palette
+                let hash = label.wrapping_mul(2_654_435_761);
+                [
+                    64 + (hash & 0xbf) as u8,
+                    64 + ((hash >> 8) & 0xbf) as u8,
+                    64 + ((hash >> 16) & 0xbf) as u8,
+                ]
             }
-        }
-    }
-    
-    // Calculate flow
-    let flow_direction = calculate_flow_direction(&heights, total_width, total_height);
-    let flow_accumulation = calculate_flow_accumulation(&heights, &flow_direction, total_width, total_height);
-    
-    // Normalize flow to 0-255 range for texture
-    let max_flow = flow_accumulation.iter().cloned().fold(0.0f32, f32::max);
-    let flow_bytes: Vec<u8> = flow_accumulation.iter()
-        .map(|&f| ((f / max_flow) * 255.0).min(255.0) as u8)
+        })
         .collect();
-    
-    Ok(flow_bytes)
+
+    Ok(bytes)
 }
 
-/// Save terrain to database
+/// Get the drainage basin id of every cell as little-endian u32s (4 bytes per cell, row-major)
+/// Sea cells carry NO_BASIN (u32::MAX)
+#[tauri::command]
+pub async fn get_watersheds(
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<Vec<u8>, String> {
+    let terrain_data = terrain.lock().await;
+    terrain_data.validate_chunk_coords()?;
+    let watersheds = terrain_data.compute_watersheds();
+
+    Ok(watersheds.labels.iter().flat_map(|label| label.to_le_bytes()).collect())
+}
+
+/// Find waterfalls along the current rivers
+/// `min_drop` is a normalized height step; multiply heights by max_elevation for meters
+#[tauri::command]
+pub async fn get_waterfalls(
+    min_drop: f32,
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<Vec<super::rivers::Waterfall>, String> {
+    use super::rivers::detect_waterfalls;
+
+    if min_drop.is_nan() || min_drop <= 0.0 {
+        return Err("Minimum drop must be positive".to_string());
+    }
+
+    let terrain_data = terrain.lock().await;
+    terrain_data.validate_chunk_coords()?;
+    let heights = terrain_data.flatten_heights();
+    let width = terrain_data.config.world_width as usize;
+    let height = terrain_data.config.world_height as usize;
+
+    Ok(detect_waterfalls(&terrain_data.river_network, &heights, width, height, min_drop))
+}
+
+/// Get the lakes that fill the current terrain's depressions
+#[tauri::command]
+pub async fn get_lakes(
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<Vec<super::hydrology::Lake>, String> {
+    let terrain_data = terrain.lock().await;
+    terrain_data.validate_chunk_coords()?;
+    Ok(terrain_data.extract_lakes())
+}
+
+/// Build a road network linking the given settlements (grid cell coordinates)
+/// Roads avoid the sea and rivers, bridging crossings up to `bridge_threshold` cells;
+/// `simplify_epsilon` (cells) thins each path down to the points that shape it
+#[tauri::command]
+pub async fn generate_roads(
+    points: Vec<(i32, i32)>,
+    bridge_threshold: Option<u32>,
+    simplify_epsilon: Option<f32>,
+    terrain: State<'_, Mutex<TerrainData>>,
+) -> Result<Vec<super::roads::Road>, String> {
+    use super::roads::{generate_road_network, RoadContext, DEFAULT_BRIDGE_CELLS};
+
+    if !(2..=64).contains(&points.len()) {
+        return Err("Provide between 2 and 64 settlements".to_string());
+    }
+    if simplify_epsilon.is_some_and(|epsilon| epsilon.is_nan() || epsilon < 0.0) {
+        return Err("Simplify epsilon must not be negative".to_string());
+    }
+
+    let mut terrain_data = terrain.lock().await;
+    let width = terrain_data.config.world_width as usize;
+    let height = terrain_data.config.world_height as usize;
+    if let Some((x, z)) = points.iter().find(|(x, z)| *x < 0 || *z < 0 || *x >= width as i32 || *z >= height as i32) {
+        return Err(format!("Settlement ({}, {}) is outside the world", x, z));
+    }
+    terrain_data.validate_chunk_coords()?;
+    let heights = terrain_data.flatten_heights();
+    let river_mask = terrain_data.river_mask();
+    let context = RoadContext {
+        sea_level: terrain_data.config.sea_level,
+        river_mask: Some(&river_mask),
+        bridge_threshold: bridge_threshold.unwrap_or(DEFAULT_BRIDGE_CELLS),
+        simplify_epsilon,
+    };
+
+    let roads = generate_road_network(&points, &heights, width, height, &context);
+    terrain_data.roads = roads.clone();
+    Ok(roads)
+}
+
+/// WORLDS_DIR inside the app's data directory, so saves don't depend on the
+/// working directory the app was launched from
+fn worlds_dir(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    use tauri::Manager;
+
+    app.path().app_data_dir()
+        .map(|dir| dir.join(super::persistence::WORLDS_DIR))
+        .map_err(|e| format!("Failed to locate app data directory: {}", e))
+}
+
+/// Open the database of a named world in `dir`
+/// With `create` the world (and directory) is created if missing; otherwise it must exist
+fn open_world(dir: &std::path::Path, world_name: &str, create: bool) -> Result<super::persistence::TerrainDatabase, String> {
+    use super::persistence::{world_path, TerrainDatabase};
+
+    let path = world_path(dir, world_name).map_err(|e| e.to_string())?;
+    if create {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create worlds directory: {}", e))?;
+    } else if !path.exists() {
+        return Err(format!("World '{}' not found", world_name));
+    }
+
+    TerrainDatabase::new(path)
+        .map_err(|e| format!("Failed to open database: {}", e))
+}
+
+/// List the saved worlds with their configs
+#[tauri::command]
+pub async fn list_saved_worlds(app: tauri::AppHandle) -> Result<Vec<super::persistence::SavedWorld>, String> {
+    super::persistence::list_saved_worlds(&worlds_dir(&app)?)
+        .map_err(|e| format!("Failed to list worlds: {}", e))
+}
+
+/// Save terrain to the named world's database
 #[tauri::command]
 pub async fn save_terrain(
+    world_name: String,
     terrain: State<'_, Mutex<TerrainData>>,
+    app: tauri::AppHandle,
 ) -> Result<String, String> {
-    use super::persistence::TerrainDatabase;
-
     let terrain = terrain.lock().await;
     
-    let db = TerrainDatabase::new("terrain.db")
-        .map_err(|e| format!("Failed to open database: {}", e))?;
+    let mut db = open_world(&worlds_dir(&app)?, &world_name, true)?;
 
     // Save config
     db.save_config(&terrain.config)
         .map_err(|e| format!("Failed to save config: {}", e))?;
+    db.save_biome_registry(&terrain.biome_definitions)
+        .map_err(|e| format!("Failed to save biomes: {}", e))?;
 
-    // Save all chunks
+    // Save all chunks, with coarse LOD copies for streaming
     let mut saved_count = 0;
     for chunk in terrain.chunks.values() {
-        db.save_chunk(chunk)
+        db.save_chunk_with_lods(chunk, terrain.config.vertex_count)
             .map_err(|e| format!("Failed to save chunk: {}", e))?;
         saved_count += 1;
     }
@@ -717,48 +1598,95 @@ pub async fn save_terrain(
             .map_err(|e| format!("Failed to save river: {}", e))?;
     }
 
-    Ok(format!("Saved {} chunks and {} rivers", saved_count, terrain.river_network.segments.len()))
+    // Save resource deposits
+    db.save_deposits(&terrain.deposits)
+        .map_err(|e| format!("Failed to save deposits: {}", e))?;
+
+    // Save roads, dropping any left over from a larger network
+    db.save_roads(&terrain.roads)
+        .map_err(|e| format!("Failed to save roads: {}", e))?;
+
+    Ok(format!(
+        "Saved {} chunks, {} rivers and {} roads",
+        saved_count, terrain.river_network.segments.len(), terrain.roads.len()
+    ))
 }
 
-/// Load terrain from database
+/// Load terrain from the named world's database
 #[tauri::command]
 pub async fn load_terrain(
+    world_name: String,
     terrain: State<'_, Mutex<TerrainData>>,
+    app: tauri::AppHandle,
+) -> Result<String, String> {
+    load_terrain_chunks(&worlds_dir(&app)?, &world_name, &terrain, None).await
+}
+
+/// Load only the chunks in an inclusive chunk rectangle, leaving the rest of the
+/// world absent; rivers, deposits and roads are always loaded in full
+/// Whole-world passes and exports are refused until the rest of the world is loaded
+#[tauri::command]
+pub async fn load_terrain_region(
+    world_name: String,
+    min_chunk: (i32, i32),
+    max_chunk: (i32, i32),
+    terrain: State<'_, Mutex<TerrainData>>,
+    app: tauri::AppHandle,
 ) -> Result<String, String> {
-    use super::persistence::TerrainDatabase;
+    load_terrain_chunks(&worlds_dir(&app)?, &world_name, &terrain, Some((min_chunk, max_chunk))).await
+}
 
-    let db = TerrainDatabase::new("terrain.db")
-        .map_err(|e| format!("Failed to open database: {}", e))?;
+/// Replace the in-memory terrain with a saved world, limited to `region` when given
+async fn load_terrain_chunks(
+    dir: &std::path::Path,
+    world_name: &str,
+    terrain: &Mutex<TerrainData>,
+    region: Option<((i32, i32), (i32, i32))>,
+) -> Result<String, String> {
+    let db = open_world(dir, world_name, false)?;
 
     // Load config
     let config = db.load_config()
         .map_err(|e| format!("Failed to load config: {}", e))?;
 
-    // Load all chunks
-    let mut chunks = std::collections::HashMap::new();
-    for chunk_z in 0..config.chunk_count_z() {
-        for chunk_x in 0..config.chunk_count_x() {
-            if db.chunk_exists(chunk_x, chunk_z, 0)
-                .map_err(|e| format!("Failed to check chunk: {}", e))? {
-                let chunk = db.load_chunk(chunk_x, chunk_z, 0)
-                    .map_err(|e| format!("Failed to load chunk: {}", e))?;
-                chunks.insert((chunk_x, chunk_z), chunk);
-            }
-        }
-    }
+    // Load custom biomes; older worlds only used the built-ins
+    let biome_definitions = db.load_biome_registry()
+        .map_err(|e| format!("Failed to load biomes: {}", e))?
+        .unwrap_or_default();
+
+    // Load chunks, defaulting to the whole world
+    let (min_chunk, max_chunk) = region.unwrap_or(((0, 0), (config.chunk_count_x() - 1, config.chunk_count_z() - 1)));
+    let chunks: std::collections::HashMap<_, _> = db.load_chunks_in_region(min_chunk, max_chunk)
+        .map_err(|e| format!("Failed to load chunks: {}", e))?
+        .into_iter()
+        .map(|chunk| (chunk.coord, chunk))
+        .collect();
 
     // Load rivers
     let river_segments = db.load_river_segments()
         .map_err(|e| format!("Failed to load rivers: {}", e))?;
 
+    // Load resource deposits
+    let deposits = db.load_deposits()
+        .map_err(|e| format!("Failed to load deposits: {}", e))?;
+
+    // Load roads
+    let roads = db.load_roads()
+        .map_err(|e| format!("Failed to load roads: {}", e))?;
+
     let chunk_count = chunks.len();
     let river_count = river_segments.len();
+    let road_count = roads.len();
 
     let mut terrain = terrain.lock().await;
     terrain.config = config;
     terrain.chunks = chunks;
+    terrain.biome_definitions = biome_definitions;
     terrain.river_network.segments = river_segments;
+    terrain.deposits = deposits;
+    terrain.roads = roads;
     terrain.dirty_chunks.clear();
+    terrain.undo_stack.clear();
 
-    Ok(format!("Loaded {} chunks and {} rivers", chunk_count, river_count))
+    Ok(format!("Loaded {} chunks, {} rivers and {} roads", chunk_count, river_count, road_count))
 }
@@ -1,5 +1,8 @@
 use bevy_ecs::prelude::*;
 use serde::{Serialize, Deserialize};
+use super::hydrology::FlowRouting;
+use super::commands::NoiseParameters;
+use super::noise_gen::TerrainStyle;
 
 /// World theme for biome naming and styling
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -15,6 +18,16 @@ impl Default for WorldTheme {
     }
 }
 
+/// Side of the map the prevailing wind blows from; moisture travels away from it
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WindDirection {
+    #[default]
+    West,
+    East,
+    North,
+    South,
+}
+
 /// Terrain configuration - bevy_ecs Component
 #[derive(Component, Serialize, Deserialize, Clone, Debug)]
 pub struct TerrainConfig {
@@ -27,6 +40,26 @@ pub struct TerrainConfig {
     pub sea_level: f32,           // 0.2 (normalized)
     pub seed: u32,
     pub theme: WorldTheme,
+    #[serde(default)]
+    pub wrap: bool,               // Tileable: east edge matches west, south matches north
+    #[serde(default)]
+    pub flow_routing: FlowRouting, // Drainage algorithm for flow accumulation and rivers
+    #[serde(default)]
+    pub wind_direction: WindDirection, // Prevailing wind for moisture and rain shadows
+    #[serde(default = "default_coast_width")]
+    pub coast_width: f32,         // 0.02 (normalized height of the beach band above sea level)
+    #[serde(default)]
+    pub noise_params: Option<NoiseParameters>, // Parameters the world was generated with (None = defaults)
+    #[serde(default)]
+    pub style: TerrainStyle,      // Land shaping applied by post-processing
+    #[serde(default)]
+    pub height_range: Option<(f32, f32)>, // Raw (min, max) post-processing normalized by (None = not recorded)
+    #[serde(default)]
+    pub cave_density: Option<f32>, // Density caves were last generated with (None = no caves)
+}
+
+fn default_coast_width() -> f32 {
+    0.02
 }
 
 impl Default for TerrainConfig {
@@ -41,6 +74,14 @@ impl Default for TerrainConfig {
             sea_level: 0.2,
             seed: 12345,
             theme: WorldTheme::Fantasy,
+            wrap: false,
+            flow_routing: FlowRouting::D8,
+            wind_direction: WindDirection::West,
+            coast_width: default_coast_width(),
+            noise_params: None,
+            style: TerrainStyle::default(),
+            height_range: None,
+            cave_density: None,
         }
     }
 }
@@ -56,6 +97,18 @@ impl TerrainConfig {
         }
     }
 
+    /// Reject worlds too small to hold a cell; other sizes are fine, with the
+    /// right/bottom chunks only partly inside the world when not a multiple of chunk_size
+    pub fn validate_dimensions(&self) -> Result<(), String> {
+        if self.world_width < 2 || self.world_height < 2 {
+            return Err(format!(
+                "World must be at least 2×2, got {}×{}",
+                self.world_width, self.world_height
+            ));
+        }
+        Ok(())
+    }
+
     pub fn chunk_count_x(&self) -> i32 {
         ((self.world_width + self.chunk_size - 1) / self.chunk_size) as i32
     }
@@ -1,5 +1,9 @@
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
 use rayon::prelude::*;
+use std::fmt;
+use std::sync::mpsc;
+use std::thread;
 
 /// Erosion parameters for particle-based hydraulic erosion
 #[derive(Clone, Debug)]
@@ -14,6 +18,7 @@ pub struct ErosionParams {
     pub deposition_speed: f32,
     pub evaporation_rate: f32,
     pub gravity: f32,
+    pub seed: Option<u64>,  // Fixed seed for reproducible erosion; None draws a fresh one each run
 }
 
 impl Default for ErosionParams {
@@ -29,6 +34,152 @@ impl Default for ErosionParams {
             deposition_speed: 0.3,
             evaporation_rate: 0.02,
             gravity: 8.0,
+            seed: None,
+        }
+    }
+}
+
+impl ErosionParams {
+    /// Droplet RNG for a run (or one tile of a parallel run), seeded when `seed` is set
+    fn rng(&self, stream: u64) -> StdRng {
+        match self.seed {
+            // Golden-ratio stride keeps the per-stream seeds well apart
+            Some(seed) => StdRng::seed_from_u64(seed ^ stream.wrapping_mul(0x9E37_79B9_7F4A_7C15)),
+            None => StdRng::from_rng(&mut rand::rng()),
+        }
+    }
+}
+
+/// Optional per-cell inputs that constrain an erosion pass
+/// Buffers are row-major with the same dimensions as the heightmap
+#[derive(Clone, Copy, Default)]
+pub struct ErosionContext<'a> {
+    pub locked_mask: Option<&'a [bool]>, // true = cell is never eroded or deposited on
+    pub rainfall: Option<&'a [f32]>,     // Relative droplet spawn weight; None = uniform
+    pub hardness: Option<&'a [f32]>,     // Rock hardness 0..1; scales erosion speed by (1 - hardness)
+    pub progress: Option<&'a (dyn Fn(f32) + Sync)>, // Called with the fraction of droplets done, every ~1%
+}
+
+impl fmt::Debug for ErosionContext<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErosionContext")
+            .field("locked_mask", &self.locked_mask.map(<[bool]>::len))
+            .field("rainfall", &self.rainfall.map(<[f32]>::len))
+            .field("hardness", &self.hardness.map(<[f32]>::len))
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+impl ErosionContext<'_> {
+    fn is_locked(&self, idx: usize) -> bool {
+        self.locked_mask.is_some_and(|mask| mask.get(idx).copied().unwrap_or(false))
+    }
+
+    /// Fraction of the normal erosion speed a cell allows
+    fn softness(&self, idx: usize) -> f32 {
+        self.hardness.map_or(1.0, |hardness| 1.0 - hardness.get(idx).copied().unwrap_or(0.0).clamp(0.0, 1.0))
+    }
+}
+
+/// Counts simulated droplets and reports to the context's progress callback each time
+/// another 1% completes. Only the thread that owns the tracker calls the callback;
+/// loops hand it droplets in batches of `step` (see `progress_batch`)
+struct ProgressTracker<'a> {
+    callback: Option<&'a (dyn Fn(f32) + Sync)>,
+    total: u32,
+    step: u32,
+    done: u32,
+}
+
+impl<'a> ProgressTracker<'a> {
+    fn new(callback: Option<&'a (dyn Fn(f32) + Sync)>, total: u32) -> Self {
+        Self { callback, total, step: (total / 100).max(1), done: 0 }
+    }
+
+    /// Record droplet `index` (0-based, within the caller's loop of `count`) as finished
+    fn tick(&mut self, index: u32, count: u32) {
+        if self.callback.is_some() {
+            self.advance(progress_batch(self.step, index, count));
+        }
+    }
+
+    fn advance(&mut self, droplets: u32) {
+        let Some(callback) = self.callback else { return };
+        if droplets == 0 {
+            return;
+        }
+        let before = self.done;
+        self.done += droplets;
+        if self.done / self.step > before / self.step || self.done == self.total {
+            callback((self.done as f32 / self.total as f32).min(1.0));
+        }
+    }
+}
+
+/// Droplets to report once droplet `index` of a loop of `count` finishes: a full `step`
+/// on every step boundary, the remainder on the last droplet, otherwise nothing
+fn progress_batch(step: u32, index: u32, count: u32) -> u32 {
+    let finished = index + 1;
+    if finished % step == 0 {
+        step
+    } else if finished == count {
+        count % step
+    } else {
+        0
+    }
+}
+
+/// Copy a rectangular window (x0, z0, x1, z1) out of a row-major buffer
+fn copy_window<T: Copy>(buffer: &[T], width: usize, window: (usize, usize, usize, usize)) -> Vec<T> {
+    let (x0, z0, x1, z1) = window;
+    (z0..z1).flat_map(|z| buffer[z * width + x0..z * width + x1].iter().copied()).collect()
+}
+
+/// Picks droplet start positions inside a rectangle of the map, weighted by rainfall if given
+struct SpawnSampler {
+    rect: (usize, usize, usize, usize), // x0, z0, x1, z1 (exclusive)
+    cdf: Option<Vec<f64>>,              // Running rainfall total over the rect's cells, row-major
+}
+
+impl SpawnSampler {
+    fn new(rect: (usize, usize, usize, usize), width: usize, rainfall: Option<&[f32]>) -> Self {
+        let (x0, z0, x1, z1) = rect;
+        let cdf = rainfall.map(|rain| {
+            let mut total = 0.0f64;
+            (z0..z1)
+                .flat_map(|z| (x0..x1).map(move |x| z * width + x))
+                .map(|idx| {
+                    total += rain.get(idx).copied().unwrap_or(0.0).max(0.0) as f64;
+                    total
+                })
+                .collect()
+        });
+        Self { rect, cdf }
+    }
+
+    /// Total spawn weight; the cell count when uniform
+    fn total(&self) -> f64 {
+        let (x0, z0, x1, z1) = self.rect;
+        match &self.cdf {
+            Some(cdf) => cdf.last().copied().unwrap_or(0.0),
+            None => ((x1 - x0) * (z1 - z0)) as f64,
+        }
+    }
+
+    fn sample(&self, rng: &mut impl Rng) -> (f32, f32) {
+        let (x0, z0, x1, z1) = self.rect;
+        match &self.cdf {
+            None => (rng.random_range(x0 as f32..x1 as f32), rng.random_range(z0 as f32..z1 as f32)),
+            Some(cdf) => {
+                let target = rng.random_range(0.0..self.total());
+                let cell = cdf.partition_point(|&running| running <= target).min(cdf.len() - 1);
+                let rect_width = x1 - x0;
+                (
+                    (x0 + cell % rect_width) as f32 + rng.random::<f32>(),
+                    (z0 + cell / rect_width) as f32 + rng.random::<f32>(),
+                )
+            }
         }
     }
 }
@@ -40,13 +191,72 @@ pub fn erode_terrain(
     height: usize,
     params: &ErosionParams,
 ) {
-    let mut rng = rand::rng();
+    erode_terrain_with_context(heights, width, height, params, &ErosionContext::default());
+}
 
-    for _ in 0..params.num_droplets {
-        simulate_droplet(heights, width, height, params, &mut rng);
+/// Apply hydraulic erosion to heightmap, honoring the context's per-cell constraints
+#[tracing::instrument(skip_all, fields(droplets = params.num_droplets))]
+pub fn erode_terrain_with_context(
+    heights: &mut [f32],
+    width: usize,
+    height: usize,
+    params: &ErosionParams,
+    context: &ErosionContext,
+) {
+    let mut rng = params.rng(0);
+    let mut tracker = ProgressTracker::new(context.progress, params.num_droplets);
+
+    if context.rainfall.is_none() {
+        for i in 0..params.num_droplets {
+            simulate_droplet(heights, width, height, params, context, &mut rng);
+            tracker.tick(i, params.num_droplets);
+        }
+        return;
+    }
+
+    if width < 2 || height < 2 {
+        return;
+    }
+    let sampler = SpawnSampler::new((0, 0, width - 1, height - 1), width, context.rainfall);
+    if sampler.total() <= 0.0 {
+        return; // No rain anywhere
+    }
+    for i in 0..params.num_droplets {
+        let (x, z) = sampler.sample(&mut rng);
+        run_droplet(heights, width, height, params, context, x, z);
+        tracker.tick(i, params.num_droplets);
     }
 }
 
+/// Estimate how settled a heightmap is by eroding a copy with a small droplet batch
+/// Returns the average absolute height change per cell; lower means more settled.
+/// A fixed seed is used so repeated estimates on the same terrain are comparable.
+pub fn erosion_delta_estimate(
+    heights: &[f32],
+    width: usize,
+    height: usize,
+    sample_droplets: u32,
+) -> f32 {
+    if heights.is_empty() {
+        return 0.0;
+    }
+
+    let params = ErosionParams::default();
+    let mut sample = heights.to_vec();
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for _ in 0..sample_droplets {
+        simulate_droplet(&mut sample, width, height, &params, &ErosionContext::default(), &mut rng);
+    }
+
+    let total_change: f32 = heights.iter()
+        .zip(&sample)
+        .map(|(before, after)| (after - before).abs())
+        .sum();
+
+    total_change / heights.len() as f32
+}
+
 /// Apply hydraulic erosion in parallel
 pub fn erode_terrain_parallel(
     heights: &mut [f32],
@@ -54,126 +264,198 @@ pub fn erode_terrain_parallel(
     height: usize,
     params: &ErosionParams,
 ) {
-    use std::sync::Mutex;
-    let heights_mutex = Mutex::new(heights);
+    erode_terrain_parallel_with_context(heights, width, height, params, &ErosionContext::default());
+}
 
-    (0..params.num_droplets).into_par_iter().for_each(|_| {
-        let mut rng = rand::rng();
-        let mut local_changes: Vec<(usize, f32)> = Vec::new();
+/// Side length of the square tiles the parallel erosion splits the map into
+const EROSION_TILE_SIZE: usize = 256;
 
-        // Simulate droplet and collect changes
-        {
-            let heights = heights_mutex.lock().unwrap();
-            simulate_droplet_collect(&heights, width, height, params, &mut rng, &mut local_changes);
-        }
+/// Apply hydraulic erosion in parallel, honoring the context's per-cell constraints
+/// The map is split into tiles that erode independently on private copies padded with a halo
+/// wide enough that no droplet spawned in a tile can leave its copy; the per-tile height
+/// deltas are then summed back into the map
+#[tracing::instrument(skip_all, fields(droplets = params.num_droplets))]
+pub fn erode_terrain_parallel_with_context(
+    heights: &mut [f32],
+    width: usize,
+    height: usize,
+    params: &ErosionParams,
+    context: &ErosionContext,
+) {
+    erode_tiled(heights, width, height, params, context, EROSION_TILE_SIZE);
+}
 
-        // Apply changes atomically
-        if !local_changes.is_empty() {
-            let mut heights = heights_mutex.lock().unwrap();
-            for (idx, delta) in local_changes {
-                if idx < heights.len() {
-                    heights[idx] = (heights[idx] + delta).clamp(0.0, 1.0);
-                }
-            }
-        }
-    });
+/// A tile's core (where its droplets spawn) and its halo-padded window, in map cells
+struct ErosionTile {
+    core: (usize, usize, usize, usize),   // x0, z0, x1, z1 (exclusive)
+    window: (usize, usize, usize, usize),
+    droplets: u32,
 }
 
-/// Simulate a single water droplet (Beyer algorithm)
-fn simulate_droplet(
+fn erode_tiled(
     heights: &mut [f32],
     width: usize,
     height: usize,
     params: &ErosionParams,
-    rng: &mut impl Rng,
+    context: &ErosionContext,
+    tile_size: usize,
 ) {
-    let mut x = rng.random_range(0.0..width as f32);
-    let mut z = rng.random_range(0.0..height as f32);
-    let mut dir_x = 0.0;
-    let mut dir_z = 0.0;
-    let mut velocity = 1.0;
-    let mut water = 1.0;
-    let mut sediment = 0.0;
-
-    for _ in 0..params.max_lifetime {
-        let ix = x as usize;
-        let iz = z as usize;
-
-        if ix >= width - 1 || iz >= height - 1 {
-            break;
-        }
-
-        // Calculate gradient
-        let (grad_x, grad_z) = calculate_gradient(heights, ix, iz, width);
+    if width < 2 || height < 2 {
+        return;
+    }
 
-        // Update direction with inertia
-        dir_x = dir_x * params.inertia - grad_x * (1.0 - params.inertia);
-        dir_z = dir_z * params.inertia - grad_z * (1.0 - params.inertia);
+    // Droplets move at most one cell per axis per step and touch cells within the brush radius
+    let halo = params.max_lifetime as usize + params.erosion_radius as usize + 2;
+    let tiles = plan_tiles(width, height, tile_size, halo, params.num_droplets, context.rainfall);
+    let total_droplets = tiles.iter().map(|tile| tile.droplets).sum();
+    let mut tracker = ProgressTracker::new(context.progress, total_droplets);
+    let step = tracker.step;
+
+    // Tiles send finished droplet batches back over a channel so the progress callback
+    // runs on this thread, in order, instead of on whichever worker finishes a batch
+    let (sender, receiver) = mpsc::channel::<u32>();
+    let erode_tiles = || -> Vec<Vec<f32>> {
+        tiles.par_iter()
+            .enumerate()
+            .map_with(sender, |sender, (tile_index, tile)| {
+                let (wx0, wz0, wx1, wz1) = tile.window;
+                let window_width = wx1 - wx0;
+                let window_height = wz1 - wz0;
+
+                let original = copy_window(heights, width, tile.window);
+                let mut local = original.clone();
+                let local_mask = context.locked_mask.map(|mask| copy_window(mask, width, tile.window));
+                let local_hardness = context.hardness.map(|hardness| copy_window(hardness, width, tile.window));
+                let local_context = ErosionContext {
+                    locked_mask: local_mask.as_deref(),
+                    hardness: local_hardness.as_deref(),
+                    ..Default::default()
+                };
+
+                let sampler = SpawnSampler::new(tile.core, width, context.rainfall);
+                let mut rng = params.rng(tile_index as u64);
+                for i in 0..tile.droplets {
+                    let (x, z) = sampler.sample(&mut rng);
+                    run_droplet(&mut local, window_width, window_height, params, &local_context, x - wx0 as f32, z - wz0 as f32);
+                    let batch = progress_batch(step, i, tile.droplets);
+                    if batch > 0 {
+                        let _ = sender.send(batch);
+                    }
+                }
 
-        // Normalize direction
-        let len = (dir_x * dir_x + dir_z * dir_z).sqrt();
-        if len > 0.0 {
-            dir_x /= len;
-            dir_z /= len;
+                local.iter().zip(&original).map(|(after, before)| after - before).collect()
+            })
+            .collect()
+    };
+    let deltas = thread::scope(|scope| {
+        let worker = scope.spawn(erode_tiles);
+        // Ends once every worker's sender is dropped
+        for batch in receiver {
+            tracker.advance(batch);
         }
+        worker.join().expect("erosion worker panicked")
+    });
 
-        // Move droplet
-        let new_x = x + dir_x;
-        let new_z = z + dir_z;
-
-        if new_x < 0.0 || new_x >= (width - 1) as f32 || new_z < 0.0 || new_z >= (height - 1) as f32 {
-            break;
+    // Merge: overlapping halos mean several tiles may have touched the same cell
+    // Summing in tile order keeps seeded runs bit-identical regardless of thread scheduling
+    let mut total_delta = vec![0.0f32; width * height];
+    for (tile, delta) in tiles.iter().zip(deltas) {
+        let (wx0, wz0, wx1, _) = tile.window;
+        let window_width = wx1 - wx0;
+        for (local_idx, d) in delta.into_iter().enumerate() {
+            if d != 0.0 {
+                let x = wx0 + local_idx % window_width;
+                let z = wz0 + local_idx / window_width;
+                total_delta[z * width + x] += d;
+            }
         }
+    }
 
-        // Calculate height difference
-        let old_height = sample_height(heights, x, z, width);
-        let new_height = sample_height(heights, new_x, new_z, width);
-        let height_diff = new_height - old_height;
-
-        // Calculate sediment capacity
-        let capacity = (-height_diff).max(params.min_sediment_capacity) 
-            * velocity 
-            * water 
-            * params.sediment_capacity_factor;
-
-        // Erode or deposit
-        if sediment > capacity || height_diff > 0.0 {
-            // Deposit
-            let amount_to_deposit = if height_diff > 0.0 {
-                (height_diff).min(sediment)
-            } else {
-                (sediment - capacity) * params.deposition_speed
-            };
+    heights.par_iter_mut()
+        .zip(total_delta.par_iter())
+        .for_each(|(h, d)| {
+            if *d != 0.0 {
+                *h = (*h + d).clamp(0.0, 1.0);
+            }
+        });
+}
 
-            sediment -= amount_to_deposit;
-            deposit(heights, x, z, amount_to_deposit, params.erosion_radius, width, height);
-        } else {
-            // Erode
-            let amount_to_erode = (capacity - sediment).min(-height_diff) * params.erosion_speed;
-            erode(heights, x, z, amount_to_erode, params.erosion_radius, width, height);
-            sediment += amount_to_erode;
+/// Split the map into tiles and share the droplets between them in proportion to
+/// each core's spawn weight (its area, or its total rainfall when given)
+fn plan_tiles(
+    width: usize,
+    height: usize,
+    tile_size: usize,
+    halo: usize,
+    num_droplets: u32,
+    rainfall: Option<&[f32]>,
+) -> Vec<ErosionTile> {
+    let tile_size = tile_size.max(1);
+    // Droplets spawn in [0, size - 1) since the last row/column has no gradient
+    let spawn_width = width - 1;
+    let spawn_height = height - 1;
+
+    let mut tiles = Vec::new();
+    let mut weights = Vec::new();
+    for z0 in (0..spawn_height).step_by(tile_size) {
+        for x0 in (0..spawn_width).step_by(tile_size) {
+            let core = (x0, z0, (x0 + tile_size).min(spawn_width), (z0 + tile_size).min(spawn_height));
+            weights.push(SpawnSampler::new(core, width, rainfall).total());
+            tiles.push(ErosionTile {
+                core,
+                window: (
+                    x0.saturating_sub(halo),
+                    z0.saturating_sub(halo),
+                    (core.2 + halo + 1).min(width),
+                    (core.3 + halo + 1).min(height),
+                ),
+                droplets: 0,
+            });
         }
+    }
 
-        // Update velocity and water
-        velocity = (velocity * velocity + height_diff * params.gravity).sqrt();
-        water *= 1.0 - params.evaporation_rate;
-
-        x = new_x;
-        z = new_z;
+    // Cumulative rounding so the per-tile counts add up to exactly num_droplets
+    let total_weight: f64 = weights.iter().sum();
+    if total_weight <= 0.0 {
+        return tiles; // No rain anywhere
     }
+    let mut assigned_weight = 0.0;
+    let mut assigned_droplets = 0u32;
+    for (tile, weight) in tiles.iter_mut().zip(weights) {
+        assigned_weight += weight;
+        let cumulative = (num_droplets as f64 * assigned_weight / total_weight) as u32;
+        tile.droplets = cumulative - assigned_droplets;
+        assigned_droplets = cumulative;
+    }
+
+    tiles
 }
 
-/// Simulate droplet and collect changes (for parallel version)
-fn simulate_droplet_collect(
-    heights: &[f32],
+/// Simulate a single water droplet (Beyer algorithm)
+fn simulate_droplet(
+    heights: &mut [f32],
     width: usize,
     height: usize,
     params: &ErosionParams,
+    context: &ErosionContext,
     rng: &mut impl Rng,
-    changes: &mut Vec<(usize, f32)>,
 ) {
-    let mut x = rng.random_range(0.0..width as f32);
-    let mut z = rng.random_range(0.0..height as f32);
+    let x = rng.random_range(0.0..width as f32);
+    let z = rng.random_range(0.0..height as f32);
+    run_droplet(heights, width, height, params, context, x, z);
+}
+
+/// Move one droplet from (x, z) until it evaporates, stalls, or leaves the map
+fn run_droplet(
+    heights: &mut [f32],
+    width: usize,
+    height: usize,
+    params: &ErosionParams,
+    context: &ErosionContext,
+    mut x: f32,
+    mut z: f32,
+) {
+    let brush = ErosionBrush::new(params.erosion_radius);
     let mut dir_x = 0.0;
     let mut dir_z = 0.0;
     let mut velocity = 1.0;
@@ -188,17 +470,21 @@ fn simulate_droplet_collect(
             break;
         }
 
+        // Calculate gradient
         let (grad_x, grad_z) = calculate_gradient(heights, ix, iz, width);
 
+        // Update direction with inertia
         dir_x = dir_x * params.inertia - grad_x * (1.0 - params.inertia);
         dir_z = dir_z * params.inertia - grad_z * (1.0 - params.inertia);
 
+        // Normalize direction
         let len = (dir_x * dir_x + dir_z * dir_z).sqrt();
         if len > 0.0 {
             dir_x /= len;
             dir_z /= len;
         }
 
+        // Move droplet
         let new_x = x + dir_x;
         let new_z = z + dir_z;
 
@@ -206,30 +492,35 @@ fn simulate_droplet_collect(
             break;
         }
 
+        // Calculate height difference
         let old_height = sample_height(heights, x, z, width);
         let new_height = sample_height(heights, new_x, new_z, width);
         let height_diff = new_height - old_height;
 
+        // Calculate sediment capacity
         let capacity = (-height_diff).max(params.min_sediment_capacity) 
             * velocity 
             * water 
             * params.sediment_capacity_factor;
 
+        // Erode or deposit
         if sediment > capacity || height_diff > 0.0 {
+            // Deposit
             let amount_to_deposit = if height_diff > 0.0 {
                 (height_diff).min(sediment)
             } else {
                 (sediment - capacity) * params.deposition_speed
             };
 
-            sediment -= amount_to_deposit;
-            collect_deposit(changes, x, z, amount_to_deposit, params.erosion_radius, width, height);
+            sediment -= deposit(heights, x, z, amount_to_deposit, &brush, width, height, context);
         } else {
+            // Erode; hardness scales each cell inside `erode`, so only what actually
+            // came off the terrain joins the sediment
             let amount_to_erode = (capacity - sediment).min(-height_diff) * params.erosion_speed;
-            collect_erosion(changes, x, z, amount_to_erode, params.erosion_radius, width, height);
-            sediment += amount_to_erode;
+            sediment += erode(heights, x, z, amount_to_erode, &brush, width, height, context);
         }
 
+        // Update velocity and water
         velocity = (velocity * velocity + height_diff * params.gravity).sqrt();
         water *= 1.0 - params.evaporation_rate;
 
@@ -263,100 +554,339 @@ fn sample_height(heights: &[f32], x: f32, z: f32, width: usize) -> f32 {
     h0 * (1.0 - fz) + h1 * fz
 }
 
-/// Erode terrain at position with Gaussian brush
-fn erode(heights: &mut [f32], x: f32, z: f32, amount: f32, radius: u32, width: usize, height: usize) {
-    let ix = x as i32;
-    let iz = z as i32;
-    let r = radius as i32;
-
-    for dz in -r..=r {
-        for dx in -r..=r {
-            let nx = ix + dx;
-            let nz = iz + dz;
+/// Gaussian brush offsets and weights around a droplet's cell, built once per droplet
+struct ErosionBrush {
+    cells: Vec<(i32, i32, f32)>, // dx, dz, weight
+}
 
-            if nx >= 0 && nx < width as i32 && nz >= 0 && nz < height as i32 {
+impl ErosionBrush {
+    fn new(radius: u32) -> Self {
+        let r = radius as i32;
+        let mut cells = Vec::new();
+        for dz in -r..=r {
+            for dx in -r..=r {
                 let dist = ((dx * dx + dz * dz) as f32).sqrt();
                 if dist <= radius as f32 {
-                    let weight = gaussian_weight(dist, radius as f32);
-                    let idx = nz as usize * width + nx as usize;
-                    heights[idx] = (heights[idx] - amount * weight).max(0.0);
+                    cells.push((dx, dz, gaussian_weight(dist, radius as f32)));
                 }
             }
         }
+        Self { cells }
+    }
+
+    /// Map index of the brush cell offset by (dx, dz) from (x, z), or None off the map
+    fn index(x: f32, z: f32, dx: i32, dz: i32, width: usize, height: usize) -> Option<usize> {
+        let nx = x as i32 + dx;
+        let nz = z as i32 + dz;
+        (nx >= 0 && nx < width as i32 && nz >= 0 && nz < height as i32).then(|| nz as usize * width + nx as usize)
     }
 }
 
-/// Deposit sediment at position with Gaussian brush
-fn deposit(heights: &mut [f32], x: f32, z: f32, amount: f32, radius: u32, width: usize, height: usize) {
-    let ix = x as i32;
-    let iz = z as i32;
-    let r = radius as i32;
+/// Erode terrain at position with Gaussian brush, returning the total height removed
+/// The weights are normalized over the brush, so at most `amount` comes off the terrain;
+/// hard and locked cells give up less
+#[allow(clippy::too_many_arguments)]
+fn erode(heights: &mut [f32], x: f32, z: f32, amount: f32, brush: &ErosionBrush, width: usize, height: usize, context: &ErosionContext) -> f32 {
+    let mut total_weight = 0.0;
+    for &(dx, dz, weight) in &brush.cells {
+        if ErosionBrush::index(x, z, dx, dz, width, height).is_some() {
+            total_weight += weight;
+        }
+    }
+    if total_weight <= 0.0 {
+        return 0.0;
+    }
 
-    for dz in -r..=r {
-        for dx in -r..=r {
-            let nx = ix + dx;
-            let nz = iz + dz;
+    let mut removed = 0.0;
+    for &(dx, dz, weight) in &brush.cells {
+        let Some(idx) = ErosionBrush::index(x, z, dx, dz, width, height) else { continue };
+        if !context.is_locked(idx) {
+            let lowered = (heights[idx] - amount * weight / total_weight * context.softness(idx)).max(0.0);
+            removed += heights[idx] - lowered;
+            heights[idx] = lowered;
+        }
+    }
 
-            if nx >= 0 && nx < width as i32 && nz >= 0 && nz < height as i32 {
-                let dist = ((dx * dx + dz * dz) as f32).sqrt();
-                if dist <= radius as f32 {
-                    let weight = gaussian_weight(dist, radius as f32);
-                    let idx = nz as usize * width + nx as usize;
-                    heights[idx] = (heights[idx] + amount * weight).min(1.0);
-                }
-            }
+    removed
+}
+
+/// Deposit sediment at position with Gaussian brush, returning the total height added
+/// The weights are normalized over the cells that can take sediment, so at most
+/// `amount` lands on the terrain
+#[allow(clippy::too_many_arguments)]
+fn deposit(heights: &mut [f32], x: f32, z: f32, amount: f32, brush: &ErosionBrush, width: usize, height: usize, context: &ErosionContext) -> f32 {
+    let mut total_weight = 0.0;
+    for &(dx, dz, weight) in &brush.cells {
+        if ErosionBrush::index(x, z, dx, dz, width, height).is_some_and(|idx| !context.is_locked(idx)) {
+            total_weight += weight;
         }
     }
+    if total_weight <= 0.0 {
+        return 0.0;
+    }
+
+    let mut added = 0.0;
+    for &(dx, dz, weight) in &brush.cells {
+        let Some(idx) = ErosionBrush::index(x, z, dx, dz, width, height) else { continue };
+        if !context.is_locked(idx) {
+            let raised = (heights[idx] + amount * weight / total_weight).min(1.0);
+            added += raised - heights[idx];
+            heights[idx] = raised;
+        }
+    }
+
+    added
+}
+
+/// Gaussian weight function
+fn gaussian_weight(distance: f32, radius: f32) -> f32 {
+    let normalized = distance / radius;
+    (-normalized * normalized * 4.0).exp()
 }
 
-/// Collect erosion changes
-fn collect_erosion(changes: &mut Vec<(usize, f32)>, x: f32, z: f32, amount: f32, radius: u32, width: usize, height: usize) {
-    let ix = x as i32;
-    let iz = z as i32;
-    let r = radius as i32;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eroded_terrain_has_lower_delta() {
+        let size = 64;
+        let mut rng = StdRng::seed_from_u64(42);
+        let fresh: Vec<f32> = (0..size * size)
+            .map(|_| rng.random_range(0.2..0.8))
+            .collect();
+
+        let mut eroded = fresh.clone();
+        let params = ErosionParams {
+            num_droplets: 20_000,
+            ..Default::default()
+        };
+        for _ in 0..params.num_droplets {
+            simulate_droplet(&mut eroded, size, size, &params, &ErosionContext::default(), &mut rng);
+        }
 
-    for dz in -r..=r {
-        for dx in -r..=r {
-            let nx = ix + dx;
-            let nz = iz + dz;
+        let fresh_delta = erosion_delta_estimate(&fresh, size, size, 500);
+        let eroded_delta = erosion_delta_estimate(&eroded, size, size, 500);
+        assert!(fresh_delta > eroded_delta, "fresh {} vs eroded {}", fresh_delta, eroded_delta);
+    }
 
-            if nx >= 0 && nx < width as i32 && nz >= 0 && nz < height as i32 {
-                let dist = ((dx * dx + dz * dz) as f32).sqrt();
-                if dist <= radius as f32 {
-                    let weight = gaussian_weight(dist, radius as f32);
-                    let idx = nz as usize * width + nx as usize;
-                    changes.push((idx, -amount * weight));
-                }
+    #[test]
+    fn test_tiled_erosion_matches_serial() {
+        let size = 160;
+        let mut rng = StdRng::seed_from_u64(3);
+        // Noisy slope so droplets travel far enough to cross tile boundaries
+        let original: Vec<f32> = (0..size * size)
+            .map(|i| 0.3 + (i % size) as f32 / size as f32 * 0.5 + rng.random_range(0.0..0.05))
+            .collect();
+        let params = ErosionParams {
+            num_droplets: 20_000,
+            seed: Some(3),
+            ..Default::default()
+        };
+
+        let mut serial = original.clone();
+        erode_terrain(&mut serial, size, size, &params);
+        let mut tiled = original.clone();
+        erode_tiled(&mut tiled, size, size, &params, &ErosionContext::default(), 48);
+
+        // Tiles can't see each other's channels, so compare the material carried off
+        // rather than the exact carving
+        let mean_lowering = |eroded: &[f32]| {
+            original.iter().zip(eroded).map(|(before, after)| before - after).sum::<f32>() / original.len() as f32
+        };
+
+        let (serial_lowering, tiled_lowering) = (mean_lowering(&serial), mean_lowering(&tiled));
+        assert!(serial_lowering > 0.0);
+        assert!(tiled_lowering > serial_lowering * 0.8 && tiled_lowering < serial_lowering * 1.2,
+            "serial {} vs tiled {}", serial_lowering, tiled_lowering);
+    }
+
+    #[test]
+    fn test_tile_plan_covers_map_and_droplets() {
+        let tiles = plan_tiles(1000, 700, 256, 70, 123_457, None);
+        assert_eq!(tiles.iter().map(|t| t.droplets as u64).sum::<u64>(), 123_457);
+        let core_area: usize = tiles.iter().map(|t| (t.core.2 - t.core.0) * (t.core.3 - t.core.1)).sum();
+        assert_eq!(core_area, 999 * 699);
+        assert!(tiles.iter().all(|t| t.window.2 <= 1000 && t.window.3 <= 700));
+    }
+
+    #[test]
+    fn test_seeded_erosion_is_reproducible() {
+        let size = 96;
+        let mut rng = StdRng::seed_from_u64(11);
+        let original: Vec<f32> = (0..size * size)
+            .map(|_| rng.random_range(0.2..0.8))
+            .collect();
+        let params = ErosionParams {
+            num_droplets: 5_000,
+            seed: Some(1234),
+            ..Default::default()
+        };
+
+        let run = |parallel: bool, params: &ErosionParams| {
+            let mut heights = original.clone();
+            if parallel {
+                erode_tiled(&mut heights, size, size, params, &ErosionContext::default(), 32);
+            } else {
+                erode_terrain(&mut heights, size, size, params);
+            }
+            heights
+        };
+
+        assert_eq!(run(false, &params), run(false, &params));
+        assert_eq!(run(true, &params), run(true, &params));
+
+        let reseeded = ErosionParams { seed: Some(1235), ..params.clone() };
+        assert_ne!(run(true, &params), run(true, &reseeded));
+    }
+
+    #[test]
+    fn test_rainfall_concentrates_erosion() {
+        let size = 128;
+        let mut rng = StdRng::seed_from_u64(5);
+        let original: Vec<f32> = (0..size * size)
+            .map(|_| rng.random_range(0.2..0.8))
+            .collect();
+
+        // All the rain falls in the north-west quadrant
+        let in_quadrant = |i: usize| i % size < size / 2 && i / size < size / 2;
+        let rainfall: Vec<f32> = (0..size * size).map(|i| if in_quadrant(i) { 1.0 } else { 0.0 }).collect();
+        let context = ErosionContext { rainfall: Some(&rainfall), ..Default::default() };
+        let params = ErosionParams {
+            num_droplets: 10_000,
+            seed: Some(9),
+            ..Default::default()
+        };
+
+        let mut serial = original.clone();
+        erode_terrain_with_context(&mut serial, size, size, &params, &context);
+        let mut tiled = original.clone();
+        erode_tiled(&mut tiled, size, size, &params, &context, 32);
+
+        for eroded in [&serial, &tiled] {
+            let (mut wet, mut dry) = (0.0, 0.0);
+            for i in 0..size * size {
+                let change = (eroded[i] - original[i]).abs();
+                if in_quadrant(i) { wet += change } else { dry += change }
             }
+            // The quadrant is a quarter of the map, so compare per-cell averages
+            assert!(wet > dry / 3.0 * 2.0, "wet {} vs dry {}", wet, dry / 3.0);
+        }
+    }
+
+    #[test]
+    fn test_hard_band_resists_erosion() {
+        let size = 96;
+        let mut rng = StdRng::seed_from_u64(17);
+        // Slope running north to south so droplets cross the band
+        let original: Vec<f32> = (0..size * size)
+            .map(|i| 0.8 - (i / size) as f32 / size as f32 * 0.5 + rng.random_range(0.0..0.02))
+            .collect();
+
+        let in_band = |i: usize| (40..56).contains(&(i / size));
+        let hardness: Vec<f32> = (0..size * size).map(|i| if in_band(i) { 0.95 } else { 0.0 }).collect();
+        let context = ErosionContext { hardness: Some(&hardness), ..Default::default() };
+        let params = ErosionParams {
+            num_droplets: 20_000,
+            seed: Some(2),
+            ..Default::default()
+        };
+
+        let mut serial = original.clone();
+        erode_terrain_with_context(&mut serial, size, size, &params, &context);
+        let mut tiled = original.clone();
+        erode_tiled(&mut tiled, size, size, &params, &context, 32);
+
+        for eroded in [&serial, &tiled] {
+            let mean_lowering = |band: bool| {
+                let cells: Vec<usize> = (0..size * size).filter(|&i| in_band(i) == band).collect();
+                cells.iter().map(|&i| original[i] - eroded[i]).sum::<f32>() / cells.len() as f32
+            };
+            let (hard, soft) = (mean_lowering(true), mean_lowering(false));
+            assert!(hard < soft * 0.5, "hard band lowered {} vs soft {}", hard, soft);
         }
     }
-}
 
-/// Collect deposition changes
-fn collect_deposit(changes: &mut Vec<(usize, f32)>, x: f32, z: f32, amount: f32, radius: u32, width: usize, height: usize) {
-    let ix = x as i32;
-    let iz = z as i32;
-    let r = radius as i32;
+    #[test]
+    fn test_erode_reports_height_actually_removed() {
+        let size = 16;
+        let hardness: Vec<f32> = (0..size * size).map(|i| if i % size < 8 { 0.5 } else { 1.0 }).collect();
+        let context = ErosionContext { hardness: Some(&hardness), ..Default::default() };
+
+        let original = vec![0.5; size * size];
+        let mut heights = original.clone();
+        let removed = erode(&mut heights, 8.0, 8.0, 0.01, &ErosionBrush::new(3), size, size, &context);
+
+        let lowered: f32 = original.iter().zip(&heights).map(|(before, after)| before - after).sum();
+        assert!(removed > 0.0);
+        assert!((removed - lowered).abs() < 1e-6, "reported {} vs lowered {}", removed, lowered);
+        // Fully hard cells give up nothing
+        assert!((0..size * size).filter(|i| i % size >= 8).all(|i| heights[i] == 0.5));
+    }
 
-    for dz in -r..=r {
-        for dx in -r..=r {
-            let nx = ix + dx;
-            let nz = iz + dz;
+    #[test]
+    fn test_progress_reported_every_percent() {
+        use std::sync::Mutex;
+
+        let size = 64;
+        let mut rng = StdRng::seed_from_u64(5);
+        let original: Vec<f32> = (0..size * size)
+            .map(|_| rng.random_range(0.2..0.8))
+            .collect();
+        let params = ErosionParams {
+            num_droplets: 5_000,
+            seed: Some(9),
+            ..Default::default()
+        };
+
+        for tiled in [false, true] {
+            let reports = Mutex::new(Vec::new());
+            let callback = |fraction: f32| reports.lock().unwrap().push(fraction);
+            let context = ErosionContext { progress: Some(&callback), ..Default::default() };
+
+            let mut heights = original.clone();
+            if tiled {
+                erode_tiled(&mut heights, size, size, &params, &context, 16);
+            } else {
+                erode_terrain_with_context(&mut heights, size, size, &params, &context);
+            }
 
-            if nx >= 0 && nx < width as i32 && nz >= 0 && nz < height as i32 {
-                let dist = ((dx * dx + dz * dz) as f32).sqrt();
-                if dist <= radius as f32 {
-                    let weight = gaussian_weight(dist, radius as f32);
-                    let idx = nz as usize * width + nx as usize;
-                    changes.push((idx, amount * weight));
+            let reports = reports.into_inner().unwrap();
+            assert!((95..=105).contains(&reports.len()), "{} progress reports", reports.len());
+            // Reported from one thread, so the fractions only ever grow
+            assert!(reports.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", reports);
+            assert_eq!(reports.last().copied(), Some(1.0));
+        }
+    }
+
+    #[test]
+    fn test_locked_cells_keep_exact_height() {
+        let size = 64;
+        let mut rng = StdRng::seed_from_u64(7);
+        let original: Vec<f32> = (0..size * size)
+            .map(|_| rng.random_range(0.2..0.8))
+            .collect();
+
+        // Lock the left half of the map
+        let locked: Vec<bool> = (0..size * size).map(|i| i % size < size / 2).collect();
+        let context = ErosionContext { locked_mask: Some(&locked), ..Default::default() };
+        let params = ErosionParams {
+            num_droplets: 5_000,
+            ..Default::default()
+        };
+
+        let mut serial = original.clone();
+        erode_terrain_with_context(&mut serial, size, size, &params, &context);
+        let mut parallel = original.clone();
+        erode_terrain_parallel_with_context(&mut parallel, size, size, &params, &context);
+
+        for eroded in [&serial, &parallel] {
+            for i in 0..size * size {
+                if locked[i] {
+                    assert_eq!(eroded[i], original[i], "locked cell {} changed", i);
                 }
             }
+            assert!((0..size * size).any(|i| !locked[i] && eroded[i] != original[i]));
         }
     }
 }
-
-/// Gaussian weight function
-fn gaussian_weight(distance: f32, radius: f32) -> f32 {
-    let normalized = distance / radius;
-    (-normalized * normalized * 4.0).exp()
-}
@@ -0,0 +1,350 @@
+use std::io::Write;
+use std::path::Path;
+use anyhow::{Context, Result};
+use image::{ImageBuffer, ImageFormat, Luma};
+use super::TerrainData;
+
+/// Grayscale levels in a 16-bit PNG
+const MAX_LEVEL: f32 = u16::MAX as f32;
+
+/// Write row-major heights as a 16-bit grayscale PNG
+/// Heights are clamped to 0.0..=1.0 and rounded to the nearest of 65,536 levels,
+/// so reading the file back is off by at most 0.5 / 65535
+pub fn write_heightmap_png<P: AsRef<Path>>(path: P, heights: &[f32], width: u32, height: u32) -> Result<()> {
+    if heights.len() != width as usize * height as usize {
+        anyhow::bail!("Expected {}×{} heights, got {}", width, height, heights.len());
+    }
+
+    let levels: Vec<u16> = heights.iter()
+        .map(|h| (h.clamp(0.0, 1.0) * MAX_LEVEL).round() as u16)
+        .collect();
+    let image: ImageBuffer<Luma<u16>, Vec<u16>> = ImageBuffer::from_raw(width, height, levels)
+        .context("Heightmap buffer does not match its dimensions")?;
+
+    image.save_with_format(path.as_ref(), ImageFormat::Png)
+        .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", path.as_ref().display(), e))
+}
+
+/// Read a grayscale PNG back into row-major heights in 0.0..=1.0, with its (width, height)
+/// 8-bit images are widened, so they only carry 256 distinct levels
+pub fn read_heightmap_png<P: AsRef<Path>>(path: P) -> Result<(Vec<f32>, u32, u32)> {
+    let image = image::open(path.as_ref())
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.as_ref().display(), e))?
+        .into_luma16();
+    let (width, height) = image.dimensions();
+    let heights = image.into_raw().into_iter()
+        .map(|level| level as f32 / MAX_LEVEL)
+        .collect();
+    Ok((heights, width, height))
+}
+
+/// Bilinearly resample a row-major grid to new dimensions, keeping the corners aligned
+pub fn resample_bilinear(heights: &[f32], width: usize, height: usize, new_width: usize, new_height: usize) -> Vec<f32> {
+    if (width, height) == (new_width, new_height) {
+        return heights.to_vec();
+    }
+
+    let scale = |size: usize, new_size: usize| {
+        if new_size > 1 { (size - 1) as f32 / (new_size - 1) as f32 } else { 0.0 }
+    };
+    let (scale_x, scale_z) = (scale(width, new_width), scale(height, new_height));
+
+    let mut resampled = Vec::with_capacity(new_width * new_height);
+    for z in 0..new_height {
+        let src_z = z as f32 * scale_z;
+        let z0 = (src_z.floor() as usize).min(height - 1);
+        let z1 = (z0 + 1).min(height - 1);
+        let fz = src_z - z0 as f32;
+        for x in 0..new_width {
+            let src_x = x as f32 * scale_x;
+            let x0 = (src_x.floor() as usize).min(width - 1);
+            let x1 = (x0 + 1).min(width - 1);
+            let fx = src_x - x0 as f32;
+
+            let top = heights[z0 * width + x0] * (1.0 - fx) + heights[z0 * width + x1] * fx;
+            let bottom = heights[z1 * width + x0] * (1.0 - fx) + heights[z1 * width + x1] * fx;
+            resampled.push(top * (1.0 - fz) + bottom * fz);
+        }
+    }
+    resampled
+}
+
+/// Size of an exported mesh
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MeshStats {
+    pub vertices: usize,
+    pub faces: usize,
+}
+
+/// Write the loaded chunks as a single Wavefront OBJ triangle mesh in metres (+y up)
+/// Vertices form one global grid spanning the world (world_width + 1 vertices across),
+/// so shared chunk edges become shared vertices and the mesh has no cracks. `lod`
+/// keeps every 2^lod-th vertex (plus the far edge); cells touching an unloaded chunk are left open
+pub fn write_mesh_obj<W: Write>(terrain: &TerrainData, lod: u8, include_normals: bool, mut out: W) -> Result<MeshStats> {
+    let config = &terrain.config;
+    let step = 1usize << lod.min(7);
+    let axis = |size: u32| {
+        let mut coords: Vec<usize> = (0..=size as usize).step_by(step).collect();
+        if coords.last() != Some(&(size as usize)) {
+            coords.push(size as usize);
+        }
+        coords
+    };
+    let xs = axis(config.world_width);
+    let zs = axis(config.world_height);
+
+    // Heights for every grid vertex present in a loaded chunk
+    let grid: Vec<Option<f32>> = zs.iter()
+        .flat_map(|&z| xs.iter().map(move |&x| (x, z)))
+        .map(|(x, z)| global_vertex_height(terrain, x, z))
+        .collect();
+
+    writeln!(out, "# WorldWeaver terrain, {} chunks, lod {}", terrain.chunks.len(), lod)?;
+
+    let mut indices = vec![0usize; grid.len()];
+    let mut vertices = 0;
+    for (i, height) in grid.iter().enumerate() {
+        let Some(height) = height else { continue };
+        let (x, z) = (xs[i % xs.len()], zs[i / xs.len()]);
+        writeln!(
+            out,
+            "v {} {} {}",
+            x as f32 * config.cell_size_meters,
+            height * config.max_elevation,
+            z as f32 * config.cell_size_meters
+        )?;
+        vertices += 1;
+        indices[i] = vertices;
+    }
+
+    if include_normals {
+        for (i, height) in grid.iter().enumerate() {
+            if height.is_none() {
+                continue;
+            }
+            let [nx, ny, nz] = grid_normal(&grid, &xs, &zs, i, config.cell_size_meters, config.max_elevation);
+            writeln!(out, "vn {} {} {}", nx, ny, nz)?;
+        }
+    }
+
+    let mut faces = 0;
+    let width = xs.len();
+    for row in 0..zs.len().saturating_sub(1) {
+        for col in 0..width.saturating_sub(1) {
+            let corners = [row * width + col, (row + 1) * width + col, row * width + col + 1, (row + 1) * width + col + 1];
+            if corners.iter().any(|&i| grid[i].is_none()) {
+                continue;
+            }
+            let [v00, v01, v10, v11] = corners.map(|i| indices[i]);
+            // Counter-clockwise seen from above
+            for [a, b, c] in [[v00, v01, v10], [v10, v01, v11]] {
+                if include_normals {
+                    writeln!(out, "f {a}//{a} {b}//{b} {c}//{c}")?;
+                } else {
+                    writeln!(out, "f {a} {b} {c}")?;
+                }
+                faces += 1;
+            }
+        }
+    }
+
+    Ok(MeshStats { vertices, faces })
+}
+
+/// Height at a global vertex, read from whichever loaded chunk holds it
+/// (vertices on a chunk edge live in both neighbours)
+fn global_vertex_height(terrain: &TerrainData, x: usize, z: usize) -> Option<f32> {
+    let chunk_size = terrain.config.chunk_size as usize;
+    let vertex_count = terrain.config.vertex_count;
+    let candidates = |v: usize| {
+        let chunk = v / chunk_size;
+        let shared = v % chunk_size == 0 && chunk > 0;
+        [Some((chunk, v % chunk_size)), shared.then(|| (chunk - 1, chunk_size))]
+    };
+
+    for (chunk_z, local_z) in candidates(z).into_iter().flatten() {
+        for (chunk_x, local_x) in candidates(x).into_iter().flatten() {
+            if let Some(chunk) = terrain.chunks.get(&(chunk_x as i32, chunk_z as i32)) {
+                return Some(chunk.get_height(local_x, local_z, vertex_count));
+            }
+        }
+    }
+    None
+}
+
+/// Unit normal at grid vertex `i` from central differences over present neighbours
+fn grid_normal(grid: &[Option<f32>], xs: &[usize], zs: &[usize], i: usize, cell_size_meters: f32, max_elevation: f32) -> [f32; 3] {
+    let width = xs.len();
+    let (col, row) = (i % width, i / width);
+    let height = |col: usize, row: usize| grid[row * width + col];
+    let slope = |lo: (usize, usize), hi: (usize, usize), span: usize| -> f32 {
+        match (height(lo.0, lo.1), height(hi.0, hi.1)) {
+            (Some(a), Some(b)) if span > 0 => (b - a) * max_elevation / (span as f32 * cell_size_meters),
+            _ => 0.0,
+        }
+    };
+
+    let (left, right) = (col.saturating_sub(1), (col + 1).min(width - 1));
+    let (up, down) = (row.saturating_sub(1), (row + 1).min(zs.len() - 1));
+    let dx = slope((left, row), (right, row), xs[right] - xs[left]);
+    let dz = slope((col, up), (col, down), zs[down] - zs[up]);
+
+    let length = (dx * dx + 1.0 + dz * dz).sqrt();
+    [-dx / length, 1.0 / length, -dz / length]
+}
+
+/// Rivers and roads as a GeoJSON FeatureCollection of LineStrings in world metres
+/// (x east, y north, so y = -z; no CRS). Rivers carry `strahler_order` and `width_meters`;
+/// roads carry `cost` and their `bridges` as index ranges into the coordinates
+pub fn features_geojson(terrain: &TerrainData) -> serde_json::Value {
+    use serde_json::json;
+
+    let cell = terrain.config.cell_size_meters;
+    let rivers = terrain.river_network.segments.iter().map(|segment| json!({
+        "type": "Feature",
+        "geometry": {
+            "type": "LineString",
+            "coordinates": segment.path.iter().map(|&(x, z)| [x * cell, -z * cell]).collect::<Vec<_>>(),
+        },
+        "properties": {
+            "kind": "river",
+            "id": segment.id,
+            "strahler_order": segment.strahler_order,
+            "width_meters": segment.width_meters,
+        },
+    }));
+    let roads = terrain.roads.iter().enumerate().map(|(id, road)| json!({
+        "type": "Feature",
+        "geometry": {
+            "type": "LineString",
+            "coordinates": road.path.iter().map(|&(x, z)| [x as f32 * cell, -(z as f32) * cell]).collect::<Vec<_>>(),
+        },
+        "properties": {
+            "kind": "road",
+            "id": id,
+            "cost": road.cost,
+            "bridges": road.bridges,
+        },
+    }));
+
+    json!({
+        "type": "FeatureCollection",
+        "features": rivers.chain(roads).collect::<Vec<_>>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terrain::TerrainData;
+    use crate::terrain::config::{TerrainConfig, WorldTheme};
+
+    #[test]
+    fn test_png_round_trip_within_one_level() {
+        let (width, height) = (33, 17);
+        let heights: Vec<f32> = (0..width * height)
+            .map(|i| (i as f32 * 0.618_034).fract())
+            .collect();
+        let path = std::env::temp_dir().join(format!("worldweaver-heightmap-{}.png", std::process::id()));
+
+        write_heightmap_png(&path, &heights, width, height).unwrap();
+        let (loaded, loaded_width, loaded_height) = read_heightmap_png(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!((loaded_width, loaded_height), (width, height));
+        let max_error = heights.iter().zip(&loaded)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f32::max);
+        assert!(max_error < 1.0 / 65535.0, "max error {}", max_error);
+    }
+
+    #[test]
+    fn test_single_chunk_mesh_counts() {
+        let config = TerrainConfig::new(128, 128, 1, WorldTheme::Fantasy);
+        let vertex_count = config.vertex_count as usize;
+        let mut terrain = TerrainData::empty(config);
+        for chunk in crate::terrain::noise_gen::generate_terrain(&terrain.config) {
+            terrain.chunks.insert(chunk.coord, chunk);
+        }
+
+        let mut obj = Vec::new();
+        let stats = write_mesh_obj(&terrain, 0, true, &mut obj).unwrap();
+        let obj = String::from_utf8(obj).unwrap();
+
+        assert_eq!(stats, MeshStats { vertices: vertex_count * vertex_count, faces: (vertex_count - 1).pow(2) * 2 });
+        assert_eq!(obj.lines().filter(|line| line.starts_with("v ")).count(), stats.vertices);
+        assert_eq!(obj.lines().filter(|line| line.starts_with("vn ")).count(), stats.vertices);
+        assert_eq!(obj.lines().filter(|line| line.starts_with("f ")).count(), stats.faces);
+    }
+
+    #[test]
+    fn test_mesh_stitches_chunk_borders() {
+        let mut terrain = TerrainData::empty(TerrainConfig::new(256, 128, 1, WorldTheme::Fantasy));
+        for chunk in crate::terrain::noise_gen::generate_terrain(&terrain.config) {
+            terrain.chunks.insert(chunk.coord, chunk);
+        }
+
+        let stats = write_mesh_obj(&terrain, 1, false, std::io::sink()).unwrap();
+
+        // One 129×65 grid: the shared border column appears once
+        assert_eq!(stats, MeshStats { vertices: 129 * 65, faces: 128 * 64 * 2 });
+    }
+
+    #[test]
+    fn test_geojson_has_feature_per_segment() {
+        use crate::terrain::rivers::RiverSegment;
+        use crate::terrain::roads::Road;
+
+        let mut terrain = TerrainData::empty(TerrainConfig::new(128, 128, 1, WorldTheme::Fantasy));
+        terrain.river_network.segments = vec![
+            RiverSegment::new(0, vec![(1.0, 1.0), (2.0, 2.0), (3.0, 2.0)], 1, 12.0),
+            RiverSegment::new(1, vec![(3.0, 2.0), (4.0, 3.0)], 2, 30.0),
+        ];
+        terrain.roads = vec![Road { path: vec![(5, 5), (6, 6)], cost: 40, bridges: Vec::new() }];
+
+        let text = features_geojson(&terrain).to_string();
+        let parsed: serde_json::Value = serde_json::from_str(&text).unwrap();
+
+        assert_eq!(parsed["type"], "FeatureCollection");
+        let features = parsed["features"].as_array().unwrap();
+        let rivers: Vec<_> = features.iter().filter(|f| f["properties"]["kind"] == "river").collect();
+        assert_eq!(rivers.len(), 2);
+        assert_eq!(features.len(), 3);
+        assert!(features.iter().all(|f| f["type"] == "Feature" && f["geometry"]["type"] == "LineString"));
+        assert_eq!(rivers[1]["properties"]["strahler_order"], 2);
+        assert_eq!(rivers[0]["geometry"]["coordinates"][1], serde_json::json!([200.0, -200.0]));
+    }
+
+    #[test]
+    fn test_import_resamples_gradient() {
+        use crate::terrain::heightmap::HeightmapChunk;
+        use crate::terrain::rivers::RiverSegment;
+        use crate::terrain::roads::Road;
+
+        // West-to-east gradient at a quarter of the world's resolution
+        let (width, height) = (64, 32);
+        let gradient: Vec<f32> = (0..width * height)
+            .map(|i| (i % width) as f32 / (width - 1) as f32)
+            .collect();
+        let path = std::env::temp_dir().join(format!("worldweaver-gradient-{}.png", std::process::id()));
+        write_heightmap_png(&path, &gradient, width, height).unwrap();
+        let (heights, width, height) = read_heightmap_png(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let mut terrain = TerrainData::empty(TerrainConfig::new(256, 128, 1, WorldTheme::Fantasy));
+        terrain.chunks.insert((0, 0), HeightmapChunk::new((0, 0), 129));
+        terrain.generate_biomes();
+        terrain.river_network.segments = vec![RiverSegment::new(0, vec![(1.0, 1.0), (2.0, 2.0)], 1, 12.0)];
+        terrain.roads = vec![Road { path: vec![(5, 5), (6, 6)], cost: 40, bridges: Vec::new() }];
+        terrain.import_heights(&heights, width as usize, height as usize);
+
+        assert_eq!(terrain.chunks.len(), 2);
+        assert!(terrain.river_network.segments.is_empty() && terrain.roads.is_empty());
+        assert!(terrain.chunks.values().all(|chunk| chunk.biome_ids.is_none()));
+        let cell = terrain.config.cell_size_meters;
+        for x in [0.0, 64.0, 128.0, 200.0, 255.0] {
+            let sampled = terrain.sample_height(x * cell, 50.0 * cell).unwrap();
+            assert!((sampled - x / 255.0).abs() < 1e-3, "x {}: {}", x, sampled);
+        }
+    }
+}
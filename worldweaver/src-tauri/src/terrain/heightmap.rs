@@ -1,5 +1,38 @@
 use serde::{Serialize, Deserialize};
 
+/// Creator-placed markers for things a 2D heightmap can't express
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TerrainFeature {
+    None,
+    Cave,
+    Cliff,
+    Ruin,
+}
+
+impl TerrainFeature {
+    pub fn code(&self) -> u8 {
+        match self {
+            TerrainFeature::None => 0,
+            TerrainFeature::Cave => 1,
+            TerrainFeature::Cliff => 2,
+            TerrainFeature::Ruin => 3,
+        }
+    }
+
+    /// Unknown codes read as None so newer maps still load
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            1 => TerrainFeature::Cave,
+            2 => TerrainFeature::Cliff,
+            3 => TerrainFeature::Ruin,
+            _ => TerrainFeature::None,
+        }
+    }
+}
+
+/// Coarsest level of detail stored alongside each full-resolution chunk
+pub const MAX_STORED_LOD: u8 = 3;
+
 /// A single chunk of heightmap data (128×128 cells, 129×129 vertices)
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct HeightmapChunk {
@@ -8,6 +41,13 @@ pub struct HeightmapChunk {
     pub lod: u8,
     pub flow_accumulation: Option<Vec<f32>>,
     pub biome_ids: Option<Vec<u8>>,
+    pub feature_ids: Option<Vec<u8>>, // TerrainFeature codes, same layout as heights
+    #[serde(default)]
+    pub locked: Option<Vec<bool>>,    // Vertices erosion must leave untouched, same layout as heights
+    #[serde(default)]
+    pub cave_mask: Option<Vec<u8>>,   // 1 where a cave opens below the surface, same layout as heights
+    #[serde(skip)]
+    pub bounds: Option<(f32, f32)>, // cached (min, max) height, None when stale
 }
 
 impl HeightmapChunk {
@@ -20,6 +60,10 @@ impl HeightmapChunk {
             lod: 0,
             flow_accumulation: None,
             biome_ids: None,
+            feature_ids: None,
+            locked: None,
+            cave_mask: None,
+            bounds: None,
         }
     }
 
@@ -31,6 +75,10 @@ impl HeightmapChunk {
             lod: 0,
             flow_accumulation: None,
             biome_ids: None,
+            feature_ids: None,
+            locked: None,
+            cave_mask: None,
+            bounds: None,
         }
     }
 
@@ -68,6 +116,7 @@ impl HeightmapChunk {
             let idx = local_z * vertex_count as usize + local_x;
             if idx < self.heights.len() {
                 self.heights[idx] = height;
+                self.bounds = None;
             }
         }
     }
@@ -101,8 +150,179 @@ impl HeightmapChunk {
         (hx - h, hz - h)
     }
 
+    /// Height change per metre (dh/dx, dh/dz) at a vertex, in metres
+    /// The last row and column have no forward neighbour, so they reuse the interior gradient
+    pub fn scaled_gradient(&self, x: usize, z: usize, vertex_count: u32, cell_size_meters: f32, max_elevation: f32) -> (f32, f32) {
+        let last_interior = (vertex_count as usize).saturating_sub(2);
+        let (dx, _) = self.calculate_gradient(x.min(last_interior), z, vertex_count);
+        let (_, dz) = self.calculate_gradient(x, z.min(last_interior), vertex_count);
+        let scale = max_elevation / cell_size_meters;
+        (dx * scale, dz * scale)
+    }
+
+    /// Unit surface normal (x, y, z) per vertex with +y up, same layout as heights
+    pub fn calculate_normals(&self, vertex_count: u32, cell_size_meters: f32, max_elevation: f32) -> Vec<[f32; 3]> {
+        let size = vertex_count as usize;
+        let mut normals = Vec::with_capacity(size * size);
+        for z in 0..size {
+            for x in 0..size {
+                let (dx, dz) = self.scaled_gradient(x, z, vertex_count, cell_size_meters, max_elevation);
+                let length = (dx * dx + 1.0 + dz * dz).sqrt();
+                normals.push([-dx / length, 1.0 / length, -dz / length]);
+            }
+        }
+        normals
+    }
+
+    /// Slope steepness in degrees (0 = flat, 90 = vertical) per vertex, same layout as heights
+    pub fn calculate_slopes(&self, vertex_count: u32, cell_size_meters: f32, max_elevation: f32) -> Vec<f32> {
+        let size = vertex_count as usize;
+        let mut slopes = Vec::with_capacity(size * size);
+        for z in 0..size {
+            for x in 0..size {
+                let (dx, dz) = self.scaled_gradient(x, z, vertex_count, cell_size_meters, max_elevation);
+                slopes.push((dx * dx + dz * dz).sqrt().atan().to_degrees());
+            }
+        }
+        slopes
+    }
+
+    /// Get (min, max) height, computing and caching it if stale
+    pub fn bounds(&mut self) -> (f32, f32) {
+        if let Some(bounds) = self.bounds {
+            return bounds;
+        }
+        self.recompute_bounds()
+    }
+
+    /// Recompute the cached (min, max) height from the current heights
+    pub fn recompute_bounds(&mut self) -> (f32, f32) {
+        let bounds = if self.heights.is_empty() {
+            (0.0, 0.0)
+        } else {
+            self.heights.iter().fold((f32::MAX, f32::MIN), |(min, max), &h| (min.min(h), max.max(h)))
+        };
+        self.bounds = Some(bounds);
+        bounds
+    }
+
+    /// Mark the cached bounds stale after heights were written directly
+    pub fn invalidate_bounds(&mut self) {
+        self.bounds = None;
+    }
+
+    /// Heights at level of detail `lod`: every 2^lod-th vertex along each axis, giving
+    /// a ((vertex_count - 1) / 2^lod + 1)² grid. Striding keeps the edge vertices, so
+    /// neighbouring chunks still meet exactly. LOD is capped at a 2×2 grid; 0 is a plain copy
+    pub fn downsample(&self, lod: u8, vertex_count: u32) -> Vec<f32> {
+        let cells = vertex_count.saturating_sub(1).max(1) as usize;
+        let max_lod = cells.ilog2() as u8;
+        let step = 1usize << lod.min(max_lod);
+        if step == 1 {
+            return self.heights.clone();
+        }
+
+        let vertex_count = vertex_count as usize;
+        let reduced = cells / step + 1;
+        let mut heights = Vec::with_capacity(reduced * reduced);
+        for z in 0..reduced {
+            for x in 0..reduced {
+                let idx = (z * step).min(cells) * vertex_count + (x * step).min(cells);
+                heights.push(self.heights.get(idx).copied().unwrap_or(0.0));
+            }
+        }
+        heights
+    }
+
+    /// Height-only copies of this chunk at LODs 1..=MAX_STORED_LOD, for streaming coarse tiles
+    pub fn generate_lods(&self, vertex_count: u32) -> Vec<HeightmapChunk> {
+        (1..=MAX_STORED_LOD)
+            .map(|lod| {
+                let mut chunk = HeightmapChunk::from_heights(self.coord, self.downsample(lod, vertex_count));
+                chunk.lod = lod;
+                chunk
+            })
+            .collect()
+    }
+
     /// Get the vertex count (assumes square chunk)
     pub fn vertex_count(&self) -> u32 {
         (self.heights.len() as f32).sqrt() as u32
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terrain::brush::{BrushOp, FalloffCurve};
+
+    fn brute_force_bounds(heights: &[f32]) -> (f32, f32) {
+        let min = heights.iter().cloned().fold(f32::MAX, f32::min);
+        let max = heights.iter().cloned().fold(f32::MIN, f32::max);
+        (min, max)
+    }
+
+    #[test]
+    fn test_downsample_quarters_data_per_lod() {
+        let vertex_count = 129;
+        let heights = (0..vertex_count * vertex_count).map(|i| i as f32).collect::<Vec<f32>>();
+        let chunk = HeightmapChunk::from_heights((0, 0), heights);
+
+        assert_eq!(chunk.downsample(0, vertex_count), chunk.heights);
+
+        let lod1 = chunk.downsample(1, vertex_count);
+        assert_eq!(lod1.len(), 65 * 65);
+        let ratio = lod1.len() as f32 / chunk.heights.len() as f32;
+        assert!((ratio - 0.25).abs() < 0.01);
+        // Corners survive so chunk edges still line up
+        assert_eq!(lod1[64], chunk.heights[128]);
+        assert_eq!(lod1[65 * 65 - 1], chunk.heights[129 * 129 - 1]);
+
+        assert_eq!(chunk.downsample(20, vertex_count).len(), 4);
+    }
+
+    #[test]
+    fn test_flat_chunk_normals_point_up() {
+        let chunk = HeightmapChunk::new((0, 0), 17);
+        let normals = chunk.calculate_normals(17, 100.0, 4000.0);
+
+        assert_eq!(normals.len(), 17 * 17);
+        assert!(normals.iter().all(|&normal| normal == [0.0, 1.0, 0.0]));
+    }
+
+    #[test]
+    fn test_slope_of_ramp_and_flat() {
+        let vertex_count = 17;
+        let (cell_size, max_elevation) = (100.0, 4000.0);
+        // Left half climbs one cell width per cell (45°), right half is flat
+        let heights = (0..vertex_count * vertex_count)
+            .map(|i| {
+                let x = (i % vertex_count).min(8);
+                x as f32 * cell_size / max_elevation
+            })
+            .collect::<Vec<f32>>();
+        let chunk = HeightmapChunk::from_heights((0, 0), heights);
+
+        let slopes = chunk.calculate_slopes(vertex_count, cell_size, max_elevation);
+
+        assert!((slopes[4 * 17 + 3] - 45.0).abs() < 0.01);
+        assert!(slopes[4 * 17 + 12].abs() < 0.01);
+    }
+
+    #[test]
+    fn test_bounds_match_and_update_after_raise() {
+        let vertex_count = 17;
+        let heights = (0..vertex_count * vertex_count)
+            .map(|i| 0.3 + (i % 7) as f32 * 0.01)
+            .collect::<Vec<f32>>();
+        let mut chunk = HeightmapChunk::from_heights((0, 0), heights);
+
+        assert_eq!(chunk.bounds(), brute_force_bounds(&chunk.heights));
+
+        let (_, old_max) = chunk.bounds();
+        chunk.apply_brush(8.0, 8.0, 4.0, 10.0, BrushOp::Raise, FalloffCurve::Gaussian, vertex_count);
+
+        assert_eq!(chunk.bounds, Some(brute_force_bounds(&chunk.heights)));
+        assert!(chunk.bounds().1 > old_max);
+    }
+}
@@ -1,5 +1,51 @@
-use std::collections::BinaryHeap;
+use std::collections::{BinaryHeap, VecDeque};
 use std::cmp::Ordering;
+use serde::{Serialize, Deserialize};
+
+/// D-infinity angle for cells with no downslope neighbor
+pub const NO_FLOW_ANGLE: f32 = -1.0;
+
+/// How flow is routed between cells when accumulating drainage
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FlowRouting {
+    #[default]
+    D8,        // All flow goes to the single steepest neighbor
+    DInfinity, // Flow splits between the two neighbors bracketing the steepest facet (Tarboton 1997)
+}
+
+/// Watershed label for sea cells, which belong to no drainage basin
+pub const NO_BASIN: u32 = u32::MAX;
+
+/// A drainage basin: every land cell whose flow ends at the same outlet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Basin {
+    pub id: u32,
+    pub outlet_x: usize,
+    pub outlet_z: usize,
+    pub cell_count: usize,
+    pub area_km2: f32,
+}
+
+/// Per-cell basin labels (row-major, NO_BASIN for sea) plus the basins they index
+#[derive(Debug, Clone, Default)]
+pub struct Watersheds {
+    pub labels: Vec<u32>,
+    pub basins: Vec<Basin>,
+}
+
+/// A body of standing water: a connected region that depression filling raised
+/// Bounds are inclusive cell coordinates; elevations are normalized heights
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lake {
+    pub id: u32,
+    pub surface_elevation: f32,
+    pub max_depth: f32,
+    pub cell_count: usize,
+    pub min_x: usize,
+    pub min_z: usize,
+    pub max_x: usize,
+    pub max_z: usize,
+}
 
 /// Cell for priority queue (min-heap)
 #[derive(Copy, Clone)]
@@ -31,7 +77,10 @@ impl Ord for Cell {
 }
 
 /// Fill depressions using Priority-Flood algorithm (Barnes et al. 2014)
-pub fn fill_depressions(heights: &mut [f32], width: usize, height: usize) {
+/// Cells set in `locked` keep their height even when that leaves a pit
+#[tracing::instrument(skip_all)]
+pub fn fill_depressions(heights: &mut [f32], width: usize, height: usize, locked: Option<&[bool]>) {
+    let is_locked = |idx: usize| locked.is_some_and(|mask| mask.get(idx).copied().unwrap_or(false));
     let mut open = BinaryHeap::new();
     let mut closed = vec![false; width * height];
     let epsilon = 0.0001; // Small increment to ensure drainage
@@ -69,7 +118,7 @@ pub fn fill_depressions(heights: &mut [f32], width: usize, height: usize) {
             let idx = nz * width + nx;
             if !closed[idx] {
                 let neighbor_height = heights[idx];
-                if neighbor_height < cell.height {
+                if neighbor_height < cell.height && !is_locked(idx) {
                     // Fill depression
                     heights[idx] = cell.height + epsilon;
                     open.push(Cell { x: nx, z: nz, height: heights[idx] });
@@ -163,6 +212,115 @@ pub fn calculate_flow_accumulation(
     accumulation
 }
 
+/// Calculate D-infinity flow angles (radians, 0 = +x, increasing toward +z, so the
+/// multiples of PI/4 line up with the D8 directions). NO_FLOW_ANGLE marks pits and flats
+pub fn calculate_flow_direction_dinf(heights: &[f32], width: usize, height: usize) -> Vec<f32> {
+    use std::f32::consts::{FRAC_PI_4, SQRT_2, TAU};
+
+    let mut angles = vec![NO_FLOW_ANGLE; width * height];
+
+    // D8 direction offsets: E, SE, S, SW, W, NW, N, NE
+    let dx = [1, 1, 0, -1, -1, -1, 0, 1];
+    let dz = [0, 1, 1, 1, 0, -1, -1, -1];
+
+    for z in 0..height {
+        for x in 0..width {
+            let idx = z * width + x;
+            let neighbor = |dir: usize| -> Option<f32> {
+                let nx = x as i32 + dx[dir % 8];
+                let nz = z as i32 + dz[dir % 8];
+                (nx >= 0 && nx < width as i32 && nz >= 0 && nz < height as i32)
+                    .then(|| heights[nz as usize * width + nx as usize])
+            };
+
+            let mut steepest_slope = 0.0;
+            // Eight triangular facets, each spanning a cardinal and an adjacent diagonal neighbor
+            for facet in 0..8 {
+                let (cardinal, diagonal) = if facet % 2 == 0 { (facet, facet + 1) } else { (facet + 1, facet) };
+                let (Some(e1), Some(e2)) = (neighbor(cardinal), neighbor(diagonal)) else { continue };
+
+                let s1 = heights[idx] - e1;
+                let s2 = e1 - e2;
+                let (mut r, mut slope) = (s2.atan2(s1), s1.hypot(s2));
+                if r < 0.0 {
+                    (r, slope) = (0.0, s1);
+                } else if r > FRAC_PI_4 {
+                    (r, slope) = (FRAC_PI_4, (heights[idx] - e2) / SQRT_2);
+                }
+
+                if slope > steepest_slope {
+                    steepest_slope = slope;
+                    // Rotate from the cardinal direction toward the diagonal
+                    let toward = if diagonal > cardinal { r } else { -r };
+                    angles[idx] = (cardinal as f32 * FRAC_PI_4 + toward).rem_euclid(TAU);
+                }
+            }
+        }
+    }
+
+    angles
+}
+
+/// Calculate flow accumulation from D-infinity angles, splitting each cell's flow between
+/// the two neighbors whose directions bracket its angle in proportion to their closeness
+pub fn calculate_flow_accumulation_dinf(
+    heights: &[f32],
+    flow_angles: &[f32],
+    width: usize,
+    height: usize,
+) -> Vec<f32> {
+    use std::f32::consts::FRAC_PI_4;
+
+    let mut accumulation = vec![1.0; width * height]; // Each cell starts with 1
+
+    // Sort cells by elevation (descending)
+    let mut cells: Vec<usize> = (0..width * height).collect();
+    cells.sort_by(|&a, &b| heights[b].partial_cmp(&heights[a]).unwrap_or(Ordering::Equal));
+
+    // D8 direction offsets
+    let dx = [1, 1, 0, -1, -1, -1, 0, 1];
+    let dz = [0, 1, 1, 1, 0, -1, -1, -1];
+
+    // Propagate flow downstream; shares that would leave the map are dropped
+    for idx in cells {
+        let angle = flow_angles[idx];
+        if angle < 0.0 {
+            continue;
+        }
+
+        let sector = angle / FRAC_PI_4;
+        let first = sector.floor() as usize % 8;
+        let fraction = sector - sector.floor();
+        for (dir, share) in [(first, 1.0 - fraction), ((first + 1) % 8, fraction)] {
+            let nx = (idx % width) as i32 + dx[dir];
+            let nz = (idx / width) as i32 + dz[dir];
+            if share > 0.0 && nx >= 0 && nx < width as i32 && nz >= 0 && nz < height as i32 {
+                accumulation[nz as usize * width + nx as usize] += accumulation[idx] * share;
+            }
+        }
+    }
+
+    accumulation
+}
+
+/// Flow accumulation using the chosen routing; `flow_direction` is the D8 field, which
+/// D-infinity ignores in favor of its own angles
+pub fn accumulate_flow(
+    routing: FlowRouting,
+    heights: &[f32],
+    flow_direction: &[u8],
+    width: usize,
+    height: usize,
+) -> Vec<f32> {
+    match routing {
+        FlowRouting::D8 => calculate_flow_accumulation(heights, flow_direction, width, height),
+        FlowRouting::DInfinity => {
+            let angles = calculate_flow_direction_dinf(heights, width, height);
+            calculate_flow_accumulation_dinf(heights, &angles, width, height)
+        }
+    }
+}
+
 /// Get 8-directional neighbors
 fn get_neighbors_8(x: usize, z: usize, width: usize, height: usize) -> Vec<(usize, usize)> {
     let mut neighbors = Vec::new();
@@ -182,14 +340,22 @@ fn get_neighbors_8(x: usize, z: usize, width: usize, height: usize) -> Vec<(usiz
 }
 
 /// Apply thermal erosion (simpler supplement to hydraulic erosion)
+/// Heights are normalized, so the talus slope is converted to a per-cell height step
+/// using the cell spacing and the elevation that 1.0 stands for
+/// Cells set in `locked` neither shed nor receive material
+#[allow(clippy::too_many_arguments)]
 pub fn apply_thermal_erosion(
     heights: &mut [f32],
     width: usize,
     height: usize,
     talus_angle: f32,
+    cell_size_meters: f32,
+    max_elevation: f32,
     iterations: u32,
+    locked: Option<&[bool]>,
 ) {
-    let talus_threshold = talus_angle.tan();
+    let is_locked = |idx: usize| locked.is_some_and(|mask| mask.get(idx).copied().unwrap_or(false));
+    let talus_threshold = talus_angle.tan() * cell_size_meters / max_elevation;
 
     for _ in 0..iterations {
         let mut changes = vec![0.0; width * height];
@@ -197,10 +363,16 @@ pub fn apply_thermal_erosion(
         for z in 0..height {
             for x in 0..width {
                 let idx = z * width + x;
+                if is_locked(idx) {
+                    continue;
+                }
                 let h = heights[idx];
 
                 for (nx, nz) in get_neighbors_8(x, z, width, height) {
                     let nidx = nz * width + nx;
+                    if is_locked(nidx) {
+                        continue;
+                    }
                     let nh = heights[nidx];
                     let diff = h - nh;
 
@@ -219,3 +391,298 @@ pub fn apply_thermal_erosion(
         }
     }
 }
+
+/// Label every land cell with the drainage basin its D8 flow ends in
+/// An outlet is a land cell that drains into the sea or has nowhere lower to go (map edge or pit)
+#[tracing::instrument(skip_all)]
+pub fn compute_watersheds(
+    heights: &[f32],
+    flow_direction: &[u8],
+    width: usize,
+    height: usize,
+    sea_level: f32,
+    cell_size_meters: f32,
+) -> Watersheds {
+    let dx = [1, 1, 0, -1, -1, -1, 0, 1];
+    let dz = [0, 1, 1, 1, 0, -1, -1, -1];
+
+    let downstream = |idx: usize| -> Option<usize> {
+        let dir = flow_direction[idx];
+        if dir >= 8 {
+            return None;
+        }
+        let nx = (idx % width) as i32 + dx[dir as usize];
+        let nz = (idx / width) as i32 + dz[dir as usize];
+        if nx >= 0 && nx < width as i32 && nz >= 0 && nz < height as i32 {
+            Some(nz as usize * width + nx as usize)
+        } else {
+            None
+        }
+    };
+    let is_sea = |idx: usize| heights[idx] <= sea_level;
+
+    let mut labels = vec![NO_BASIN; width * height];
+    let mut basins = Vec::new();
+    let cell_area_km2 = (cell_size_meters / 1000.0).powi(2);
+
+    for outlet in 0..width * height {
+        if is_sea(outlet) || downstream(outlet).is_some_and(|d| !is_sea(d)) {
+            continue;
+        }
+
+        // Flood upstream from the outlet through cells that flow into the current one
+        let id = basins.len() as u32;
+        let mut cell_count = 0;
+        let mut queue = VecDeque::from([outlet]);
+        labels[outlet] = id;
+
+        while let Some(idx) = queue.pop_front() {
+            cell_count += 1;
+            for (nx, nz) in get_neighbors_8(idx % width, idx / width, width, height) {
+                let nidx = nz * width + nx;
+                if labels[nidx] == NO_BASIN && !is_sea(nidx) && downstream(nidx) == Some(idx) {
+                    labels[nidx] = id;
+                    queue.push_back(nidx);
+                }
+            }
+        }
+
+        basins.push(Basin {
+            id,
+            outlet_x: outlet % width,
+            outlet_z: outlet / width,
+            cell_count,
+            area_km2: cell_count as f32 * cell_area_km2,
+        });
+    }
+
+    Watersheds { labels, basins }
+}
+
+/// Find the lakes left by `fill_depressions`: 8-connected regions where the filled
+/// surface sits above the original terrain. Pits whose surface is at or below
+/// `sea_level` are part of the sea, not lakes
+#[tracing::instrument(skip_all)]
+pub fn extract_lakes(heights: &[f32], filled_heights: &[f32], width: usize, height: usize, sea_level: f32) -> Vec<Lake> {
+    let is_water = |idx: usize| filled_heights[idx] > heights[idx] && filled_heights[idx] > sea_level;
+    let mut visited = vec![false; width * height];
+    let mut lakes = Vec::new();
+
+    for start in 0..width * height {
+        if visited[start] || !is_water(start) {
+            continue;
+        }
+
+        let mut lake = Lake {
+            id: lakes.len() as u32,
+            surface_elevation: 0.0,
+            max_depth: 0.0,
+            cell_count: 0,
+            min_x: start % width,
+            min_z: start / width,
+            max_x: start % width,
+            max_z: start / width,
+        };
+        let mut queue = VecDeque::from([start]);
+        visited[start] = true;
+
+        while let Some(idx) = queue.pop_front() {
+            let (x, z) = (idx % width, idx / width);
+            lake.cell_count += 1;
+            // Filling leaves a tiny drainage gradient, so the highest fill is the surface
+            lake.surface_elevation = lake.surface_elevation.max(filled_heights[idx]);
+            lake.max_depth = lake.max_depth.max(filled_heights[idx] - heights[idx]);
+            lake.min_x = lake.min_x.min(x);
+            lake.min_z = lake.min_z.min(z);
+            lake.max_x = lake.max_x.max(x);
+            lake.max_z = lake.max_z.max(z);
+
+            for (nx, nz) in get_neighbors_8(x, z, width, height) {
+                let nidx = nz * width + nx;
+                if !visited[nidx] && is_water(nidx) {
+                    visited[nidx] = true;
+                    queue.push_back(nidx);
+                }
+            }
+        }
+
+        lakes.push(lake);
+    }
+
+    lakes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thermal_erosion_flattens_steep_slopes() {
+        // A single spike on flat ground
+        let size = 16;
+        let mut heights = vec![0.2; size * size];
+        heights[8 * size + 8] = 0.9;
+
+        let max_slope = |h: &[f32]| {
+            (0..size * size)
+                .flat_map(|idx| {
+                    get_neighbors_8(idx % size, idx / size, size, size)
+                        .into_iter()
+                        .map(move |(nx, nz)| (h[idx] - h[nz * size + nx]).abs())
+                })
+                .fold(0.0f32, f32::max)
+        };
+
+        let before = max_slope(&heights);
+        apply_thermal_erosion(&mut heights, size, size, 0.05, 100.0, 4000.0, 50, None);
+        assert!(max_slope(&heights) < before * 0.5, "{} -> {}", before, max_slope(&heights));
+    }
+
+    #[test]
+    fn test_thermal_erosion_measures_talus_in_meters() {
+        // 0.02 per 100 m cell of a 4000 m range: an 80 m rise per cell, about 39°
+        let size = 16;
+        let ramp: Vec<f32> = (0..size * size).map(|i| 0.2 + (i % size) as f32 * 0.02).collect();
+
+        let mut gentle = ramp.clone();
+        apply_thermal_erosion(&mut gentle, size, size, 45f32.to_radians(), 100.0, 4000.0, 10, None);
+        assert_eq!(gentle, ramp);
+
+        let mut steep = ramp.clone();
+        apply_thermal_erosion(&mut steep, size, size, 30f32.to_radians(), 100.0, 4000.0, 10, None);
+        assert_ne!(steep, ramp);
+    }
+
+    #[test]
+    fn test_dinf_spreads_flow_on_tilted_plane() {
+        // Plane falling toward +x and, more gently, +z: D8 picks one direction everywhere
+        let size = 32;
+        let heights: Vec<f32> = (0..size * size)
+            .map(|i| 1.0 - (i % size) as f32 * 0.02 - (i / size) as f32 * 0.01)
+            .collect();
+
+        let angles = calculate_flow_direction_dinf(&heights, size, size);
+        let expected = 0.5f32.atan(); // atan(dz slope / dx slope)
+        assert!((angles[10 * size + 10] - expected).abs() < 1e-3, "angle {}", angles[10 * size + 10]);
+
+        let d8 = calculate_flow_accumulation(&heights, &calculate_flow_direction(&heights, size, size), size, size);
+        let dinf = calculate_flow_accumulation_dinf(&heights, &angles, size, size);
+
+        // On a plane, a cell's flow should be proportional to the length of the straight
+        // flow line upslope of it, so the ratio between them should barely vary
+        let spread = |acc: &[f32]| {
+            // Edge cells collect everything running along the map border, so skip them
+            let ratios: Vec<f32> = (4..size - 1)
+                .flat_map(|z| (4..size - 1).map(move |x| (x, z)))
+                .map(|(x, z)| {
+                    let upslope = (x as f32 / expected.cos()).min(z as f32 / expected.sin());
+                    acc[z * size + x] / upslope
+                })
+                .collect();
+            let mean = ratios.iter().sum::<f32>() / ratios.len() as f32;
+            let variance = ratios.iter().map(|r| (r - mean).powi(2)).sum::<f32>() / ratios.len() as f32;
+            variance.sqrt() / mean
+        };
+        assert!(spread(&dinf) < spread(&d8) * 0.5, "dinf {} vs d8 {}", spread(&dinf), spread(&d8));
+
+        // Every cell keeps at least its own rain
+        assert!(dinf.iter().all(|&a| a >= 1.0));
+    }
+
+    #[test]
+    fn test_carved_bowl_becomes_one_lake() {
+        // A 4x3 pit 0.3 deep in a gentle slope
+        let size = 20;
+        let mut heights: Vec<f32> = (0..size * size).map(|i| 0.5 + (i % size) as f32 * 0.01).collect();
+        for z in 8..11 {
+            for x in 6..10 {
+                heights[z * size + x] -= 0.3;
+            }
+        }
+
+        let mut filled = heights.clone();
+        fill_depressions(&mut filled, size, size, None);
+        let lakes = extract_lakes(&heights, &filled, size, size, 0.0);
+
+        assert_eq!(lakes.len(), 1);
+        let lake = &lakes[0];
+        assert_eq!(lake.cell_count, 12);
+        assert_eq!((lake.min_x, lake.min_z, lake.max_x, lake.max_z), (6, 8, 9, 10));
+        // Water rises to the lowest rim cell, just west of the pit
+        assert!((lake.surface_elevation - 0.55).abs() < 0.01, "surface {}", lake.surface_elevation);
+        assert!(lake.max_depth > 0.25);
+    }
+
+    #[test]
+    fn test_locked_cells_survive_filling_and_slumping() {
+        // A pit and a spike, each locked, on a gentle slope
+        let size = 16;
+        let mut heights: Vec<f32> = (0..size * size).map(|i| 0.5 + (i % size) as f32 * 0.01).collect();
+        let pit = 6 * size + 6;
+        let spike = 10 * size + 10;
+        heights[pit] = 0.1;
+        heights[spike] = 0.95;
+        let mut locked = vec![false; size * size];
+        locked[pit] = true;
+        locked[spike] = true;
+
+        let mut filled = heights.clone();
+        fill_depressions(&mut filled, size, size, Some(&locked));
+        assert_eq!(filled[pit], heights[pit]);
+
+        let mut slumped = heights.clone();
+        apply_thermal_erosion(&mut slumped, size, size, 0.05, 100.0, 4000.0, 20, Some(&locked));
+        assert_eq!(slumped[pit], heights[pit]);
+        assert_eq!(slumped[spike], heights[spike]);
+    }
+
+    #[test]
+    fn test_sea_floor_pits_are_not_lakes() {
+        // Sea floor at 0.1 under a 0.4 sea, with one pit; a highland pit in the east
+        let size = 20;
+        let mut heights: Vec<f32> = (0..size * size)
+            .map(|i| if i % size < 10 { 0.1 } else { 0.6 })
+            .collect();
+        heights[10 * size + 4] = 0.05;
+        heights[10 * size + 15] = 0.5;
+
+        let mut filled = heights.clone();
+        fill_depressions(&mut filled, size, size, None);
+        let lakes = extract_lakes(&heights, &filled, size, size, 0.4);
+
+        assert_eq!(lakes.len(), 1);
+        assert_eq!((lakes[0].min_x, lakes[0].min_z), (15, 10));
+    }
+
+    #[test]
+    fn test_ridge_splits_two_basins() {
+        // A north-south ridge down the middle, with both slopes tilted toward the center row
+        let width = 32;
+        let height = 17;
+        let mut heights = vec![0.0; width * height];
+        for z in 0..height {
+            for x in 0..width {
+                let ridge = (x as f32 - 15.5).abs() * 0.02;
+                let tilt = (z as f32 - 8.0).abs() * 0.01;
+                heights[z * width + x] = 0.8 - ridge + tilt;
+            }
+        }
+
+        let flow_direction = calculate_flow_direction(&heights, width, height);
+        let watersheds = compute_watersheds(&heights, &flow_direction, width, height, 0.0, 1000.0);
+
+        assert_eq!(watersheds.basins.len(), 2);
+        let west = watersheds.labels[8 * width];
+        let east = watersheds.labels[8 * width + width - 1];
+        assert_ne!(west, east);
+        for z in 0..height {
+            assert_eq!(watersheds.labels[z * width + 15], west);
+            assert_eq!(watersheds.labels[z * width + 16], east);
+        }
+
+        let basin = &watersheds.basins[west as usize];
+        assert_eq!((basin.outlet_x, basin.outlet_z), (0, 8));
+        assert_eq!(basin.cell_count, width / 2 * height);
+        assert_eq!(basin.area_km2, (width / 2 * height) as f32);
+    }
+}
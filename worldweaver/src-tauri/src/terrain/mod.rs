@@ -11,13 +11,23 @@ pub mod rivers;
 pub mod biomes;
 pub mod roads;
 pub mod persistence;
+pub mod export;
 pub mod brush;
+pub mod resources;
+pub mod seed_search;
 pub mod commands;
 
 use config::TerrainConfig;
 use heightmap::HeightmapChunk;
+use commands::NoiseParameters;
 use rivers::RiverNetwork;
 use biomes::BiomeRegistry;
+use resources::Deposit;
+use roads::Road;
+use brush::{BrushOp, FalloffCurve, HeightWindow, Ramp};
+
+/// Widest averaging kernel a brush reaches (erode's), in vertices
+const BRUSH_KERNEL_REACH: f32 = 2.0;
 
 /// Water source for hydrology simulation
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +38,33 @@ pub struct WaterSource {
     pub active: bool,
 }
 
+/// Elevation distribution of the whole world, for tuning generation
+/// The histogram splits the normalized 0..1 range into equal bins
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerrainAnalysis {
+    pub histogram: Vec<u32>,
+    pub land_fraction: f32,
+    pub mean_elevation: f32,
+    pub max: f32,
+    pub min: f32,
+}
+
+/// World-wide channels the terrain carried before some chunks were regenerated
+#[derive(Debug, Clone, Copy)]
+struct DerivedChannels {
+    biomes: bool,
+    flow: bool,
+}
+
+impl DerivedChannels {
+    fn of(terrain: &TerrainData) -> Self {
+        Self {
+            biomes: terrain.chunks.values().any(|chunk| chunk.biome_ids.is_some()),
+            flow: terrain.chunks.values().any(|chunk| chunk.flow_accumulation.is_some()),
+        }
+    }
+}
+
 /// Main terrain data resource for bevy_ecs
 #[derive(Resource)]
 pub struct TerrainData {
@@ -38,27 +75,34 @@ pub struct TerrainData {
     pub biome_definitions: BiomeRegistry,
     pub undo_stack: UndoStack,
     pub water_sources: Vec<WaterSource>,
+    pub deposits: Vec<Deposit>,
+    pub roads: Vec<Road>,
 }
 
 impl Default for TerrainData {
     fn default() -> Self {
-        Self {
-            config: TerrainConfig::default(),
-            chunks: HashMap::new(),
-            dirty_chunks: HashSet::new(),
-            river_network: RiverNetwork::new(),
-            biome_definitions: BiomeRegistry::new(),
-            undo_stack: UndoStack::new(),
-            water_sources: Vec::new(),
-        }
+        Self::empty(TerrainConfig::default())
     }
 }
 
 impl TerrainData {
     pub fn new(config: TerrainConfig) -> Self {
+        Self::empty(config)
+    }
+
+    /// Terrain with no chunks, rivers, roads, water sources, deposits, or undo history
+    /// Only the static biome definitions are populated, so tests can build precise terrain
+    pub fn empty(config: TerrainConfig) -> Self {
         Self {
             config,
-            ..Default::default()
+            chunks: HashMap::new(),
+            dirty_chunks: HashSet::new(),
+            river_network: RiverNetwork::new(),
+            biome_definitions: BiomeRegistry::new(),
+            undo_stack: UndoStack::new(),
+            water_sources: Vec::new(),
+            deposits: Vec::new(),
+            roads: Vec::new(),
         }
     }
 
@@ -95,19 +139,661 @@ impl TerrainData {
     pub fn clear_dirty(&mut self) {
         self.dirty_chunks.clear();
     }
+
+    /// Apply a brush stroke centred in `chunk`'s local vertex space to every loaded
+    /// chunk the radius overlaps, so strokes near an edge don't leave a seam
+    /// The stroke is recorded as one undo group. Returns the touched chunk
+    /// coordinates, each of which is marked dirty
+    #[allow(clippy::too_many_arguments)]
+    pub fn apply_brush(
+        &mut self,
+        chunk: (i32, i32),
+        center_x: f32,
+        center_z: f32,
+        radius: f32,
+        strength: f32,
+        op: BrushOp,
+        curve: FalloffCurve,
+    ) -> Vec<(i32, i32)> {
+        let chunk_size = self.config.chunk_size as f32;
+        let vertex_count = self.config.vertex_count;
+        let world_x = chunk.0 as f32 * chunk_size + center_x;
+        let world_z = chunk.1 as f32 * chunk_size + center_z;
+
+        // Smooth and erode average a kernel around each vertex; sample it from the
+        // pre-stroke world so both sides of a shared edge see the same neighbours
+        let neighbors = match op {
+            BrushOp::Smooth | BrushOp::Erode { .. } => Some(self.height_window(
+                (world_x - radius - BRUSH_KERNEL_REACH, world_z - radius - BRUSH_KERNEL_REACH),
+                (world_x + radius + BRUSH_KERNEL_REACH, world_z + radius + BRUSH_KERNEL_REACH),
+            )),
+            _ => None,
+        };
+
+        self.undo_stack.begin_group();
+
+        let touched = self.loaded_chunks_overlapping(
+            (world_x - radius, world_z - radius),
+            (world_x + radius, world_z + radius),
+        );
+        for &(chunk_x, chunk_z) in &touched {
+            let target = self.chunks.get_mut(&(chunk_x, chunk_z)).unwrap();
+            let local_x = world_x - chunk_x as f32 * chunk_size;
+            let local_z = world_z - chunk_z as f32 * chunk_size;
+            let before = target.heights.clone();
+            target.apply_brush_with_neighbors(
+                local_x, local_z, radius, strength, op.clone(), curve, vertex_count, neighbors.as_ref(),
+            );
+            self.undo_stack.record(target, &before);
+            self.dirty_chunks.insert((chunk_x, chunk_z));
+        }
+
+        touched
+    }
+
+    /// Grade a ramp between two global vertex positions across every loaded chunk it
+    /// crosses, recorded as one undo group. Returns the touched chunk coordinates
+    pub fn apply_ramp(&mut self, ramp: &Ramp, strength: f32, curve: FalloffCurve) -> Vec<(i32, i32)> {
+        let chunk_size = self.config.chunk_size as f32;
+        let vertex_count = self.config.vertex_count;
+        let half_width = ramp.width / 2.0;
+
+        self.undo_stack.begin_group();
+
+        let touched = self.loaded_chunks_overlapping(
+            (ramp.start.0.min(ramp.end.0) - half_width, ramp.start.1.min(ramp.end.1) - half_width),
+            (ramp.start.0.max(ramp.end.0) + half_width, ramp.start.1.max(ramp.end.1) + half_width),
+        );
+        for &(chunk_x, chunk_z) in &touched {
+            let target = self.chunks.get_mut(&(chunk_x, chunk_z)).unwrap();
+            let local = ramp.translated(-(chunk_x as f32) * chunk_size, -(chunk_z as f32) * chunk_size);
+            let before = target.heights.clone();
+            target.apply_ramp(&local, strength, curve, vertex_count);
+            self.undo_stack.record(target, &before);
+            self.dirty_chunks.insert((chunk_x, chunk_z));
+        }
+
+        touched
+    }
+
+    /// Loaded chunks sharing any vertex with the global vertex-space box `min..=max`
+    fn loaded_chunks_overlapping(&self, min: (f32, f32), max: (f32, f32)) -> Vec<(i32, i32)> {
+        let chunk_size = self.config.chunk_size as f32;
+
+        // Chunk c spans vertices [c * size, (c + 1) * size], sharing its edges with neighbours
+        let min_x = (min.0 / chunk_size - 1.0).ceil() as i32;
+        let max_x = (max.0 / chunk_size).floor() as i32;
+        let min_z = (min.1 / chunk_size - 1.0).ceil() as i32;
+        let max_z = (max.1 / chunk_size).floor() as i32;
+
+        let mut overlapping = Vec::new();
+        for chunk_z in min_z..=max_z {
+            for chunk_x in min_x..=max_x {
+                if self.chunks.contains_key(&(chunk_x, chunk_z)) {
+                    overlapping.push((chunk_x, chunk_z));
+                }
+            }
+        }
+        overlapping
+    }
+
+    /// Snapshot the heights of every global vertex in the box `min..=max`
+    fn height_window(&self, min: (f32, f32), max: (f32, f32)) -> HeightWindow {
+        let origin = (min.0.floor() as i32, min.1.floor() as i32);
+        let width = (max.0.ceil() as i32 - origin.0 + 1).max(0) as usize;
+        let depth = (max.1.ceil() as i32 - origin.1 + 1).max(0) as usize;
+        let heights = (0..depth)
+            .flat_map(|z| (0..width).map(move |x| (x, z)))
+            .map(|(x, z)| self.vertex_height((origin.0 + x as i32) as f32, (origin.1 + z as i32) as f32))
+            .collect();
+        HeightWindow { origin, width, heights }
+    }
+
+    /// Height at a global vertex position (bilinear), or None outside the loaded chunks
+    pub fn vertex_height(&self, x: f32, z: f32) -> Option<f32> {
+        let chunk_size = self.config.chunk_size as f32;
+        let chunk_x = (x / chunk_size).floor() as i32;
+        let chunk_z = (z / chunk_size).floor() as i32;
+        let chunk = self.chunks.get(&(chunk_x, chunk_z))?;
+        Some(chunk.sample_bilinear(
+            x - chunk_x as f32 * chunk_size,
+            z - chunk_z as f32 * chunk_size,
+            self.config.vertex_count,
+        ))
+    }
+
+    /// Undo the most recent stroke, returning the chunks it restored (marked dirty)
+    pub fn undo(&mut self) -> Vec<(i32, i32)> {
+        let mut undo_stack = std::mem::take(&mut self.undo_stack);
+        let restored = undo_stack.undo(self);
+        self.undo_stack = undo_stack;
+        self.dirty_chunks.extend(&restored);
+        restored
+    }
+
+    /// Reapply the most recently undone stroke, returning the chunks it changed (marked dirty)
+    pub fn redo(&mut self) -> Vec<(i32, i32)> {
+        let mut undo_stack = std::mem::take(&mut self.undo_stack);
+        let restored = undo_stack.redo(self);
+        self.undo_stack = undo_stack;
+        self.dirty_chunks.extend(&restored);
+        restored
+    }
+
+    /// Re-roll the chunks in an inclusive rectangle, leaving the rest of the world intact
+    /// The chunks go through the world's generator, style and normalisation with the
+    /// seed shifted by `seed_offset` and, when given, `params` in place of the world's
+    /// noise parameters. They are then blended toward the old heights near the region
+    /// boundary so seams with neighboring chunks stay continuous, and their derived
+    /// channels are refreshed (see refresh_derived_channels). Undo history is dropped
+    /// since it refers to the replaced chunks. Returns the regenerated coordinates.
+    pub fn regenerate_region(
+        &mut self,
+        min_chunk: (i32, i32),
+        max_chunk: (i32, i32),
+        seed_offset: u32,
+        params: Option<&NoiseParameters>,
+    ) -> Vec<(i32, i32)> {
+        let mut config = self.config.clone();
+        config.seed = config.seed.wrapping_add(seed_offset);
+        if let Some(params) = params {
+            config.noise_params = Some(params.clone());
+        }
+
+        let min_x = min_chunk.0.min(max_chunk.0).max(0);
+        let min_z = min_chunk.1.min(max_chunk.1).max(0);
+        let max_x = min_chunk.0.max(max_chunk.0).min(config.chunk_count_x() - 1);
+        let max_z = min_chunk.1.max(max_chunk.1).min(config.chunk_count_z() - 1);
+
+        let chunk_size = config.chunk_size as i32;
+        let vertex_count = config.vertex_count as usize;
+        let blend_width = (chunk_size / 4).max(1) as f32;
+
+        // Region boundary in global vertex coordinates; only sides touching
+        // existing terrain need blending
+        let region_x0 = min_x * chunk_size;
+        let region_z0 = min_z * chunk_size;
+        let region_x1 = (max_x + 1) * chunk_size;
+        let region_z1 = (max_z + 1) * chunk_size;
+        let blend_left = min_x > 0;
+        let blend_top = min_z > 0;
+        let blend_right = max_x < config.chunk_count_x() - 1;
+        let blend_bottom = max_z < config.chunk_count_z() - 1;
+
+        if min_x > max_x || min_z > max_z {
+            return Vec::new();
+        }
+
+        let derived = DerivedChannels::of(self);
+        let mut regenerated = Vec::new();
+        for mut chunk in noise_gen::regenerate_chunks(&config, (min_x, min_z), (max_x, max_z)) {
+            let (chunk_x, chunk_z) = chunk.coord;
+
+            if let Some(old_chunk) = self.chunks.get(&(chunk_x, chunk_z)) {
+                for local_z in 0..vertex_count {
+                    for local_x in 0..vertex_count {
+                        let global_x = chunk_x * chunk_size + local_x as i32;
+                        let global_z = chunk_z * chunk_size + local_z as i32;
+
+                        let mut distance = f32::MAX;
+                        if blend_left { distance = distance.min((global_x - region_x0) as f32); }
+                        if blend_top { distance = distance.min((global_z - region_z0) as f32); }
+                        if blend_right { distance = distance.min((region_x1 - global_x) as f32); }
+                        if blend_bottom { distance = distance.min((region_z1 - global_z) as f32); }
+
+                        let t = (distance / blend_width).min(1.0);
+                        let idx = local_z * vertex_count + local_x;
+                        chunk.heights[idx] = old_chunk.heights[idx] * (1.0 - t) + chunk.heights[idx] * t;
+                    }
+                }
+            }
+
+            self.chunks.insert((chunk_x, chunk_z), chunk);
+            self.dirty_chunks.insert((chunk_x, chunk_z));
+            regenerated.push((chunk_x, chunk_z));
+        }
+
+        self.refresh_derived_channels(&regenerated, derived);
+        self.undo_stack.clear();
+        regenerated
+    }
+
+    /// Rebuild a single chunk through the same generator, style and normalisation as
+    /// the world's generation, so it matches an uneroded full generation; edges may not
+    /// match edited or eroded neighbours. Derived channels are refreshed (see
+    /// refresh_derived_channels). Undo history is dropped since it refers to the replaced chunk
+    pub fn regenerate_chunk(&mut self, chunk_x: i32, chunk_z: i32) -> Result<(), String> {
+        if !(0..self.config.chunk_count_x()).contains(&chunk_x) || !(0..self.config.chunk_count_z()).contains(&chunk_z) {
+            return Err(format!("Chunk ({}, {}) is outside the world", chunk_x, chunk_z));
+        }
+
+        let derived = DerivedChannels::of(self);
+        for chunk in noise_gen::regenerate_chunks(&self.config, (chunk_x, chunk_z), (chunk_x, chunk_z)) {
+            self.chunks.insert(chunk.coord, chunk);
+        }
+        self.dirty_chunks.insert((chunk_x, chunk_z));
+        self.refresh_derived_channels(&[(chunk_x, chunk_z)], derived);
+        self.undo_stack.clear();
+
+        Ok(())
+    }
+
+    /// Give freshly regenerated chunks the derived channels the rest of the world has
+    /// Biomes and flow depend on terrain upwind and upstream, so they are recomputed
+    /// world-wide when the whole world is loaded (and left unset otherwise); caves are
+    /// redrawn at the recorded density. Feature markers were painted on the old terrain
+    /// and are dropped
+    fn refresh_derived_channels(&mut self, regenerated: &[(i32, i32)], derived: DerivedChannels) {
+        if let Some(density) = self.config.cave_density {
+            for coord in regenerated {
+                if let Some(chunk) = self.chunks.get_mut(coord) {
+                    chunk.cave_mask = Some(noise_gen::generate_cave_mask(&self.config, chunk, density));
+                }
+            }
+        }
+
+        if self.validate_chunk_coords().is_err() {
+            return;
+        }
+        if derived.biomes {
+            self.generate_biomes();
+        }
+        if derived.flow {
+            let width = self.config.world_width as usize;
+            let height = self.config.world_height as usize;
+            let heights = self.flatten_heights();
+            let flow_direction = hydrology::calculate_flow_direction(&heights, width, height);
+            let flow = hydrology::accumulate_flow(self.config.flow_routing, &heights, &flow_direction, width, height);
+            self.store_flow_accumulation(&flow);
+        }
+    }
+
+    /// Global vertex position of a chunk's first vertex
+    /// The world grid starts at chunk (0, 0), so negative coordinates have no position
+    pub fn chunk_origin(&self, coord: (i32, i32)) -> Option<(usize, usize)> {
+        let chunk_size = self.config.chunk_size as usize;
+        let chunk_x = usize::try_from(coord.0).ok()?;
+        let chunk_z = usize::try_from(coord.1).ok()?;
+        Some((chunk_x * chunk_size, chunk_z * chunk_size))
+    }
+
+    /// Reject terrain holding chunks outside the world grid, or missing some inside it
+    /// Whole-world operations (hydrology, weathering, flow, export) need every chunk, so
+    /// they refuse to run after a region load rather than treat the gaps as sea floor
+    pub fn validate_chunk_coords(&self) -> Result<(), String> {
+        if let Some((chunk_x, chunk_z)) = self.chunks.keys().find(|coord| self.chunk_origin(**coord).is_none()) {
+            return Err(format!(
+                "Chunk ({}, {}) is outside the world grid; only non-negative chunk coordinates are supported",
+                chunk_x, chunk_z
+            ));
+        }
+
+        if self.chunks.is_empty() {
+            return Ok(());
+        }
+        let missing = (0..self.config.chunk_count_z())
+            .flat_map(|chunk_z| (0..self.config.chunk_count_x()).map(move |chunk_x| (chunk_x, chunk_z)))
+            .find(|coord| !self.chunks.contains_key(coord));
+        match missing {
+            Some((chunk_x, chunk_z)) => Err(format!(
+                "Chunk ({}, {}) is not loaded; load the whole world before running whole-world operations",
+                chunk_x, chunk_z
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Flatten all chunks into a single world-sized heightmap (row-major)
+    /// Chunks outside the world grid are skipped
+    pub fn flatten_heights(&self) -> Vec<f32> {
+        let total_width = self.config.world_width as usize;
+        let total_height = self.config.world_height as usize;
+        let vertex_count = self.config.vertex_count as usize;
+        let mut heights = vec![0.0; total_width * total_height];
+
+        for (coord, chunk) in &self.chunks {
+            let Some((chunk_offset_x, chunk_offset_z)) = self.chunk_origin(*coord) else { continue };
+
+            for local_z in 0..vertex_count {
+                for local_x in 0..vertex_count {
+                    let global_x = chunk_offset_x + local_x;
+                    let global_z = chunk_offset_z + local_z;
+
+                    if global_x < total_width && global_z < total_height {
+                        heights[global_z * total_width + global_x] = chunk.heights[local_z * vertex_count + local_x];
+                    }
+                }
+            }
+        }
+
+        heights
+    }
+
+    /// Elevation histogram and land/ocean ratio against `config.sea_level`
+    /// Shared chunk edges are counted once
+    pub fn analyze(&self, bins: usize) -> TerrainAnalysis {
+        let heights = self.flatten_heights();
+        let mut histogram = vec![0u32; bins];
+        let (mut min, mut max, mut sum, mut land) = (f32::MAX, f32::MIN, 0.0f64, 0usize);
+
+        for &height in &heights {
+            let bin = ((height.clamp(0.0, 1.0) * bins as f32) as usize).min(bins - 1);
+            histogram[bin] += 1;
+            min = min.min(height);
+            max = max.max(height);
+            sum += height as f64;
+            if height > self.config.sea_level {
+                land += 1;
+            }
+        }
+
+        let count = heights.len().max(1);
+        TerrainAnalysis {
+            histogram,
+            land_fraction: land as f32 / count as f32,
+            mean_elevation: (sum / count as f64) as f32,
+            max,
+            min,
+        }
+    }
+
+    /// Copy a world-sized heightmap back into every chunk, marking them dirty
+    /// The write is recorded as one undo group, so whole-world passes can be undone like a stroke
+    /// On wrapped worlds opposite edges are averaged first: erosion and hydrology don't
+    /// wrap around, and this keeps their results tileable
+    pub fn store_heights(&mut self, heights: &[f32]) {
+        let total_width = self.config.world_width as usize;
+        let total_height = self.config.world_height as usize;
+        let vertex_count = self.config.vertex_count as usize;
+
+        let stitched;
+        let heights = if self.config.wrap {
+            stitched = stitch_wrapped_edges(heights, total_width, total_height);
+            &stitched
+        } else {
+            heights
+        };
+
+        let origins: Vec<_> = self.chunks.keys()
+            .filter_map(|coord| self.chunk_origin(*coord).map(|origin| (*coord, origin)))
+            .collect();
+
+        self.undo_stack.begin_group();
+        for (coord, (chunk_offset_x, chunk_offset_z)) in origins {
+            let chunk = self.chunks.get_mut(&coord).unwrap();
+            let before = chunk.heights.clone();
+            for local_z in 0..vertex_count {
+                for local_x in 0..vertex_count {
+                    let global_x = chunk_offset_x + local_x;
+                    let global_z = chunk_offset_z + local_z;
+
+                    if global_x < total_width && global_z < total_height {
+                        chunk.heights[local_z * vertex_count + local_x] = heights[global_z * total_width + global_x];
+                    }
+                }
+            }
+            chunk.invalidate_bounds();
+            self.undo_stack.record(chunk, &before);
+            self.dirty_chunks.insert(coord);
+        }
+    }
+
+    /// Split a world-sized flow accumulation map (row-major) onto the chunks
+    /// Vertices past the world edge get no flow
+    pub fn store_flow_accumulation(&mut self, flow: &[f32]) {
+        let total_width = self.config.world_width as usize;
+        let total_height = self.config.world_height as usize;
+        let vertex_count = self.config.vertex_count as usize;
+
+        let origins: Vec<_> = self.chunks.keys()
+            .filter_map(|coord| self.chunk_origin(*coord).map(|origin| (*coord, origin)))
+            .collect();
+
+        for (coord, (chunk_offset_x, chunk_offset_z)) in origins {
+            let mut chunk_flow = vec![0.0; vertex_count * vertex_count];
+            for local_z in 0..vertex_count {
+                for local_x in 0..vertex_count {
+                    let global_x = chunk_offset_x + local_x;
+                    let global_z = chunk_offset_z + local_z;
+
+                    if global_x < total_width && global_z < total_height {
+                        chunk_flow[local_z * vertex_count + local_x] = flow[global_z * total_width + global_x];
+                    }
+                }
+            }
+            self.chunks.get_mut(&coord).unwrap().flow_accumulation = Some(chunk_flow);
+            self.dirty_chunks.insert(coord);
+        }
+    }
+
+    /// Replace all chunks with an external heightmap, resampled to the world size
+    /// Undo history, rivers, roads and deposits are dropped since they were derived from
+    /// the old chunks; the new chunks start without biome, flow or feature data
+    pub fn import_heights(&mut self, heights: &[f32], width: usize, height: usize) {
+        let total_width = self.config.world_width as usize;
+        let total_height = self.config.world_height as usize;
+        let world = export::resample_bilinear(heights, width, height, total_width, total_height);
+
+        let chunk_size = self.config.chunk_size as usize;
+        let vertex_count = self.config.vertex_count as usize;
+        self.chunks.clear();
+        for chunk_z in 0..self.config.chunk_count_z() {
+            for chunk_x in 0..self.config.chunk_count_x() {
+                let mut chunk_heights = Vec::with_capacity(vertex_count * vertex_count);
+                for local_z in 0..vertex_count {
+                    // Vertices past the world edge repeat the edge value
+                    let global_z = (chunk_z as usize * chunk_size + local_z).min(total_height - 1);
+                    for local_x in 0..vertex_count {
+                        let global_x = (chunk_x as usize * chunk_size + local_x).min(total_width - 1);
+                        chunk_heights.push(world[global_z * total_width + global_x].clamp(0.0, 1.0));
+                    }
+                }
+                self.chunks.insert((chunk_x, chunk_z), HeightmapChunk::from_heights((chunk_x, chunk_z), chunk_heights));
+                self.dirty_chunks.insert((chunk_x, chunk_z));
+            }
+        }
+
+        self.undo_stack.clear();
+        self.river_network = RiverNetwork::new();
+        self.roads.clear();
+        self.deposits.clear();
+    }
+
+    /// Flow accumulation normalized to 0-255 for rendering rivers/lakes (one byte per cell)
+    /// All zeros when nothing flows, e.g. on a flat painting canvas
+    pub fn flow_data(&self) -> Result<Vec<u8>, String> {
+        let width = self.config.world_width as usize;
+        let height = self.config.world_height as usize;
+        self.validate_chunk_coords()?;
+        let heights = self.flatten_heights();
+
+        let flow_direction = hydrology::calculate_flow_direction(&heights, width, height);
+        let flow_accumulation = hydrology::accumulate_flow(self.config.flow_routing, &heights, &flow_direction, width, height);
+
+        // Every cell starts with 1.0 of its own rainfall, so only the excess is actual flow
+        let max_flow = flow_accumulation.iter().cloned().fold(0.0f32, f32::max) - 1.0;
+        if max_flow < 1e-3 {
+            return Ok(vec![0; flow_accumulation.len()]);
+        }
+
+        Ok(flow_accumulation.iter()
+            .map(|&f| (((f - 1.0) / max_flow) * 255.0).clamp(0.0, 255.0) as u8)
+            .collect())
+    }
+
+    /// Split the current terrain into drainage basins using D8 flow directions
+    pub fn compute_watersheds(&self) -> hydrology::Watersheds {
+        let width = self.config.world_width as usize;
+        let height = self.config.world_height as usize;
+        let heights = self.flatten_heights();
+        let flow_direction = hydrology::calculate_flow_direction(&heights, width, height);
+        hydrology::compute_watersheds(
+            &heights, &flow_direction, width, height,
+            self.config.sea_level, self.config.cell_size_meters,
+        )
+    }
+
+    /// Classify every vertex into a biome and store the ids on the chunks, marking them dirty
+    pub fn generate_biomes(&mut self) {
+        let total_width = self.config.world_width as usize;
+        let total_height = self.config.world_height as usize;
+        let vertex_count = self.config.vertex_count as usize;
+        let heights = self.flatten_heights();
+        let biome_map = biomes::generate_biome_map(&heights, total_width, total_height, &self.config, &self.biome_definitions);
+
+        let origins: Vec<_> = self.chunks.keys()
+            .filter_map(|coord| self.chunk_origin(*coord).map(|origin| (*coord, origin)))
+            .collect();
+
+        for (coord, (chunk_offset_x, chunk_offset_z)) in origins {
+            // Vertices past the world edge have no height data and stay ocean
+            let mut biome_ids = vec![biomes::Biome::Ocean as u8; vertex_count * vertex_count];
+            for local_z in 0..vertex_count {
+                for local_x in 0..vertex_count {
+                    let global_x = chunk_offset_x + local_x;
+                    let global_z = chunk_offset_z + local_z;
+
+                    if global_x < total_width && global_z < total_height {
+                        biome_ids[local_z * vertex_count + local_x] = biome_map[global_z * total_width + global_x];
+                    }
+                }
+            }
+            self.chunks.get_mut(&coord).unwrap().biome_ids = Some(biome_ids);
+            self.dirty_chunks.insert(coord);
+        }
+    }
+
+    /// Fill every chunk's cave mask from 3D noise, marking them dirty; heights are untouched
+    /// The density is kept on the config so regenerated chunks get matching caves
+    pub fn generate_caves(&mut self, density: f32) {
+        self.config.cave_density = Some(density);
+        for (coord, chunk) in self.chunks.iter_mut() {
+            chunk.cave_mask = Some(noise_gen::generate_cave_mask(&self.config, chunk, density));
+            self.dirty_chunks.insert(*coord);
+        }
+    }
+
+    /// Combine every chunk's biome ids into a single world-sized map
+    /// Returns None until biomes have been generated
+    pub fn flatten_biome_ids(&self) -> Option<Vec<u8>> {
+        if self.chunks.values().any(|chunk| chunk.biome_ids.is_none()) {
+            return None;
+        }
+
+        let total_width = self.config.world_width as usize;
+        let total_height = self.config.world_height as usize;
+        let vertex_count = self.config.vertex_count as usize;
+        let mut biome_map = vec![biomes::Biome::Ocean as u8; total_width * total_height];
+
+        for (coord, chunk) in &self.chunks {
+            let Some(biome_ids) = &chunk.biome_ids else { continue };
+            let Some((chunk_offset_x, chunk_offset_z)) = self.chunk_origin(*coord) else { continue };
+
+            for local_z in 0..vertex_count {
+                for local_x in 0..vertex_count {
+                    let global_x = chunk_offset_x + local_x;
+                    let global_z = chunk_offset_z + local_z;
+
+                    if global_x < total_width && global_z < total_height {
+                        biome_map[global_z * total_width + global_x] = biome_ids[local_z * vertex_count + local_x];
+                    }
+                }
+            }
+        }
+
+        Some(biome_map)
+    }
+
+    /// World-sized mask (row-major) of the cells river paths run through
+    pub fn river_mask(&self) -> Vec<bool> {
+        let width = self.config.world_width as usize;
+        let height = self.config.world_height as usize;
+        let mut mask = vec![false; width * height];
+
+        for (x, z) in self.river_network.segments.iter().flat_map(|segment| &segment.path) {
+            let (x, z) = (x.round(), z.round());
+            if x >= 0.0 && z >= 0.0 && (x as usize) < width && (z as usize) < height {
+                mask[z as usize * width + x as usize] = true;
+            }
+        }
+
+        mask
+    }
+
+    /// Find the lakes that depression filling would create on the current terrain
+    pub fn extract_lakes(&self) -> Vec<hydrology::Lake> {
+        let width = self.config.world_width as usize;
+        let height = self.config.world_height as usize;
+        let heights = self.flatten_heights();
+        let mut filled = heights.clone();
+        hydrology::fill_depressions(&mut filled, width, height, self.flatten_locked_mask().as_deref());
+        hydrology::extract_lakes(&heights, &filled, width, height, self.config.sea_level)
+    }
+
+    /// Combine every chunk's erosion lock into a single world-sized mask
+    /// Returns None when nothing is locked so erosion can skip the per-cell check
+    pub fn flatten_locked_mask(&self) -> Option<Vec<bool>> {
+        if self.chunks.values().all(|chunk| chunk.locked.is_none()) {
+            return None;
+        }
+
+        let total_width = self.config.world_width as usize;
+        let total_height = self.config.world_height as usize;
+        let vertex_count = self.config.vertex_count as usize;
+        let mut mask = vec![false; total_width * total_height];
+
+        for (coord, chunk) in &self.chunks {
+            let Some(locked) = &chunk.locked else { continue };
+            let Some((chunk_offset_x, chunk_offset_z)) = self.chunk_origin(*coord) else { continue };
+
+            for local_z in 0..vertex_count {
+                for local_x in 0..vertex_count {
+                    let global_x = chunk_offset_x + local_x;
+                    let global_z = chunk_offset_z + local_z;
+
+                    // Shared edge vertices are locked if either chunk locks them
+                    if global_x < total_width && global_z < total_height && locked[local_z * vertex_count + local_x] {
+                        mask[global_z * total_width + global_x] = true;
+                    }
+                }
+            }
+        }
+
+        Some(mask)
+    }
+}
+
+/// Average each edge vertex with its wrapped counterpart so the west column matches the
+/// east and the north row matches the south
+fn stitch_wrapped_edges(heights: &[f32], width: usize, height: usize) -> Vec<f32> {
+    let mut stitched = heights.to_vec();
+    for z in 0..height {
+        let (west, east) = (z * width, z * width + width - 1);
+        let average = (stitched[west] + stitched[east]) * 0.5;
+        stitched[west] = average;
+        stitched[east] = average;
+    }
+    for x in 0..width {
+        let (north, south) = (x, (height - 1) * width + x);
+        let average = (stitched[north] + stitched[south]) * 0.5;
+        stitched[north] = average;
+        stitched[south] = average;
+    }
+    stitched
 }
 
 /// Undo/redo system using XOR deltas
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UndoEntry {
     pub chunk_coord: (i32, i32),
-    pub delta: Vec<u8>,                    // zstd-compressed XOR
-    pub affected_rect: (u16, u16, u16, u16),
+    pub delta: Vec<u8>,                    // zstd-compressed XOR of the cells in affected_rect
+    pub affected_rect: (u16, u16, u16, u16), // inclusive (min_x, min_z, max_x, max_z) of changed vertices
     pub group_id: u64,                     // groups multi-chunk strokes
 }
 
 pub struct UndoStack {
     entries: VecDeque<UndoEntry>,
+    redo_entries: Vec<UndoEntry>,
     current_group: u64,
     max_entries: usize,
 }
@@ -116,6 +802,7 @@ impl UndoStack {
     pub fn new() -> Self {
         Self {
             entries: VecDeque::new(),
+            redo_entries: Vec::new(),
             current_group: 0,
             max_entries: 1000,
         }
@@ -127,27 +814,37 @@ impl UndoStack {
     }
 
     /// Record an undo entry
+    /// Strokes that left the heights untouched (e.g. lock painting) record nothing
     pub fn record(&mut self, chunk: &HeightmapChunk, before: &[f32]) {
         if chunk.heights.len() != before.len() {
             return;
         }
+        let vertex_count = chunk.vertex_count() as usize;
+        let Some(rect) = changed_rect(&chunk.heights, before, vertex_count) else {
+            return;
+        };
+        let (min_x, min_z, max_x, max_z) = rect;
 
-        // Create XOR delta
-        let xor_delta: Vec<u8> = chunk.heights.iter()
-            .zip(before.iter())
-            .flat_map(|(a, b)| (a.to_bits() ^ b.to_bits()).to_le_bytes())
-            .collect();
+        // Create XOR delta over the changed rect only
+        let mut xor_delta = Vec::with_capacity((max_x - min_x + 1) as usize * (max_z - min_z + 1) as usize * 4);
+        for z in min_z as usize..=max_z as usize {
+            let cells = z * vertex_count + min_x as usize..=z * vertex_count + max_x as usize;
+            for (after, before) in chunk.heights[cells.clone()].iter().zip(&before[cells]) {
+                xor_delta.extend_from_slice(&(after.to_bits() ^ before.to_bits()).to_le_bytes());
+            }
+        }
 
         // Compress delta
         if let Ok(compressed) = zstd::encode_all(&xor_delta[..], 3) {
             let entry = UndoEntry {
                 chunk_coord: chunk.coord,
                 delta: compressed,
-                affected_rect: (0, 0, 0, 0), // TODO: Calculate actual rect
+                affected_rect: rect,
                 group_id: self.current_group,
             };
 
             self.entries.push_back(entry);
+            self.redo_entries.clear();
 
             // Limit size
             while self.entries.len() > self.max_entries {
@@ -156,32 +853,61 @@ impl UndoStack {
         }
     }
 
-    /// Undo last operation
-    pub fn undo(&mut self, terrain: &mut TerrainData) -> bool {
-        if let Some(entry) = self.entries.pop_back() {
+    /// Undo the last group of entries, returning the restored chunk coordinates
+    pub fn undo(&mut self, terrain: &mut TerrainData) -> Vec<(i32, i32)> {
+        let mut restored = Vec::new();
+        let Some(group_id) = self.entries.back().map(|entry| entry.group_id) else {
+            return restored;
+        };
+
+        while self.entries.back().is_some_and(|entry| entry.group_id == group_id) {
+            let entry = self.entries.pop_back().unwrap();
             self.apply_delta(terrain, &entry);
-            true
-        } else {
-            false
+            restored.push(entry.chunk_coord);
+            self.redo_entries.push(entry);
         }
+
+        restored
+    }
+
+    /// Reapply the last undone group (XOR deltas are their own inverse)
+    pub fn redo(&mut self, terrain: &mut TerrainData) -> Vec<(i32, i32)> {
+        let mut restored = Vec::new();
+        let Some(group_id) = self.redo_entries.last().map(|entry| entry.group_id) else {
+            return restored;
+        };
+
+        while self.redo_entries.last().is_some_and(|entry| entry.group_id == group_id) {
+            let entry = self.redo_entries.pop().unwrap();
+            self.apply_delta(terrain, &entry);
+            restored.push(entry.chunk_coord);
+            self.entries.push_back(entry);
+        }
+
+        restored
     }
 
     /// Apply XOR delta to terrain
     fn apply_delta(&self, terrain: &mut TerrainData, entry: &UndoEntry) {
         if let Some(chunk) = terrain.get_chunk_mut(entry.chunk_coord.0, entry.chunk_coord.1) {
             if let Ok(xor_delta) = zstd::decode_all(&entry.delta[..]) {
-                let xor_values: Vec<u32> = xor_delta
+                let mut xor_values = xor_delta
                     .chunks_exact(4)
-                    .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]))
-                    .collect();
-
-                for (i, xor_val) in xor_values.iter().enumerate() {
-                    if i < chunk.heights.len() {
-                        let current_bits = chunk.heights[i].to_bits();
-                        let new_bits = current_bits ^ xor_val;
-                        chunk.heights[i] = f32::from_bits(new_bits);
+                    .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]));
+
+                let vertex_count = chunk.vertex_count() as usize;
+                let (min_x, min_z, max_x, max_z) = entry.affected_rect;
+                for z in min_z as usize..=max_z as usize {
+                    let row = z * vertex_count;
+                    for idx in row + min_x as usize..=row + max_x as usize {
+                        let Some(xor_val) = xor_values.next() else { break };
+                        if idx < chunk.heights.len() {
+                            let current_bits = chunk.heights[idx].to_bits();
+                            chunk.heights[idx] = f32::from_bits(current_bits ^ xor_val);
+                        }
                     }
                 }
+                chunk.invalidate_bounds();
 
                 terrain.mark_dirty(entry.chunk_coord.0, entry.chunk_coord.1);
             }
@@ -192,6 +918,44 @@ impl UndoStack {
     pub fn can_undo(&self) -> bool {
         !self.entries.is_empty()
     }
+
+    /// Check if redo is available
+    pub fn can_redo(&self) -> bool {
+        !self.redo_entries.is_empty()
+    }
+
+    /// Number of undoable entries (one per chunk per stroke)
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Drop all undo and redo history
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.redo_entries.clear();
+    }
+}
+
+/// Inclusive bounding rect (min_x, min_z, max_x, max_z) of vertices whose bits differ,
+/// or None when nothing changed
+fn changed_rect(after: &[f32], before: &[f32], vertex_count: usize) -> Option<(u16, u16, u16, u16)> {
+    let mut rect: Option<(u16, u16, u16, u16)> = None;
+    for (idx, (a, b)) in after.iter().zip(before).enumerate() {
+        if a.to_bits() == b.to_bits() {
+            continue;
+        }
+        let x = (idx % vertex_count) as u16;
+        let z = (idx / vertex_count) as u16;
+        rect = Some(match rect {
+            Some((min_x, min_z, max_x, max_z)) => (min_x.min(x), min_z.min(z), max_x.max(x), max_z.max(z)),
+            None => (x, z, x, z),
+        });
+    }
+    rect
 }
 
 impl Default for UndoStack {
@@ -216,3 +980,398 @@ pub fn sync_terrain_rooms(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use config::WorldTheme;
+
+    fn generated_terrain() -> TerrainData {
+        let config = TerrainConfig::new(384, 384, 11, WorldTheme::Fantasy);
+        let mut terrain = TerrainData::empty(config);
+        for chunk in noise_gen::generate_terrain_with_params(&terrain.config, &NoiseParameters::default()) {
+            terrain.chunks.insert(chunk.coord, chunk);
+        }
+        terrain
+    }
+
+    #[test]
+    fn test_biomes_mark_sea_as_ocean() {
+        let mut terrain = generated_terrain();
+        terrain.generate_biomes();
+
+        let sea_level = terrain.config.sea_level;
+        let mut ocean_cells = 0;
+        for chunk in terrain.chunks.values() {
+            let biome_ids = chunk.biome_ids.as_ref().expect("every chunk gets biomes");
+            assert_eq!(biome_ids.len(), chunk.heights.len());
+            for (height, &id) in chunk.heights.iter().zip(biome_ids) {
+                if *height < sea_level {
+                    assert_eq!(id, biomes::Biome::Ocean as u8);
+                    ocean_cells += 1;
+                }
+            }
+        }
+        assert!(ocean_cells > 0);
+        assert_eq!(terrain.dirty_chunks.len(), terrain.chunks.len());
+    }
+
+    #[test]
+    fn test_brush_on_chunk_edge_modifies_both_chunks() {
+        let config = TerrainConfig::new(256, 128, 1, WorldTheme::Fantasy);
+        let vertex_count = config.vertex_count;
+        let mut terrain = TerrainData::empty(config);
+        for chunk_x in 0..2 {
+            terrain.chunks.insert((chunk_x, 0), HeightmapChunk::new((chunk_x, 0), vertex_count));
+        }
+
+        // Stroke on the right edge of chunk (0, 0)
+        let touched = terrain.apply_brush((0, 0), 127.0, 64.0, 8.0, 10.0, BrushOp::Raise, FalloffCurve::Gaussian);
+
+        assert_eq!(touched.len(), 2);
+        let row = 64 * vertex_count as usize;
+        let left = &terrain.chunks[&(0, 0)];
+        let right = &terrain.chunks[&(1, 0)];
+        assert!(left.heights[row + 127] > 0.2);
+        assert!(right.heights[row + 3] > 0.2);
+        // The shared edge vertex gets the same value on both sides
+        assert_eq!(left.heights[row + 128], right.heights[row]);
+        assert!(terrain.dirty_chunks.contains(&(0, 0)) && terrain.dirty_chunks.contains(&(1, 0)));
+    }
+
+    #[test]
+    fn test_smooth_and_erode_strokes_keep_seams_shared() {
+        for op in [BrushOp::Smooth, BrushOp::Erode { droplet_count: 50 }] {
+            let mut terrain = generated_terrain();
+            let vertex_count = terrain.config.vertex_count as usize;
+            let last = vertex_count - 1;
+            let before = terrain.chunks[&(1, 1)].heights.clone();
+
+            // Centred on the corner shared by four chunks
+            let touched = terrain.apply_brush((1, 1), 0.0, 0.0, 10.0, 1.0, op.clone(), FalloffCurve::Constant);
+            assert_eq!(touched.len(), 4, "{:?}", op);
+
+            let center = &terrain.chunks[&(1, 1)];
+            let west = &terrain.chunks[&(0, 1)];
+            let north = &terrain.chunks[&(1, 0)];
+            assert_ne!(center.heights[5 * vertex_count], before[5 * vertex_count], "{:?}", op);
+            for i in 0..12 {
+                assert_eq!(center.heights[i * vertex_count], west.heights[i * vertex_count + last], "{:?} row {}", op, i);
+                assert_eq!(center.heights[i], north.heights[last * vertex_count + i], "{:?} column {}", op, i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ramp_forms_monotonic_gradient() {
+        let config = TerrainConfig::new(256, 128, 1, WorldTheme::Fantasy);
+        let vertex_count = config.vertex_count;
+        let mut terrain = TerrainData::empty(config);
+        for chunk_x in 0..2 {
+            terrain.chunks.insert((chunk_x, 0), HeightmapChunk::new((chunk_x, 0), vertex_count));
+        }
+
+        let ramp = Ramp { start: (20.0, 64.0), end: (200.0, 64.0), start_height: 0.3, end_height: 0.8, width: 6.0 };
+        let touched = terrain.apply_ramp(&ramp, 1.0, FalloffCurve::Constant);
+        assert_eq!(touched.len(), 2);
+
+        let axis: Vec<f32> = (20..=200)
+            .map(|x| terrain.vertex_height(x as f32, 64.0).unwrap())
+            .collect();
+        assert!((axis[0] - 0.3).abs() < 1e-5);
+        assert!((axis[axis.len() - 1] - 0.8).abs() < 1e-5);
+        assert!(axis.windows(2).all(|pair| pair[1] > pair[0]));
+        // Cells off the corridor are untouched
+        assert_eq!(terrain.vertex_height(100.0, 80.0), Some(0.2));
+    }
+
+    #[test]
+    fn test_undo_brush_restores_heights() {
+        let mut terrain = generated_terrain();
+        let original = terrain.chunks[&(1, 1)].heights.clone();
+
+        terrain.apply_brush((1, 1), 64.0, 64.0, 12.0, 10.0, BrushOp::Raise, FalloffCurve::Gaussian);
+        let raised = terrain.chunks[&(1, 1)].heights.clone();
+        assert_ne!(raised, original);
+
+        assert_eq!(terrain.undo(), vec![(1, 1)]);
+        assert_eq!(terrain.chunks[&(1, 1)].heights, original);
+        assert!(!terrain.undo_stack.can_undo());
+
+        assert_eq!(terrain.redo(), vec![(1, 1)]);
+        assert_eq!(terrain.chunks[&(1, 1)].heights, raised);
+    }
+
+    #[test]
+    fn test_whole_world_pass_undoes_as_one_group() {
+        let mut terrain = generated_terrain();
+        let original = terrain.flatten_heights();
+
+        let lowered: Vec<f32> = original.iter().map(|h| h * 0.5).collect();
+        terrain.store_heights(&lowered);
+        terrain.clear_dirty();
+
+        let restored = terrain.undo();
+        assert_eq!(restored.len(), terrain.chunks.len());
+        assert_eq!(terrain.flatten_heights(), original);
+        assert!(restored.iter().all(|coord| terrain.dirty_chunks.contains(coord)));
+
+        // Replacing a chunk outright forgets the history
+        terrain.redo();
+        terrain.regenerate_chunk(0, 0).unwrap();
+        assert!(!terrain.undo_stack.can_undo() && !terrain.undo_stack.can_redo());
+    }
+
+    #[test]
+    fn test_stored_heights_stay_tileable_on_wrapped_worlds() {
+        let mut terrain = generated_terrain();
+        terrain.config.wrap = true;
+        let (width, height) = (terrain.config.world_width as usize, terrain.config.world_height as usize);
+
+        // A pass that ignores the seam, like erosion
+        let heights: Vec<f32> = (0..width * height).map(|i| (i % width) as f32 / width as f32).collect();
+        terrain.store_heights(&heights);
+
+        let stored = terrain.flatten_heights();
+        for z in 0..height {
+            assert_eq!(stored[z * width], stored[z * width + width - 1]);
+        }
+        for x in 0..width {
+            assert_eq!(stored[x], stored[(height - 1) * width + x]);
+        }
+    }
+
+    #[test]
+    fn test_partial_edge_chunks_round_trip() {
+        let config = TerrainConfig::new(300, 200, 5, WorldTheme::Fantasy);
+        let mut terrain = TerrainData::empty(config);
+        for chunk in noise_gen::generate_terrain_simd(&terrain.config) {
+            terrain.chunks.insert(chunk.coord, chunk);
+        }
+        assert_eq!(terrain.chunks.len(), 3 * 2);
+
+        let heights: Vec<f32> = (0..300 * 200).map(|i| i as f32 / (300 * 200) as f32).collect();
+        terrain.store_heights(&heights);
+        assert_eq!(terrain.flatten_heights(), heights);
+
+        // The far corner lives in the partial chunk (2, 1)
+        let vertex_count = terrain.config.vertex_count as usize;
+        let corner = terrain.chunks[&(2, 1)].heights[(199 - 128) * vertex_count + (299 - 256)];
+        assert_eq!(corner, heights[300 * 200 - 1]);
+
+        // Flow splits the same way, with full-size per-chunk maps even at the edge
+        terrain.store_flow_accumulation(&heights);
+        let flow = terrain.chunks[&(2, 1)].flow_accumulation.as_ref().unwrap();
+        assert_eq!(flow.len(), vertex_count * vertex_count);
+        assert_eq!(flow[(199 - 128) * vertex_count + (299 - 256)], heights[300 * 200 - 1]);
+    }
+
+    #[test]
+    fn test_regenerated_chunk_matches_full_generation() {
+        let custom = NoiseParameters { continent_frequency: 0.0004, ..NoiseParameters::default() };
+        // Default (simd) and custom generators, the latter as if saved before the
+        // normalisation range was recorded
+        for (noise_params, keep_range) in [(None, true), (Some(custom), false)] {
+            let mut config = TerrainConfig::new(384, 256, 11, WorldTheme::Fantasy);
+            config.style = noise_gen::TerrainStyle::Mesa { bands: 4 };
+            config.noise_params = noise_params;
+            let chunks = noise_gen::generate_world(&mut config);
+            let mut terrain = TerrainData::empty(config);
+            for chunk in chunks {
+                terrain.chunks.insert(chunk.coord, chunk);
+            }
+            terrain.generate_biomes();
+            terrain.generate_caves(0.5);
+            let expected = terrain.chunks[&(2, 1)].clone();
+            if !keep_range {
+                terrain.config.height_range = None;
+            }
+
+            let vertex_count = terrain.config.vertex_count as usize;
+            let chunk = terrain.chunks.get_mut(&(2, 1)).unwrap();
+            chunk.heights.fill(0.9);
+            chunk.biome_ids = None;
+            chunk.cave_mask = None;
+            chunk.feature_ids = Some(vec![1; vertex_count * vertex_count]);
+            terrain.clear_dirty();
+            terrain.regenerate_chunk(2, 1).unwrap();
+
+            let regenerated = &terrain.chunks[&(2, 1)];
+            assert_eq!(regenerated.heights, expected.heights);
+            assert_eq!(regenerated.biome_ids, expected.biome_ids);
+            assert_eq!(regenerated.cave_mask, expected.cave_mask);
+            assert!(regenerated.feature_ids.is_none());
+            assert!(terrain.dirty_chunks.contains(&(2, 1)));
+        }
+
+        assert!(generated_terrain().regenerate_chunk(3, 0).is_err());
+    }
+
+    #[test]
+    fn test_half_ocean_world_analysis() {
+        let config = TerrainConfig::new(256, 256, 1, WorldTheme::Fantasy);
+        let mut terrain = TerrainData::empty(config);
+        for chunk in noise_gen::generate_terrain_simd(&terrain.config) {
+            terrain.chunks.insert(chunk.coord, chunk);
+        }
+        // West half ocean, east half land
+        let heights: Vec<f32> = (0..256 * 256).map(|i| if i % 256 < 128 { 0.1 } else { 0.6 }).collect();
+        terrain.store_heights(&heights);
+
+        let analysis = terrain.analyze(10);
+
+        assert!((analysis.land_fraction - 0.5).abs() < 1e-3);
+        assert!((analysis.mean_elevation - 0.35).abs() < 1e-3);
+        assert_eq!((analysis.min, analysis.max), (0.1, 0.6));
+        assert_eq!(analysis.histogram.len(), 10);
+        assert_eq!(analysis.histogram[1], 256 * 128);
+        assert_eq!(analysis.histogram[6], 256 * 128);
+        assert_eq!(analysis.histogram.iter().sum::<u32>(), 256 * 256);
+    }
+
+    #[test]
+    fn test_undo_stack_state() {
+        let mut chunk = HeightmapChunk::new((0, 0), 17);
+        let before = chunk.heights.clone();
+        chunk.heights[5] = 0.9;
+
+        let mut undo_stack = UndoStack::new();
+        assert!(!undo_stack.can_undo());
+        undo_stack.begin_group();
+        undo_stack.record(&chunk, &before);
+
+        assert!(undo_stack.can_undo());
+        assert!(!undo_stack.can_redo());
+        assert_eq!(undo_stack.len(), 1);
+
+        undo_stack.clear();
+        assert!(undo_stack.is_empty());
+    }
+
+    #[test]
+    fn test_single_cell_edit_records_unit_rect() {
+        let mut terrain = generated_terrain();
+        let vertex_count = terrain.config.vertex_count as usize;
+        let chunk = terrain.chunks.get_mut(&(0, 0)).unwrap();
+        let before = chunk.heights.clone();
+        chunk.heights[37 * vertex_count + 90] += 0.1;
+
+        terrain.undo_stack.record(&terrain.chunks[&(0, 0)], &before);
+        assert_eq!(terrain.undo_stack.entries.back().unwrap().affected_rect, (90, 37, 90, 37));
+
+        terrain.undo();
+        assert_eq!(terrain.chunks[&(0, 0)].heights, before);
+    }
+
+    #[test]
+    fn test_flat_terrain_has_no_flow() {
+        let config = TerrainConfig::new(256, 256, 1, WorldTheme::Fantasy);
+        let mut terrain = TerrainData::empty(config);
+        let flat = NoiseParameters {
+            continent_frequency: 0.0,
+            mountain_frequency: 0.0,
+            hill_frequency: 0.0,
+            detail_frequency: 0.0,
+            ..Default::default()
+        };
+        for chunk in noise_gen::generate_terrain_with_params(&terrain.config, &flat) {
+            terrain.chunks.insert(chunk.coord, chunk);
+        }
+
+        let bytes = terrain.flow_data().unwrap();
+        assert_eq!(bytes.len(), 256 * 256);
+        assert!(bytes.iter().all(|&b| b == 0));
+
+        // Real terrain still produces a full-range texture
+        let bytes = generated_terrain().flow_data().unwrap();
+        assert!(bytes.contains(&255));
+    }
+
+    #[test]
+    fn test_partial_load_rejects_whole_world_passes() {
+        let mut terrain = generated_terrain();
+        assert!(terrain.validate_chunk_coords().is_ok());
+
+        terrain.chunks.remove(&(1, 2));
+        let err = terrain.validate_chunk_coords().unwrap_err();
+        assert!(err.contains("(1, 2)") && err.contains("not loaded"), "{}", err);
+        assert!(terrain.flow_data().is_err());
+    }
+
+    #[test]
+    fn test_negative_chunk_coords_flatten_without_panic() {
+        let mut terrain = generated_terrain();
+        let vertex_count = terrain.config.vertex_count;
+        let mut stray = heightmap::HeightmapChunk::new((-1, -1), vertex_count);
+        stray.heights.fill(0.9);
+        terrain.chunks.insert((-1, -1), stray);
+
+        let heights = terrain.flatten_heights();
+        assert_eq!(heights.len(), 384 * 384);
+        assert_eq!(heights[0], terrain.chunks[&(0, 0)].heights[0]);
+
+        let err = terrain.validate_chunk_coords().unwrap_err();
+        assert!(err.contains("(-1, -1)"), "{}", err);
+
+        // Writing back leaves the out-of-grid chunk alone
+        terrain.store_heights(&heights);
+        assert!(terrain.chunks[&(-1, -1)].heights.iter().all(|&h| h == 0.9));
+        assert!(!terrain.dirty_chunks.contains(&(-1, -1)));
+    }
+
+    #[test]
+    fn test_regenerating_whole_world_reproduces_generation() {
+        let mut config = TerrainConfig::new(384, 256, 4, WorldTheme::Fantasy);
+        config.style = noise_gen::TerrainStyle::Alpine;
+        config.noise_params = Some(NoiseParameters { mountain_frequency: 0.003, ..NoiseParameters::default() });
+        let chunks = noise_gen::generate_world(&mut config);
+        let mut terrain = TerrainData::empty(config);
+        for chunk in chunks {
+            terrain.chunks.insert(chunk.coord, chunk);
+        }
+        terrain.generate_biomes();
+        let expected = terrain.chunks.clone();
+
+        for chunk in terrain.chunks.values_mut() {
+            chunk.heights.fill(0.5);
+            chunk.biome_ids = Some(vec![0; chunk.heights.len()]);
+        }
+        // Nothing borders the region, so no blending: the world's own params, style
+        // and range give back the original chunks, biomes included
+        assert_eq!(terrain.regenerate_region((0, 0), (2, 1), 0, None).len(), 6);
+        for (coord, chunk) in &expected {
+            assert_eq!(terrain.chunks[coord].heights, chunk.heights, "{:?}", coord);
+            assert_eq!(terrain.chunks[coord].biome_ids, chunk.biome_ids, "{:?}", coord);
+        }
+    }
+
+    #[test]
+    fn test_regenerate_region_leaves_neighbors_intact() {
+        let mut terrain = generated_terrain();
+        let before = terrain.chunks.clone();
+        let vertex_count = terrain.config.vertex_count as usize;
+        let last = vertex_count - 1;
+
+        let regenerated = terrain.regenerate_region((1, 1), (1, 1), 99, None);
+        assert_eq!(regenerated, vec![(1, 1)]);
+
+        // The regenerated chunk actually changed
+        assert_ne!(terrain.chunks[&(1, 1)].heights, before[&(1, 1)].heights);
+
+        // Every other chunk is untouched
+        for (coord, chunk) in &before {
+            if *coord != (1, 1) {
+                assert_eq!(terrain.chunks[coord].heights, chunk.heights);
+            }
+        }
+
+        // Shared seams still line up with the neighbors
+        let center = &terrain.chunks[&(1, 1)];
+        let west = &terrain.chunks[&(0, 1)];
+        let north = &terrain.chunks[&(1, 0)];
+        for i in 0..vertex_count {
+            assert_eq!(center.heights[i * vertex_count], west.heights[i * vertex_count + last]);
+            assert_eq!(center.heights[i], north.heights[last * vertex_count + i]);
+        }
+    }
+}
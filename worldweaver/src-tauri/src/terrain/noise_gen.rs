@@ -1,30 +1,72 @@
-use noise::{Fbm, RidgedMulti, Perlin, NoiseFn, MultiFractal};
+use noise::{Fbm, RidgedMulti, Perlin, OpenSimplex, Simplex, NoiseFn, MultiFractal, Seedable};
+use serde::{Serialize, Deserialize};
 use super::config::TerrainConfig;
 use super::heightmap::HeightmapChunk;
 
-use super::commands::NoiseParameters;
+use super::commands::{NoiseBackend, NoiseParameters};
 
 /// Generate base terrain using layered noise with geological realism
 pub fn generate_terrain(config: &TerrainConfig) -> Vec<HeightmapChunk> {
     generate_terrain_with_params(config, &NoiseParameters::default())
 }
 
+/// Base heights for a whole world, before post-processing, from the config's noise
+/// parameters. Without parameters the faster simdnoise generator is used, except for
+/// wrapped worlds: only the layered generator can sample on a torus
+pub fn generate_base_terrain(config: &TerrainConfig) -> Vec<HeightmapChunk> {
+    generate_base_region(config, (0, 0), (config.chunk_count_x() - 1, config.chunk_count_z() - 1))
+}
+
+/// Base heights for the chunks in an inclusive rectangle, identical to what
+/// generate_base_terrain gives those chunks
+pub fn generate_base_region(config: &TerrainConfig, min_chunk: (i32, i32), max_chunk: (i32, i32)) -> Vec<HeightmapChunk> {
+    match &config.noise_params {
+        Some(params) => generate_region_with_params(config, params, min_chunk, max_chunk),
+        None if config.wrap => generate_region_with_params(config, &NoiseParameters::default(), min_chunk, max_chunk),
+        None => generate_simd_region(config, min_chunk, max_chunk),
+    }
+}
+
+/// A new world's heights as generate_terrain builds them before erosion: base noise,
+/// then post-processing in the config's style. The normalisation range is recorded on
+/// the config so chunks can later be rebuilt to match
+pub fn generate_world(config: &mut TerrainConfig) -> Vec<HeightmapChunk> {
+    let mut chunks = generate_base_terrain(config);
+    config.height_range = Some(post_process_terrain(&mut chunks, config, config.style));
+    chunks
+}
+
+/// Rebuild the chunks in an inclusive rectangle through the same generator and
+/// post-processing as generate_world, normalised by the world's recorded range
+/// Worlds saved before the range was recorded recompute it from the full base terrain
+pub fn regenerate_chunks(config: &TerrainConfig, min_chunk: (i32, i32), max_chunk: (i32, i32)) -> Vec<HeightmapChunk> {
+    let range = config.height_range
+        .unwrap_or_else(|| height_range(&generate_base_terrain(config)));
+    let mut chunks = generate_base_region(config, min_chunk, max_chunk);
+    post_process_with_range(&mut chunks, config, config.style, range);
+    chunks
+}
+
 /// Generate terrain with custom noise parameters
+#[tracing::instrument(skip_all, fields(seed = config.seed))]
 pub fn generate_terrain_with_params(config: &TerrainConfig, params: &NoiseParameters) -> Vec<HeightmapChunk> {
-    let chunk_count_x = config.chunk_count_x();
-    let chunk_count_z = config.chunk_count_z();
+    generate_region_with_params(config, params, (0, 0), (config.chunk_count_x() - 1, config.chunk_count_z() - 1))
+}
+
+/// Generate the chunks in an inclusive rectangle with custom noise parameters
+fn generate_region_with_params(
+    config: &TerrainConfig,
+    params: &NoiseParameters,
+    min_chunk: (i32, i32),
+    max_chunk: (i32, i32),
+) -> Vec<HeightmapChunk> {
     let mut chunks = Vec::new();
 
     // Check if we're generating a flat/blank world (all frequencies are 0)
-    let is_flat = params.continent_frequency == 0.0 
-        && params.mountain_frequency == 0.0 
-        && params.hill_frequency == 0.0 
-        && params.detail_frequency == 0.0;
-
-    if is_flat {
+    if is_flat_params(params) {
         // Generate flat terrain at sea level for painting
-        for chunk_z in 0..chunk_count_z {
-            for chunk_x in 0..chunk_count_x {
+        for chunk_z in min_chunk.1..=max_chunk.1 {
+            for chunk_x in min_chunk.0..=max_chunk.0 {
                 let vertex_count = config.vertex_count as usize;
                 let heights = vec![config.sea_level; vertex_count * vertex_count];
                 chunks.push(HeightmapChunk::from_heights((chunk_x, chunk_z), heights));
@@ -33,86 +75,249 @@ pub fn generate_terrain_with_params(config: &TerrainConfig, params: &NoiseParame
         return chunks;
     }
 
-    // APPROACH: Multiple independent noise layers that create archipelagos
-    // Instead of one big blob, we want scattered landmasses
-    
-    // Layer 1: Primary continent mask (creates 2-3 large landmasses)
-    let continent_mask1 = Fbm::<Perlin>::new(config.seed)
-        .set_octaves(2)
-        .set_frequency(params.continent_frequency.max(0.00001) * 0.8)
-        .set_persistence(0.5)
-        .set_lacunarity(2.5);
-    
-    // Layer 2: Secondary continent mask (creates additional islands)
-    let _continent_mask2 = Fbm::<Perlin>::new(config.seed + 100)
-        .set_octaves(2)
-        .set_frequency(params.continent_frequency.max(0.00001) * 1.2)
-        .set_persistence(0.4)
-        .set_lacunarity(2.8);
-    
-    // Layer 3: Base terrain elevation
-    let continents = Fbm::<Perlin>::new(config.seed + 1)
-        .set_octaves(params.continent_octaves.max(1))
-        .set_frequency(params.continent_frequency.max(0.00001) * 2.0)
-        .set_persistence(0.5)
-        .set_lacunarity(2.0);
-
-    // Layer 4: Mountain ranges
-    let mountains = RidgedMulti::<Perlin>::new(config.seed + 2)
-        .set_octaves(params.mountain_octaves.max(1))
-        .set_frequency(params.mountain_frequency.max(0.0001))
-        .set_lacunarity(2.2);
-
-    // Layer 5: Hills
-    let hills = Fbm::<Perlin>::new(config.seed + 3)
-        .set_octaves(params.hill_octaves.max(1))
-        .set_frequency(params.hill_frequency.max(0.0001))
-        .set_persistence(0.4)
-        .set_lacunarity(2.3);
-
-    // Layer 6: Detail
-    let detail = Fbm::<Perlin>::new(config.seed + 4)
-        .set_octaves(params.detail_octaves.max(1))
-        .set_frequency(params.detail_frequency.max(0.0001))
-        .set_persistence(0.25)
-        .set_lacunarity(2.5);
-
-    // Combine masks to create archipelagos
-    let combined_mask = continent_mask1;
+    let layers = NoiseLayers::new(config, params);
 
     // Generate each chunk with multi-mask approach for archipelagos
-    for chunk_z in 0..chunk_count_z {
-        for chunk_x in 0..chunk_count_x {
-            let chunk = generate_chunk_with_archipelago(
-                chunk_x, chunk_z, config, params,
-                &combined_mask, &continents, &mountains, &hills, &detail
-            );
-            chunks.push(chunk);
+    for chunk_z in min_chunk.1..=max_chunk.1 {
+        for chunk_x in min_chunk.0..=max_chunk.0 {
+            chunks.push(generate_chunk_with_archipelago(chunk_x, chunk_z, config, params, &layers));
         }
     }
 
     chunks
 }
 
+/// Overall land shape applied on top of the post-processing S-curve
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum TerrainStyle {
+    /// The plain S-curve
+    #[default]
+    Rolling,
+    /// Flat-topped plateaus: land quantized into `bands` steps with soft edges
+    Mesa { bands: u32 },
+    /// Broad valleys and sharper, taller peaks
+    Alpine,
+}
+
+impl TerrainStyle {
+    /// Fraction of each mesa band spent ramping up to the next plateau
+    const MESA_EDGE: f32 = 0.15;
+
+    /// Reshape a curved land height (0 = coast)
+    fn reshape_land(&self, land: f32) -> f32 {
+        match *self {
+            TerrainStyle::Rolling => land,
+            TerrainStyle::Mesa { bands } => {
+                let bands = bands.max(1) as f32;
+                let scaled = land.max(0.0) * bands;
+                let step = scaled.floor();
+                // Flat until the last MESA_EDGE of the band, then smoothstep to the next plateau
+                let edge = ((scaled - step - (1.0 - Self::MESA_EDGE)) / Self::MESA_EDGE).clamp(0.0, 1.0);
+                (step + edge * edge * (3.0 - 2.0 * edge)) / bands
+            }
+            TerrainStyle::Alpine => land.max(0.0).powf(1.6) * 1.3,
+        }
+    }
+}
+
+/// Generate a single chunk at its global coordinates with custom noise parameters
+/// Produces the same heights the chunk would get from a full generation
+pub fn generate_chunk_with_params(config: &TerrainConfig, params: &NoiseParameters, chunk_x: i32, chunk_z: i32) -> HeightmapChunk {
+    if is_flat_params(params) {
+        let vertex_count = config.vertex_count as usize;
+        return HeightmapChunk::from_heights((chunk_x, chunk_z), vec![config.sea_level; vertex_count * vertex_count]);
+    }
+
+    let layers = NoiseLayers::new(config, params);
+    generate_chunk_with_archipelago(chunk_x, chunk_z, config, params, &layers)
+}
+
+/// Generate a low-resolution square preview heightmap without creating chunks
+/// Samples the same noise layers as full generation at coarser spacing
+pub fn preview_terrain(config: &TerrainConfig, params: &NoiseParameters, preview_size: u32) -> Vec<f32> {
+    let preview_size = preview_size.max(1) as usize;
+
+    if is_flat_params(params) {
+        return vec![config.sea_level; preview_size * preview_size];
+    }
+
+    let layers = NoiseLayers::new(config, params);
+    let spacing_x = config.world_width as f64 * config.cell_size_meters as f64 / preview_size as f64;
+    let spacing_z = config.world_height as f64 * config.cell_size_meters as f64 / preview_size as f64;
+
+    let mut heights = Vec::with_capacity(preview_size * preview_size);
+    for z in 0..preview_size {
+        for x in 0..preview_size {
+            heights.push(layers.sample(x as f64 * spacing_x, z as f64 * spacing_z, config, params));
+        }
+    }
+
+    heights
+}
+
+/// Whether all noise frequencies are 0 (flat/blank world for painting)
+pub(super) fn is_flat_params(params: &NoiseParameters) -> bool {
+    params.continent_frequency == 0.0 
+        && params.mountain_frequency == 0.0 
+        && params.hill_frequency == 0.0 
+        && params.detail_frequency == 0.0
+}
+
+/// Noise that can be sampled on the plane (2D) or on a torus (4D) for wrapping worlds
+trait LayerNoise: NoiseFn<f64, 2> + NoiseFn<f64, 4> + Send + Sync {}
+
+impl<N: NoiseFn<f64, 2> + NoiseFn<f64, 4> + Send + Sync> LayerNoise for N {}
+
+/// A fractal noise layer over whichever base noise the parameters select
+type NoiseLayer = Box<dyn LayerNoise>;
+
+/// Evaluate a layer at world coordinates (meters)
+/// Wrapping worlds map x and z onto two circles so the last vertex column/row matches the first
+fn sample_layer(layer: &NoiseLayer, world_x: f64, world_z: f64, config: &TerrainConfig) -> f64 {
+    use std::f64::consts::TAU;
+
+    if !config.wrap {
+        return NoiseFn::<f64, 2>::get(layer.as_ref(), [world_x, world_z]);
+    }
+
+    // Period is one cell short of the world size so vertex 0 and vertex width-1 coincide
+    let period_x = (config.world_width.max(2) - 1) as f64 * config.cell_size_meters as f64;
+    let period_z = (config.world_height.max(2) - 1) as f64 * config.cell_size_meters as f64;
+    // Radius keeps arc length equal to world distance, so frequencies mean the same thing
+    let (radius_x, radius_z) = (period_x / TAU, period_z / TAU);
+    let (angle_x, angle_z) = (world_x / period_x * TAU, world_z / period_z * TAU);
+
+    NoiseFn::<f64, 4>::get(layer.as_ref(), [
+        radius_x * angle_x.cos(),
+        radius_x * angle_x.sin(),
+        radius_z * angle_z.cos(),
+        radius_z * angle_z.sin(),
+    ])
+}
+
+/// Noise layers combined to create archipelagos
+/// Instead of one big blob, we want scattered landmasses
+struct NoiseLayers {
+    continent_mask: NoiseLayer,
+    base_terrain: NoiseLayer,
+    mountains: NoiseLayer,
+    hills: NoiseLayer,
+    detail: NoiseLayer,
+    warp: NoiseLayer,
+}
+
+impl NoiseLayers {
+    fn new(config: &TerrainConfig, params: &NoiseParameters) -> Self {
+        let params = &params.clamped();
+        match params.noise_backend {
+            NoiseBackend::Perlin => Self::with_source::<Perlin>(config, params),
+            NoiseBackend::OpenSimplex => Self::with_source::<OpenSimplex>(config, params),
+            NoiseBackend::Simplex => Self::with_source::<Simplex>(config, params),
+        }
+    }
+
+    /// Build every layer on top of the same base noise type
+    fn with_source<T>(config: &TerrainConfig, params: &NoiseParameters) -> Self
+    where
+        T: NoiseFn<f64, 2> + NoiseFn<f64, 4> + Default + Seedable + Send + Sync + 'static,
+    {
+        Self {
+            // Layer 1: Primary continent mask (creates 2-3 large landmasses)
+            continent_mask: Box::new(Fbm::<T>::new(config.seed)
+                .set_octaves(2)
+                .set_frequency(params.continent_frequency.max(0.00001) * 0.8)
+                .set_persistence(0.5)
+                .set_lacunarity(2.5)),
+            // Layer 2: Base terrain elevation
+            base_terrain: Box::new(Fbm::<T>::new(config.seed + 1)
+                .set_octaves(params.continent_octaves.max(1))
+                .set_frequency(params.continent_frequency.max(0.00001) * 2.0)
+                .set_persistence(params.continent_persistence)
+                .set_lacunarity(params.continent_lacunarity)),
+            // Layer 3: Mountain ranges
+            mountains: Box::new(RidgedMulti::<T>::new(config.seed + 2)
+                .set_octaves(params.mountain_octaves.max(1))
+                .set_frequency(params.mountain_frequency.max(0.0001))
+                .set_persistence(params.mountain_persistence)
+                .set_lacunarity(params.mountain_lacunarity)),
+            // Layer 4: Hills
+            hills: Box::new(Fbm::<T>::new(config.seed + 3)
+                .set_octaves(params.hill_octaves.max(1))
+                .set_frequency(params.hill_frequency.max(0.0001))
+                .set_persistence(params.hill_persistence)
+                .set_lacunarity(params.hill_lacunarity)),
+            // Layer 5: Detail
+            detail: Box::new(Fbm::<T>::new(config.seed + 4)
+                .set_octaves(params.detail_octaves.max(1))
+                .set_frequency(params.detail_frequency.max(0.0001))
+                .set_persistence(params.detail_persistence)
+                .set_lacunarity(params.detail_lacunarity)),
+            // Domain warp for the continent mask (only sampled when warp_strength > 0)
+            warp: Box::new(Fbm::<T>::new(config.seed + 5)
+                .set_octaves(2)
+                .set_frequency(params.warp_frequency)),
+        }
+    }
+
+    /// Sample normalized height at world coordinates (meters)
+    fn sample(&self, world_x: f64, world_z: f64, config: &TerrainConfig, params: &NoiseParameters) -> f32 {
+        // Sample continent mask (determines land vs ocean), warped for less blobby coastlines
+        let (mask_x, mask_z) = if params.warp_strength > 0.0 {
+            let strength = params.warp_strength as f64;
+            // Offset the second sample so the x and z displacements are uncorrelated
+            (
+                world_x + sample_layer(&self.warp, world_x, world_z, config) * strength,
+                world_z + sample_layer(&self.warp, world_x + 5200.0, world_z + 1300.0, config) * strength,
+            )
+        } else {
+            (world_x, world_z)
+        };
+        let mask = sample_layer(&self.continent_mask, mask_x, mask_z, config) as f32;
+        let mask_norm = (mask + 1.0) * 0.5;
+        
+        // CRITICAL: Apply SHARP threshold to create distinct continents
+        // Values above threshold = land, below = ocean
+        // land_coverage is the desired land fraction, so higher coverage lowers the threshold
+        let land_threshold = (1.0 - params.land_coverage.unwrap_or(0.55)).clamp(0.01, 0.99);
+        
+        if mask_norm > land_threshold {
+            // This is LAND - sample terrain layers
+            let base = sample_layer(&self.base_terrain, world_x, world_z, config) as f32;
+            let mount = sample_layer(&self.mountains, world_x, world_z, config) as f32;
+            let hill = sample_layer(&self.hills, world_x, world_z, config) as f32;
+            let det = sample_layer(&self.detail, world_x, world_z, config) as f32;
+            
+            // Normalize
+            let base_norm = (base + 1.0) * 0.5;
+            let mount_norm = (mount + 1.0) * 0.5;
+            let hill_norm = (hill + 1.0) * 0.5;
+            let det_norm = (det + 1.0) * 0.5;
+            
+            // Composite terrain
+            let terrain = base_norm * 0.5 + mount_norm * 0.25 + hill_norm * 0.15 + det_norm * 0.1;
+            
+            // Fade at coastlines (smooth transition)
+            let coast_fade = ((mask_norm - land_threshold) / (1.0 - land_threshold)).powf(0.5);
+            let height = config.sea_level + terrain * coast_fade * (1.0 - config.sea_level);
+            
+            height.clamp(0.0, 1.0)
+        } else {
+            // This is OCEAN
+            let ocean_depth = (land_threshold - mask_norm) / land_threshold;
+            let height = config.sea_level * (1.0 - ocean_depth * 0.5);
+            height.clamp(0.0, 1.0)
+        }
+    }
+}
+
 /// Generate chunk with archipelago masking for distinct continents
-fn generate_chunk_with_archipelago<N1, N2, N3, N4, N5>(
+fn generate_chunk_with_archipelago(
     chunk_x: i32,
     chunk_z: i32,
     config: &TerrainConfig,
     params: &NoiseParameters,
-    continent_mask: &N1,
-    base_terrain: &N2,
-    mountains: &N3,
-    hills: &N4,
-    detail: &N5,
-) -> HeightmapChunk
-where
-    N1: NoiseFn<f64, 2>,
-    N2: NoiseFn<f64, 2>,
-    N3: NoiseFn<f64, 2>,
-    N4: NoiseFn<f64, 2>,
-    N5: NoiseFn<f64, 2>,
-{
+    layers: &NoiseLayers,
+) -> HeightmapChunk {
     let vertex_count = config.vertex_count;
     let mut heights = Vec::with_capacity((vertex_count * vertex_count) as usize);
 
@@ -124,41 +329,7 @@ where
             let world_x = chunk_world_x + local_x as f32 * config.cell_size_meters;
             let world_z = chunk_world_z + local_z as f32 * config.cell_size_meters;
 
-            // Sample continent mask (determines land vs ocean)
-            let mask = continent_mask.get([world_x as f64, world_z as f64]) as f32;
-            let mask_norm = (mask + 1.0) * 0.5;
-            
-            // CRITICAL: Apply SHARP threshold to create distinct continents
-            // Values above threshold = land, below = ocean
-            let land_threshold = params.land_coverage.unwrap_or(0.45);
-            
-            if mask_norm > land_threshold {
-                // This is LAND - sample terrain layers
-                let base = base_terrain.get([world_x as f64, world_z as f64]) as f32;
-                let mount = mountains.get([world_x as f64, world_z as f64]) as f32;
-                let hill = hills.get([world_x as f64, world_z as f64]) as f32;
-                let det = detail.get([world_x as f64, world_z as f64]) as f32;
-                
-                // Normalize
-                let base_norm = (base + 1.0) * 0.5;
-                let mount_norm = (mount + 1.0) * 0.5;
-                let hill_norm = (hill + 1.0) * 0.5;
-                let det_norm = (det + 1.0) * 0.5;
-                
-                // Composite terrain
-                let terrain = base_norm * 0.5 + mount_norm * 0.25 + hill_norm * 0.15 + det_norm * 0.1;
-                
-                // Fade at coastlines (smooth transition)
-                let coast_fade = ((mask_norm - land_threshold) / (1.0 - land_threshold)).powf(0.5);
-                let height = config.sea_level + terrain * coast_fade * (1.0 - config.sea_level);
-                
-                heights.push(height.clamp(0.0, 1.0));
-            } else {
-                // This is OCEAN
-                let ocean_depth = (land_threshold - mask_norm) / land_threshold;
-                let height = config.sea_level * (1.0 - ocean_depth * 0.5);
-                heights.push(height.clamp(0.0, 1.0));
-            }
+            heights.push(layers.sample(world_x as f64, world_z as f64, config, params));
         }
     }
 
@@ -243,11 +414,17 @@ where
 }
 
 /// Generate terrain using simdnoise for performance (bulk generation)
+#[tracing::instrument(skip_all, fields(seed = config.seed))]
 pub fn generate_terrain_simd(config: &TerrainConfig) -> Vec<HeightmapChunk> {
+    generate_simd_region(config, (0, 0), (config.chunk_count_x() - 1, config.chunk_count_z() - 1))
+}
+
+/// The simdnoise chunks in an inclusive rectangle
+/// The continental layer is scaled by its range over the whole world, so it is always
+/// generated in full
+fn generate_simd_region(config: &TerrainConfig, min_chunk: (i32, i32), max_chunk: (i32, i32)) -> Vec<HeightmapChunk> {
     use simdnoise::NoiseBuilder;
 
-    let chunk_count_x = config.chunk_count_x();
-    let chunk_count_z = config.chunk_count_z();
     let mut chunks = Vec::new();
 
     // Generate base continental layer with simdnoise
@@ -267,8 +444,8 @@ pub fn generate_terrain_simd(config: &TerrainConfig) -> Vec<HeightmapChunk> {
         .set_persistence(0.4);
 
     // Split into chunks
-    for chunk_z in 0..chunk_count_z {
-        for chunk_x in 0..chunk_count_x {
+    for chunk_z in min_chunk.1..=max_chunk.1 {
+        for chunk_x in min_chunk.0..=max_chunk.0 {
             let mut heights = Vec::with_capacity((config.vertex_count * config.vertex_count) as usize);
 
             let chunk_world_x = chunk_x as f32 * config.chunk_size as f32 * config.cell_size_meters;
@@ -276,14 +453,12 @@ pub fn generate_terrain_simd(config: &TerrainConfig) -> Vec<HeightmapChunk> {
 
             for local_z in 0..config.vertex_count {
                 for local_x in 0..config.vertex_count {
-                    let global_x = (chunk_x * config.chunk_size as i32 + local_x as i32) as usize;
-                    let global_z = (chunk_z * config.chunk_size as i32 + local_z as i32) as usize;
+                    // Vertices past the world edge (the shared last row/column, or the
+                    // unused part of a partial edge chunk) repeat the nearest edge value
+                    let global_x = ((chunk_x * config.chunk_size as i32 + local_x as i32) as usize).min(total_width - 1);
+                    let global_z = ((chunk_z * config.chunk_size as i32 + local_z as i32) as usize).min(total_height - 1);
 
-                    let base_height = if global_x < total_width && global_z < total_height {
-                        base_noise[global_z * total_width + global_x]
-                    } else {
-                        0.5
-                    };
+                    let base_height = base_noise[global_z * total_width + global_x];
 
                     // Add fine detail
                     let world_x = chunk_world_x + local_x as f32 * config.cell_size_meters;
@@ -303,19 +478,70 @@ pub fn generate_terrain_simd(config: &TerrainConfig) -> Vec<HeightmapChunk> {
     chunks
 }
 
-/// Apply post-processing to normalize and enhance terrain
-pub fn post_process_terrain(chunks: &mut [HeightmapChunk], config: &TerrainConfig) {
-    // Find min/max heights
+/// Depth below the surface, in meters, at which the cave noise is sampled
+const CAVE_SAMPLE_DEPTH: f32 = 8.0;
+
+/// Mark cave openings by thresholding 3D noise just below a chunk's surface
+/// `density` (0..1) sets how much of the land is hollow; vertices at or below sea
+/// level are never caves. Returns 1 per cave vertex, 0 elsewhere
+pub fn generate_cave_mask(config: &TerrainConfig, chunk: &HeightmapChunk, density: f32) -> Vec<u8> {
+    let caves = Fbm::<Perlin>::new(config.seed + 200)
+        .set_octaves(3)
+        .set_frequency(0.02)
+        .set_persistence(0.5);
+
+    // Fbm output clusters around zero, so this range spans almost nothing to almost everything
+    let threshold = 0.6 - density.clamp(0.0, 1.0) * 1.2;
+    let vertex_count = config.vertex_count as usize;
+    let chunk_world_x = chunk.coord.0 as f32 * config.chunk_size as f32 * config.cell_size_meters;
+    let chunk_world_z = chunk.coord.1 as f32 * config.chunk_size as f32 * config.cell_size_meters;
+
+    chunk.heights.iter().enumerate()
+        .map(|(idx, &height)| {
+            if height <= config.sea_level {
+                return 0;
+            }
+            let world_x = chunk_world_x + (idx % vertex_count) as f32 * config.cell_size_meters;
+            let world_z = chunk_world_z + (idx / vertex_count) as f32 * config.cell_size_meters;
+            let world_y = height * config.max_elevation - CAVE_SAMPLE_DEPTH;
+            let value = caves.get([world_x as f64, world_y as f64, world_z as f64]) as f32;
+            (value > threshold) as u8
+        })
+        .collect()
+}
+
+/// Lowest and highest height across the chunks
+fn height_range(chunks: &[HeightmapChunk]) -> (f32, f32) {
     let mut min_height = f32::MAX;
     let mut max_height = f32::MIN;
 
-    for chunk in chunks.iter() {
+    for chunk in chunks {
         for &h in &chunk.heights {
             min_height = min_height.min(h);
             max_height = max_height.max(h);
         }
     }
 
+    (min_height, max_height)
+}
+
+/// Apply post-processing to normalize and enhance terrain
+/// Returns the (min, max) raw heights the terrain was normalized by
+#[tracing::instrument(skip_all)]
+pub fn post_process_terrain(chunks: &mut [HeightmapChunk], config: &TerrainConfig, style: TerrainStyle) -> (f32, f32) {
+    let range = height_range(chunks);
+    post_process_with_range(chunks, config, style, range);
+    range
+}
+
+/// Post-process chunks normalized by a given raw (min, max), e.g. the range of the
+/// whole world a few regenerated chunks belong to
+pub fn post_process_with_range(
+    chunks: &mut [HeightmapChunk],
+    config: &TerrainConfig,
+    style: TerrainStyle,
+    (min_height, max_height): (f32, f32),
+) {
     // Normalize to full 0-1 range
     let range = max_height - min_height;
     if range > 0.0 {
@@ -354,6 +580,7 @@ pub fn post_process_terrain(chunks: &mut [HeightmapChunk], config: &TerrainConfi
                     // Highlands - dramatic peaks
                     0.3_f32.powf(0.6) + 0.3_f32.powf(0.8) * 0.4 + (land_height - 0.6).powf(1.5) * 0.4
                 };
+                let adjusted = style.reshape_land(adjusted);
                 
                 // Boost land elevation to create clear continents
                 height = sea_level + adjusted * (1.0 - sea_level) * 1.2;
@@ -363,3 +590,225 @@ pub fn post_process_terrain(chunks: &mut [HeightmapChunk], config: &TerrainConfi
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terrain::config::WorldTheme;
+    use std::sync::{Arc, Mutex};
+    use tracing_subscriber::layer::{Context, Layer};
+    use tracing_subscriber::prelude::*;
+
+    /// Records the name of every span opened while installed
+    struct SpanRecorder(Arc<Mutex<Vec<String>>>);
+
+    impl<S: tracing::Subscriber> Layer<S> for SpanRecorder {
+        fn on_new_span(&self, attrs: &tracing::span::Attributes<'_>, _id: &tracing::span::Id, _ctx: Context<'_, S>) {
+            self.0.lock().unwrap().push(attrs.metadata().name().to_string());
+        }
+    }
+
+    #[test]
+    fn test_extreme_octaves_are_clamped() {
+        use crate::terrain::commands::{MAX_NOISE_OCTAVES, MAX_NOISE_FREQUENCY};
+
+        let params = NoiseParameters {
+            continent_octaves: 100,
+            mountain_octaves: 1000,
+            hill_octaves: 0,
+            detail_frequency: 10.0,
+            ..Default::default()
+        };
+        let clamped = params.clamped();
+        assert_eq!(clamped.continent_octaves, MAX_NOISE_OCTAVES);
+        assert_eq!(clamped.mountain_octaves, MAX_NOISE_OCTAVES);
+        assert_eq!(clamped.hill_octaves, 1);
+        assert_eq!(clamped.detail_frequency, MAX_NOISE_FREQUENCY);
+
+        // Flat-world requests pass through untouched
+        let flat = NoiseParameters {
+            continent_frequency: 0.0,
+            mountain_frequency: 0.0,
+            hill_frequency: 0.0,
+            detail_frequency: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(flat.clamped().continent_octaves, flat.continent_octaves);
+
+        let config = TerrainConfig::new(256, 256, 3, WorldTheme::Fantasy);
+        let start = std::time::Instant::now();
+        let chunks = generate_terrain_with_params(&config, &params);
+        assert_eq!(chunks.len(), 4);
+        assert!(start.elapsed() < std::time::Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_generation_emits_stage_spans() {
+        let spans = Arc::new(Mutex::new(Vec::new()));
+        let subscriber = tracing_subscriber::registry().with(SpanRecorder(spans.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let config = TerrainConfig::new(256, 256, 11, WorldTheme::Fantasy);
+            let mut chunks = generate_terrain_with_params(&config, &NoiseParameters::default());
+            post_process_terrain(&mut chunks, &config, TerrainStyle::Rolling);
+        });
+
+        let spans = spans.lock().unwrap();
+        assert!(spans.iter().any(|s| s == "generate_terrain_with_params"), "spans: {:?}", spans);
+        assert!(spans.iter().any(|s| s == "post_process_terrain"), "spans: {:?}", spans);
+    }
+
+    #[test]
+    fn test_layer_persistence_changes_roughness() {
+        let config = TerrainConfig::new(256, 256, 5, WorldTheme::Fantasy);
+        let variance = |persistence: f64| {
+            let params = NoiseParameters {
+                land_coverage: Some(1.0),
+                mountain_persistence: persistence,
+                hill_persistence: persistence,
+                detail_persistence: persistence,
+                ..Default::default()
+            };
+            let heights = generate_chunk_with_params(&config, &params, 0, 0).heights;
+            let mean = heights.iter().sum::<f32>() / heights.len() as f32;
+            heights.iter().map(|h| (h - mean).powi(2)).sum::<f32>() / heights.len() as f32
+        };
+
+        assert_ne!(variance(0.1), variance(0.9));
+
+        // Requests saved before the fields existed still deserialize with the old hard-coded values
+        let legacy: NoiseParameters = serde_json::from_str(r#"{
+            "continent_frequency": 0.00005, "continent_octaves": 3,
+            "mountain_frequency": 0.0002, "mountain_octaves": 4,
+            "hill_frequency": 0.0005, "hill_octaves": 3,
+            "detail_frequency": 0.001, "detail_octaves": 2
+        }"#).unwrap();
+        assert_eq!(legacy.hill_persistence, 0.4);
+        assert_eq!(legacy.detail_lacunarity, 2.5);
+    }
+
+    #[test]
+    fn test_noise_backend_changes_output() {
+        let config = TerrainConfig::new(256, 256, 9, WorldTheme::Fantasy);
+        let heights = |noise_backend: NoiseBackend| {
+            let params = NoiseParameters { noise_backend, ..Default::default() };
+            generate_chunk_with_params(&config, &params, 0, 0).heights
+        };
+
+        let perlin = heights(NoiseBackend::Perlin);
+        assert_eq!(perlin, heights(NoiseBackend::Perlin));
+        assert_ne!(perlin, heights(NoiseBackend::OpenSimplex));
+        assert_ne!(perlin, heights(NoiseBackend::Simplex));
+    }
+
+    #[test]
+    fn test_zero_warp_strength_is_unchanged() {
+        let config = TerrainConfig::new(256, 256, 13, WorldTheme::Fantasy);
+        let heights = |warp_strength: f32| {
+            let params = NoiseParameters { warp_strength, warp_frequency: 0.0003, ..Default::default() };
+            generate_chunk_with_params(&config, &params, 1, 1).heights
+        };
+
+        let baseline = generate_chunk_with_params(&config, &NoiseParameters::default(), 1, 1).heights;
+        assert_eq!(heights(0.0), baseline);
+        assert_ne!(heights(20_000.0), baseline);
+    }
+
+    #[test]
+    fn test_wrapped_world_edges_match() {
+        let mut config = TerrainConfig::new(256, 256, 21, WorldTheme::Fantasy);
+        config.wrap = true;
+        let params = NoiseParameters { land_coverage: Some(0.7), ..Default::default() };
+        let chunks = generate_terrain_with_params(&config, &params);
+
+        let mut terrain = crate::terrain::TerrainData::new(config.clone());
+        for chunk in chunks {
+            terrain.chunks.insert(chunk.coord, chunk);
+        }
+        let heights = terrain.flatten_heights();
+
+        let width = config.world_width as usize;
+        let height = config.world_height as usize;
+        for z in 0..height {
+            let (west, east) = (heights[z * width], heights[z * width + width - 1]);
+            assert!((west - east).abs() < 1e-4, "row {}: {} vs {}", z, west, east);
+        }
+        for x in 0..width {
+            let (north, south) = (heights[x], heights[(height - 1) * width + x]);
+            assert!((north - south).abs() < 1e-4, "column {}: {} vs {}", x, north, south);
+        }
+    }
+
+    #[test]
+    fn test_wrapped_world_without_params_still_tiles() {
+        let mut config = TerrainConfig::new(256, 256, 8, WorldTheme::Fantasy);
+        config.wrap = true;
+        let mut chunks = generate_base_terrain(&config);
+        post_process_terrain(&mut chunks, &config, TerrainStyle::Alpine);
+
+        let mut terrain = crate::terrain::TerrainData::new(config.clone());
+        for chunk in chunks {
+            terrain.chunks.insert(chunk.coord, chunk);
+        }
+        let heights = terrain.flatten_heights();
+
+        let width = config.world_width as usize;
+        for z in 0..config.world_height as usize {
+            let (west, east) = (heights[z * width], heights[z * width + width - 1]);
+            assert!((west - east).abs() < 1e-4, "row {}: {} vs {}", z, west, east);
+        }
+    }
+
+    #[test]
+    fn test_mesa_style_forms_plateaus() {
+        let config = TerrainConfig::new(129, 129, 1, WorldTheme::Fantasy);
+        // A smooth ramp from ocean floor to summit
+        let ramp: Vec<f32> = (0..129 * 129).map(|i| i as f32 / (129.0 * 129.0)).collect();
+        let distinct_heights = |style: TerrainStyle| {
+            let mut chunks = vec![HeightmapChunk::from_heights((0, 0), ramp.clone())];
+            post_process_terrain(&mut chunks, &config, style);
+            let mut bits: Vec<u32> = chunks[0].heights.iter()
+                .filter(|&&h| h > config.sea_level)
+                .map(|h| h.to_bits())
+                .collect();
+            bits.sort_unstable();
+            bits.dedup();
+            bits.len()
+        };
+
+        let rolling = distinct_heights(TerrainStyle::Rolling);
+        let mesa = distinct_heights(TerrainStyle::Mesa { bands: 5 });
+        assert!(mesa * 4 < rolling, "mesa {} vs rolling {}", mesa, rolling);
+    }
+
+    #[test]
+    fn test_preview_respects_land_coverage() {
+        let config = TerrainConfig::new(2048, 2048, 7, WorldTheme::Fantasy);
+        let land_pixels = |coverage: f32| {
+            let params = NoiseParameters {
+                land_coverage: Some(coverage),
+                ..Default::default()
+            };
+            let preview = preview_terrain(&config, &params, 64);
+            assert_eq!(preview.len(), 64 * 64);
+            preview.iter().filter(|&&h| h > config.sea_level).count()
+        };
+
+        assert!(land_pixels(0.7) > land_pixels(0.3));
+    }
+
+    #[test]
+    fn test_cave_density_controls_coverage() {
+        let config = TerrainConfig::new(128, 128, 9, WorldTheme::Fantasy);
+        let chunk = generate_terrain_simd(&config).remove(0);
+        let cave_cells = |density: f32| {
+            let mask = generate_cave_mask(&config, &chunk, density);
+            assert_eq!(mask.len(), chunk.heights.len());
+            mask.iter().filter(|&&cave| cave == 1).count()
+        };
+
+        let sparse = cave_cells(0.1);
+        let dense = cave_cells(0.9);
+        assert!(dense > sparse, "dense {} vs sparse {}", dense, sparse);
+    }
+}
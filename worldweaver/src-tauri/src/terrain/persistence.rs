@@ -1,12 +1,24 @@
-use rusqlite::{Connection, params};
-use std::path::Path;
+use rusqlite::{Connection, OpenFlags, OptionalExtension, params};
+use serde::{Serialize, Deserialize};
+use std::path::{Path, PathBuf};
 use super::heightmap::HeightmapChunk;
 use super::rivers::RiverSegment;
+use super::roads::Road;
 use super::config::TerrainConfig;
+use super::biomes::BiomeRegistry;
+use super::resources::{Deposit, ResourceType};
 use anyhow::{Result, Context};
 
+/// Version written by this build; bump it and add a step to `migrate` for schema changes
+const SCHEMA_VERSION: u32 = 4;
+
 /// SQL schema for terrain database
 const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS terrain_meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+
 CREATE TABLE IF NOT EXISTS terrain_config (
     key TEXT PRIMARY KEY,
     value TEXT NOT NULL
@@ -19,6 +31,9 @@ CREATE TABLE IF NOT EXISTS terrain_chunks (
     data BLOB NOT NULL,
     flow_data BLOB,
     biome_data BLOB,
+    feature_data BLOB,
+    cave_data BLOB,
+    locked_data BLOB,
     modified_at INTEGER NOT NULL,
     PRIMARY KEY (chunk_x, chunk_z, lod)
 );
@@ -30,9 +45,87 @@ CREATE TABLE IF NOT EXISTS river_segments (
     width_meters REAL NOT NULL
 );
 
+CREATE TABLE IF NOT EXISTS roads (
+    id INTEGER PRIMARY KEY,
+    path BLOB NOT NULL,
+    cost INTEGER NOT NULL,
+    bridges BLOB NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS deposits (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    x INTEGER NOT NULL,
+    y INTEGER NOT NULL,
+    resource TEXT NOT NULL,
+    richness REAL NOT NULL
+);
+
 CREATE INDEX IF NOT EXISTS idx_chunks_modified ON terrain_chunks(modified_at);
 "#;
 
+/// Write one road row; shared by single saves and the transactional full replace
+fn insert_road(conn: &Connection, id: u32, road: &Road) -> Result<()> {
+    let path_bytes = bincode::serialize(&road.path)?;
+    let bridge_bytes = bincode::serialize(&road.bridges)?;
+
+    conn.execute(
+        "INSERT OR REPLACE INTO roads (id, path, cost, bridges) VALUES (?1, ?2, ?3, ?4)",
+        params![id, path_bytes, road.cost, bridge_bytes],
+    )?;
+
+    Ok(())
+}
+
+/// Directory under the app data directory holding one `{name}.db` file per saved world
+pub const WORLDS_DIR: &str = "worlds";
+
+/// A saved world file and the configuration stored in it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedWorld {
+    pub name: String,
+    pub config: TerrainConfig,
+}
+
+/// Path of the database for world `name` inside `dir`
+/// Names are limited to letters, digits, '-' and '_' so they can't escape the directory
+pub fn world_path(dir: &Path, name: &str) -> Result<PathBuf> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        anyhow::bail!("Invalid world name '{}': use letters, digits, '-' or '_'", name);
+    }
+    Ok(dir.join(format!("{}.db", name)))
+}
+
+/// Every world saved in `dir` with a readable config, sorted by name
+/// Files are opened read-only, so listing never creates tables in or migrates them
+/// A missing directory simply means nothing has been saved yet
+pub fn list_saved_worlds(dir: &Path) -> Result<Vec<SavedWorld>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut worlds = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("db") {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        // Skip files that aren't terrain databases or were never saved to
+        let Ok(config) = TerrainDatabase::open_read_only(&path).and_then(|db| db.load_config()) else {
+            continue;
+        };
+        worlds.push(SavedWorld { name: name.to_string(), config });
+    }
+    worlds.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(worlds)
+}
+
+/// Raw chunk columns: (heights, flow, biomes, features, caves, locks)
+type ChunkRow = (Vec<u8>, Option<Vec<u8>>, Option<Vec<u8>>, Option<Vec<u8>>, Option<Vec<u8>>, Option<Vec<u8>>);
+
 /// Terrain database manager
 pub struct TerrainDatabase {
     conn: Connection,
@@ -41,11 +134,101 @@ pub struct TerrainDatabase {
 impl TerrainDatabase {
     /// Create or open terrain database
     pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let conn = Connection::open(path)?;
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    /// Open an existing database without creating tables or migrating it
+    /// Only good for reading what every schema version has, such as the config
+    pub fn open_read_only<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        Ok(Self { conn })
+    }
+
+    /// Create missing tables and upgrade older databases to the current schema
+    fn from_connection(conn: Connection) -> Result<Self> {
         conn.execute_batch(SCHEMA)?;
+        Self::migrate(&conn)?;
         Ok(Self { conn })
     }
 
+    /// Stored schema version; databases from before versioning have none and read as 0
+    fn schema_version(conn: &Connection) -> Result<u32> {
+        let version: Option<String> = conn
+            .query_row("SELECT value FROM terrain_meta WHERE key = 'schema_version'", [], |row| row.get(0))
+            .optional()?;
+        match version {
+            Some(version) => version.parse().context("Invalid schema_version in terrain_meta"),
+            None => Ok(0),
+        }
+    }
+
+    /// Run every forward migration between the stored version and SCHEMA_VERSION
+    /// The steps and the version bump share one transaction, so an interrupted upgrade
+    /// leaves the old version and data in place to be retried
+    fn migrate(conn: &Connection) -> Result<()> {
+        let version = Self::schema_version(conn)?;
+        if version > SCHEMA_VERSION {
+            anyhow::bail!(
+                "Terrain database schema version {} is newer than this build supports ({})",
+                version, SCHEMA_VERSION
+            );
+        }
+        if version == SCHEMA_VERSION {
+            return Ok(());
+        }
+
+        let tx = conn.unchecked_transaction()?;
+        let conn = &*tx;
+
+        // v0 -> v1: columns added to terrain_chunks after release (new tables such as
+        // roads are already created by SCHEMA)
+        if version < 1 {
+            for column in ["biome_data", "feature_data"] {
+                if !Self::has_column(conn, "terrain_chunks", column)? {
+                    conn.execute(&format!("ALTER TABLE terrain_chunks ADD COLUMN {} BLOB", column), [])?;
+                }
+            }
+        }
+
+        // v1 -> v2: biome_data went from raw bytes to zstd
+        if version < 2 {
+            let raw_biomes = conn
+                .prepare("SELECT rowid, biome_data FROM terrain_chunks WHERE biome_data IS NOT NULL")?
+                .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, Vec<u8>>(1)?)))?
+                .collect::<Result<Vec<_>, _>>()?;
+            for (rowid, biomes) in raw_biomes {
+                conn.execute(
+                    "UPDATE terrain_chunks SET biome_data = ?1 WHERE rowid = ?2",
+                    params![zstd::encode_all(&biomes[..], 3)?, rowid],
+                )?;
+            }
+        }
+
+        // v2 -> v3: cave mask channel
+        if version < 3 && !Self::has_column(conn, "terrain_chunks", "cave_data")? {
+            conn.execute("ALTER TABLE terrain_chunks ADD COLUMN cave_data BLOB", [])?;
+        }
+
+        // v3 -> v4: erosion lock mask
+        if version < 4 && !Self::has_column(conn, "terrain_chunks", "locked_data")? {
+            conn.execute("ALTER TABLE terrain_chunks ADD COLUMN locked_data BLOB", [])?;
+        }
+
+        conn.execute(
+            "INSERT OR REPLACE INTO terrain_meta (key, value) VALUES ('schema_version', ?1)",
+            params![SCHEMA_VERSION.to_string()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn has_column(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+        let exists = conn
+            .prepare("SELECT 1 FROM pragma_table_info(?1) WHERE name = ?2")?
+            .exists(params![table, column])?;
+        Ok(exists)
+    }
+
     /// Save terrain configuration
     pub fn save_config(&self, config: &TerrainConfig) -> Result<()> {
         let config_json = serde_json::to_string(config)?;
@@ -67,6 +250,28 @@ impl TerrainDatabase {
         Ok(config)
     }
 
+    /// Save the biome registry, so chunk biome ids keep resolving to custom biomes
+    pub fn save_biome_registry(&self, registry: &BiomeRegistry) -> Result<()> {
+        let registry_json = serde_json::to_string(registry)?;
+        self.conn.execute(
+            "INSERT OR REPLACE INTO terrain_config (key, value) VALUES (?1, ?2)",
+            params!["biomes", registry_json],
+        )?;
+        Ok(())
+    }
+
+    /// Load the biome registry, or None for worlds saved before it was stored
+    pub fn load_biome_registry(&self) -> Result<Option<BiomeRegistry>> {
+        let registry_json: Option<String> = self.conn.query_row(
+            "SELECT value FROM terrain_config WHERE key = ?1",
+            params!["biomes"],
+            |row| row.get(0),
+        ).optional()?;
+        registry_json
+            .map(|json| serde_json::from_str(&json).context("Invalid biome registry"))
+            .transpose()
+    }
+
     /// Save a chunk to database with zstd compression
     pub fn save_chunk(&self, chunk: &HeightmapChunk) -> Result<()> {
         // Serialize heights to bytes
@@ -88,15 +293,40 @@ impl TerrainDatabase {
             None
         };
 
-        // Compress biome data if present
-        let biome_compressed = chunk.biome_ids.as_ref().map(|b| b.clone());
+        // Biome IDs come in long runs of the same value, so they compress well
+        let biome_compressed = if let Some(ref biomes) = chunk.biome_ids {
+            Some(zstd::encode_all(&biomes[..], 3)?)
+        } else {
+            None
+        };
+
+        // Feature markers are mostly zero, so they compress well
+        let feature_compressed = if let Some(ref features) = chunk.feature_ids {
+            Some(zstd::encode_all(&features[..], 3)?)
+        } else {
+            None
+        };
+
+        let cave_compressed = if let Some(ref caves) = chunk.cave_mask {
+            Some(zstd::encode_all(&caves[..], 3)?)
+        } else {
+            None
+        };
+
+        // One byte per vertex; locks are painted in blobs, so the runs compress well
+        let locked_compressed = if let Some(ref locked) = chunk.locked {
+            let locked_bytes: Vec<u8> = locked.iter().map(|&l| l as u8).collect();
+            Some(zstd::encode_all(&locked_bytes[..], 3)?)
+        } else {
+            None
+        };
 
         let now = chrono::Utc::now().timestamp();
 
         self.conn.execute(
             "INSERT OR REPLACE INTO terrain_chunks 
-             (chunk_x, chunk_z, lod, data, flow_data, biome_data, modified_at) 
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+             (chunk_x, chunk_z, lod, data, flow_data, biome_data, feature_data, cave_data, locked_data, modified_at) 
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
             params![
                 chunk.coord.0,
                 chunk.coord.1,
@@ -104,6 +334,9 @@ impl TerrainDatabase {
                 compressed,
                 flow_compressed,
                 biome_compressed,
+                feature_compressed,
+                cave_compressed,
+                locked_compressed,
                 now,
             ],
         )?;
@@ -111,14 +344,23 @@ impl TerrainDatabase {
         Ok(())
     }
 
+    /// Save a full-resolution chunk together with its downsampled LOD rows
+    pub fn save_chunk_with_lods(&self, chunk: &HeightmapChunk, vertex_count: u32) -> Result<()> {
+        self.save_chunk(chunk)?;
+        for lod_chunk in chunk.generate_lods(vertex_count) {
+            self.save_chunk(&lod_chunk)?;
+        }
+        Ok(())
+    }
+
     /// Load a chunk from database
     pub fn load_chunk(&self, chunk_x: i32, chunk_z: i32, lod: u8) -> Result<HeightmapChunk> {
-        let (compressed, flow_compressed, biome_data): (Vec<u8>, Option<Vec<u8>>, Option<Vec<u8>>) = 
+        let (compressed, flow_compressed, biome_compressed, feature_compressed, cave_compressed, locked_compressed): ChunkRow = 
             self.conn.query_row(
-                "SELECT data, flow_data, biome_data FROM terrain_chunks 
+                "SELECT data, flow_data, biome_data, feature_data, cave_data, locked_data FROM terrain_chunks 
                  WHERE chunk_x = ?1 AND chunk_z = ?2 AND lod = ?3",
                 params![chunk_x, chunk_z, lod],
-                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?)),
             )?;
 
         // Decompress heights
@@ -142,15 +384,79 @@ impl TerrainDatabase {
             None
         };
 
+        // Decompress biome data if present; one ID per height, so a length mismatch
+        // means the column holds some other format
+        let biome_ids = if let Some(biome_comp) = biome_compressed {
+            let biomes = zstd::decode_all(&biome_comp[..])
+                .context("Failed to decompress biome data")?;
+            if biomes.len() != heights.len() {
+                anyhow::bail!(
+                    "Chunk ({}, {}) has {} biome IDs for {} heights",
+                    chunk_x, chunk_z, biomes.len(), heights.len()
+                );
+            }
+            Some(biomes)
+        } else {
+            None
+        };
+
+        let feature_ids = match feature_compressed {
+            Some(feature_comp) => Some(zstd::decode_all(&feature_comp[..])
+                .context("Failed to decompress feature data")?),
+            None => None,
+        };
+
+        let cave_mask = match cave_compressed {
+            Some(cave_comp) => Some(zstd::decode_all(&cave_comp[..])
+                .context("Failed to decompress cave data")?),
+            None => None,
+        };
+
+        let locked = match locked_compressed {
+            Some(locked_comp) => Some(zstd::decode_all(&locked_comp[..])
+                .context("Failed to decompress lock data")?
+                .into_iter()
+                .map(|byte| byte != 0)
+                .collect()),
+            None => None,
+        };
+
         Ok(HeightmapChunk {
             coord: (chunk_x, chunk_z),
             heights,
             lod,
             flow_accumulation,
-            biome_ids: biome_data,
+            biome_ids,
+            feature_ids,
+            locked,
+            cave_mask,
+            bounds: None,
         })
     }
 
+    /// Load the full-resolution chunks within an inclusive chunk rectangle
+    pub fn load_chunks_in_region(&self, min_chunk: (i32, i32), max_chunk: (i32, i32)) -> Result<Vec<HeightmapChunk>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT chunk_x, chunk_z FROM terrain_chunks
+             WHERE lod = 0 AND chunk_x BETWEEN ?1 AND ?2 AND chunk_z BETWEEN ?3 AND ?4
+             ORDER BY chunk_z, chunk_x",
+        )?;
+        let coords = stmt.query_map(
+            params![
+                min_chunk.0.min(max_chunk.0),
+                min_chunk.0.max(max_chunk.0),
+                min_chunk.1.min(max_chunk.1),
+                min_chunk.1.max(max_chunk.1),
+            ],
+            |row| Ok((row.get::<_, i32>(0)?, row.get::<_, i32>(1)?)),
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        coords.into_iter()
+            .map(|(chunk_x, chunk_z)| self.load_chunk(chunk_x, chunk_z, 0))
+            .collect()
+    }
+
     /// Check if a chunk exists
     pub fn chunk_exists(&self, chunk_x: i32, chunk_z: i32, lod: u8) -> Result<bool> {
         let count: i64 = self.conn.query_row(
@@ -195,18 +501,106 @@ impl TerrainDatabase {
             let path: Vec<(f32, f32)> = bincode::deserialize(&path_bytes)
                 .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
 
-            Ok(RiverSegment {
-                id,
-                path,
-                strahler_order,
-                width_meters,
-            })
+            Ok(RiverSegment::new(id, path, strahler_order, width_meters))
         })?
         .collect::<Result<Vec<_>, _>>()?;
 
         Ok(segments)
     }
 
+    /// Save a road under the given id, replacing any road already stored there
+    pub fn save_road(&self, id: u32, road: &Road) -> Result<()> {
+        insert_road(&self.conn, id, road)
+    }
+
+    /// Replace all stored roads in one transaction, numbered in slice order
+    pub fn save_roads(&mut self, roads: &[Road]) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM roads", [])?;
+        for (id, road) in roads.iter().enumerate() {
+            insert_road(&tx, id as u32, road)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Delete every stored road
+    pub fn clear_roads(&self) -> Result<()> {
+        self.conn.execute("DELETE FROM roads", [])?;
+        Ok(())
+    }
+
+    /// Load all roads in id order
+    pub fn load_roads(&self) -> Result<Vec<Road>> {
+        let mut stmt = self.conn.prepare("SELECT path, cost, bridges FROM roads ORDER BY id")?;
+
+        let roads = stmt.query_map([], |row| {
+            let path_bytes: Vec<u8> = row.get(0)?;
+            let cost: u32 = row.get(1)?;
+            let bridge_bytes: Vec<u8> = row.get(2)?;
+
+            let path = bincode::deserialize(&path_bytes)
+                .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+            let bridges = bincode::deserialize(&bridge_bytes)
+                .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+
+            Ok(Road { path, cost, bridges })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(roads)
+    }
+
+    /// Replace all stored resource deposits
+    pub fn save_deposits(&mut self, deposits: &[Deposit]) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM deposits", [])?;
+        for deposit in deposits {
+            tx.execute(
+                "INSERT INTO deposits (x, y, resource, richness) VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    deposit.x as i64,
+                    deposit.y as i64,
+                    deposit.resource.to_string(),
+                    deposit.richness,
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Load all resource deposits
+    pub fn load_deposits(&self) -> Result<Vec<Deposit>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT x, y, resource, richness FROM deposits ORDER BY id"
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, f32>(3)?,
+            ))
+        })?;
+
+        let mut deposits = Vec::new();
+        for row in rows {
+            let (x, y, resource, richness) = row?;
+            let resource = ResourceType::parse(&resource)
+                .with_context(|| format!("Unknown resource type: {}", resource))?;
+            deposits.push(Deposit {
+                x: x as usize,
+                y: y as usize,
+                resource,
+                richness,
+            });
+        }
+
+        Ok(deposits)
+    }
+
     /// Delete all chunks (for regeneration)
     pub fn clear_chunks(&self) -> Result<()> {
         self.conn.execute("DELETE FROM terrain_chunks", [])?;
@@ -219,13 +613,272 @@ impl TerrainDatabase {
         Ok(())
     }
 
-    /// Get chunk count
+    /// Get chunk count (full-resolution rows only)
     pub fn get_chunk_count(&self) -> Result<i64> {
         let count: i64 = self.conn.query_row(
-            "SELECT COUNT(*) FROM terrain_chunks",
+            "SELECT COUNT(*) FROM terrain_chunks WHERE lod = 0",
             [],
             |row| row.get(0),
         )?;
         Ok(count)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terrain::heightmap::TerrainFeature;
+
+    #[test]
+    fn test_feature_layer_round_trip() {
+        let db = TerrainDatabase::new(":memory:").unwrap();
+        let vertex_count = 17;
+        let mut chunk = HeightmapChunk::new((2, 3), vertex_count);
+        chunk.mark_feature(4.0, 4.0, 1.5, TerrainFeature::Cave, vertex_count);
+        chunk.mark_feature(12.0, 12.0, 0.0, TerrainFeature::Ruin, vertex_count);
+
+        db.save_chunk(&chunk).unwrap();
+        let loaded = db.load_chunk(2, 3, 0).unwrap();
+
+        assert_eq!(loaded.feature_ids, chunk.feature_ids);
+        let features = loaded.feature_ids.unwrap();
+        assert_eq!(TerrainFeature::from_code(features[4 * 17 + 4]), TerrainFeature::Cave);
+        assert_eq!(TerrainFeature::from_code(features[12 * 17 + 12]), TerrainFeature::Ruin);
+        assert_eq!(TerrainFeature::from_code(features[0]), TerrainFeature::None);
+
+        // Chunks without markers stay unallocated
+        db.save_chunk(&HeightmapChunk::new((0, 0), vertex_count)).unwrap();
+        assert!(db.load_chunk(0, 0, 0).unwrap().feature_ids.is_none());
+    }
+
+    #[test]
+    fn test_cave_mask_round_trip() {
+        let db = TerrainDatabase::new(":memory:").unwrap();
+        let mut chunk = HeightmapChunk::new((1, 0), 17);
+        chunk.cave_mask = Some((0..17 * 17).map(|i| (i % 5 == 0) as u8).collect());
+
+        db.save_chunk(&chunk).unwrap();
+
+        assert_eq!(db.load_chunk(1, 0, 0).unwrap().cave_mask, chunk.cave_mask);
+    }
+
+    #[test]
+    fn test_locked_mask_round_trip() {
+        let db = TerrainDatabase::new(":memory:").unwrap();
+        let mut chunk = HeightmapChunk::new((0, 2), 17);
+        chunk.locked = Some((0..17 * 17).map(|i| i % 17 < 8).collect());
+
+        db.save_chunk(&chunk).unwrap();
+
+        assert_eq!(db.load_chunk(0, 2, 0).unwrap().locked, chunk.locked);
+    }
+
+    #[test]
+    fn test_save_writes_lod_rows() {
+        let db = TerrainDatabase::new(":memory:").unwrap();
+        let vertex_count = 129;
+        let chunk = HeightmapChunk::new((1, 2), vertex_count);
+
+        db.save_chunk_with_lods(&chunk, vertex_count).unwrap();
+
+        assert!(db.chunk_exists(1, 2, 1).unwrap());
+        assert!(db.chunk_exists(1, 2, 3).unwrap());
+        let coarse = db.load_chunk(1, 2, 1).unwrap();
+        assert_eq!(coarse.lod, 1);
+        assert!(coarse.heights.len() < chunk.heights.len());
+        assert_eq!(db.get_chunk_count().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_region_load_only_reads_requested_chunks() {
+        use crate::terrain::TerrainData;
+        use crate::terrain::config::{TerrainConfig, WorldTheme};
+
+        let config = TerrainConfig::new(512, 512, 1, WorldTheme::Fantasy);
+        let vertex_count = config.vertex_count;
+        let db = TerrainDatabase::new(":memory:").unwrap();
+        for chunk_z in 0..4 {
+            for chunk_x in 0..4 {
+                db.save_chunk(&HeightmapChunk::new((chunk_x, chunk_z), vertex_count)).unwrap();
+            }
+        }
+
+        let mut terrain = TerrainData::empty(config);
+        for chunk in db.load_chunks_in_region((1, 1), (2, 2)).unwrap() {
+            terrain.chunks.insert(chunk.coord, chunk);
+        }
+
+        let mut coords: Vec<_> = terrain.chunks.keys().copied().collect();
+        coords.sort();
+        assert_eq!(coords, vec![(1, 1), (1, 2), (2, 1), (2, 2)]);
+        assert!(terrain.sample_height(10.0, 10.0).is_none());
+    }
+
+    #[test]
+    fn test_biome_layer_compresses_and_round_trips() {
+        let db = TerrainDatabase::new(":memory:").unwrap();
+        let vertex_count = 65;
+        let mut chunk = HeightmapChunk::new((1, 1), vertex_count);
+        chunk.biome_ids = Some(vec![7; (vertex_count * vertex_count) as usize]);
+
+        db.save_chunk(&chunk).unwrap();
+
+        let stored: i64 = db.conn
+            .query_row("SELECT length(biome_data) FROM terrain_chunks", [], |row| row.get(0))
+            .unwrap();
+        assert!((stored as usize) < chunk.biome_ids.as_ref().unwrap().len() / 10);
+        assert_eq!(db.load_chunk(1, 1, 0).unwrap().biome_ids, chunk.biome_ids);
+    }
+
+    #[test]
+    fn test_biome_ids_load_byte_identical() {
+        let db = TerrainDatabase::new(":memory:").unwrap();
+        let vertex_count = 17;
+        let mut chunk = HeightmapChunk::new((0, 2), vertex_count);
+        chunk.biome_ids = Some((0..vertex_count * vertex_count).map(|i| (i % 13) as u8).collect());
+
+        db.save_chunk(&chunk).unwrap();
+
+        assert_eq!(db.load_chunk(0, 2, 0).unwrap().biome_ids, chunk.biome_ids);
+
+        // Biomes that don't line up with the heights are rejected rather than misread
+        db.conn.execute(
+            "UPDATE terrain_chunks SET biome_data = ?1",
+            params![zstd::encode_all(&[1u8, 2, 3][..], 3).unwrap()],
+        ).unwrap();
+        assert!(db.load_chunk(0, 2, 0).is_err());
+    }
+
+    #[test]
+    fn test_v0_database_upgrades_without_data_loss() {
+        // Layout of terrain.db before biome/feature columns, roads, or versioning
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE terrain_chunks (
+                chunk_x INTEGER NOT NULL,
+                chunk_z INTEGER NOT NULL,
+                lod INTEGER NOT NULL DEFAULT 0,
+                data BLOB NOT NULL,
+                flow_data BLOB,
+                modified_at INTEGER NOT NULL,
+                PRIMARY KEY (chunk_x, chunk_z, lod)
+            );",
+        ).unwrap();
+        let heights: Vec<u8> = [0.25f32, 0.5, 0.75, 1.0].iter().flat_map(|h| h.to_le_bytes()).collect();
+        conn.execute(
+            "INSERT INTO terrain_chunks (chunk_x, chunk_z, lod, data, modified_at) VALUES (4, 5, 0, ?1, 0)",
+            params![zstd::encode_all(&heights[..], 3).unwrap()],
+        ).unwrap();
+
+        let db = TerrainDatabase::from_connection(conn).unwrap();
+
+        assert_eq!(TerrainDatabase::schema_version(&db.conn).unwrap(), SCHEMA_VERSION);
+        let chunk = db.load_chunk(4, 5, 0).unwrap();
+        assert_eq!(chunk.heights, vec![0.25, 0.5, 0.75, 1.0]);
+        assert!(chunk.biome_ids.is_none() && chunk.feature_ids.is_none() && chunk.cave_mask.is_none());
+        assert!(chunk.locked.is_none());
+        assert!(db.load_roads().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_v1_biomes_are_recompressed_with_version_bump() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(SCHEMA).unwrap();
+        conn.execute("INSERT INTO terrain_meta (key, value) VALUES ('schema_version', '1')", []).unwrap();
+        let heights: Vec<u8> = [0.5f32; 4].iter().flat_map(|h| h.to_le_bytes()).collect();
+        conn.execute(
+            "INSERT INTO terrain_chunks (chunk_x, chunk_z, lod, data, biome_data, modified_at) VALUES (0, 0, 0, ?1, ?2, 0)",
+            params![zstd::encode_all(&heights[..], 3).unwrap(), vec![3u8, 3, 5, 5]],
+        ).unwrap();
+
+        let db = TerrainDatabase::from_connection(conn).unwrap();
+
+        assert_eq!(TerrainDatabase::schema_version(&db.conn).unwrap(), SCHEMA_VERSION);
+        assert_eq!(db.load_chunk(0, 0, 0).unwrap().biome_ids, Some(vec![3, 3, 5, 5]));
+        // The migration's transaction was committed, not left open
+        assert!(db.conn.is_autocommit());
+    }
+
+    #[test]
+    fn test_named_worlds_are_independent() {
+        use crate::terrain::config::{TerrainConfig, WorldTheme};
+
+        let dir = std::env::temp_dir().join(format!("worldweaver-worlds-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        for (name, seed) in [("alpha", 1), ("beta", 2)] {
+            let db = TerrainDatabase::new(world_path(&dir, name).unwrap()).unwrap();
+            db.save_config(&TerrainConfig::new(256, 256, seed, WorldTheme::Fantasy)).unwrap();
+        }
+        // Some other file that happens to end in .db
+        let stray = dir.join("notes.db");
+        std::fs::write(&stray, b"").unwrap();
+
+        let worlds = list_saved_worlds(&dir).unwrap();
+        let stray_len = std::fs::metadata(&stray).unwrap().len();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let summary: Vec<_> = worlds.iter().map(|w| (w.name.as_str(), w.config.seed)).collect();
+        assert_eq!(summary, vec![("alpha", 1), ("beta", 2)]);
+        assert_eq!(stray_len, 0, "listing wrote to a file it doesn't own");
+        assert!(world_path(&dir, "../escape").is_err());
+    }
+
+    #[test]
+    fn test_biome_registry_round_trip() {
+        use crate::terrain::biomes::BiomeDefinition;
+        use crate::terrain::config::WorldTheme;
+
+        let db = TerrainDatabase::new(":memory:").unwrap();
+        assert!(db.load_biome_registry().unwrap().is_none());
+
+        let mut registry = BiomeRegistry::new();
+        let definition = BiomeDefinition {
+            name: "Salt Flats".to_string(),
+            color: [240, 235, 225],
+            theme_names: std::collections::HashMap::from([(WorldTheme::Fantasy, "White Waste".to_string())]),
+            climate: None,
+        };
+        let id = registry.register("SaltFlats".to_string(), definition).unwrap();
+        db.save_biome_registry(&registry).unwrap();
+
+        let loaded = db.load_biome_registry().unwrap().unwrap();
+        assert_eq!(loaded.id_of("saltflats"), Some(id));
+        assert_eq!(loaded.get_name_by_key("SaltFlats", WorldTheme::Fantasy), "White Waste");
+        assert_eq!(loaded.list().len(), registry.list().len());
+    }
+
+    #[test]
+    fn test_road_round_trip() {
+        let db = TerrainDatabase::new(":memory:").unwrap();
+        let road = Road {
+            path: vec![(3, 4), (4, 5), (5, 5), (9, 5)],
+            cost: 812,
+            bridges: vec![(2, 3)],
+        };
+
+        db.save_road(0, &road).unwrap();
+        let loaded = db.load_roads().unwrap();
+
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].path, road.path);
+        assert_eq!(loaded[0].cost, road.cost);
+        assert_eq!(loaded[0].bridges, road.bridges);
+
+        db.clear_roads().unwrap();
+        assert!(db.load_roads().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_save_roads_replaces_larger_network() {
+        let mut db = TerrainDatabase::new(":memory:").unwrap();
+        let road = |cost| Road { path: vec![(0, 0), (1, 1)], cost, bridges: Vec::new() };
+
+        db.save_roads(&[road(1), road(2), road(3)]).unwrap();
+        db.save_roads(&[road(7)]).unwrap();
+
+        let loaded = db.load_roads().unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].cost, 7);
+    }
+}
@@ -0,0 +1,196 @@
+use noise::{NoiseFn, Perlin};
+use serde::{Serialize, Deserialize};
+
+use super::biomes::{generate_temperature, whittaker_biome, Biome};
+use super::config::TerrainConfig;
+use super::rivers::RiverNetwork;
+
+/// Spacing between candidate deposit sites (cells)
+const DEPOSIT_GRID_SPACING: usize = 8;
+
+/// Noise value (normalized 0-1) a site must exceed to hold a deposit
+const DEPOSIT_THRESHOLD: f32 = 0.65;
+
+/// Distance (cells) from a river path that counts as riverside
+const RIVER_PROXIMITY: i32 = 3;
+
+/// Minable/harvestable resource types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ResourceType {
+    Iron,
+    Gold,
+    Stone,
+    Clay,
+    Timber,
+}
+
+impl ResourceType {
+    pub const ALL: [ResourceType; 5] = [
+        ResourceType::Iron,
+        ResourceType::Gold,
+        ResourceType::Stone,
+        ResourceType::Clay,
+        ResourceType::Timber,
+    ];
+
+    /// Whether this resource can occur at a site
+    /// Iron in mountains, gold in mountains or riverbeds, clay near rivers and coasts
+    fn allowed_at(&self, biome: Biome, elevation: f32, near_river: bool) -> bool {
+        if biome == Biome::Ocean {
+            return false;
+        }
+
+        match self {
+            ResourceType::Iron => matches!(biome, Biome::Alpine | Biome::Glacier) || elevation > 0.6,
+            ResourceType::Gold => matches!(biome, Biome::Alpine) || (near_river && elevation > 0.4),
+            ResourceType::Stone => biome != Biome::Coast,
+            ResourceType::Clay => near_river || biome == Biome::Coast,
+            ResourceType::Timber => matches!(
+                biome,
+                Biome::TemperateForest | Biome::BorealForest | Biome::TropicalRainforest
+            ),
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            ResourceType::Iron => "iron",
+            ResourceType::Gold => "gold",
+            ResourceType::Stone => "stone",
+            ResourceType::Clay => "clay",
+            ResourceType::Timber => "timber",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|r| r.as_str() == value)
+    }
+}
+
+impl std::fmt::Display for ResourceType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A resource deposit at a heightmap cell
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Deposit {
+    pub x: usize,
+    pub y: usize,
+    pub resource: ResourceType,
+    pub richness: f32, // 0.0 to 1.0
+}
+
+/// Place resource deposits deterministically from the terrain seed
+/// Each resource samples its own noise field; sites are gated by biome, elevation, and river proximity
+pub fn generate_resource_deposits(
+    config: &TerrainConfig,
+    heights: &[f32],
+    rivers: &RiverNetwork,
+    resource_types: &[ResourceType],
+) -> Vec<Deposit> {
+    let width = config.world_width as usize;
+    let height = config.world_height as usize;
+    if heights.len() < width * height {
+        return Vec::new();
+    }
+
+    let near_river = river_proximity_mask(rivers, width, height);
+    let moisture_noise = Perlin::new(config.seed.wrapping_add(50));
+    let mut deposits = Vec::new();
+
+    for (index, resource) in resource_types.iter().enumerate() {
+        let noise = Perlin::new(config.seed.wrapping_add(200 + index as u32 * 17));
+
+        for y in (0..height).step_by(DEPOSIT_GRID_SPACING) {
+            for x in (0..width).step_by(DEPOSIT_GRID_SPACING) {
+                let value = (noise.get([x as f64 * 0.05, y as f64 * 0.05]) as f32 + 1.0) * 0.5;
+                if value <= DEPOSIT_THRESHOLD {
+                    continue;
+                }
+
+                let elevation = heights[y * width + x];
+                let biome = biome_at(config, &moisture_noise, x, y, elevation);
+                if !resource.allowed_at(biome, elevation, near_river[y * width + x]) {
+                    continue;
+                }
+
+                deposits.push(Deposit {
+                    x,
+                    y,
+                    resource: *resource,
+                    richness: ((value - DEPOSIT_THRESHOLD) / (1.0 - DEPOSIT_THRESHOLD)).clamp(0.0, 1.0),
+                });
+            }
+        }
+    }
+
+    deposits
+}
+
+/// Classify the built-in biome at a cell using latitude temperature and noise-based moisture
+/// Deposit rules are written against the built-ins, so custom biomes don't apply here
+fn biome_at(config: &TerrainConfig, moisture_noise: &Perlin, x: usize, y: usize, elevation: f32) -> Biome {
+    let latitude = ((y as f32 / config.world_height as f32) - 0.5).abs() * 2.0;
+    let temperature = generate_temperature(elevation, latitude, config.max_elevation);
+    let moisture = (moisture_noise.get([x as f64 * 0.01, y as f64 * 0.01]) as f32 + 1.0) * 0.5;
+    whittaker_biome(elevation, temperature, moisture, config.sea_level, config.coast_width)
+}
+
+/// Mark cells within RIVER_PROXIMITY of any river path
+fn river_proximity_mask(rivers: &RiverNetwork, width: usize, height: usize) -> Vec<bool> {
+    let mut mask = vec![false; width * height];
+
+    for segment in &rivers.segments {
+        for &(px, pz) in &segment.path {
+            for dz in -RIVER_PROXIMITY..=RIVER_PROXIMITY {
+                for dx in -RIVER_PROXIMITY..=RIVER_PROXIMITY {
+                    let x = px as i32 + dx;
+                    let z = pz as i32 + dz;
+                    if x >= 0 && z >= 0 && (x as usize) < width && (z as usize) < height {
+                        mask[z as usize * width + x as usize] = true;
+                    }
+                }
+            }
+        }
+    }
+
+    mask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terrain::config::WorldTheme;
+
+    fn test_world() -> (TerrainConfig, Vec<f32>) {
+        let config = TerrainConfig::new(256, 256, 3, WorldTheme::Fantasy);
+        // Ocean on the left half, rising mountains on the right half
+        let heights = (0..256 * 256)
+            .map(|i| {
+                let x = i % 256;
+                if x < 128 { 0.1 } else { 0.3 + (x - 128) as f32 / 128.0 * 0.65 }
+            })
+            .collect();
+        (config, heights)
+    }
+
+    #[test]
+    fn test_deposits_respect_biome_gating_and_are_reproducible() {
+        let (config, heights) = test_world();
+        let rivers = RiverNetwork::new();
+
+        let deposits = generate_resource_deposits(&config, &heights, &rivers, &ResourceType::ALL);
+        assert!(!deposits.is_empty());
+
+        // No ore in the ocean
+        for deposit in &deposits {
+            assert!(heights[deposit.y * 256 + deposit.x] >= config.sea_level, "deposit in ocean: {:?}", deposit);
+        }
+        assert!(deposits.iter().any(|d| d.resource == ResourceType::Iron));
+
+        let again = generate_resource_deposits(&config, &heights, &rivers, &ResourceType::ALL);
+        assert_eq!(deposits, again);
+    }
+}
@@ -4,9 +4,21 @@ use serde::{Serialize, Deserialize};
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RiverSegment {
     pub id: u32,
-    pub path: Vec<(f32, f32)>,
+    pub path: Vec<(f32, f32)>,          // Cell centers the river flows through
     pub strahler_order: u8,
     pub width_meters: f32,
+    #[serde(default)]
+    pub smoothed_path: Vec<(f32, f32)>, // Spline through `path` for rendering
+}
+
+/// Spline samples per cell step when smoothing river paths
+const RIVER_SMOOTH_SAMPLES: usize = 4;
+
+impl RiverSegment {
+    pub fn new(id: u32, path: Vec<(f32, f32)>, strahler_order: u8, width_meters: f32) -> Self {
+        let smoothed_path = smooth_path(&path, RIVER_SMOOTH_SAMPLES);
+        Self { id, path, strahler_order, width_meters, smoothed_path }
+    }
 }
 
 /// River network containing all river segments
@@ -31,38 +43,99 @@ impl RiverNetwork {
     }
 }
 
+/// A steep drop along a river, at the cell where the water goes over the edge
+/// Heights are normalized like the heightmap
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Waterfall {
+    pub segment_id: u32,
+    pub x: f32,
+    pub z: f32,
+    pub top_height: f32,
+    pub drop: f32,
+}
+
+/// Cell size the default river widths are calibrated for (meters)
+const REFERENCE_CELL_SIZE_METERS: f32 = 100.0;
+
+/// Parameters controlling river extraction, ordering, and width
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RiverParams {
+    pub threshold: f32,              // Min flow accumulation for a cell to count as river
+    pub max_order: u8,               // Strahler orders above this are clamped
+    pub base_width_meters: f32,      // Width of an order-1 river at the reference cell size
+    pub width_growth: f32,           // Width multiplier per order
+    pub cell_size_meters: f32,       // Larger cells drain more area, so rivers scale wider
+}
+
+impl Default for RiverParams {
+    fn default() -> Self {
+        Self {
+            threshold: 1000.0,
+            max_order: 5,
+            base_width_meters: 5.0,
+            width_growth: 1.5,
+            cell_size_meters: REFERENCE_CELL_SIZE_METERS,
+        }
+    }
+}
+
+impl RiverParams {
+    /// Default ordering and widths for a world's cell size with a custom threshold
+    pub fn with_threshold(threshold: f32, cell_size_meters: f32) -> Self {
+        Self {
+            threshold,
+            cell_size_meters,
+            ..Default::default()
+        }
+    }
+}
+
+// D8 direction offsets: E, SE, S, SW, W, NW, N, NE
+const D8_DX: [i32; 8] = [1, 1, 0, -1, -1, -1, 0, 1];
+const D8_DZ: [i32; 8] = [0, 1, 1, 1, 0, -1, -1, -1];
+
 /// Extract rivers from flow accumulation data
+/// River cells form a tree along the flow directions. Each segment is one Strahler stream:
+/// it starts at a headwater or at the confluence that raised its order, runs downstream
+/// past any lower-order tributaries, and ends on the cell where it joins a higher order
+#[tracing::instrument(skip_all)]
 pub fn extract_rivers(
     flow_accumulation: &[f32],
     flow_direction: &[u8],
     width: usize,
     height: usize,
-    threshold: f32,
+    params: &RiverParams,
 ) -> RiverNetwork {
     let mut network = RiverNetwork::new();
-    let mut visited = vec![false; width * height];
-    let mut segment_id = 0;
+    let is_river: Vec<bool> = flow_accumulation.iter().map(|&acc| acc >= params.threshold).collect();
+    let downstream: Vec<Option<usize>> = (0..width * height)
+        .map(|idx| downstream_cell(idx, flow_direction, width, height).filter(|&next| is_river[idx] && is_river[next]))
+        .collect();
+    let orders = strahler_orders(&is_river, &downstream);
+
+    // A cell starts a stream unless exactly one upstream neighbor of the same order feeds it
+    let mut same_order_inflow = vec![0u8; width * height];
+    for (idx, next) in downstream.iter().enumerate() {
+        if let Some(next) = *next {
+            if orders[next] == orders[idx] {
+                same_order_inflow[next] += 1;
+            }
+        }
+    }
 
-    // Find all cells above threshold
+    let mut segment_id = 0;
     for z in 0..height {
         for x in 0..width {
             let idx = z * width + x;
-            if flow_accumulation[idx] >= threshold && !visited[idx] {
-                // Trace river from this headwater
-                let path = trace_river_path(x, z, flow_direction, width, height, &mut visited);
-                if path.len() > 2 {
-                    // Calculate Strahler order (simplified: based on flow accumulation)
-                    let order = calculate_order(flow_accumulation[idx]);
-                    let width_meters = calculate_width(order);
-
-                    network.add_segment(RiverSegment {
-                        id: segment_id,
-                        path,
-                        strahler_order: order,
-                        width_meters,
-                    });
-                    segment_id += 1;
-                }
+            if orders[idx] == 0 || same_order_inflow[idx] != 0 {
+                continue;
+            }
+
+            let path = trace_stream(idx, &orders, &downstream, width);
+            if path.len() >= 2 {
+                let order = orders[idx].min(params.max_order.max(1));
+                network.add_segment(RiverSegment::new(segment_id, path, order, calculate_width(order, params)));
+                segment_id += 1;
             }
         }
     }
@@ -70,74 +143,247 @@ pub fn extract_rivers(
     network
 }
 
-/// Trace a river path following flow direction
-fn trace_river_path(
-    start_x: usize,
-    start_z: usize,
-    flow_direction: &[u8],
-    width: usize,
-    height: usize,
-    visited: &mut [bool],
-) -> Vec<(f32, f32)> {
-    let mut path = Vec::new();
-    let mut x = start_x;
-    let mut z = start_z;
-
-    // D8 direction offsets: E, SE, S, SW, W, NW, N, NE
-    let dx = [1, 1, 0, -1, -1, -1, 0, 1];
-    let dz = [0, 1, 1, 1, 0, -1, -1, -1];
-
-    for _ in 0..1000 {
-        // Max path length
-        let idx = z * width + x;
-        if idx >= visited.len() {
-            break;
-        }
+/// Cell that a D8 direction points at, if it stays on the map
+fn downstream_cell(idx: usize, flow_direction: &[u8], width: usize, height: usize) -> Option<usize> {
+    let dir = *flow_direction.get(idx)? as usize;
+    if dir >= 8 {
+        return None; // No valid direction
+    }
 
-        path.push((x as f32, z as f32));
-        visited[idx] = true;
+    let nx = (idx % width) as i32 + D8_DX[dir];
+    let nz = (idx / width) as i32 + D8_DZ[dir];
+    if nx < 0 || nx >= width as i32 || nz < 0 || nz >= height as i32 {
+        return None; // Flows off the edge
+    }
+    Some(nz as usize * width + nx as usize)
+}
 
-        let dir = flow_direction[idx] as usize;
-        if dir >= 8 {
-            break; // No valid direction
-        }
+/// Strahler order of every river cell (0 = not river), computed headwaters-first:
+/// a cell takes the highest order flowing into it, plus one when two or more share it
+fn strahler_orders(is_river: &[bool], downstream: &[Option<usize>]) -> Vec<u8> {
+    let mut inflow = vec![0u32; is_river.len()];
+    for next in downstream.iter().flatten() {
+        inflow[*next] += 1;
+    }
+
+    let mut orders = vec![0u8; is_river.len()];
+    let mut max_inflow_order = vec![0u8; is_river.len()];
+    let mut max_inflow_count = vec![0u8; is_river.len()];
+    let mut ready: Vec<usize> = (0..is_river.len()).filter(|&idx| is_river[idx] && inflow[idx] == 0).collect();
 
-        let nx = x as i32 + dx[dir];
-        let nz = z as i32 + dz[dir];
+    // Cells caught in a direction loop never become ready and stay order 0
+    while let Some(idx) = ready.pop() {
+        orders[idx] = match max_inflow_order[idx] {
+            0 => 1,
+            order if max_inflow_count[idx] >= 2 => order.saturating_add(1),
+            order => order,
+        };
 
-        if nx < 0 || nx >= width as i32 || nz < 0 || nz >= height as i32 {
-            break; // Reached edge
+        if let Some(next) = downstream[idx] {
+            if orders[idx] > max_inflow_order[next] {
+                max_inflow_order[next] = orders[idx];
+                max_inflow_count[next] = 1;
+            } else if orders[idx] == max_inflow_order[next] {
+                max_inflow_count[next] = max_inflow_count[next].saturating_add(1);
+            }
+            inflow[next] -= 1;
+            if inflow[next] == 0 {
+                ready.push(next);
+            }
         }
+    }
 
-        x = nx as usize;
-        z = nz as usize;
+    orders
+}
+
+/// Follow a stream downstream while its order holds, ending on the cell where it joins
+/// a higher-order river so the network stays connected
+fn trace_stream(start: usize, orders: &[u8], downstream: &[Option<usize>], width: usize) -> Vec<(f32, f32)> {
+    let to_point = |idx: usize| ((idx % width) as f32, (idx / width) as f32);
+    let mut path = vec![to_point(start)];
+    let mut idx = start;
 
-        let next_idx = z * width + x;
-        if visited[next_idx] {
-            break; // Already visited (confluence or loop)
+    while let Some(next) = downstream[idx] {
+        path.push(to_point(next));
+        if orders[next] != orders[start] {
+            break;
         }
+        idx = next;
     }
 
     path
 }
 
-/// Calculate Strahler order from flow accumulation
-fn calculate_order(flow_accumulation: f32) -> u8 {
-    if flow_accumulation < 1000.0 {
-        1
-    } else if flow_accumulation < 5000.0 {
-        2
-    } else if flow_accumulation < 20000.0 {
-        3
-    } else if flow_accumulation < 100000.0 {
-        4
-    } else {
-        5
+/// Find waterfalls: steps along a river's path where the ground falls by at least `min_drop`
+#[tracing::instrument(skip_all)]
+pub fn detect_waterfalls(
+    network: &RiverNetwork,
+    heights: &[f32],
+    width: usize,
+    height: usize,
+    min_drop: f32,
+) -> Vec<Waterfall> {
+    let sample = |(x, z): (f32, f32)| -> Option<f32> {
+        let (x, z) = (x.round(), z.round());
+        (x >= 0.0 && z >= 0.0 && (x as usize) < width && (z as usize) < height)
+            .then(|| heights[z as usize * width + x as usize])
+    };
+
+    let mut waterfalls = Vec::new();
+    for segment in &network.segments {
+        for step in segment.path.windows(2) {
+            let (Some(top), Some(bottom)) = (sample(step[0]), sample(step[1])) else { continue };
+            if top - bottom >= min_drop {
+                waterfalls.push(Waterfall {
+                    segment_id: segment.id,
+                    x: step[0].0,
+                    z: step[0].1,
+                    top_height: top,
+                    drop: top - bottom,
+                });
+            }
+        }
     }
+
+    waterfalls
+}
+
+/// Resample a polyline along a Catmull-Rom spline through its points
+/// Each step between input points becomes `samples_per_segment` pieces; the curve passes
+/// through every input point, so the endpoints are kept exactly
+pub fn smooth_path(path: &[(f32, f32)], samples_per_segment: usize) -> Vec<(f32, f32)> {
+    if path.len() < 3 || samples_per_segment < 2 {
+        return path.to_vec();
+    }
+
+    let last = path.len() - 1;
+    let mut smoothed = Vec::with_capacity(last * samples_per_segment + 1);
+    for i in 0..last {
+        // Duplicate the end points so the first and last steps have neighbors
+        let p0 = path[i.saturating_sub(1)];
+        let (p1, p2) = (path[i], path[i + 1]);
+        let p3 = path[(i + 2).min(last)];
+
+        for sample in 0..samples_per_segment {
+            let t = sample as f32 / samples_per_segment as f32;
+            let (t2, t3) = (t * t, t * t * t);
+            let blend = |a: f32, b: f32, c: f32, d: f32| {
+                0.5 * (2.0 * b + (c - a) * t + (2.0 * a - 5.0 * b + 4.0 * c - d) * t2 + (3.0 * b - a - 3.0 * c + d) * t3)
+            };
+            smoothed.push((blend(p0.0, p1.0, p2.0, p3.0), blend(p0.1, p1.1, p2.1, p3.1)));
+        }
+    }
+    smoothed.push(path[last]);
+
+    smoothed
 }
 
-/// Calculate river width from Strahler order
-fn calculate_width(order: u8) -> f32 {
-    let base_width = 5.0; // meters
-    base_width * 1.5_f32.powi(order as i32 - 1)
+/// Calculate river width from Strahler order, scaled by cell size
+fn calculate_width(order: u8, params: &RiverParams) -> f32 {
+    let cell_scale = params.cell_size_meters / REFERENCE_CELL_SIZE_METERS;
+    params.base_width_meters * params.width_growth.powi(order as i32 - 1) * cell_scale
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_width_scales_with_cell_size() {
+        let small_cells = RiverParams::with_threshold(1000.0, 50.0);
+        let large_cells = RiverParams::with_threshold(1000.0, 200.0);
+
+        for order in 1..=5 {
+            let small = calculate_width(order, &small_cells);
+            let large = calculate_width(order, &large_cells);
+            assert!((large / small - 4.0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_smoothing_adds_points_and_softens_corners() {
+        // Staircase of right-angle turns, like a D8 river along the grid
+        let path = vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (2.0, 1.0), (2.0, 2.0), (3.0, 2.0)];
+        let smoothed = smooth_path(&path, 4);
+
+        assert!(smoothed.len() > path.len());
+        assert_eq!(smoothed[0], path[0]);
+        assert_eq!(smoothed.last(), path.last());
+
+        // Sum of squared turning angles: sharp corners cost far more than gentle bends
+        let bending = |points: &[(f32, f32)]| {
+            points.windows(3)
+                .map(|w| {
+                    let a = (w[1].1 - w[0].1).atan2(w[1].0 - w[0].0);
+                    let b = (w[2].1 - w[1].1).atan2(w[2].0 - w[1].0);
+                    let turn = (b - a + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI;
+                    turn * turn
+                })
+                .sum::<f32>()
+        };
+        assert!(bending(&smoothed) < bending(&path), "{} vs {}", bending(&smoothed), bending(&path));
+    }
+
+    #[test]
+    fn test_waterfall_found_at_cliff() {
+        // Gentle eastward descent with a 0.2 cliff between x = 9 and x = 10
+        let (width, height) = (20, 8);
+        let heights: Vec<f32> = (0..width * height)
+            .map(|i| {
+                let x = i % width;
+                0.8 - x as f32 * 0.005 - if x >= 10 { 0.2 } else { 0.0 }
+            })
+            .collect();
+
+        let mut network = RiverNetwork::new();
+        let path = (0..width).map(|x| (x as f32, 4.0)).collect();
+        network.add_segment(RiverSegment::new(3, path, 1, 5.0));
+
+        let waterfalls = detect_waterfalls(&network, &heights, width, height, 0.1);
+        assert_eq!(waterfalls.len(), 1);
+        let fall = &waterfalls[0];
+        assert_eq!((fall.segment_id, fall.x, fall.z), (3, 9.0, 4.0));
+        assert!((fall.drop - 0.205).abs() < 1e-4, "drop {}", fall.drop);
+    }
+
+    #[test]
+    fn test_confluence_of_equal_orders_raises_trunk_order() {
+        // Two order-1 tributaries meet at (2, 2) and the trunk runs south off the map
+        //   x . . . x
+        //   . x . x .
+        //   . . x . .
+        //   . . x . .
+        //   . . x . .
+        let (width, height) = (5, 5);
+        let mut flow_direction = vec![255u8; width * height];
+        let mut flow_accumulation = vec![0.0f32; width * height];
+        let mut river = |x: usize, z: usize, dir: u8| {
+            flow_direction[z * width + x] = dir;
+            flow_accumulation[z * width + x] = 1.0;
+        };
+        river(0, 0, 1); // SE
+        river(1, 1, 1);
+        river(4, 0, 3); // SW
+        river(3, 1, 3);
+        for z in 2..height {
+            river(2, z, 2); // S
+        }
+
+        let params = RiverParams::with_threshold(1.0, 100.0);
+        let network = extract_rivers(&flow_accumulation, &flow_direction, width, height, &params);
+        assert_eq!(network.segments.len(), 3);
+
+        let trunk = network.segments.iter().find(|segment| segment.path[0] == (2.0, 2.0)).unwrap();
+        assert_eq!(trunk.strahler_order, 2);
+        assert_eq!(trunk.path.len(), 3);
+        for tributary in network.segments.iter().filter(|segment| segment.path[0] != (2.0, 2.0)) {
+            assert_eq!(tributary.strahler_order, 1);
+            assert_eq!(*tributary.path.last().unwrap(), (2.0, 2.0));
+        }
+        assert!(trunk.width_meters > network.segments[0].width_meters);
+
+        let capped = RiverParams { max_order: 1, ..params };
+        let network = extract_rivers(&flow_accumulation, &flow_direction, width, height, &capped);
+        assert!(network.segments.iter().all(|segment| segment.strahler_order == 1));
+    }
 }
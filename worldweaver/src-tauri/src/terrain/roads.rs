@@ -1,20 +1,57 @@
-use pathfinding::prelude::astar;
+use pathfinding::prelude::{astar, kruskal_indices};
 use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
 
 /// A road path between two points
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Road {
     pub path: Vec<(i32, i32)>,
     pub cost: u32,
+    #[serde(default)]
+    pub bridges: Vec<(usize, usize)>, // Inclusive index ranges of `path` that cross water on a bridge
+}
+
+/// Cost multipliers for stepping onto water; finite so roads can still cross when they must
+const SEA_COST_MULTIPLIER: f32 = 20.0;
+const RIVER_COST_MULTIPLIER: f32 = 40.0;
+/// Cost multiplier for a bridged water cell, cheap enough that short crossings beat long detours
+const BRIDGE_COST_MULTIPLIER: f32 = 3.0;
+
+/// Bridge length used when the caller doesn't choose one
+pub const DEFAULT_BRIDGE_CELLS: u32 = 4;
+
+/// Water the road router avoids or bridges, and how the result is shaped
+/// Buffers are row-major like the heightmap. The default has no water at all, so only slope
+/// matters, and keeps every cell of the path
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RoadContext<'a> {
+    pub sea_level: f32,                  // Cells below this are open water
+    pub river_mask: Option<&'a [bool]>,  // true = river cell
+    pub bridge_threshold: u32,           // Longest water crossing (cells) that can be bridged
+    pub simplify_epsilon: Option<f32>,   // Drop path points within this many cells of a straight run
+}
+
+impl RoadContext<'_> {
+    fn is_river(&self, idx: usize) -> bool {
+        self.river_mask.is_some_and(|mask| mask.get(idx).copied().unwrap_or(false))
+    }
+
+    fn is_water(&self, heights: &[f32], idx: usize) -> bool {
+        heights[idx] < self.sea_level || self.is_river(idx)
+    }
 }
 
 /// Calculate road cost based on slope
+/// `bridged` prices a water destination as a bridge span instead of as open water
+#[allow(clippy::too_many_arguments)]
 pub fn road_cost(
     from: (i32, i32),
     to: (i32, i32),
     heights: &[f32],
     width: usize,
     height: usize,
+    context: &RoadContext,
+    bridged: bool,
 ) -> u32 {
     let (fx, fz) = from;
     let (tx, tz) = to;
@@ -26,28 +63,44 @@ pub fn road_cost(
         return u32::MAX;
     }
 
+    let to_idx = tz as usize * width + tx as usize;
     let h_from = heights[fz as usize * width + fx as usize];
-    let h_to = heights[tz as usize * width + tx as usize];
+    let h_to = heights[to_idx];
 
     // Diagonal movement costs more
     let horizontal = if fx != tx && fz != tz { 141 } else { 100 };
 
+    // Water is flat to build on, so only its crossing penalty applies
+    if context.is_water(heights, to_idx) {
+        let multiplier = if bridged {
+            BRIDGE_COST_MULTIPLIER
+        } else if context.is_river(to_idx) {
+            RIVER_COST_MULTIPLIER
+        } else {
+            SEA_COST_MULTIPLIER
+        };
+        return (horizontal as f32 * multiplier) as u32;
+    }
+
     // Slope-squared cost function
     let slope = (h_to - h_from).abs() / (horizontal as f32 / 100.0);
     (horizontal as f32 * (1.0 + 8.0 * slope * slope)) as u32
 }
 
 /// Generate a road between two points using A*
+/// The search tracks how many water cells in a row the road has crossed, so a crossing
+/// stays a cheap bridge only up to `bridge_threshold` cells
 pub fn generate_road(
     start: (i32, i32),
     goal: (i32, i32),
     heights: &[f32],
     width: usize,
     height: usize,
+    context: &RoadContext,
 ) -> Option<Road> {
     let result = astar(
-        &start,
-        |&(x, z)| {
+        &(start, 0u32),
+        |&((x, z), water_run)| {
             let mut neighbors = Vec::new();
             for dz in -1..=1 {
                 for dx in -1..=1 {
@@ -57,23 +110,154 @@ pub fn generate_road(
                     let nx = x + dx;
                     let nz = z + dz;
                     if nx >= 0 && nx < width as i32 && nz >= 0 && nz < height as i32 {
-                        let cost = road_cost((x, z), (nx, nz), heights, width, height);
+                        let wet = context.is_water(heights, nz as usize * width + nx as usize);
+                        let run = if wet { (water_run + 1).min(context.bridge_threshold + 1) } else { 0 };
+                        let bridged = wet && run <= context.bridge_threshold;
+                        let cost = road_cost((x, z), (nx, nz), heights, width, height, context, bridged);
                         if cost < u32::MAX {
-                            neighbors.push(((nx, nz), cost));
+                            neighbors.push((((nx, nz), run), cost));
                         }
                     }
                 }
             }
             neighbors
         },
-        |&(x, z)| {
+        |&((x, z), _)| {
             // Manhattan distance heuristic
             ((goal.0 - x).abs() + (goal.1 - z).abs()) as u32 * 100
         },
-        |&pos| pos == goal,
+        |&(pos, _)| pos == goal,
     );
 
-    result.map(|(path, cost)| Road { path, cost })
+    result.map(|(states, cost)| {
+        let path: Vec<(i32, i32)> = states.into_iter().map(|(pos, _)| pos).collect();
+        let bridges = bridge_spans(&path, heights, width, context);
+        let road = Road { path, cost, bridges };
+        match context.simplify_epsilon {
+            Some(epsilon) => simplify_road(road, epsilon),
+            None => road,
+        }
+    })
+}
+
+/// Simplify a polyline with Ramer-Douglas-Peucker: keep the endpoints and only the points
+/// needed so that no dropped point lies more than `epsilon` cells from the result
+pub fn simplify_path(path: &[(i32, i32)], epsilon: f32) -> Vec<(i32, i32)> {
+    simplify_indices(path, epsilon).into_iter().map(|i| path[i]).collect()
+}
+
+/// Indices of the points Ramer-Douglas-Peucker keeps, ascending
+fn simplify_indices(path: &[(i32, i32)], epsilon: f32) -> Vec<usize> {
+    if path.len() < 3 {
+        return (0..path.len()).collect();
+    }
+
+    let mut keep = vec![false; path.len()];
+    keep[0] = true;
+    keep[path.len() - 1] = true;
+
+    // Explicit stack instead of recursion so long roads can't overflow it
+    let mut stack = vec![(0, path.len() - 1)];
+    while let Some((first, last)) = stack.pop() {
+        let (farthest, distance) = (first + 1..last)
+            .map(|i| (i, distance_to_segment(path[i], path[first], path[last])))
+            .fold((first, 0.0), |best, candidate| if candidate.1 > best.1 { candidate } else { best });
+
+        if distance > epsilon {
+            keep[farthest] = true;
+            stack.push((first, farthest));
+            stack.push((farthest, last));
+        }
+    }
+
+    (0..path.len()).filter(|&i| keep[i]).collect()
+}
+
+/// Distance (cells) from a point to the segment between two others
+fn distance_to_segment(point: (i32, i32), start: (i32, i32), end: (i32, i32)) -> f32 {
+    let (px, pz) = (point.0 as f32, point.1 as f32);
+    let (ax, az) = (start.0 as f32, start.1 as f32);
+    let (dx, dz) = (end.0 as f32 - ax, end.1 as f32 - az);
+
+    let length_squared = dx * dx + dz * dz;
+    let t = if length_squared > 0.0 {
+        (((px - ax) * dx + (pz - az) * dz) / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (px - (ax + t * dx)).hypot(pz - (az + t * dz))
+}
+
+/// Simplify a road's path while keeping both ends of every bridge, so the spans stay exact
+fn simplify_road(road: Road, epsilon: f32) -> Road {
+    let last = road.path.len().saturating_sub(1);
+    let mut anchors: Vec<usize> = std::iter::once(0)
+        .chain(road.bridges.iter().flat_map(|&(first, last)| [first, last]))
+        .chain(std::iter::once(last))
+        .collect();
+    anchors.dedup();
+
+    // Simplify each stretch between anchors on its own and stitch them back together
+    let mut kept = vec![0];
+    for pair in anchors.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        kept.extend(simplify_indices(&road.path[from..=to], epsilon).into_iter().skip(1).map(|i| from + i));
+    }
+
+    let position = |index: usize| kept.binary_search(&index).expect("bridge ends are kept");
+    Road {
+        path: kept.iter().map(|&i| road.path[i]).collect(),
+        cost: road.cost,
+        bridges: road.bridges.iter().map(|&(first, last)| (position(first), position(last))).collect(),
+    }
+}
+
+/// Runs of water cells along a path short enough to have been bridged
+fn bridge_spans(path: &[(i32, i32)], heights: &[f32], width: usize, context: &RoadContext) -> Vec<(usize, usize)> {
+    let wet = |&(x, z): &(i32, i32)| context.is_water(heights, z as usize * width + x as usize);
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < path.len() {
+        if !wet(&path[i]) {
+            i += 1;
+            continue;
+        }
+        let first = i;
+        while i < path.len() && wet(&path[i]) {
+            i += 1;
+        }
+        if (i - first) as u32 <= context.bridge_threshold {
+            spans.push((first, i - 1));
+        }
+    }
+    spans
+}
+
+/// Connect settlements with the cheapest set of roads that links them all
+/// Every pair is routed with A*, then a minimum spanning tree over those route costs picks
+/// which to build, so N connected points yield N - 1 roads
+#[tracing::instrument(skip_all, fields(points = points.len()))]
+pub fn generate_road_network(
+    points: &[(i32, i32)],
+    heights: &[f32],
+    width: usize,
+    height: usize,
+    context: &RoadContext,
+) -> Vec<Road> {
+    let mut routes = HashMap::new();
+    let mut edges = Vec::new();
+    for (i, &start) in points.iter().enumerate() {
+        for (j, &goal) in points.iter().enumerate().skip(i + 1) {
+            if let Some(road) = generate_road(start, goal, heights, width, height, context) {
+                edges.push((i, j, road.cost));
+                routes.insert((i, j), road);
+            }
+        }
+    }
+
+    kruskal_indices(points.len(), &edges)
+        .filter_map(|(i, j, _)| routes.remove(&(i.min(j), i.max(j))))
+        .collect()
 }
 
 /// Get neighbors for a position (8-directional)
@@ -96,3 +280,92 @@ pub fn get_neighbors(pos: (i32, i32), width: usize, height: usize) -> Vec<(i32,
 
     neighbors
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_connects_every_point_with_n_minus_one_roads() {
+        let size = 40;
+        // Rolling ground so routes have to weigh slope against distance
+        let heights: Vec<f32> = (0..size * size)
+            .map(|i| 0.5 + ((i % size) as f32 * 0.3).sin() * 0.05 + ((i / size) as f32 * 0.2).cos() * 0.05)
+            .collect();
+        let points = [(2, 3), (35, 4), (20, 20), (5, 36), (33, 33), (18, 2)];
+
+        let roads = generate_road_network(&points, &heights, size, size, &RoadContext::default());
+        assert_eq!(roads.len(), points.len() - 1);
+
+        // Walk the road graph from the first point; every settlement must be reachable
+        let mut reached = vec![false; points.len()];
+        reached[0] = true;
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for road in &roads {
+                let ends = [road.path[0], *road.path.last().unwrap()];
+                let [a, b] = ends.map(|end| points.iter().position(|&p| p == end).unwrap());
+                if reached[a] != reached[b] {
+                    reached[a] = true;
+                    reached[b] = true;
+                    changed = true;
+                }
+            }
+        }
+        assert!(reached.iter().all(|&r| r));
+    }
+
+    #[test]
+    fn test_simplify_collapses_straight_runs_within_epsilon() {
+        let diagonal: Vec<(i32, i32)> = (0..50).map(|i| (i, i)).collect();
+        assert_eq!(simplify_path(&diagonal, 0.5), vec![(0, 0), (49, 49)]);
+
+        // A stair-stepped curve keeps its ends and never strays more than epsilon
+        let curve: Vec<(i32, i32)> = (0..60).map(|i| (i, ((i as f32 * 0.15).sin() * 12.0).round() as i32)).collect();
+        let simplified = simplify_path(&curve, 1.0);
+        assert!(simplified.len() < curve.len() / 2);
+        assert_eq!(simplified.first(), curve.first());
+        assert_eq!(simplified.last(), curve.last());
+        for &point in &curve {
+            let deviation = simplified.windows(2)
+                .map(|pair| distance_to_segment(point, pair[0], pair[1]))
+                .fold(f32::MAX, f32::min);
+            assert!(deviation <= 1.0, "{:?} is {} cells off", point, deviation);
+        }
+    }
+
+    #[test]
+    fn test_roads_bridge_short_crossings_and_skirt_long_ones() {
+        // A lake 10 cells wide blocks all but the last two rows between two shore towns
+        let (width, height) = (40, 30);
+        let in_lake = |x: usize, z: usize| (15..25).contains(&x) && z < 28;
+        let heights: Vec<f32> = (0..width * height)
+            .map(|i| if in_lake(i % width, i / width) { 0.1 } else { 0.5 })
+            .collect();
+        let wet = |road: &Road| road.path.iter().filter(|&&(x, z)| in_lake(x as usize, z as usize)).count();
+
+        // Ten cells is beyond a 3-cell bridge, so the road goes around
+        let short_bridges = RoadContext { sea_level: 0.2, bridge_threshold: 3, ..Default::default() };
+        let road = generate_road((5, 5), (34, 5), &heights, width, height, &short_bridges).unwrap();
+        assert_eq!(wet(&road), 0);
+        assert!(road.bridges.is_empty());
+
+        // A 12-cell bridge spans it
+        let long_bridges = RoadContext { sea_level: 0.2, bridge_threshold: 12, ..Default::default() };
+        let road = generate_road((5, 5), (34, 5), &heights, width, height, &long_bridges).unwrap();
+        assert_eq!(wet(&road), 10);
+        assert_eq!(road.bridges.len(), 1);
+        let (first, last) = road.bridges[0];
+        assert_eq!(last - first + 1, 10);
+        assert!(road.path[first..=last].iter().all(|&(x, z)| in_lake(x as usize, z as usize)));
+
+        // Simplifying keeps the bridge ends where they were
+        let simplified = RoadContext { simplify_epsilon: Some(1.0), ..long_bridges };
+        let simple_road = generate_road((5, 5), (34, 5), &heights, width, height, &simplified).unwrap();
+        assert!(simple_road.path.len() < road.path.len());
+        let (simple_first, simple_last) = simple_road.bridges[0];
+        assert_eq!(simple_road.path[simple_first], road.path[first]);
+        assert_eq!(simple_road.path[simple_last], road.path[last]);
+    }
+}
@@ -0,0 +1,180 @@
+use serde::{Serialize, Deserialize};
+
+use super::commands::NoiseParameters;
+use super::config::TerrainConfig;
+use super::hydrology::{calculate_flow_accumulation, calculate_flow_direction, fill_depressions};
+use super::noise_gen::preview_terrain;
+use super::rivers::{extract_rivers, RiverParams};
+
+/// Resolution of the preview each candidate seed is evaluated on
+const SEARCH_PREVIEW_SIZE: u32 = 128;
+
+/// Land regions smaller than this many preview pixels don't count as landmasses
+const MIN_LANDMASS_PIXELS: usize = 16;
+
+/// Flow accumulation (preview cells) needed to start a river in the preview
+const PREVIEW_RIVER_THRESHOLD: f32 = 100.0;
+
+/// Constraints a generated world must satisfy
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SeedCriteria {
+    pub min_land_fraction: Option<f32>,
+    pub max_land_fraction: Option<f32>,
+    pub min_landmasses: Option<usize>,
+    pub min_river_length: Option<usize>, // Preview cells
+}
+
+/// A seed that met the criteria, with the metrics it was judged on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FoundWorld {
+    pub seed: u32,
+    pub land_fraction: f32,
+    pub landmass_count: usize,
+    pub longest_river: usize,
+}
+
+/// Try successive seeds starting at `config.seed` until one meets the criteria
+/// Each attempt only generates a low-resolution preview, never full chunks or erosion
+pub fn find_seed(
+    config: &TerrainConfig,
+    params: &NoiseParameters,
+    criteria: &SeedCriteria,
+    max_tries: u32,
+) -> Option<FoundWorld> {
+    let size = SEARCH_PREVIEW_SIZE as usize;
+
+    for attempt in 0..max_tries {
+        let mut candidate = config.clone();
+        candidate.seed = config.seed.wrapping_add(attempt);
+
+        let heights = preview_terrain(&candidate, params, SEARCH_PREVIEW_SIZE);
+        let land: Vec<bool> = heights.iter().map(|&h| h > candidate.sea_level).collect();
+
+        let land_fraction = land.iter().filter(|&&is_land| is_land).count() as f32 / land.len() as f32;
+        if criteria.min_land_fraction.is_some_and(|min| land_fraction < min)
+            || criteria.max_land_fraction.is_some_and(|max| land_fraction > max)
+        {
+            continue;
+        }
+
+        let landmass_count = count_landmasses(&land, size, size);
+        if criteria.min_landmasses.is_some_and(|min| landmass_count < min) {
+            continue;
+        }
+
+        // Rivers are the most expensive metric, so only trace them when asked
+        let longest_river = if criteria.min_river_length.is_some() {
+            longest_river(heights, size, size)
+        } else {
+            0
+        };
+        if criteria.min_river_length.is_some_and(|min| longest_river < min) {
+            continue;
+        }
+
+        return Some(FoundWorld {
+            seed: candidate.seed,
+            land_fraction,
+            landmass_count,
+            longest_river,
+        });
+    }
+
+    None
+}
+
+/// Count 4-connected land regions via flood fill
+fn count_landmasses(land: &[bool], width: usize, height: usize) -> usize {
+    let mut visited = vec![false; land.len()];
+    let mut count = 0;
+    let mut stack = Vec::new();
+
+    for start in 0..land.len() {
+        if !land[start] || visited[start] {
+            continue;
+        }
+
+        let mut size = 0;
+        visited[start] = true;
+        stack.push(start);
+
+        while let Some(idx) = stack.pop() {
+            size += 1;
+            let x = idx % width;
+            let z = idx / width;
+
+            let mut neighbors = Vec::with_capacity(4);
+            if x > 0 { neighbors.push(idx - 1); }
+            if x + 1 < width { neighbors.push(idx + 1); }
+            if z > 0 { neighbors.push(idx - width); }
+            if z + 1 < height { neighbors.push(idx + width); }
+
+            for neighbor in neighbors {
+                if land[neighbor] && !visited[neighbor] {
+                    visited[neighbor] = true;
+                    stack.push(neighbor);
+                }
+            }
+        }
+
+        if size >= MIN_LANDMASS_PIXELS {
+            count += 1;
+        }
+    }
+
+    count
+}
+
+/// Length (cells) of the longest river traced on the preview
+fn longest_river(mut heights: Vec<f32>, width: usize, height: usize) -> usize {
+    fill_depressions(&mut heights, width, height, None);
+    let flow_direction = calculate_flow_direction(&heights, width, height);
+    let flow_accumulation = calculate_flow_accumulation(&heights, &flow_direction, width, height);
+    let params = RiverParams { threshold: PREVIEW_RIVER_THRESHOLD, ..Default::default() };
+
+    extract_rivers(&flow_accumulation, &flow_direction, width, height, &params)
+        .segments
+        .iter()
+        .map(|segment| segment.path.len())
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terrain::config::WorldTheme;
+
+    #[test]
+    fn test_trivial_criteria_accepts_first_seed() {
+        let config = TerrainConfig::new(1024, 1024, 500, WorldTheme::Fantasy);
+        let criteria = SeedCriteria {
+            min_land_fraction: Some(0.0),
+            ..Default::default()
+        };
+
+        let found = find_seed(&config, &NoiseParameters::default(), &criteria, 5).unwrap();
+        assert_eq!(found.seed, 500);
+    }
+
+    #[test]
+    fn test_impossible_criteria_gives_up() {
+        let config = TerrainConfig::new(1024, 1024, 500, WorldTheme::Fantasy);
+        let criteria = SeedCriteria {
+            min_land_fraction: Some(1.1),
+            ..Default::default()
+        };
+
+        assert!(find_seed(&config, &NoiseParameters::default(), &criteria, 3).is_none());
+    }
+
+    #[test]
+    fn test_count_landmasses() {
+        // Two 5x5 islands separated by water
+        let width = 12;
+        let land: Vec<bool> = (0..width * 5)
+            .map(|i| !(5..=6).contains(&(i % width)))
+            .collect();
+        assert_eq!(count_landmasses(&land, width, 5), 2);
+    }
+}
@@ -8,13 +8,76 @@ export interface TerrainConfig {
   sea_level: number;
   seed: number;
   theme: 'Fantasy' | 'Modern' | 'SciFi';
+  wrap?: boolean;  // Tileable world: opposite edges match
+  flow_routing?: FlowRouting;
+  wind_direction?: WindDirection;  // Side the prevailing wind blows from
+  coast_width?: number;  // Normalized height of the beach band above sea level (default 0.02)
+  noise_params?: NoiseParameters | null;  // Parameters the world was generated with (null = defaults)
+  style?: TerrainStyle;  // Land shaping applied by post-processing
+  height_range?: [number, number] | null;  // Raw (min, max) post-processing normalized by
+  cave_density?: number | null;  // Density caves were last generated with (null = no caves)
 }
 
+export type WindDirection = 'West' | 'East' | 'North' | 'South';
+
+// D8 sends all flow to the steepest neighbor; DInfinity splits it between two for smoother rivers
+export type FlowRouting = 'D8' | 'DInfinity';
+
 export interface RiverSegment {
   id: number;
   path: [number, number][];
   strahler_order: number;
   width_meters: number;
+  smoothed_path?: [number, number][];  // Spline through path; prefer this for rendering
+}
+
+// Path is in grid cells from one settlement to another
+export interface Road {
+  path: [number, number][];
+  cost: number;
+  bridges?: [number, number][];  // Inclusive path index ranges carried over water
+}
+
+export interface BiomeDefinition {
+  name: string;
+  color: [number, number, number];
+  theme_names: Partial<Record<TerrainConfig['theme'], string>>;
+  climate?: BiomeClimate | null;  // Where a custom biome appears; built-ins use the Whittaker diagram
+}
+
+// Inclusive ranges: temperature in °C, moisture 0..1, elevation normalized like heights
+export interface BiomeClimate {
+  temperature: [number, number];
+  moisture: [number, number];
+  elevation: [number, number];
+}
+
+// id is the value stored in chunk biome maps; custom biomes follow the built-ins
+export interface RegisteredBiome {
+  id: number;
+  key: string;
+  definition: BiomeDefinition;
+}
+
+// Heights are normalized 0..1; (x, z) is the cell at the top of the fall
+export interface Waterfall {
+  segment_id: number;
+  x: number;
+  z: number;
+  top_height: number;
+  drop: number;
+}
+
+// Bounds are inclusive cell coordinates; elevations are normalized 0..1
+export interface Lake {
+  id: number;
+  surface_elevation: number;
+  max_depth: number;
+  cell_count: number;
+  min_x: number;
+  min_z: number;
+  max_x: number;
+  max_z: number;
 }
 
 export interface RoomMarker {
@@ -35,9 +98,23 @@ export interface NoiseParameters {
   hill_octaves: number;
   detail_frequency: number;
   detail_octaves: number;
-  land_coverage?: number;  // Threshold for land vs ocean (0.0-1.0)
+  // Per-layer fractal shaping; omitted values use the backend defaults
+  continent_persistence?: number;
+  continent_lacunarity?: number;
+  mountain_persistence?: number;
+  mountain_lacunarity?: number;
+  hill_persistence?: number;
+  hill_lacunarity?: number;
+  detail_persistence?: number;
+  detail_lacunarity?: number;
+  land_coverage?: number;  // Fraction of land vs ocean (0.0-1.0)
+  noise_backend?: NoiseBackend;
+  warp_strength?: number;   // Coastline domain warp in meters (0 = off)
+  warp_frequency?: number;
 }
 
+export type NoiseBackend = 'Perlin' | 'OpenSimplex' | 'Simplex';
+
 export interface GenerateTerrainRequest {
   width: number;
   height: number;
@@ -46,12 +123,54 @@ export interface GenerateTerrainRequest {
   use_erosion: boolean;
   erosion_iterations: number;
   noise_params?: NoiseParameters;
+  wrap?: boolean;
+  style?: TerrainStyle;
+  flow_routing?: FlowRouting;
+  wind_direction?: WindDirection;
+}
+
+// Land shaping applied after generation; Mesa quantizes land into flat plateau bands
+export type TerrainStyle = 'Rolling' | 'Alpine' | { Mesa: { bands: number } };
+
+export interface PreviewTerrainRequest {
+  width: number;
+  height: number;
+  seed: number;
+  theme: 'Fantasy' | 'Modern' | 'SciFi';
+  noise_params?: NoiseParameters;
+  preview_size: number;
+  wrap?: boolean;
+}
+
+export interface SeedCriteria {
+  min_land_fraction?: number;
+  max_land_fraction?: number;
+  min_landmasses?: number;
+  min_river_length?: number; // Preview cells
+}
+
+export interface FindSeedRequest {
+  width: number;
+  height: number;
+  start_seed: number;
+  theme: 'Fantasy' | 'Modern' | 'SciFi';
+  noise_params?: NoiseParameters;
+  criteria: SeedCriteria;
+  max_tries: number;
+}
+
+export interface FoundWorld {
+  seed: number;
+  land_fraction: number;
+  landmass_count: number;
+  longest_river: number;
 }
 
 export interface GenerateTerrainResponse {
   success: boolean;
   message: string;
   chunk_count: number;
+  noise_params?: NoiseParameters; // Parameters actually used, after clamping
 }
 
 export interface GetChunkRequest {
@@ -60,6 +179,34 @@ export interface GetChunkRequest {
   lod: number;
 }
 
+export type ResourceType = 'Iron' | 'Gold' | 'Stone' | 'Clay' | 'Timber';
+
+export interface Deposit {
+  x: number;
+  y: number;
+  resource: ResourceType;
+  richness: number;
+}
+
+export interface ChunkBounds {
+  chunk_x: number;
+  chunk_z: number;
+  min_height: number;
+  max_height: number;
+}
+
+export interface BrushedChunk {
+  chunk_x: number;
+  chunk_z: number;
+  heights: number[];  // Raw f32 LE bytes of the chunk's heights
+}
+
+export interface UndoState {
+  can_undo: boolean;
+  can_redo: boolean;
+  entry_count: number;
+}
+
 export interface ApplyBrushRequest {
   chunk_x: number;
   chunk_z: number;
@@ -68,9 +215,34 @@ export interface ApplyBrushRequest {
   radius: number;
   strength: number;
   brush_type: string;
+  seed?: number;  // Noise brush seed (derived from the stroke position if omitted)
+  falloff?: FalloffCurve;  // Defaults to 'Gaussian'
+  stamp?: StampPattern;    // Required by the 'stamp' brush
+}
+
+export interface ApplyRampRequest {
+  start_x: number;  // Global vertex coordinates
+  start_z: number;
+  end_x: number;
+  end_z: number;
+  width: number;
+  strength: number;
+  start_height?: number;  // Defaults to the terrain height at the start point
+  end_height?: number;    // Defaults to the terrain height at the end point
+  falloff?: FalloffCurve;
 }
 
-export type BrushType = 'raise' | 'lower' | 'smooth' | 'flatten' | 'erode' | 'noise';
+export type StampBlend = 'Add' | 'Max' | 'Replace';
+
+export interface StampPattern {
+  pattern: number[];  // Row-major, pattern_size × pattern_size
+  pattern_size: number;
+  blend?: StampBlend; // Defaults to 'Add'
+}
+
+export type FalloffCurve = 'Gaussian' | 'Linear' | 'Smoothstep' | 'Constant';
+
+export type BrushType = 'raise' | 'lower' | 'smooth' | 'flatten' | 'erode' | 'noise' | 'lock' | 'unlock' | 'stamp';
 
 export interface ViewTransform {
   translateX: number;
@@ -86,3 +258,36 @@ export interface VisibleChunks {
   minZ: number;
   maxZ: number;
 }
+
+export type TerrainFeature = 'None' | 'Cave' | 'Cliff' | 'Ruin';
+
+/** Per-vertex feature codes returned by get_feature_map */
+export const TERRAIN_FEATURE_CODES: Record<TerrainFeature, number> = {
+  None: 0,
+  Cave: 1,
+  Cliff: 2,
+  Ruin: 3,
+};
+
+export interface MarkFeatureRequest {
+  chunk_x: number;
+  chunk_z: number;
+  center_x: number;
+  center_z: number;
+  radius: number;
+  feature: TerrainFeature;
+}
+
+export interface SavedWorld {
+  name: string;
+  config: TerrainConfig;
+}
+
+// Histogram bins split the normalized 0..1 elevation range evenly
+export interface TerrainAnalysis {
+  histogram: number[];
+  land_fraction: number;
+  mean_elevation: number;
+  max: number;
+  min: number;
+}
@@ -1,4 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
+import type { TerrainConfig } from '$lib/components/terrain/types';
 
 export interface Exit {
   direction: string;
@@ -14,28 +15,208 @@ export interface RoomDetails {
 }
 
 export interface NpcInfo {
+  id: string;
   name: string;
   description: string;
   personality: string;
   greeting: string;
 }
 
-export async function getCurrentRoom(): Promise<RoomDetails> {
-  return await invoke<RoomDetails>('get_current_room');
+/** `characterId` picks a party member; omit it for the original player */
+export async function getCurrentRoom(characterId?: string): Promise<RoomDetails> {
+  return await invoke<RoomDetails>('get_current_room', { characterId });
 }
 
 export async function getNpcsInCurrentRoom(): Promise<NpcInfo[]> {
   return await invoke<NpcInfo[]>('get_npcs_in_current_room');
 }
 
-export async function movePlayer(direction: string): Promise<RoomDetails> {
-  return await invoke<RoomDetails>('move_player', { direction });
+export interface ItemInfo {
+  id: string;
+  name: string;
+  description: string;
+  item_type: string;
+  weight: number;
+  value: number;
+  stack_count: number;
+}
+
+export async function getItemsInCurrentRoom(): Promise<ItemInfo[]> {
+  return await invoke<ItemInfo[]>('get_items_in_current_room');
+}
+
+export async function getInventory(): Promise<ItemInfo[]> {
+  return await invoke<ItemInfo[]>('get_inventory');
+}
+
+export async function pickUpItem(itemId: string): Promise<void> {
+  return await invoke<void>('pick_up_item', { itemId });
+}
+
+export async function dropItem(itemId: string): Promise<void> {
+  return await invoke<void>('drop_item', { itemId });
+}
+
+export interface ShopPrice {
+  item_type: string;
+  price: number;
+  base_price: number;
+  stock: number;
+}
+
+export async function getShopPrices(shopId: string): Promise<ShopPrice[]> {
+  return await invoke<ShopPrice[]>('get_shop_prices', { shopId });
+}
+
+export async function movePlayer(direction: string, characterId?: string): Promise<RoomDetails> {
+  return await invoke<RoomDetails>('move_player', { direction, characterId });
+}
+
+/** Move to an adjacent room by id, e.g. when it's clicked on the map */
+export async function movePlayerTo(roomId: string, characterId?: string): Promise<RoomDetails> {
+  return await invoke<RoomDetails>('move_player_to', { roomId, characterId });
+}
+
+export async function goBack(): Promise<RoomDetails> {
+  return await invoke<RoomDetails>('go_back');
+}
+
+export interface PartyMember {
+  id: string;
+  name: string;
+  room_id: string;
+}
+
+export async function spawnPartyMember(name: string, description: string, roomId: string): Promise<string> {
+  return await invoke<string>('spawn_party_member', { name, description, roomId });
+}
+
+export async function getParty(): Promise<PartyMember[]> {
+  return await invoke<PartyMember[]>('get_party');
+}
+
+export async function setPlayerRoom(roomId: string): Promise<RoomDetails> {
+  return await invoke<RoomDetails>('set_player_room', { roomId });
+}
+
+export interface TravelEstimate {
+  path: string[];
+  hops: number;
+  distance_meters: number;
+  hours: number;
+}
+
+export async function travelTime(fromRoom: string, toRoom: string): Promise<TravelEstimate | null> {
+  return await invoke<TravelEstimate | null>('travel_time', { fromRoom, toRoom });
+}
+
+export async function simulateWorldProgress(days: number): Promise<string[]> {
+  return await invoke<string[]>('simulate_world_progress', { days });
+}
+
+export async function updateRoom(roomId: string, name: string, description: string): Promise<RoomDetails> {
+  return await invoke<RoomDetails>('update_room', { roomId, name, description });
+}
+
+export async function updateNpc(
+  npcId: string,
+  name: string,
+  description: string,
+  personality: string,
+  greeting: string
+): Promise<void> {
+  return await invoke<void>('update_npc', { npcId, name, description, personality, greeting });
+}
+
+interface ExitRef {
+  room_id: string;
+  direction: string;
+  target_room_id: string;
 }
 
-export async function sendPlayerAction(action: string): Promise<string> {
-  return await invoke<string>('send_player_action', { action });
+export type WorldWarning = { DanglingExit: ExitRef } | { OneWayExit: ExitRef };
+
+export async function validateWorld(): Promise<WorldWarning[]> {
+  return await invoke<WorldWarning[]>('validate_world');
+}
+
+/** Directions move the character (default: the original player) and describe the new room */
+export async function sendPlayerAction(action: string, characterId?: string): Promise<string> {
+  return await invoke<string>('send_player_action', { action, characterId });
 }
 
 export async function getWorldTick(): Promise<number> {
   return await invoke<number>('get_world_tick');
 }
+
+export interface GameTime {
+  hour: number;
+  day: number;
+  month: number;
+  year: number;
+  season: 'Spring' | 'Summer' | 'Autumn' | 'Winter';
+}
+
+export async function getWorldTime(): Promise<GameTime> {
+  return await invoke<GameTime>('get_world_time');
+}
+
+export interface ExplorationState {
+  visited: string[];
+  known: string[];
+}
+
+export async function getExploredRooms(): Promise<ExplorationState> {
+  return await invoke<ExplorationState>('get_explored_rooms');
+}
+
+export type LogLevel = 'trace' | 'debug' | 'info' | 'warn' | 'error' | 'off';
+
+export async function setLogLevel(level: LogLevel): Promise<void> {
+  return await invoke<void>('set_log_level', { level });
+}
+
+export interface WorldSlotConfig {
+  empty: boolean;
+  terrain?: TerrainConfig;
+}
+
+export interface WorldSlotInfo {
+  name: string;
+  active: boolean;
+  tick_count: number;
+}
+
+export async function createWorld(name: string, config?: WorldSlotConfig): Promise<void> {
+  return await invoke<void>('create_world', { name, config });
+}
+
+export async function switchWorld(name: string): Promise<void> {
+  return await invoke<void>('switch_world', { name });
+}
+
+export async function listWorlds(): Promise<WorldSlotInfo[]> {
+  return await invoke<WorldSlotInfo[]>('list_worlds');
+}
+
+export interface Quality {
+  id: string;
+  name: string;
+  value: number;
+  min: number;
+  max: number;
+  description: string;
+}
+
+export async function getPlayerQualities(): Promise<Quality[]> {
+  return await invoke<Quality[]>('get_player_qualities');
+}
+
+export async function getPlayerSkills(): Promise<Record<string, number>> {
+  return await invoke<Record<string, number>>('get_player_skills');
+}
+
+/** Debug builds only; returns the value after clamping */
+export async function setPlayerQuality(id: string, value: number): Promise<number> {
+  return await invoke<number>('set_player_quality', { id, value });
+}